@@ -0,0 +1,44 @@
+//! Lint: `@or` / `@and` / `@not` filter groups.
+
+use super::{DiagnosticBuilder, LintContext};
+use dibs_query_schema::*;
+
+/// Check for empty and single-filter groups in a where clause.
+pub fn lint_filter_groups(where_clause: &Where, ctx: &mut LintContext<'_>) {
+    for (key, filter) in &where_clause.filters {
+        lint_filter_group(key.span, filter, ctx);
+    }
+}
+
+fn lint_filter_group(span: Span, filter: &FilterValue, ctx: &mut LintContext<'_>) {
+    let Some((kind, junction)) = filter.junction() else {
+        return;
+    };
+
+    if junction.is_empty() {
+        DiagnosticBuilder::error("empty-filter-group")
+            .at(span)
+            .msg(format!(
+                "empty {} group - it needs at least one filter",
+                kind.tag()
+            ))
+            .emit(ctx.diagnostics);
+    } else if junction.len() == 1 && kind != JunctionKind::Not {
+        DiagnosticBuilder::warning("single-filter-group")
+            .at(span)
+            .msg(format!(
+                "{} group with a single filter has no effect - use the filter directly",
+                kind.tag()
+            ))
+            .emit(ctx.diagnostics);
+    }
+
+    match junction {
+        Junction::Group(group) => lint_filter_groups(group, ctx),
+        Junction::Each(filters) => {
+            for filter in filters {
+                lint_filter_group(span, filter, ctx);
+            }
+        }
+    }
+}
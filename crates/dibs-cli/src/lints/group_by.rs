@@ -0,0 +1,56 @@
+//! Lint: selected columns in aggregate queries must be grouped.
+
+use super::{DiagnosticBuilder, LintContext};
+use dibs_proto::TableInfo;
+use dibs_query_schema::*;
+
+/// Check that, in a query with aggregates or a `group-by`, every selected
+/// column and every non-aggregate `having` filter is on a grouped column.
+///
+/// Grouping by the whole primary key makes every column of the table
+/// selectable, like Postgres does.
+pub fn lint_group_by(query: &Select, table: &TableInfo, ctx: &mut LintContext<'_>) {
+    let Some(fields) = &query.fields else { return };
+    if query.group_by.is_none() && !fields.has_aggregates() {
+        return;
+    }
+
+    let grouped = |column: &str| {
+        query
+            .group_by
+            .as_ref()
+            .is_some_and(|group_by| group_by.columns.keys().any(|c| c.as_str() == column))
+    };
+    let mut primary_key = table.columns.iter().filter(|c| c.primary_key).peekable();
+    if primary_key.peek().is_some() && primary_key.all(|c| grouped(&c.name)) {
+        return;
+    }
+
+    for (col_name, _) in fields.columns() {
+        if !grouped(col_name.as_str()) {
+            DiagnosticBuilder::error("ungrouped-column")
+                .at(col_name.span)
+                .msg(format!(
+                    "column '{}' must appear in group-by or be aggregated",
+                    col_name.as_str()
+                ))
+                .emit(ctx.diagnostics);
+        }
+    }
+
+    let Some(having) = &query.having else { return };
+    for (col_name, _filter) in having.predicates() {
+        let aggregate = fields
+            .aggregates()
+            .any(|(name, _, _)| name.as_str() == col_name.as_str());
+        if !aggregate && !grouped(col_name.as_str()) {
+            DiagnosticBuilder::error("ungrouped-column")
+                .at(col_name.span)
+                .msg(format!(
+                    "'{}' in having must be an aggregate field or appear in group-by",
+                    col_name.as_str()
+                ))
+                .emit(ctx.diagnostics);
+        }
+    }
+}
@@ -4,6 +4,8 @@
 //! during diagnostics collection.
 
 mod empty_select;
+mod filter_group;
+mod group_by;
 mod mutation_without_where;
 mod pagination;
 mod redundant_param;
@@ -19,6 +21,8 @@ use dibs_query_schema::*;
 use styx_lsp_ext::{Diagnostic, DiagnosticSeverity};
 
 pub use empty_select::*;
+pub use filter_group::*;
+pub use group_by::*;
 pub use mutation_without_where::*;
 pub use pagination::*;
 pub use redundant_param::*;
@@ -1,10 +1,12 @@
-//! Lint: Pagination issues (limit, offset, first, order-by).
+//! Lint: Pagination issues (limit, offset, first, order-by, keyset, stream).
 
 use super::{DiagnosticBuilder, LintContext};
+use dibs_proto::TableInfo;
 use dibs_query_schema::*;
 
 pub fn lint_pagination_query(query: &Select, ctx: &mut LintContext<'_>) {
-    let has_order_by = query.order_by.is_some();
+    // keyset pagination orders by its `by` columns
+    let has_order_by = query.order_by.is_some() || query.paginate.is_some();
 
     // offset without limit
     if let Some(offset) = &query.offset
@@ -45,9 +47,111 @@ pub fn lint_pagination_query(query: &Select, ctx: &mut LintContext<'_>) {
         DiagnosticBuilder::warning("large-offset")
             .at(offset.span)
             .msg(format!(
-                "large offset ({}) may cause performance issues - consider cursor-based pagination",
+                "large offset ({}) may cause performance issues - consider keyset pagination (paginate @keyset)",
                 n
             ))
             .emit(ctx.diagnostics);
     }
+
+    if let Some(stream) = &query.stream
+        && stream.get()
+        && let Some(first) = &query.first
+        && first.get()
+    {
+        DiagnosticBuilder::error("stream-with-first")
+            .at(stream.span)
+            .msg("'first' returns a single row - there is nothing to stream")
+            .emit(ctx.diagnostics);
+    }
+
+    if let Some(keyset) = query.keyset() {
+        lint_keyset_query(query, keyset, ctx);
+    }
+}
+
+fn lint_keyset_query(query: &Select, keyset: &Keyset, ctx: &mut LintContext<'_>) {
+    let Some((first_col, _)) = keyset.by.columns.first() else {
+        return;
+    };
+
+    if let Some((col, _)) = query.order_by.as_ref().and_then(|o| o.columns.first()) {
+        DiagnosticBuilder::error("keyset-with-order-by")
+            .at(col.span)
+            .msg("'order-by' conflicts with keyset pagination, which orders by its 'by' columns")
+            .emit(ctx.diagnostics);
+    }
+
+    if let Some(offset) = &query.offset {
+        DiagnosticBuilder::error("keyset-with-offset")
+            .at(offset.span)
+            .msg("'offset' can't be combined with keyset pagination - the cursor replaces it")
+            .emit(ctx.diagnostics);
+    }
+
+    if let Some(first) = &query.first
+        && first.get()
+    {
+        DiagnosticBuilder::error("keyset-with-first")
+            .at(first.span)
+            .msg("'first' queries return a single row and can't be paginated")
+            .emit(ctx.diagnostics);
+    }
+
+    if query.limit.is_none() {
+        DiagnosticBuilder::error("keyset-without-limit")
+            .at(first_col.span)
+            .msg("keyset pagination needs a 'limit' (the page size)")
+            .emit(ctx.diagnostics);
+    }
+
+    if let Some(fields) = &query.fields {
+        for col_name in keyset.by.columns.keys() {
+            if !fields
+                .columns()
+                .any(|(name, _)| name.as_str() == col_name.as_str())
+            {
+                DiagnosticBuilder::error("keyset-column-not-selected")
+                    .at(col_name.span)
+                    .msg(format!(
+                        "keyset column '{}' must be selected in 'fields' to build the next cursor",
+                        col_name.as_str()
+                    ))
+                    .emit(ctx.diagnostics);
+            }
+        }
+    }
+}
+
+/// Check that keyset columns are NOT NULL and that the last one is unique,
+/// so that every row has a distinct position between pages.
+pub fn lint_keyset_columns(keyset: &Keyset, table: &TableInfo, ctx: &mut LintContext<'_>) {
+    for col_name in keyset.by.columns.keys() {
+        if let Some(column) = table.columns.iter().find(|c| c.name == col_name.as_str())
+            && column.nullable
+        {
+            DiagnosticBuilder::error("keyset-nullable-column")
+                .at(col_name.span)
+                .msg(format!(
+                    "keyset column '{}' is nullable - NULLs can't be compared with a cursor",
+                    col_name.as_str()
+                ))
+                .emit(ctx.diagnostics);
+        }
+    }
+
+    let Some((last_col, _)) = keyset.by.columns.last() else {
+        return;
+    };
+    if let Some(column) = table.columns.iter().find(|c| c.name == last_col.as_str())
+        && !column.primary_key
+        && !column.unique
+    {
+        DiagnosticBuilder::error("keyset-not-unique")
+            .at(last_col.span)
+            .msg(format!(
+                "last keyset column '{}' is not unique - rows sharing a value would be skipped between pages; end with a unique column like the primary key",
+                last_col.as_str()
+            ))
+            .emit(ctx.diagnostics);
+    }
 }
@@ -97,6 +97,11 @@ pub fn lint_redundant_params_in_conflict_update(
 
 pub fn lint_redundant_params_in_where(where_clause: &Where, ctx: &mut LintContext<'_>) {
     for (col_name, filter) in &where_clause.filters {
+        // Entries of `{...}` groups are `column value` pairs too; filters in
+        // `(...)` lists aren't, so the shorthand doesn't apply there.
+        if let Some((_, Junction::Group(group))) = filter.junction() {
+            lint_redundant_params_in_where(group, ctx);
+        }
         if let FilterValue::EqBare(Some(meta)) = filter
             && let Some(param_name) = meta.as_str().strip_prefix('$')
             && param_name == col_name.as_str()
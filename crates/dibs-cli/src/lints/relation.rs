@@ -1,7 +1,7 @@
 //! Lint: Relation (@rel) issues.
 
 use super::{DiagnosticBuilder, LintContext};
-use dibs_proto::SchemaInfo;
+use dibs_proto::{SchemaInfo, TableInfo};
 use dibs_query_schema::*;
 
 /// Check if there's a FK relationship between two tables (in either direction).
@@ -27,6 +27,31 @@ fn has_fk_relationship(table_a: &str, table_b: &str, schema: &SchemaInfo) -> boo
     false
 }
 
+/// Whether `junction` has a FK to `table_a` and a different FK to `table_b`.
+fn is_junction_between(junction: &TableInfo, table_a: &str, table_b: &str) -> bool {
+    let fks = &junction.foreign_keys;
+    fks.iter().enumerate().any(|(i, a)| {
+        a.references_table == table_a
+            && fks
+                .iter()
+                .enumerate()
+                .any(|(j, b)| j != i && b.references_table == table_b)
+    })
+}
+
+/// Junction tables (exactly two FKs) linking two different tables.
+fn junction_candidates<'s>(table_a: &str, table_b: &str, schema: &'s SchemaInfo) -> Vec<&'s str> {
+    if table_a == table_b {
+        return vec![];
+    }
+    schema
+        .tables
+        .iter()
+        .filter(|t| t.foreign_keys.len() == 2 && is_junction_between(t, table_a, table_b))
+        .map(|t| t.name.as_str())
+        .collect()
+}
+
 pub fn lint_relation(rel: &Relation, parent_table: Option<&str>, ctx: &mut LintContext<'_>) {
     // first without order-by
     if let Some(first) = &rel.first
@@ -39,18 +64,55 @@ pub fn lint_relation(rel: &Relation, parent_table: Option<&str>, ctx: &mut LintC
             .emit(ctx.diagnostics);
     }
 
+    let (Some(parent), Some(from)) = (parent_table, rel.from.as_ref()) else {
+        return;
+    };
+
+    // Explicit junction table
+    if let Some(through) = &rel.through {
+        let valid = ctx
+            .schema
+            .tables
+            .iter()
+            .find(|t| t.name == through.as_str())
+            .is_some_and(|t| is_junction_between(t, parent, from.as_str()));
+        if !valid {
+            DiagnosticBuilder::error("invalid-junction")
+                .at(through.span)
+                .msg(format!(
+                    "'{}' needs a FK to both '{}' and '{}'",
+                    through.as_str(),
+                    parent,
+                    from.as_str()
+                ))
+                .emit(ctx.diagnostics);
+        }
+        return;
+    }
+
     // FK relationship check
-    if let (Some(parent), Some(from)) = (parent_table, rel.from.as_ref())
-        && !has_fk_relationship(parent, from.as_str(), ctx.schema)
-    {
-        DiagnosticBuilder::error("no-fk-relationship")
+    if has_fk_relationship(parent, from.as_str(), ctx.schema) {
+        return;
+    }
+    match junction_candidates(parent, from.as_str(), ctx.schema).as_slice() {
+        [] => DiagnosticBuilder::error("no-fk-relationship")
             .at(from.span)
             .msg(format!(
                 "no FK relationship between '{}' and '{}'",
                 parent,
                 from.as_str()
             ))
-            .emit(ctx.diagnostics);
+            .emit(ctx.diagnostics),
+        [_] => {}
+        candidates => DiagnosticBuilder::error("ambiguous-junction")
+            .at(from.span)
+            .msg(format!(
+                "'{}' and '{}' are linked through {} - pick one with 'through'",
+                parent,
+                from.as_str(),
+                candidates.join(", ")
+            ))
+            .emit(ctx.diagnostics),
     }
 }
 
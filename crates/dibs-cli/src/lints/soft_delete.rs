@@ -6,7 +6,7 @@ use dibs_query_schema::*;
 /// Check if a where clause filters on deleted_at.
 fn where_filters_deleted_at(where_clause: &Where) -> bool {
     where_clause
-        .filters
+        .predicates()
         .iter()
         .any(|(col, _)| col.as_str() == "deleted_at")
 }
@@ -16,6 +16,10 @@ pub fn lint_missing_deleted_at_filter(query: &Select, ctx: &mut LintContext<'_>)
     let Some(table) = ctx.find_table(from.as_str()) else {
         return;
     };
+    // Declared soft-delete tables get the filter generated for them
+    if table.soft_delete.is_some() {
+        return;
+    }
     let has_deleted_at = table.columns.iter().any(|c| c.name == "deleted_at");
     if !has_deleted_at {
         return;
@@ -41,12 +45,26 @@ pub fn lint_hard_delete_on_soft_delete_table(delete: &Delete, ctx: &mut LintCont
     let Some(table) = ctx.find_table(delete.from.as_str()) else {
         return;
     };
+
+    if table.soft_delete.is_some() {
+        if let Some(hard) = delete.hard.as_ref().filter(|h| h.value) {
+            DiagnosticBuilder::warning("hard-delete-on-soft-delete-table")
+                .at(hard.span)
+                .msg(format!(
+                    "'hard true' permanently removes rows from soft-delete table '{}'",
+                    delete.from.as_str()
+                ))
+                .emit(ctx.diagnostics);
+        }
+        return;
+    }
+
     let has_deleted_at = table.columns.iter().any(|c| c.name == "deleted_at");
 
     if has_deleted_at {
         DiagnosticBuilder::warning("hard-delete-on-soft-delete-table")
             .at(delete.from.span)
-            .msg("@delete on table with 'deleted_at' column - consider soft delete with @update instead")
+            .msg("@delete on table with 'deleted_at' column - consider declaring it with #[facet(dibs::soft_delete = \"deleted_at\")]")
             .emit(ctx.diagnostics);
     }
 }
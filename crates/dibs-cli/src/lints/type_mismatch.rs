@@ -1,49 +1,10 @@
 //! Lint: Type mismatches between params and columns.
 
 use super::{DiagnosticBuilder, LintContext};
+use dibs::types::{infer_literal_type, types_compatible};
 use dibs_proto::TableInfo;
 use dibs_query_schema::*;
 
-/// Check if a param type is compatible with a SQL column type.
-fn types_compatible(param_type: &str, sql_type: &str) -> bool {
-    match param_type {
-        "string" => matches!(
-            sql_type.to_uppercase().as_str(),
-            "TEXT" | "VARCHAR" | "CHAR" | "CHARACTER VARYING"
-        ),
-        "int" => matches!(
-            sql_type.to_uppercase().as_str(),
-            "INT" | "INTEGER" | "BIGINT" | "SMALLINT" | "INT4" | "INT8" | "INT2"
-        ),
-        "bool" | "boolean" => matches!(sql_type.to_uppercase().as_str(), "BOOLEAN" | "BOOL"),
-        "float" => matches!(
-            sql_type.to_uppercase().as_str(),
-            "FLOAT" | "DOUBLE" | "REAL" | "NUMERIC" | "DECIMAL" | "FLOAT4" | "FLOAT8"
-        ),
-        "jsonb" => matches!(sql_type.to_uppercase().as_str(), "JSON" | "JSONB"),
-        _ => true, // Unknown types are assumed compatible
-    }
-}
-
-/// Infer the type of a literal value.
-/// Returns None if it's a param reference (starts with $) or unknown.
-fn infer_literal_type(value: &str) -> Option<&'static str> {
-    if value.starts_with('$') {
-        return None; // Param reference, not a literal
-    }
-    if value == "true" || value == "false" {
-        return Some("boolean");
-    }
-    if value.parse::<i64>().is_ok() {
-        return Some("int");
-    }
-    if value.parse::<f64>().is_ok() {
-        return Some("float");
-    }
-    // Everything else is a string (quoted strings arrive without quotes in EqBare)
-    Some("string")
-}
-
 fn param_type_name(param_type: &ParamType) -> String {
     match param_type {
         ParamType::String => "string".to_string(),
@@ -72,7 +33,7 @@ pub fn lint_literal_types_in_where(
     table: &TableInfo,
     ctx: &mut LintContext<'_>,
 ) {
-    for (col_name, filter) in &where_clause.filters {
+    for (col_name, filter) in where_clause.predicates() {
         let Some(column) = table.columns.iter().find(|c| c.name == col_name.as_str()) else {
             continue;
         };
@@ -105,7 +66,7 @@ pub fn lint_param_types_in_where(
     params: &Params,
     ctx: &mut LintContext<'_>,
 ) {
-    for (col_name, filter) in &where_clause.filters {
+    for (col_name, filter) in where_clause.predicates() {
         let Some(column) = table.columns.iter().find(|c| c.name == col_name.as_str()) else {
             continue;
         };
@@ -10,9 +10,14 @@ pub fn lint_unknown_columns_select(
     ctx: &mut LintContext<'_>,
 ) {
     for (col_name, field_def) in &select.fields {
-        if matches!(field_def, Some(FieldDef::Rel(_))) {
-            continue;
-        }
+        // Aggregates are named freely; check the column they aggregate
+        let col_name = match field_def {
+            None => col_name,
+            Some(field_def) => match field_def.aggregate() {
+                Some((_, Some(column))) => column,
+                _ => continue,
+            },
+        };
         if !table.columns.iter().any(|c| c.name == col_name.as_str()) {
             let available = table
                 .columns
@@ -38,7 +43,7 @@ pub fn lint_unknown_columns_where(
     table: &TableInfo,
     ctx: &mut LintContext<'_>,
 ) {
-    for (col_name, _filter) in &where_clause.filters {
+    for (col_name, _filter) in where_clause.predicates() {
         if !table.columns.iter().any(|c| c.name == col_name.as_str()) {
             let available = table
                 .columns
@@ -85,6 +90,32 @@ pub fn lint_unknown_columns_order_by(
     }
 }
 
+pub fn lint_unknown_columns_group_by(
+    group_by: &GroupBy,
+    table: &TableInfo,
+    ctx: &mut LintContext<'_>,
+) {
+    for col_name in group_by.columns.keys() {
+        if !table.columns.iter().any(|c| c.name == col_name.as_str()) {
+            let available = table
+                .columns
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            DiagnosticBuilder::error("unknown-column")
+                .at(col_name.span)
+                .msg(format!(
+                    "Unknown column '{}' in table '{}'. Available columns: {}",
+                    col_name.as_str(),
+                    table.name,
+                    available
+                ))
+                .emit(ctx.diagnostics);
+        }
+    }
+}
+
 pub fn lint_unknown_columns_values(values: &Values, table: &TableInfo, ctx: &mut LintContext<'_>) {
     for (col_name, _value_expr) in &values.columns {
         if !table.columns.iter().any(|c| c.name == col_name.as_str()) {
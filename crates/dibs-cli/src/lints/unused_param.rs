@@ -6,7 +6,7 @@ use dibs_query_schema::*;
 /// Collect param refs from a Where clause.
 fn collect_param_refs_from_where(where_clause: &Where) -> Vec<String> {
     let mut refs = Vec::new();
-    for (col_name, filter) in &where_clause.filters {
+    for (col_name, filter) in where_clause.predicates() {
         match filter {
             FilterValue::EqBare(Some(meta)) => {
                 if let Some(param) = meta.as_str().strip_prefix('$') {
@@ -37,6 +37,8 @@ fn collect_param_refs_from_where(where_clause: &Where) -> Vec<String> {
                 }
             }
             FilterValue::Null | FilterValue::NotNull => {}
+            // Flattened by `predicates()`
+            FilterValue::Or(_) | FilterValue::And(_) | FilterValue::Not(_) => {}
         }
     }
     refs
@@ -110,6 +112,9 @@ pub fn lint_unused_params_query(query: &Select, ctx: &mut LintContext<'_>) {
     if let Some(where_clause) = &query.where_clause {
         used.extend(collect_param_refs_from_where(where_clause));
     }
+    if let Some(having) = &query.having {
+        used.extend(collect_param_refs_from_where(having));
+    }
     if let Some(limit) = &query.limit
         && let Some(param) = limit.as_str().strip_prefix('$')
     {
@@ -161,7 +161,10 @@ impl DibsExtension {
         state
             .as_ref()
             .map(|s| s.schema.clone())
-            .unwrap_or_else(|| SchemaInfo { tables: vec![] })
+            .unwrap_or_else(|| SchemaInfo {
+                tables: vec![],
+                extensions: vec![],
+            })
     }
 
     /// Parse styx content into typed QueryFile.
@@ -220,6 +223,7 @@ impl DibsExtension {
                             lints::lint_relations_in_select(fields, Some(from.as_str()), &mut ctx);
                         }
                         if let Some(where_clause) = &query.where_clause {
+                            lints::lint_filter_groups(where_clause, &mut ctx);
                             lints::lint_unknown_columns_where(where_clause, table, &mut ctx);
                             lints::lint_redundant_params_in_where(where_clause, &mut ctx);
                             lints::lint_literal_types_in_where(where_clause, table, &mut ctx);
@@ -235,6 +239,17 @@ impl DibsExtension {
                         if let Some(order_by) = &query.order_by {
                             lints::lint_unknown_columns_order_by(order_by, table, &mut ctx);
                         }
+                        if let Some(group_by) = &query.group_by {
+                            lints::lint_unknown_columns_group_by(group_by, table, &mut ctx);
+                        }
+                        if let Some(keyset) = query.keyset() {
+                            lints::lint_unknown_columns_order_by(&keyset.by, table, &mut ctx);
+                            lints::lint_keyset_columns(keyset, table, &mut ctx);
+                        }
+                        if let Some(having) = &query.having {
+                            lints::lint_filter_groups(having, &mut ctx);
+                        }
+                        lints::lint_group_by(query, table, &mut ctx);
                     }
                 }
                 Decl::Insert(insert) => {
@@ -290,6 +305,7 @@ impl DibsExtension {
                     if let Some(table) = ctx.find_table(update.table.as_str()) {
                         lints::lint_unknown_columns_values(&update.set, table, &mut ctx);
                         if let Some(where_clause) = &update.where_clause {
+                            lints::lint_filter_groups(where_clause, &mut ctx);
                             lints::lint_unknown_columns_where(where_clause, table, &mut ctx);
                             lints::lint_redundant_params_in_where(where_clause, &mut ctx);
                         }
@@ -304,6 +320,7 @@ impl DibsExtension {
                     if let Some(table) = ctx.find_table(delete.from.as_str())
                         && let Some(where_clause) = &delete.where_clause
                     {
+                        lints::lint_filter_groups(where_clause, &mut ctx);
                         lints::lint_unknown_columns_where(where_clause, table, &mut ctx);
                         lints::lint_redundant_params_in_where(where_clause, &mut ctx);
                     }
@@ -616,6 +633,79 @@ impl DibsExtension {
             .collect()
     }
 
+    /// Get completions for filter operators, including the `@or`/`@and`/`@not`
+    /// groups, in value position of a where entry.
+    fn filter_completions(prefix: &str) -> Vec<CompletionItem> {
+        const FILTERS: &[(&str, &str, &str)] = &[
+            ("@null", "@null", "IS NULL"),
+            ("@not-null", "@not-null", "IS NOT NULL"),
+            ("@eq", "@eq($)", "Equality: `column = value`"),
+            ("@ne", "@ne($)", "Inequality: `column <> value`"),
+            ("@gt", "@gt($)", "Greater than: `column > value`"),
+            (
+                "@gte",
+                "@gte($)",
+                "Greater than or equal: `column >= value`",
+            ),
+            ("@lt", "@lt($)", "Less than: `column < value`"),
+            ("@lte", "@lte($)", "Less than or equal: `column <= value`"),
+            ("@like", "@like($)", "Pattern match: `column LIKE pattern`"),
+            (
+                "@ilike",
+                "@ilike($)",
+                "Case-insensitive pattern match: `column ILIKE pattern`",
+            ),
+            ("@in", "@in($)", "Array membership: `column = ANY(array)`"),
+            ("@json-get", "@json-get($)", "JSONB field: `column -> key`"),
+            (
+                "@json-get-text",
+                "@json-get-text($)",
+                "JSONB field as text: `column ->> key`",
+            ),
+            (
+                "@contains",
+                "@contains($)",
+                "JSONB containment: `column @> value`",
+            ),
+            (
+                "@key-exists",
+                "@key-exists($)",
+                "JSONB key exists: `column ? key`",
+            ),
+            (
+                "@or",
+                "@or{}",
+                "Any filter matches. `@or{a $a, b $b}` groups filters on other columns \
+                 (the key is a label); `@or(@null @gt($x))` combines filters on this column.",
+            ),
+            (
+                "@and",
+                "@and{}",
+                "All filters match. `@and(@gte($min) @lte($max))` applies several \
+                 filters to this column; `@and{...}` groups filters inside an `@or`.",
+            ),
+            (
+                "@not",
+                "@not{}",
+                "Negates the filters, ANDed: `@not{a $a, b $b}` or `@not(@in($xs))`.",
+            ),
+        ];
+
+        let prefix = prefix.trim_start_matches('@');
+        FILTERS
+            .iter()
+            .filter(|(label, _, _)| label[1..].starts_with(prefix))
+            .map(|(label, insert, doc)| CompletionItem {
+                label: label.to_string(),
+                detail: None,
+                documentation: Some(doc.to_string()),
+                kind: Some(CompletionKind::Keyword),
+                sort_text: None,
+                insert_text: Some(insert.to_string()),
+            })
+            .collect()
+    }
+
     /// Get completions for query structure fields (from, select, where, etc.)
     fn query_field_completions(&self, prefix: &str, is_rel: bool) -> Vec<CompletionItem> {
         let type_name = if is_rel { "Relation" } else { "Query" };
@@ -729,6 +819,19 @@ impl StyxLspExtension for DibsExtension {
             last
         };
 
+        // Value of a where entry, or of an entry in a filter group: offer filters
+        let scope = if !params.prefix.is_empty() && last == params.prefix {
+            &params.path[..params.path.len() - 1]
+        } else {
+            &params.path[..]
+        };
+        if let [.., block, key] = scope
+            && matches!(block.as_str(), "where" | "having" | "@or" | "@and" | "@not")
+            && !key.starts_with('@')
+        {
+            return Self::filter_completions(&params.prefix);
+        }
+
         match context_key {
             // Inside a @query or @rel block - offer query structure fields
             "@query" => self.query_field_completions(&params.prefix, false),
@@ -742,8 +845,8 @@ impl StyxLspExtension for DibsExtension {
             "from" | "into" | "table" | "join" => self.table_completions(&params.prefix).await,
 
             // Column references - need to know which table
-            "fields" | "where" | "order-by" | "group-by" | "values" | "set" | "returning"
-            | "target" | "update" => {
+            "fields" | "where" | "@or" | "@and" | "@not" | "order-by" | "group-by" | "values"
+            | "set" | "returning" | "target" | "update" => {
                 // Try tagged_context first (the @query block) - most reliable
                 if let Some(tagged) = &params.tagged_context
                     && let Some(table_name) = Self::find_table_in_context(tagged)
@@ -79,6 +79,10 @@ struct Config {
     /// Database crate configuration.
     #[facet(default)]
     db: DbConfig,
+
+    /// Databases migrated together by `dibs migrate --fleet`.
+    #[facet(default)]
+    fleet: dibs_config::FleetConfig,
 }
 
 impl Config {
@@ -122,7 +126,17 @@ struct AgentInstallArgs {
 #[repr(u8)]
 enum Commands {
     /// Run pending migrations
-    Migrate,
+    Migrate {
+        /// Refuse to run if migrations are out of order or missing from the code
+        #[facet(default, args::named)]
+        strict: bool,
+        /// Only apply pending migrations up to and including this version
+        #[facet(args::named, default)]
+        to: Option<String>,
+        /// Migrate every database listed in the `fleet` config instead
+        #[facet(default, args::named)]
+        fleet: bool,
+    },
     /// Show migration status
     Status,
     /// Compare schema to database
@@ -139,6 +153,24 @@ enum Commands {
         #[facet(args::positional)]
         name: String,
     },
+    /// Write Rust table definitions for an existing database, plus a baseline migration
+    Import {
+        /// Output file, relative to the db crate (defaults to src/tables.rs)
+        #[facet(args::named, default)]
+        output: Option<String>,
+    },
+    /// Replace all migrations up to a version with a single baseline
+    Squash {
+        /// Last migration version to squash (inclusive)
+        #[facet(args::named)]
+        up_to: String,
+    },
+    /// Load seed data from the db crate's seeds/ directory
+    Seed {
+        /// Environment to seed: dev, test or prod (defaults to dev)
+        #[facet(args::named, default)]
+        env: Option<String>,
+    },
     /// Browse the current schema
     Schema {
         /// Output as plain text (default when not a TTY)
@@ -190,8 +222,12 @@ fn run(args: Args) {
 
     let config = args.config;
     match args.command {
-        Some(Commands::Migrate) => {
-            run_migrate(&config);
+        Some(Commands::Migrate { strict, to, fleet }) => {
+            if fleet {
+                run_migrate_fleet(&config, strict, to);
+            } else {
+                run_migrate(&config, strict, to);
+            }
         }
         Some(Commands::Status) => {
             run_status(&config);
@@ -205,9 +241,18 @@ fn run(args: Args) {
         Some(Commands::GenerateFromDiff { name }) => {
             run_generate_from_diff(&config, &name);
         }
+        Some(Commands::Import { output }) => {
+            run_import(&config, output.as_deref());
+        }
+        Some(Commands::Squash { up_to }) => {
+            run_squash(&config, &up_to);
+        }
+        Some(Commands::Seed { env }) => {
+            run_seed(&config, env.as_deref().unwrap_or("dev"));
+        }
         Some(Commands::Schema { plain, sql }) => {
             let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
-            let schema = rt.block_on(async {
+            let (schema, provenance) = rt.block_on(async {
                 let conn = match service::connect_to_service(&config.db).await {
                     Ok(conn) => conn,
                     Err(e) => {
@@ -216,13 +261,29 @@ fn run(args: Args) {
                     }
                 };
 
-                match conn.client().schema().await {
+                let schema = match conn.client().schema().await {
                     Ok(schema_info) => schema_info_to_schema(schema_info),
                     Err(e) => {
                         eprintln!("Failed to get schema: {:?}", e);
                         std::process::exit(1);
                     }
-                }
+                };
+
+                // Migration provenance is shown when a database is configured
+                let provenance = match &config.database_url {
+                    Some(url) => conn
+                        .client()
+                        .provenance(dibs_proto::ProvenanceRequest {
+                            database_url: url.clone(),
+                        })
+                        .await
+                        .unwrap_or_else(|e| {
+                            eprintln!("Failed to load migration provenance: {:?}", e);
+                            Vec::new()
+                        }),
+                    None => Vec::new(),
+                };
+                (schema, provenance)
             });
 
             if schema.tables.is_empty() {
@@ -237,12 +298,12 @@ fn run(args: Args) {
                 println!("{}", schema.to_sql());
             } else if stdout().is_terminal() && !plain {
                 // Use TUI if stdout is a TTY and --plain wasn't specified
-                if let Err(e) = run_schema_tui(&schema) {
+                if let Err(e) = run_schema_tui(&schema, &provenance) {
                     eprintln!("TUI error: {}", e);
                     std::process::exit(1);
                 }
             } else {
-                print_schema_plain(&schema);
+                print_schema_plain(&schema, &provenance);
             }
         }
         Some(Commands::LspExtension) => {
@@ -342,10 +403,21 @@ fn schema_info_to_schema(info: dibs_proto::SchemaInfo) -> dibs::Schema {
                     lang: c.lang,
                     icon: c.icon,
                     subtype: c.subtype,
+                    domain: None,
+                    composite: None,
                 })
                 .collect(),
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: t.soft_delete,
+            created_at: t.created_at,
+            updated_at: t.updated_at,
+            audited: t.audited,
             foreign_keys: t
                 .foreign_keys
                 .into_iter()
@@ -353,6 +425,7 @@ fn schema_info_to_schema(info: dibs_proto::SchemaInfo) -> dibs::Schema {
                     columns: fk.columns,
                     references_table: fk.references_table,
                     references_columns: fk.references_columns,
+                    deferrable: fk.deferrable,
                 })
                 .collect(),
             indices: t
@@ -392,13 +465,57 @@ fn schema_info_to_schema(info: dibs_proto::SchemaInfo) -> dibs::Schema {
         .map(|t| (t.name.clone(), t))
         .collect();
 
-    dibs::Schema { tables }
+    dibs::Schema {
+        tables,
+        extensions: info.extensions,
+        domains: Vec::new(),
+        composites: Vec::new(),
+    }
+}
+
+/// Find the recorded provenance of a table (`name: None`), column or index.
+fn find_provenance<'a>(
+    provenance: &'a [dibs_proto::ProvenanceInfo],
+    kind: &str,
+    table: &str,
+    name: Option<&str>,
+) -> Option<&'a dibs_proto::ProvenanceInfo> {
+    provenance
+        .iter()
+        .find(|p| p.kind == kind && p.table == table && p.name.as_deref() == name)
+}
+
+/// Describe which migrations created and last modified an object.
+fn describe_provenance(p: &dibs_proto::ProvenanceInfo) -> String {
+    let created = match &p.created_by {
+        Some(migration) => format!("created by migration {} at {}", migration, p.created_at),
+        None => format!("existed before tracking started at {}", p.created_at),
+    };
+    if p.modified_at == p.created_at && p.modified_by == p.created_by {
+        return created;
+    }
+    match &p.modified_by {
+        Some(migration) => format!(
+            "{}, modified by migration {} at {}",
+            created, migration, p.modified_at
+        ),
+        None => format!(
+            "{}, modified outside migrations at {}",
+            created, p.modified_at
+        ),
+    }
 }
 
 /// Print schema as plain text (for piping)
-fn print_schema_plain(schema: &dibs::Schema) {
+fn print_schema_plain(schema: &dibs::Schema, provenance: &[dibs_proto::ProvenanceInfo]) {
     for table in schema.tables.values() {
         println!("TABLE {}", table.name);
+        if table.source.is_known() {
+            println!("  -- defined at {}", table.source);
+        }
+        if let Some(p) = find_provenance(provenance, "table", &table.name, None) {
+            println!("  -- {}", describe_provenance(p));
+        }
         for col in &table.columns {
             let mut attrs = Vec::new();
             if col.primary_key {
@@ -420,7 +537,10 @@ fn print_schema_plain(schema: &dibs::Schema) {
                 format!(" [{}]", attrs.join(", "))
             };
 
-            println!("  {} {}{}", col.name, col.pg_type, attrs_str);
+            let origin = find_provenance(provenance, "column", &table.name, Some(&col.name))
+                .map(|p| format!(" -- {}", describe_provenance(p)))
+                .unwrap_or_default();
+            println!("  {} {}{}{}", col.name, col.pg_type, attrs_str, origin);
         }
 
         for fk in &table.foreign_keys {
@@ -439,19 +559,31 @@ fn print_schema_plain(schema: &dibs::Schema) {
                 .iter()
                 .map(|c| format!("{}{}{}", c.name, c.order.to_sql(), c.nulls.to_sql()))
                 .collect();
-            println!("  INDEX {} on ({}){}", idx.name, cols.join(", "), unique);
+            let origin = find_provenance(provenance, "index", &table.name, Some(&idx.name))
+                .map(|p| format!(" -- {}", describe_provenance(p)))
+                .unwrap_or_default();
+            println!(
+                "  INDEX {} on ({}){}{}",
+                idx.name,
+                cols.join(", "),
+                unique,
+                origin
+            );
         }
         println!();
     }
 }
 
 /// Run the interactive TUI for browsing schema
-fn run_schema_tui(schema: &dibs::Schema) -> io::Result<()> {
+fn run_schema_tui(
+    schema: &dibs::Schema,
+    provenance: &[dibs_proto::ProvenanceInfo],
+) -> io::Result<()> {
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
-    let mut app = SchemaApp::new(schema);
+    let mut app = SchemaApp::new(schema, provenance);
     let result = app.run(&mut terminal);
 
     disable_raw_mode()?;
@@ -462,6 +594,8 @@ fn run_schema_tui(schema: &dibs::Schema) -> io::Result<()> {
 
 struct SchemaApp<'a> {
     schema: &'a dibs::Schema,
+    /// Which migrations created and modified each object (empty without a database)
+    provenance: &'a [dibs_proto::ProvenanceInfo],
     table_state: ListState,
     selected_table: usize,
     /// Which tables are expanded (showing columns)
@@ -483,12 +617,13 @@ enum Focus {
 }
 
 impl<'a> SchemaApp<'a> {
-    fn new(schema: &'a dibs::Schema) -> Self {
+    fn new(schema: &'a dibs::Schema, provenance: &'a [dibs_proto::ProvenanceInfo]) -> Self {
         let mut table_state = ListState::default();
         table_state.select(Some(0));
         let expanded = vec![false; schema.tables.len()];
         Self {
             schema,
+            provenance,
             table_state,
             selected_table: 0,
             expanded,
@@ -885,6 +1020,15 @@ impl<'a> SchemaApp<'a> {
                 ]));
             }
 
+            // Show which migrations created and modified the table
+            if let Some(p) = find_provenance(self.provenance, "table", &table.name, None) {
+                lines.push(Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled("Migrations: ", Style::default().fg(Color::Gray)),
+                    Span::styled(describe_provenance(p), Style::default().fg(Color::DarkGray)),
+                ]));
+            }
+
             // Show doc comment if available
             if let Some(doc) = &table.doc {
                 lines.push(Line::from(vec![
@@ -937,6 +1081,15 @@ impl<'a> SchemaApp<'a> {
                         Style::default().fg(Color::Gray),
                     ));
                 }
+                if is_selected
+                    && let Some(p) =
+                        find_provenance(self.provenance, "column", &table.name, Some(&col.name))
+                {
+                    spans.push(Span::styled(
+                        format!("  {}", describe_provenance(p)),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
 
                 let mut line = Line::from(spans);
                 if is_selected {
@@ -1103,8 +1256,8 @@ fn mask_password(url: &str) -> String {
     url.to_string()
 }
 
-fn run_migrate(config: &Config) {
-    use dibs_proto::MigrateRequest;
+fn run_migrate(config: &Config, strict: bool, to: Option<String>) {
+    use dibs_proto::{LogLevel, MigrateRequest};
     use tracing::info;
 
     let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
@@ -1131,7 +1284,7 @@ fn run_migrate(config: &Config) {
         let log_collector = tokio::spawn(async move {
             let mut logs = Vec::new();
             while let Ok(Some(log)) = log_rx.recv().await {
-                logs.push(log);
+                let _ = log.map(|log| logs.push(log));
             }
             logs
         });
@@ -1141,14 +1294,22 @@ fn run_migrate(config: &Config) {
             .migrate(
                 MigrateRequest {
                     database_url: database_url.to_string(),
-                    migration: None, // Run all pending
+                    migration: to, // None runs all pending
+                    strict,
                 },
                 log_tx,
             )
             .await;
 
-        // Wait for log collector
-        let _logs = log_collector.await.unwrap_or_default();
+        // Wait for log collector; warnings are shown, the rest is summarized
+        let logs = log_collector.await.unwrap_or_default();
+        for log in &logs {
+            match log.level {
+                LogLevel::Warn => eprintln!("{} {}", "!".yellow(), log.message),
+                LogLevel::Error => eprintln!("{} {}", "✗".red(), log.message),
+                LogLevel::Debug | LogLevel::Info => {}
+            }
+        }
 
         match result {
             Ok(res) => {
@@ -1162,6 +1323,142 @@ fn run_migrate(config: &Config) {
     });
 }
 
+fn run_migrate_fleet(config: &Config, strict: bool, to: Option<String>) {
+    use dibs_proto::{FleetDiscovery, FleetMigrateRequest, FleetStatus, FleetTargetInfo, LogLevel};
+    use owo_colors::OwoColorize as _;
+
+    let fleet = &config.fleet;
+    let discovery = fleet.discover.as_ref().map(|query| FleetDiscovery {
+        database_url: config.require_database_url().to_string(),
+        query: query.clone(),
+    });
+    if fleet.databases.is_empty() && discovery.is_none() {
+        eprintln!("Error: no fleet configured.");
+        eprintln!();
+        eprintln!("List the databases to migrate in .config/dibs.styx:");
+        eprintln!(
+            "  fleet {{databases (\"postgres://host/tenant_a\" \"postgres://host/tenant_b\")}}"
+        );
+        eprintln!("  or query them from DATABASE_URL:");
+        eprintln!("  fleet {{discover \"SELECT database_url FROM tenants\"}}");
+        std::process::exit(1);
+    }
+
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+    rt.block_on(async {
+        // Connect to the db crate via vox
+        let conn = match service::connect_to_service(&config.db).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Failed to connect to db service: {}", e);
+                std::process::exit(1);
+            }
+        };
+
+        // Show progress as it streams in, since a fleet can take a while
+        let (log_tx, mut log_rx) = vox::channel::<dibs_proto::MigrationLog>();
+        let log_printer = tokio::spawn(async move {
+            while let Ok(Some(log)) = log_rx.recv().await {
+                let _ = log.map(|log| {
+                    let database = log.database.as_deref().unwrap_or("-");
+                    match log.level {
+                        LogLevel::Debug => {}
+                        LogLevel::Info => {
+                            println!("  {} {} {}", "▶".cyan(), database.bold(), log.message)
+                        }
+                        LogLevel::Warn => {
+                            eprintln!("  {} {} {}", "!".yellow(), database.bold(), log.message)
+                        }
+                        LogLevel::Error => {
+                            eprintln!("  {} {} {}", "✗".red(), database.bold(), log.message)
+                        }
+                    }
+                });
+            }
+        });
+
+        let result = conn
+            .client()
+            .migrate_fleet(
+                FleetMigrateRequest {
+                    targets: fleet
+                        .databases
+                        .iter()
+                        .map(|url| FleetTargetInfo {
+                            name: mask_password(url),
+                            database_url: url.clone(),
+                            schema: None,
+                        })
+                        .collect(),
+                    discovery,
+                    concurrency: fleet.concurrency.unwrap_or(4),
+                    halt_on_failure: fleet.halt_on_failure,
+                    migration: to,
+                    strict,
+                },
+                log_tx,
+            )
+            .await;
+        let _ = log_printer.await;
+
+        let res = match result {
+            Ok(res) => res,
+            Err(e) => {
+                eprintln!("{} Fleet migration failed: {:?}", "✗".red(), e);
+                std::process::exit(1);
+            }
+        };
+
+        println!();
+        println!(
+            "{}",
+            format!("━━━ Fleet ({} databases) ━━━", res.databases.len()).bold()
+        );
+        println!();
+        for db in &res.databases {
+            match db.status {
+                FleetStatus::Migrated => println!(
+                    "{}  {} {}",
+                    "✓".green(),
+                    db.name,
+                    format!("{} applied, {}ms", db.applied.len(), db.duration_ms).dimmed()
+                ),
+                FleetStatus::Failed => println!(
+                    "{}  {} {}",
+                    "✗".red(),
+                    db.name,
+                    db.error
+                        .as_ref()
+                        .map(|e| e.message.as_str())
+                        .unwrap_or("failed")
+                        .red()
+                ),
+                FleetStatus::Skipped => {
+                    println!("{}  {} {}", "○".dimmed(), db.name, "skipped".dimmed())
+                }
+            }
+        }
+        let count = |status| res.databases.iter().filter(|d| d.status == status).count();
+        let failed = count(FleetStatus::Failed);
+        println!();
+        println!(
+            "{}",
+            format!(
+                "Migrated: {} │ Failed: {} │ Skipped: {} │ Total: {}ms",
+                count(FleetStatus::Migrated),
+                failed,
+                count(FleetStatus::Skipped),
+                res.total_time_ms
+            )
+            .dimmed()
+        );
+        println!();
+        if failed > 0 {
+            std::process::exit(1);
+        }
+    });
+}
+
 fn print_migration_summary(res: &dibs_proto::MigrateResult) {
     use owo_colors::OwoColorize as _;
 
@@ -1215,10 +1512,15 @@ fn print_migration_summary(res: &dibs_proto::MigrateResult) {
 
         for m in &res.applied {
             println!(
-                "   {} {} {}",
+                "   {} {} {}{}",
                 "│".dimmed(),
                 m.version.as_str().cyan(),
-                format!("({}ms)", m.duration_ms).dimmed()
+                format!("({}ms)", m.duration_ms).dimmed(),
+                if m.repeatable {
+                    " repeatable".dimmed().to_string()
+                } else {
+                    String::new()
+                }
             );
         }
         println!();
@@ -1280,16 +1582,57 @@ fn run_status(config: &Config) {
                     println!("Migration status:");
                     println!();
                     for m in &migrations {
+                        if m.repeatable {
+                            let (status, state) = match (m.applied, &m.applied_at) {
+                                (true, _) => ("↻".green().to_string(), "up to date".to_string()),
+                                (false, Some(_)) => {
+                                    ("↻".yellow().to_string(), "changed, will re-run".to_string())
+                                }
+                                (false, None) => {
+                                    ("↻".yellow().to_string(), "never run".to_string())
+                                }
+                            };
+                            println!(
+                                "  {} {} - {}",
+                                status,
+                                m.version,
+                                format!("repeatable, {state}").dimmed()
+                            );
+                            continue;
+                        }
+                        if m.missing {
+                            println!(
+                                "  {} {} - {}",
+                                "?".red(),
+                                m.version,
+                                "applied, but missing from the code".red()
+                            );
+                            continue;
+                        }
                         let status = if m.applied {
                             "✓".green().to_string()
                         } else {
                             "○".yellow().to_string()
                         };
-                        println!("  {} {} - {}", status, m.version, m.name);
+                        if m.out_of_order {
+                            println!(
+                                "  {} {} - {} {}",
+                                status,
+                                m.version,
+                                m.name,
+                                "(older than the newest applied migration)".red()
+                            );
+                        } else {
+                            println!("  {} {} - {}", status, m.version, m.name);
+                        }
                     }
                     println!();
-                    let applied = migrations.iter().filter(|m| m.applied).count();
-                    let pending = migrations.len() - applied;
+                    let versioned = migrations.iter().filter(|m| !m.repeatable);
+                    let applied = versioned
+                        .clone()
+                        .filter(|m| m.applied && !m.missing)
+                        .count();
+                    let pending = versioned.filter(|m| !m.applied).count();
                     println!(
                         "{} applied, {} pending",
                         applied.to_string().green(),
@@ -1510,7 +1853,7 @@ async fn run_generate_from_diff_via_vox(config: &Config, name: &str) {
                 Ok(path) => {
                     println!("{}", "Migration created successfully!".green());
                     println!();
-                    println!("File: {}", path);
+                    println!("File: {}", path.display());
                 }
                 Err(e) => {
                     eprintln!("Failed to create migration file: {}", e);
@@ -1525,14 +1868,427 @@ async fn run_generate_from_diff_via_vox(config: &Config, name: &str) {
     }
 }
 
+fn run_import(config: &Config, output: Option<&str>) {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+    rt.block_on(run_import_via_vox(config, output));
+}
+
+async fn run_import_via_vox(config: &Config, output: Option<&str>) {
+    use dibs_proto::{BaselineRequest, DiffRequest};
+    #[allow(unused_imports)]
+    use owo_colors::OwoColorize as _;
+    use tracing::info;
+
+    let database_url = config.require_database_url();
+    info!(database_url = %mask_password(database_url), "Importing schema from database");
+
+    // Connect to the db crate via vox
+    let conn = match service::connect_to_service(&config.db).await {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("Failed to connect to db service: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let client = conn.client();
+
+    let import = match client
+        .import_schema(DiffRequest {
+            database_url: database_url.to_string(),
+        })
+        .await
+    {
+        Ok(import) => import,
+        Err(e) => {
+            eprintln!("Failed to import schema: {:?}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if import.table_count == 0 {
+        println!("No tables found in the database - nothing to import.");
+        return;
+    }
+
+    // Write the table definitions into the db crate
+    let crate_dir = config
+        .db
+        .crate_name
+        .as_ref()
+        .and_then(|crate_name| config::find_crate_path(crate_name))
+        .unwrap_or_else(|| std::path::PathBuf::from("."));
+    let output = output.unwrap_or("src/tables.rs");
+    let tables_path = crate_dir.join(output);
+
+    if tables_path.exists() {
+        eprintln!("Refusing to overwrite {}", tables_path.display());
+        std::process::exit(1);
+    }
+    if let Some(parent) = tables_path.parent()
+        && let Err(e) = fs::create_dir_all(parent)
+    {
+        eprintln!("Failed to create {}: {}", parent.display(), e);
+        std::process::exit(1);
+    }
+    if let Err(e) = fs::write(&tables_path, &import.rust) {
+        eprintln!("Failed to write {}: {}", tables_path.display(), e);
+        std::process::exit(1);
+    }
+
+    // The baseline migration recreates the schema on fresh databases, and is
+    // recorded as applied on this one, which already has it
+    let migration_path = match create_migration_file_from_sql(&config.db, "baseline", &import.sql) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Failed to create baseline migration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let file_name = migration_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let version = dibs::__derive_migration_version(file_name);
+
+    if let Err(e) = client
+        .baseline(BaselineRequest {
+            database_url: database_url.to_string(),
+            version: version.to_string(),
+        })
+        .await
+    {
+        eprintln!("Failed to record baseline migration {}: {:?}", version, e);
+        std::process::exit(1);
+    }
+
+    println!(
+        "{}",
+        format!("Imported {} tables.", import.table_count).green()
+    );
+    println!();
+    println!("Tables:    {}", tables_path.display());
+    println!(
+        "Baseline:  {} (recorded as applied)",
+        migration_path.display()
+    );
+    println!();
+    println!("Don't forget to add the module to your db crate's lib.rs:");
+    if let Some(module) = tables_path.file_stem().and_then(|stem| stem.to_str()) {
+        println!("  mod {};", module);
+    }
+}
+
+fn run_squash(config: &Config, up_to: &str) {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+    rt.block_on(run_squash_via_vox(config, up_to));
+}
+
+async fn run_squash_via_vox(config: &Config, up_to: &str) {
+    use dibs_proto::{BaselineRequest, SquashRequest};
+    #[allow(unused_imports)]
+    use owo_colors::OwoColorize as _;
+    use tracing::info;
+
+    let database_url = config.require_database_url();
+    info!(database_url = %mask_password(database_url), up_to, "Squashing migrations");
+
+    // Connect to the db crate via vox
+    let conn = match service::connect_to_service(&config.db).await {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("Failed to connect to db service: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let client = conn.client();
+
+    let squash = match client
+        .squash(SquashRequest {
+            database_url: database_url.to_string(),
+            up_to: up_to.to_string(),
+        })
+        .await
+    {
+        Ok(squash) => squash,
+        Err(e) => {
+            eprintln!("Failed to squash migrations: {:?}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let migrations_dir = migrations_dir(&config.db);
+    let baseline_path = match create_baseline_migration_file(&migrations_dir, &squash) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Failed to create baseline migration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Remove the squashed migrations; only files inside the migrations
+    // directory are touched
+    let mut removed = Vec::new();
+    for migration in &squash.squashed {
+        let Some(path) = migration.source_file.as_deref().map(std::path::Path::new) else {
+            continue;
+        };
+        let in_migrations_dir = path
+            .parent()
+            .and_then(|p| p.canonicalize().ok())
+            .zip(migrations_dir.canonicalize().ok())
+            .is_some_and(|(parent, dir)| parent == dir);
+        if !in_migrations_dir {
+            eprintln!(
+                "Skipping {} ({}): not in {}",
+                migration.version,
+                path.display(),
+                migrations_dir.display()
+            );
+            continue;
+        }
+        if let Err(e) = fs::remove_file(path) {
+            eprintln!("Failed to remove {}: {}", path.display(), e);
+            continue;
+        }
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            removed.push(stem.to_string());
+        }
+    }
+    let removed_modules: Vec<&str> = removed.iter().map(String::as_str).collect();
+    if let Err(e) = remove_migration_modules(&migrations_dir, &removed_modules) {
+        eprintln!("Failed to update migrations/mod.rs: {}", e);
+        std::process::exit(1);
+    }
+
+    println!(
+        "{}",
+        format!("Squashed {} migrations.", squash.squashed.len()).green()
+    );
+    println!();
+    println!("Baseline: {}", baseline_path.display());
+    println!("Removed:  {} migration files", removed.len());
+
+    // This database already has the squashed schema: record the baseline so
+    // it's never run here. Other databases get it recorded by `dibs migrate`.
+    if squash.squashed.iter().all(|m| m.applied) {
+        if let Err(e) = client
+            .baseline(BaselineRequest {
+                database_url: database_url.to_string(),
+                version: squash.baseline_version.clone(),
+            })
+            .await
+        {
+            eprintln!(
+                "Failed to record baseline {}: {:?}",
+                squash.baseline_version, e
+            );
+            std::process::exit(1);
+        }
+        println!(
+            "Recorded {} as applied in the database.",
+            squash.baseline_version
+        );
+    } else {
+        println!(
+            "{}",
+            "The database hasn't applied every squashed migration; migrate it with the previous build before deploying this one."
+                .yellow()
+        );
+    }
+}
+
+fn run_seed(config: &Config, env: &str) {
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+    rt.block_on(run_seed_via_vox(config, env));
+}
+
+async fn run_seed_via_vox(config: &Config, env: &str) {
+    use dibs_proto::{SeedRequest, SeedSource};
+    #[allow(unused_imports)]
+    use owo_colors::OwoColorize as _;
+    use tracing::info;
+
+    let database_url = config.require_database_url();
+    let seeds_dir = seeds_dir(&config.db);
+    info!(database_url = %mask_password(database_url), env, "Seeding database");
+
+    let files = match read_seed_files(&seeds_dir) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", seeds_dir.display(), e);
+            std::process::exit(1);
+        }
+    };
+    if files.is_empty() {
+        println!("No seed files in {}", seeds_dir.display());
+        return;
+    }
+
+    // Connect to the db crate via vox
+    let conn = match service::connect_to_service(&config.db).await {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("Failed to connect to db service: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let seeded = match conn
+        .client()
+        .seed(SeedRequest {
+            database_url: database_url.to_string(),
+            env: env.to_string(),
+            files: files
+                .into_iter()
+                .map(|(path, content)| SeedSource { path, content })
+                .collect(),
+        })
+        .await
+    {
+        Ok(seeded) => seeded,
+        Err(vox::VoxError::User(ref e))
+            if let dibs_proto::DibsError::InvalidRequest(problems) = e.as_ref() =>
+        {
+            eprintln!("{}", "Invalid seed data:".red());
+            for problem in problems.lines() {
+                eprintln!("  {}", problem);
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to seed database: {:?}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if seeded.is_empty() {
+        println!("No seed data for the {} environment.", env);
+        return;
+    }
+    for table in &seeded {
+        println!(
+            "  {} {} ({}): {} inserted, {} updated, {} unchanged",
+            "✓".green(),
+            table.table,
+            table.file,
+            table.inserted,
+            table.updated,
+            table.unchanged
+        );
+    }
+    println!();
+    println!(
+        "{}",
+        format!("Seeded {} tables for {}.", seeded.len(), env).green()
+    );
+}
+
+/// Read the `.styx` files in a seeds directory, sorted by name.
+///
+/// Paths are relative to the directory; a missing directory has no seeds.
+fn read_seed_files(seeds_dir: &std::path::Path) -> io::Result<Vec<(String, String)>> {
+    if !seeds_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<std::path::PathBuf> = fs::read_dir(seeds_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "styx"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path)?;
+            let name = path
+                .strip_prefix(seeds_dir)
+                .unwrap_or(&path)
+                .display()
+                .to_string();
+            Ok((name, content))
+        })
+        .collect()
+}
+
+/// Write the baseline migration for a squash, and declare it in mod.rs.
+fn create_baseline_migration_file(
+    migrations_dir: &std::path::Path,
+    squash: &dibs_proto::SquashResult,
+) -> Result<std::path::PathBuf, std::io::Error> {
+    let now = Zoned::now();
+
+    let mut module: String = squash
+        .baseline_version
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !module.starts_with('m') {
+        module.insert(0, 'm');
+    }
+
+    if !migrations_dir.exists() {
+        fs::create_dir_all(migrations_dir)?;
+    }
+    let filepath = migrations_dir.join(format!("{}.rs", module));
+    if filepath.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("Migration file already exists: {}", filepath.display()),
+        ));
+    }
+
+    let squashes: String = squash
+        .squashed
+        .iter()
+        .map(|m| format!("    {:?},\n", m.version))
+        .collect();
+    let content = format!(
+        r#"//! Migration: squashed baseline
+//! Created: {created}
+//!
+//! Replaces the {count} migrations up to and including `{up_to}`. Databases
+//! that applied them record this baseline without running it.
+
+use dibs::{{MigrationContext, MigrationResult}};
+
+#[dibs::migration({version:?}, squashes = [
+{squashes}])]
+pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
+{sql_calls}
+    Ok(())
+}}
+"#,
+        created = now.strftime("%Y-%m-%d %H:%M:%S %Z"),
+        count = squash.squashed.len(),
+        up_to = squash
+            .squashed
+            .last()
+            .map(|m| m.version.as_str())
+            .unwrap_or_default(),
+        version = squash.baseline_version,
+        squashes = squashes,
+        sql_calls = parse_sql_to_calls(&squash.sql),
+    );
+
+    fs::write(&filepath, content)?;
+    add_migration_module(migrations_dir, &module)?;
+
+    Ok(filepath)
+}
+
 fn create_migration_file_from_sql(
     db_config: &DbConfig,
     name: &str,
     sql: &str,
-) -> Result<String, std::io::Error> {
-    use std::fs;
-    use std::io::Write;
-
+) -> Result<std::path::PathBuf, std::io::Error> {
     let now = Zoned::now();
     let timestamp = now.strftime("%Y_%m_%d_%H%M%S");
 
@@ -1540,12 +2296,7 @@ fn create_migration_file_from_sql(
     let module_name = name.replace('-', "_").to_lowercase();
 
     // Find migrations directory from db crate
-    let migrations_dir = db_config
-        .crate_name
-        .as_ref()
-        .and_then(|crate_name| config::find_crate_path(crate_name))
-        .map(|p| p.join("src/migrations"))
-        .unwrap_or_else(|| std::path::PathBuf::from("src/migrations"));
+    let migrations_dir = migrations_dir(db_config);
 
     if !migrations_dir.exists() {
         fs::create_dir_all(&migrations_dir)?;
@@ -1586,9 +2337,34 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
     let mut file = fs::File::create(&filepath)?;
     file.write_all(content.as_bytes())?;
 
-    // Add to mod.rs
+    add_migration_module(&migrations_dir, &format!("m{}_{}", timestamp, module_name))?;
+
+    Ok(filepath)
+}
+
+/// Locate the db crate's migrations directory.
+fn migrations_dir(db_config: &DbConfig) -> std::path::PathBuf {
+    db_config
+        .crate_name
+        .as_ref()
+        .and_then(|crate_name| config::find_crate_path(crate_name))
+        .map(|p| p.join("src/migrations"))
+        .unwrap_or_else(|| std::path::PathBuf::from("src/migrations"))
+}
+
+fn seeds_dir(db_config: &DbConfig) -> std::path::PathBuf {
+    db_config
+        .crate_name
+        .as_ref()
+        .and_then(|crate_name| config::find_crate_path(crate_name))
+        .map(|p| p.join("seeds"))
+        .unwrap_or_else(|| std::path::PathBuf::from("seeds"))
+}
+
+/// Declare a migration module in the migrations directory's mod.rs.
+fn add_migration_module(migrations_dir: &std::path::Path, module: &str) -> io::Result<()> {
     let mod_rs_path = migrations_dir.join("mod.rs");
-    let module_line = format!("mod m{}_{};", timestamp, module_name);
+    let module_line = format!("mod {};", module);
 
     if mod_rs_path.exists() {
         // Read existing mod.rs and append
@@ -1603,7 +2379,28 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
         mod_file.write_all(format!("//! Database migrations.\n\n{}\n", module_line).as_bytes())?;
     }
 
-    Ok(filepath.display().to_string())
+    Ok(())
+}
+
+/// Remove the declarations of migration modules from the migrations
+/// directory's mod.rs.
+fn remove_migration_modules(migrations_dir: &std::path::Path, modules: &[&str]) -> io::Result<()> {
+    let mod_rs_path = migrations_dir.join("mod.rs");
+    if !mod_rs_path.exists() {
+        return Ok(());
+    }
+
+    let existing = fs::read_to_string(&mod_rs_path)?;
+    let kept: Vec<&str> = existing
+        .lines()
+        .filter(|line| {
+            let line = line.trim();
+            !modules.iter().any(|module| {
+                line == format!("mod {};", module) || line == format!("pub mod {};", module)
+            })
+        })
+        .collect();
+    fs::write(&mod_rs_path, kept.join("\n") + "\n")
 }
 
 /// Parse SQL into migration function calls.
@@ -1613,12 +2410,12 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
 fn parse_sql_to_calls(sql: &str) -> String {
     let mut result = String::new();
 
-    for chunk in split_sql_into_chunks(sql) {
+    for chunk in dibs::split_sql_into_chunks(sql) {
         match chunk {
-            SqlChunk::Comment(comment) => {
+            dibs::SqlChunk::Comment(comment) => {
                 result.push_str(&format!("    // {}\n", comment));
             }
-            SqlChunk::Statement(stmt) => {
+            dibs::SqlChunk::Statement(stmt) => {
                 if !stmt.trim().is_empty() {
                     result.push_str(&format_sql_call(&stmt));
                 }
@@ -1629,133 +2426,6 @@ fn parse_sql_to_calls(sql: &str) -> String {
     result
 }
 
-enum SqlChunk {
-    Comment(String),
-    Statement(String),
-}
-
-/// Split a SQL script into statements and comment lines.
-///
-/// This is *not* a full SQL parser, but it does correctly avoid splitting on
-/// semicolons inside:
-/// - single-quoted strings: `'...'`
-/// - double-quoted identifiers: `"identifier"`
-/// - dollar-quoted strings: `$$ ... $$` or `$tag$ ... $tag$`
-fn split_sql_into_chunks(sql: &str) -> Vec<SqlChunk> {
-    let mut out = Vec::new();
-    let mut current = String::new();
-
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut dollar_quote_tag: Option<String> = None;
-
-    let mut i = 0usize;
-    while i < sql.len() {
-        // Handle exiting a dollar-quoted section.
-        if let Some(tag) = dollar_quote_tag.as_deref()
-            && sql[i..].starts_with(tag)
-        {
-            current.push_str(tag);
-            i += tag.len();
-            dollar_quote_tag = None;
-            continue;
-        }
-
-        let ch = sql[i..]
-            .chars()
-            .next()
-            .expect("i is always a char boundary");
-        let ch_len = ch.len_utf8();
-
-        // SQL line comments: -- ...\n
-        if !in_single_quote
-            && !in_double_quote
-            && dollar_quote_tag.is_none()
-            && ch == '-'
-            && sql.as_bytes().get(i + 1) == Some(&b'-')
-        {
-            // Flush any pending statement first.
-            if !current.trim().is_empty() {
-                out.push(SqlChunk::Statement(std::mem::take(&mut current)));
-            } else {
-                current.clear();
-            }
-
-            let rest = &sql[i..];
-            let end = rest.find('\n').map(|pos| i + pos).unwrap_or(sql.len());
-            let line = sql[i..end].trim_start_matches("--").trim().to_string();
-            out.push(SqlChunk::Comment(line));
-
-            i = if end < sql.len() { end + 1 } else { end };
-            continue;
-        }
-
-        // Entering a dollar-quoted section: $$ ... $$ or $tag$ ... $tag$
-        if !in_single_quote && !in_double_quote && dollar_quote_tag.is_none() && ch == '$' {
-            let rest = &sql[i + 1..];
-            if let Some(end_rel) = rest.find('$') {
-                let tag_body = &rest[..end_rel];
-                if tag_body
-                    .bytes()
-                    .all(|b| b.is_ascii_alphanumeric() || b == b'_')
-                {
-                    let tag_end = i + 1 + end_rel + 1;
-                    let tag = sql[i..tag_end].to_string();
-                    current.push_str(&tag);
-                    i = tag_end;
-                    dollar_quote_tag = Some(tag);
-                    continue;
-                }
-            }
-        }
-
-        // Single-quoted string handling (including '' escaping)
-        if dollar_quote_tag.is_none() && !in_double_quote && ch == '\'' {
-            if in_single_quote && sql.as_bytes().get(i + 1) == Some(&b'\'') {
-                // Escaped single quote: ''
-                current.push_str("''");
-                i += 2;
-                continue;
-            }
-            in_single_quote = !in_single_quote;
-            current.push('\'');
-            i += ch_len;
-            continue;
-        }
-
-        // Double-quoted identifier handling (including "" escaping)
-        if dollar_quote_tag.is_none() && !in_single_quote && ch == '"' {
-            if in_double_quote && sql.as_bytes().get(i + 1) == Some(&b'"') {
-                // Escaped double quote: ""
-                current.push_str("\"\"");
-                i += 2;
-                continue;
-            }
-            in_double_quote = !in_double_quote;
-            current.push('"');
-            i += ch_len;
-            continue;
-        }
-
-        // Statement terminator (only when not inside any quoted context)
-        if !in_single_quote && !in_double_quote && dollar_quote_tag.is_none() && ch == ';' {
-            current.push(';');
-            out.push(SqlChunk::Statement(std::mem::take(&mut current)));
-            i += ch_len;
-            continue;
-        }
-
-        current.push_str(&sql[i..i + ch_len]);
-        i += ch_len;
-    }
-
-    if !current.trim().is_empty() {
-        out.push(SqlChunk::Statement(current));
-    }
-
-    out
-}
-
 /// Format a single SQL statement as a ctx.execute() call.
 fn format_sql_call(sql: &str) -> String {
     let trimmed = sql.trim().trim_end_matches(';');
@@ -801,6 +801,7 @@ impl App {
                     MigrateRequest {
                         database_url: url,
                         migration: None,
+                        strict: false,
                     },
                     log_tx,
                 )
@@ -1234,7 +1235,22 @@ impl App {
                         if self.tab == Tab::Postgres
                             && self.postgres_mode == PostgresMode::HasPending
                         {
-                            rt.block_on(self.run_migrations());
+                            rt.block_on(self.run_migrations(None));
+                        }
+                    }
+                    KeyCode::Char('a') if !self.show_migration_source => {
+                        // Apply pending migrations up to the selected one
+                        if self.tab == Tab::Postgres
+                            && self.postgres_mode == PostgresMode::HasPending
+                            && let PostgresSelection::Migration(idx) = self.postgres_selection
+                            && let Some(migration) = self
+                                .migrations
+                                .as_ref()
+                                .and_then(|m| m.get(idx))
+                                .filter(|m| !m.applied && !m.repeatable)
+                        {
+                            let target = migration.version.clone();
+                            rt.block_on(self.run_migrations(Some(target)));
                         }
                     }
                     KeyCode::Char('d') if !self.show_migration_source => {
@@ -1697,6 +1713,11 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
             return;
         };
 
+        if migration.repeatable {
+            self.error = Some("Cannot delete repeatable migration".to_string());
+            return;
+        }
+
         // Can't delete applied migrations
         if migration.applied {
             self.error = Some("Cannot delete applied migration".to_string());
@@ -1819,7 +1840,8 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
         Ok(())
     }
 
-    async fn run_migrations(&mut self) {
+    /// Run pending migrations, or only those up to and including `target`.
+    async fn run_migrations(&mut self, target: Option<String>) {
         if let (Some(conn), Some(url)) = (&self.conn, &self.database_url) {
             use dibs_proto::MigrateRequest;
 
@@ -1836,7 +1858,8 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
                 .migrate(
                     MigrateRequest {
                         database_url: url,
-                        migration: None,
+                        migration: target,
+                        strict: false,
                     },
                     log_tx,
                 )
@@ -2695,8 +2718,18 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
             .iter()
             .enumerate()
             .map(|(idx, m)| {
-                let status = if m.applied { "✓" } else { "○" };
-                let status_style = if m.applied {
+                let status = if m.missing {
+                    "?"
+                } else if m.repeatable {
+                    "↻"
+                } else if m.applied {
+                    "✓"
+                } else {
+                    "○"
+                };
+                let status_style = if m.missing || m.out_of_order {
+                    Style::default().fg(Color::Red)
+                } else if m.applied {
                     Style::default().fg(Color::Green)
                 } else {
                     Style::default().fg(Color::Yellow)
@@ -2713,7 +2746,11 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
                     Span::styled(" ", highlight),
                     Span::styled(&m.version, highlight),
                     Span::styled(" ", highlight),
-                    Span::styled(&m.name, highlight),
+                    if m.repeatable {
+                        Span::styled("(repeatable)", highlight.fg(Color::DarkGray))
+                    } else {
+                        Span::styled(&m.name, highlight)
+                    },
                 ]))
             })
             .collect();
@@ -2787,7 +2824,15 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
                     " m ",
                     Style::default().fg(Color::Black).bg(Color::Yellow).bold(),
                 ),
-                Span::styled(" to apply all", Style::default().fg(Color::White)),
+                Span::styled(" to apply all, ", Style::default().fg(Color::White)),
+                Span::styled(
+                    " a ",
+                    Style::default().fg(Color::Black).bg(Color::Yellow).bold(),
+                ),
+                Span::styled(
+                    " to apply up to the selected one",
+                    Style::default().fg(Color::White),
+                ),
             ]),
             Line::from(""),
             Line::from(Span::styled(
@@ -2807,11 +2852,16 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
             Style::default().fg(Color::Yellow),
         )));
         for m in migrations.iter().filter(|m| !m.applied) {
+            let (icon, name) = if m.repeatable {
+                ("    ↻ ", "repeatable, changed")
+            } else {
+                ("    ○ ", m.name.as_str())
+            };
             lines.push(Line::from(vec![
-                Span::styled("    ○ ", Style::default().fg(Color::Yellow)),
+                Span::styled(icon, Style::default().fg(Color::Yellow)),
                 Span::raw(&m.version),
                 Span::styled(" - ", Style::default().fg(Color::DarkGray)),
-                Span::raw(&m.name),
+                Span::raw(name),
             ]));
         }
 
@@ -2979,6 +3029,10 @@ pub async fn migrate(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {{
                 if self.postgres_mode == PostgresMode::HasPending {
                     spans.push(Span::styled("m ", Style::default().fg(Color::Yellow)));
                     spans.push(Span::raw("apply all  "));
+                    if let PostgresSelection::Migration(_) = self.postgres_selection {
+                        spans.push(Span::styled("a ", Style::default().fg(Color::Yellow)));
+                        spans.push(Span::raw("apply to here  "));
+                    }
                 } else {
                     spans.push(Span::styled("g ", Style::default().fg(Color::Yellow)));
                     spans.push(Span::raw("generate  "));
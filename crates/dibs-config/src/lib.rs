@@ -12,6 +12,10 @@ pub struct Config {
     /// Database crate configuration.
     #[facet(default)]
     pub db: DbConfig,
+
+    /// Databases migrated together by `dibs migrate --fleet`.
+    #[facet(default)]
+    pub fleet: FleetConfig,
 }
 
 /// Database crate configuration.
@@ -28,3 +32,24 @@ pub struct DbConfig {
     /// endpoint, normally through a development-only process mode.
     pub endpoint: Option<String>,
 }
+
+/// Databases migrated together, e.g. one per tenant.
+#[derive(Debug, Clone, Facet, Default)]
+pub struct FleetConfig {
+    /// Connection URLs of the databases to migrate.
+    #[facet(default)]
+    pub databases: Vec<String>,
+
+    /// Query run against `database_url` listing more databases to migrate.
+    ///
+    /// It returns a `database_url` column, or a `schema` column for
+    /// schema-per-tenant setups, plus an optional `name` for logs.
+    pub discover: Option<String>,
+
+    /// How many databases to migrate at a time (defaults to 4).
+    pub concurrency: Option<u32>,
+
+    /// Start no new databases once one fails, instead of carrying on.
+    #[facet(default)]
+    pub halt_on_failure: bool,
+}
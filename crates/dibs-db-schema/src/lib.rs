@@ -3,7 +3,10 @@
 //! This crate contains the core schema types that are shared between
 //! `dibs` (schema introspection) and `dibs-qgen` (query planning).
 
-use dibs_sql::{check_constraint_name, index_name, trigger_check_name, unique_index_name};
+use dibs_sql::{
+    check_constraint_name, exclusion_constraint_name, exclusion_def_hash, index_name,
+    policy_expr_hash, trigger_check_name, unique_index_name,
+};
 use facet::{Facet, Shape, Type, UserType};
 use indexmap::IndexMap;
 use std::fmt;
@@ -89,6 +92,82 @@ facet::define_attr_grammar! {
         /// - `#[facet(dibs::trigger_check(name = "trg_my_check", expr = "NEW.foo IS NULL OR EXISTS (...)"))]`
         TriggerCheck(TriggerCheck),
 
+        /// Declares an exclusion constraint (container-level).
+        ///
+        /// Usage:
+        /// - `#[facet(dibs::exclude(elements = "room_id WITH =, during WITH &&"))]`
+        /// - `#[facet(dibs::exclude(name = "no_overlap", using = "gist", elements = "...", filter = "NOT cancelled", deferrable = true))]`
+        Exclude(Exclude),
+
+        /// Makes a foreign key `DEFERRABLE INITIALLY DEFERRED`, so it's only
+        /// checked at commit.
+        ///
+        /// Usage: `#[facet(dibs::fk = "user.id", dibs::deferrable)]`
+        Deferrable,
+
+        /// Records every change to the table's rows in a generated
+        /// `<table>_history` table (container-level). The table needs a
+        /// single-column primary key.
+        ///
+        /// Usage: `#[facet(dibs::audited)]`
+        Audited,
+
+        /// Enables row-level security on the table (container-level).
+        ///
+        /// Usage: `#[facet(dibs::rls)]` or `#[facet(dibs::rls = true)]`
+        Rls(Option<bool>),
+
+        /// Declares a row-level security policy (container-level).
+        ///
+        /// Usage:
+        /// - `#[facet(dibs::policy(name = "tenant_isolation", using = "tenant_id = current_setting('app.tenant')::bigint"))]`
+        /// - `#[facet(dibs::policy(name = "own_rows", command = "update", roles = "app_user", using = "...", with_check = "..."))]`
+        Policy(Policy),
+
+        /// Marks the table as soft-deleted through a timestamp column
+        /// (container-level). Generated selects skip rows where it is set, and
+        /// `@delete` sets it instead of removing the row.
+        ///
+        /// Usage: `#[facet(dibs::soft_delete = "deleted_at")]`
+        SoftDelete(&'static str),
+
+        /// Creates a trigger backed by a dibs-managed trigger function (container-level).
+        ///
+        /// Usage:
+        /// - `#[facet(dibs::trigger(name = "trg_touch", events = "update", function_body = "BEGIN NEW.updated_at = now(); RETURN NEW; END;"))]`
+        /// - `#[facet(dibs::trigger(name = "trg_audit", timing = "after", events = "insert, update, delete", function_body = "..."))]`
+        Trigger(Trigger),
+
+        /// Declares the table as partitioned (container-level).
+        ///
+        /// Usage: `#[facet(dibs::partition_by(kind = "range", columns = "created_at"))]`
+        PartitionBy(PartitionBy),
+
+        /// Declares a newtype struct as a Postgres `DOMAIN` over its inner type
+        /// (container-level). Table fields of that type use the domain.
+        ///
+        /// Usage: `#[facet(dibs::domain(name = "email", check = "VALUE ~ '@'"))]`
+        Domain(Domain),
+
+        /// Declares a struct as a Postgres composite type with one attribute
+        /// per field (container-level). Table fields of that type use it.
+        ///
+        /// Usage: `#[facet(dibs::composite(name = "address"))]`
+        Composite(Composite),
+
+        /// Marks a timestamp field as the row's creation time: it defaults to
+        /// `now()` and generated inserts fill it in.
+        ///
+        /// Usage: `#[facet(dibs::created_at)]`
+        CreatedAt,
+
+        /// Marks a timestamp field as the row's last modification time: it
+        /// defaults to `now()`, a `BEFORE UPDATE` trigger maintains it, and
+        /// generated inserts, updates and upserts fill it in.
+        ///
+        /// Usage: `#[facet(dibs::updated_at)]`
+        UpdatedAt,
+
         /// Marks a field as auto-generated (e.g., SERIAL, sequences).
         ///
         /// Usage: `#[facet(dibs::auto)]`
@@ -169,6 +248,20 @@ facet::define_attr_grammar! {
         pub expr: &'static str,
     }
 
+    /// Exclusion constraint definition.
+    pub struct Exclude {
+        /// Optional constraint name (auto-generated if not provided)
+        pub name: Option<&'static str>,
+        /// Index method (default: `gist`)
+        pub using: Option<&'static str>,
+        /// Comma-separated `element WITH operator` list
+        pub elements: &'static str,
+        /// Optional WHERE clause restricting the rows the constraint applies to
+        pub filter: Option<&'static str>,
+        /// Whether the constraint is `DEFERRABLE INITIALLY DEFERRED`
+        pub deferrable: Option<bool>,
+    }
+
     /// Trigger-enforced check definition.
     pub struct TriggerCheck {
         /// Optional trigger name (auto-generated if not provided)
@@ -180,6 +273,59 @@ facet::define_attr_grammar! {
         /// Optional error message raised when the expression evaluates to false.
         pub message: Option<&'static str>,
     }
+
+    /// Declarative partitioning definition.
+    pub struct PartitionBy {
+        /// `range`, `list` or `hash`
+        pub kind: &'static str,
+        /// Comma-separated partition key columns
+        pub columns: &'static str,
+    }
+
+    /// Composite type definition.
+    pub struct Composite {
+        /// Type name
+        pub name: &'static str,
+    }
+
+    /// Domain type definition.
+    pub struct Domain {
+        /// Domain name
+        pub name: &'static str,
+        /// Optional CHECK expression; `VALUE` refers to the value being checked
+        pub check: Option<&'static str>,
+    }
+
+    /// User-defined trigger definition.
+    pub struct Trigger {
+        /// Trigger name (unique per table)
+        pub name: &'static str,
+        /// `before` (default), `after` or `instead of`
+        pub timing: Option<&'static str>,
+        /// Comma-separated events: `insert`, `update`, `delete`, `truncate`
+        pub events: &'static str,
+        /// `row` (default) or `statement`
+        pub for_each: Option<&'static str>,
+        /// PL/pgSQL function body, e.g. `BEGIN ... RETURN NEW; END;`
+        pub function_body: &'static str,
+    }
+
+    /// Row-level security policy definition.
+    pub struct Policy {
+        /// Policy name (unique per table)
+        pub name: &'static str,
+        /// Command the policy applies to: `all` (default), `select`, `insert`, `update` or `delete`.
+        ///
+        /// This is Postgres' `FOR` clause; `for` itself is a Rust keyword,
+        /// which the attribute grammar can't take as a key.
+        pub command: Option<&'static str>,
+        /// Comma-separated role names (default: `PUBLIC`)
+        pub roles: Option<&'static str>,
+        /// SQL expression for USING(...), filtering existing rows
+        pub using: Option<&'static str>,
+        /// SQL expression for WITH CHECK(...), validating new rows
+        pub with_check: Option<&'static str>,
+    }
 }
 
 /// Postgres column types.
@@ -219,6 +365,9 @@ pub enum PgType {
     BigIntArray,
     /// INTEGER[] (array of integer)
     IntegerArray,
+    /// A composite type declared with `dibs::composite`; the column's
+    /// `domain` names it
+    Composite,
 }
 
 impl PgType {
@@ -244,6 +393,7 @@ impl PgType {
             PgType::TextArray => "Vec<String>",
             PgType::BigIntArray => "Vec<i64>",
             PgType::IntegerArray => "Vec<i32>",
+            PgType::Composite => "facet_value::Value",
         }
     }
 
@@ -273,6 +423,7 @@ impl fmt::Display for PgType {
             PgType::TextArray => write!(f, "TEXT[]"),
             PgType::BigIntArray => write!(f, "BIGINT[]"),
             PgType::IntegerArray => write!(f, "INTEGER[]"),
+            PgType::Composite => write!(f, "RECORD"),
         }
     }
 }
@@ -310,9 +461,20 @@ pub struct Column {
     pub icon: Option<String>,
     /// Semantic subtype of the column (e.g., "email", "url", "password")
     pub subtype: Option<String>,
+    /// Domain of the column, if any (`pg_type` is then the domain's base type)
+    pub domain: Option<String>,
+    /// Composite type of the column, if any (`pg_type` is then
+    /// [`PgType::Composite`])
+    pub composite: Option<String>,
 }
 
 impl Column {
+    /// The domain or composite type the column is declared with, whose name
+    /// is the column's SQL type.
+    pub fn named_type(&self) -> Option<&str> {
+        self.domain.as_deref().or(self.composite.as_deref())
+    }
+
     /// True when this column should be emitted as a `GENERATED BY DEFAULT AS
     /// IDENTITY` column in DDL.
     ///
@@ -334,6 +496,8 @@ pub struct ForeignKey {
     pub references_table: String,
     /// Referenced column(s)
     pub references_columns: Vec<String>,
+    /// `DEFERRABLE INITIALLY DEFERRED`
+    pub deferrable: bool,
 }
 
 /// Sort order for index columns.
@@ -379,6 +543,48 @@ impl NullsOrder {
     }
 }
 
+/// Partitioning strategy of a partitioned table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionKind {
+    Range,
+    List,
+    Hash,
+}
+
+impl PartitionKind {
+    /// Parse a partitioning strategy name (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "range" => Some(PartitionKind::Range),
+            "list" => Some(PartitionKind::List),
+            "hash" => Some(PartitionKind::Hash),
+            _ => None,
+        }
+    }
+
+    /// Returns the SQL keyword for this strategy.
+    pub fn to_sql(&self) -> &'static str {
+        match self {
+            PartitionKind::Range => "RANGE",
+            PartitionKind::List => "LIST",
+            PartitionKind::Hash => "HASH",
+        }
+    }
+}
+
+/// The partition key of a partitioned table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionSpec {
+    pub kind: PartitionKind,
+    pub columns: Vec<String>,
+}
+
+impl fmt::Display for PartitionSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.kind.to_sql(), self.columns.join(", "))
+    }
+}
+
 /// A column in an index with optional sort order and nulls ordering.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IndexColumn {
@@ -534,6 +740,30 @@ pub struct CheckConstraint {
     pub expr: String,
 }
 
+/// A table EXCLUDE constraint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExclusionConstraint {
+    pub name: String,
+    /// Index method, e.g. `gist`
+    pub using: String,
+    /// Comma-separated `element WITH operator` list, e.g. `room_id WITH =, during WITH &&`
+    pub elements: String,
+    /// WHERE clause (without the `WHERE`), if the constraint is partial
+    pub filter: Option<String>,
+    /// `DEFERRABLE INITIALLY DEFERRED`
+    pub deferrable: bool,
+    /// Hash of the definition for explicitly named constraints, kept in the
+    /// constraint's comment (generated names end with it already)
+    pub def_hash: Option<String>,
+}
+
+impl ExclusionConstraint {
+    /// Hash of the index method, elements and WHERE clause as written.
+    pub fn declared_def_hash(&self) -> String {
+        exclusion_def_hash(&self.using, &self.elements, self.filter.as_deref())
+    }
+}
+
 /// A trigger-enforced invariant check (BEFORE INSERT OR UPDATE).
 #[derive(Debug, Clone, PartialEq)]
 pub struct TriggerCheckConstraint {
@@ -542,6 +772,131 @@ pub struct TriggerCheckConstraint {
     pub message: Option<String>,
 }
 
+/// A user-defined trigger and the body of the function it executes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableTrigger {
+    pub name: String,
+    /// `BEFORE`, `AFTER` or `INSTEAD OF`
+    pub timing: String,
+    /// Events in canonical order (`INSERT`, `UPDATE`, `DELETE`, `TRUNCATE`)
+    pub events: Vec<String>,
+    /// `ROW` or `STATEMENT`
+    pub for_each: String,
+    pub function_body: String,
+}
+
+impl TableTrigger {
+    /// Canonical order of trigger events.
+    pub const EVENTS: [&'static str; 4] = ["INSERT", "UPDATE", "DELETE", "TRUNCATE"];
+
+    /// Name of the trigger maintaining a `dibs::updated_at` column.
+    pub const UPDATED_AT: &'static str = "trg_updated_at";
+
+    /// The `BEFORE UPDATE` trigger that keeps `column` at the modification time.
+    pub fn updated_at(column: &str) -> Self {
+        Self {
+            name: Self::UPDATED_AT.to_string(),
+            timing: "BEFORE".to_string(),
+            events: vec!["UPDATE".to_string()],
+            for_each: "ROW".to_string(),
+            function_body: format!(
+                "BEGIN NEW.\"{}\" = now(); RETURN NEW; END;",
+                column.replace('"', "\"\"")
+            ),
+        }
+    }
+
+    /// Name of the trigger recording changes of a `dibs::audited` table.
+    pub const AUDIT: &'static str = "trg_audit";
+
+    /// The `AFTER` row trigger copying each change of `table` into its history table.
+    pub fn audit(table: &str, pk_column: &str) -> Self {
+        let history = Table::history_table_name(table).replace('"', "\"\"");
+        let pk = pk_column.replace('"', "\"\"");
+        Self {
+            name: Self::AUDIT.to_string(),
+            timing: "AFTER".to_string(),
+            events: vec![
+                "INSERT".to_string(),
+                "UPDATE".to_string(),
+                "DELETE".to_string(),
+            ],
+            for_each: "ROW".to_string(),
+            function_body: format!(
+                "BEGIN \
+                 INSERT INTO \"{history}\" (\"row_id\", \"operation\", \"old_row\", \"actor\") VALUES (\
+                 CASE WHEN TG_OP = 'DELETE' THEN OLD.\"{pk}\" ELSE NEW.\"{pk}\" END, \
+                 TG_OP, \
+                 CASE WHEN TG_OP = 'INSERT' THEN NULL ELSE to_jsonb(OLD) END, \
+                 current_setting('{AUDIT_ACTOR_SETTING}', true)); \
+                 RETURN NULL; \
+                 END;"
+            ),
+        }
+    }
+
+    /// Parse a comma- or `OR`-separated event list into canonical order.
+    pub fn parse_events(events: &str) -> Vec<String> {
+        let requested: Vec<String> = events
+            .split(',')
+            .flat_map(|part| part.split_whitespace())
+            .filter(|word| !word.eq_ignore_ascii_case("or"))
+            .map(|word| word.to_uppercase())
+            .collect();
+        Self::EVENTS
+            .iter()
+            .filter(|event| requested.iter().any(|r| r == *event))
+            .map(|event| event.to_string())
+            .collect()
+    }
+}
+
+/// A row-level security policy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RlsPolicy {
+    pub name: String,
+    /// `ALL`, `SELECT`, `INSERT`, `UPDATE` or `DELETE`
+    pub command: String,
+    /// Roles the policy applies to (empty means `PUBLIC`)
+    pub roles: Vec<String>,
+    pub using: Option<String>,
+    pub with_check: Option<String>,
+    /// Hash of the declared expressions (see [`RlsPolicy::declared_expr_hash`]),
+    /// stored in the policy's comment. `None` for policies dibs didn't create.
+    pub expr_hash: Option<String>,
+}
+
+impl RlsPolicy {
+    /// Hash of the USING / WITH CHECK expressions as written.
+    pub fn declared_expr_hash(&self) -> String {
+        policy_expr_hash(self.using.as_deref(), self.with_check.as_deref())
+    }
+}
+
+/// A Postgres `DOMAIN`: a base type with an optional CHECK constraint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DomainType {
+    pub name: String,
+    pub base_type: PgType,
+    /// The domain's CHECK constraint, named after a hash of its expression
+    pub check: Option<CheckConstraint>,
+}
+
+/// A Postgres composite type (`CREATE TYPE ... AS (...)`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositeType {
+    pub name: String,
+    /// Attributes in declaration order
+    pub fields: Vec<CompositeField>,
+}
+
+/// An attribute of a composite type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositeField {
+    pub name: String,
+    pub pg_type: PgType,
+}
+
 /// A database table definition.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Table {
@@ -551,8 +906,26 @@ pub struct Table {
     pub columns: Vec<Column>,
     /// CHECK constraints
     pub check_constraints: Vec<CheckConstraint>,
+    /// EXCLUDE constraints
+    pub exclusion_constraints: Vec<ExclusionConstraint>,
     /// Trigger-enforced checks
     pub trigger_checks: Vec<TriggerCheckConstraint>,
+    /// User-defined triggers
+    pub triggers: Vec<TableTrigger>,
+    /// Partition key, if this is a partitioned table
+    pub partition_by: Option<PartitionSpec>,
+    /// Whether row-level security is enabled
+    pub rls: bool,
+    /// Row-level security policies
+    pub policies: Vec<RlsPolicy>,
+    /// Timestamp column marking rows as soft-deleted, if any
+    pub soft_delete: Option<String>,
+    /// Column holding the row's creation time (`dibs::created_at`)
+    pub created_at: Option<String>,
+    /// Column holding the row's last modification time (`dibs::updated_at`)
+    pub updated_at: Option<String>,
+    /// Whether changes are recorded in a `<table>_history` table
+    pub audited: bool,
     /// Foreign keys
     pub foreign_keys: Vec<ForeignKey>,
     /// Indices
@@ -565,11 +938,123 @@ pub struct Table {
     pub icon: Option<String>,
 }
 
+/// Session setting read by audit triggers to record who made a change,
+/// e.g. `SET LOCAL dibs.actor = 'alice'`.
+pub const AUDIT_ACTOR_SETTING: &str = "dibs.actor";
+
+impl Table {
+    /// Name of the history table recording changes to an audited table.
+    pub fn history_table_name(table: &str) -> String {
+        format!("{table}_history")
+    }
+
+    /// The generated `<table>_history` table for an audited table: one row per
+    /// insert, update or delete, holding the previous row as JSONB.
+    ///
+    /// Returns `None` unless the table is audited with a single-column primary key.
+    pub fn history_table(&self) -> Option<Table> {
+        if !self.audited {
+            return None;
+        }
+        let pk = self.single_primary_key()?;
+        let name = Self::history_table_name(&self.name);
+
+        let column = |name: &str, pg_type: PgType, nullable: bool| Column {
+            name: name.to_string(),
+            pg_type,
+            rust_type: Some(pg_type.to_rust_type().to_string()),
+            nullable,
+            default: None,
+            primary_key: false,
+            unique: false,
+            auto_generated: false,
+            long: false,
+            label: false,
+            enum_variants: Vec::new(),
+            doc: None,
+            lang: None,
+            icon: None,
+            subtype: None,
+            domain: None,
+            composite: None,
+        };
+        let columns = vec![
+            Column {
+                primary_key: true,
+                auto_generated: true,
+                ..column("id", PgType::BigInt, false)
+            },
+            Column {
+                doc: Some(format!("Primary key of the changed `{}` row", self.name)),
+                ..column("row_id", pk.pg_type, false)
+            },
+            Column {
+                doc: Some("`INSERT`, `UPDATE` or `DELETE`".to_string()),
+                ..column("operation", PgType::Text, false)
+            },
+            Column {
+                doc: Some("The row before the change (NULL for inserts)".to_string()),
+                ..column("old_row", PgType::Jsonb, true)
+            },
+            Column {
+                doc: Some(format!("The `{AUDIT_ACTOR_SETTING}` setting at the time")),
+                ..column("actor", PgType::Text, true)
+            },
+            Column {
+                default: Some("now()".to_string()),
+                auto_generated: true,
+                ..column("changed_at", PgType::Timestamptz, false)
+            },
+        ];
+
+        Some(Table {
+            indices: vec![Index {
+                name: crate::index_name(&name, &["row_id"]),
+                columns: vec![IndexColumn::new("row_id")],
+                unique: false,
+                where_clause: None,
+            }],
+            name,
+            columns,
+            check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
+            foreign_keys: Vec::new(),
+            source: self.source.clone(),
+            doc: Some(format!("Change history of `{}`.", self.name)),
+            icon: Some("history".to_string()),
+        })
+    }
+
+    /// The table's primary key column, if it has exactly one.
+    pub fn single_primary_key(&self) -> Option<&Column> {
+        let mut pks = self.columns.iter().filter(|c| c.primary_key);
+        match (pks.next(), pks.next()) {
+            (Some(pk), None) => Some(pk),
+            _ => None,
+        }
+    }
+}
+
 /// A complete database schema.
 #[derive(Debug, Clone, Default)]
 pub struct Schema {
     /// Tables in the schema, indexed by name
     pub tables: IndexMap<String, Table>,
+    /// Postgres extensions the schema depends on (e.g. `pg_trgm`), sorted by name
+    pub extensions: Vec<String>,
+    /// Domain types used by the schema's columns, sorted by name
+    pub domains: Vec<DomainType>,
+    /// Composite types used by the schema's columns, sorted by name
+    pub composites: Vec<CompositeType>,
 }
 
 impl Schema {
@@ -612,19 +1097,67 @@ impl TableDef {
         shape_get_dibs_attr_str(self.shape, "table")
     }
 
+    /// Domain types used by this table's fields.
+    pub fn domains(&self) -> Vec<DomainType> {
+        let Type::User(UserType::Struct(struct_type)) = &self.shape.ty else {
+            return Vec::new();
+        };
+        struct_type
+            .fields
+            .iter()
+            .filter_map(|field| shape_to_domain(unwrap_option(field.shape.get()).0))
+            .collect()
+    }
+
+    /// Composite types used by this table's fields.
+    pub fn composites(&self) -> Vec<CompositeType> {
+        let Type::User(UserType::Struct(struct_type)) = &self.shape.ty else {
+            return Vec::new();
+        };
+        struct_type
+            .fields
+            .iter()
+            .filter_map(|field| shape_to_composite(unwrap_option(field.shape.get()).0))
+            .collect()
+    }
+
     /// Convert this definition to a Table struct.
+    ///
+    /// Invalid tables are reported on stderr and skipped; see
+    /// [`try_to_table`](Self::try_to_table).
     pub fn to_table(&self) -> Option<Table> {
-        let table_name = self.table_name()?.to_string();
+        self.try_to_table().unwrap_or_else(|problem| {
+            eprintln!("{problem}");
+            None
+        })
+    }
+
+    /// Convert this definition to a Table struct, explaining why it's invalid
+    /// if it is.
+    ///
+    /// Returns `Ok(None)` if the shape isn't a table struct at all. An
+    /// unsupported column type or partition kind is an error, and so is a
+    /// `dibs::audited` table without a single-column primary key: the
+    /// history trigger records rows by their key.
+    pub fn try_to_table(&self) -> Result<Option<Table>, String> {
+        let Some(table_name) = self.table_name() else {
+            return Ok(None);
+        };
+        let table_name = table_name.to_string();
 
         // Get the struct type to access fields
         let struct_type = match &self.shape.ty {
             Type::User(UserType::Struct(s)) => s,
-            _ => return None,
+            _ => return Ok(None),
         };
 
         let mut columns = Vec::new();
         let mut check_constraints = Vec::new();
+        let mut exclusion_constraints = Vec::new();
         let mut trigger_checks = Vec::new();
+        let mut triggers = Vec::new();
+        let mut partition_by = None;
+        let mut policies = Vec::new();
         let mut foreign_keys = Vec::new();
         let mut indices = Vec::new();
 
@@ -687,6 +1220,30 @@ impl TableDef {
                 check_constraints.push(CheckConstraint { name, expr });
             }
 
+            // Collect container-level EXCLUDE constraints
+            if attr.ns() == Some("dibs")
+                && attr.key() == "exclude"
+                && let Some(Attr::Exclude(exclude)) = attr.get_as::<Attr>()
+            {
+                let using = exclude.using.unwrap_or("gist").trim().to_lowercase();
+                let elements = unescape_rust_string_escapes(exclude.elements);
+                let filter = exclude.filter.map(unescape_rust_string_escapes);
+                let name = exclude.name.map(|s| s.to_string()).unwrap_or_else(|| {
+                    exclusion_constraint_name(&table_name, &using, &elements, filter.as_deref())
+                });
+                let def_hash = exclude
+                    .name
+                    .map(|_| exclusion_def_hash(&using, &elements, filter.as_deref()));
+                exclusion_constraints.push(ExclusionConstraint {
+                    name,
+                    using,
+                    elements,
+                    filter,
+                    deferrable: exclude.deferrable.unwrap_or(false),
+                    def_hash,
+                });
+            }
+
             // Collect container-level trigger-enforced checks
             if attr.ns() == Some("dibs")
                 && attr.key() == "trigger_check"
@@ -703,8 +1260,83 @@ impl TableDef {
                     message: trig.message.map(unescape_rust_string_escapes),
                 });
             }
+
+            // Collect container-level user-defined triggers
+            if attr.ns() == Some("dibs")
+                && attr.key() == "trigger"
+                && let Some(Attr::Trigger(trig)) = attr.get_as::<Attr>()
+            {
+                triggers.push(TableTrigger {
+                    name: trig.name.to_string(),
+                    timing: trig
+                        .timing
+                        .unwrap_or("before")
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .to_uppercase(),
+                    events: TableTrigger::parse_events(trig.events),
+                    for_each: trig.for_each.unwrap_or("row").trim().to_uppercase(),
+                    function_body: unescape_rust_string_escapes(trig.function_body)
+                        .trim()
+                        .to_string(),
+                });
+            }
+
+            // Container-level partition key
+            if attr.ns() == Some("dibs")
+                && attr.key() == "partition_by"
+                && let Some(Attr::PartitionBy(partition)) = attr.get_as::<Attr>()
+            {
+                let Some(kind) = PartitionKind::parse(partition.kind) else {
+                    return Err(format!(
+                        "dibs: unsupported partition kind '{}' in table '{}' ({})",
+                        partition.kind,
+                        table_name,
+                        self.shape.source_file.unwrap_or("<unknown>")
+                    ));
+                };
+                partition_by = Some(PartitionSpec {
+                    kind,
+                    columns: partition
+                        .columns
+                        .split(',')
+                        .map(|c| c.trim().to_string())
+                        .filter(|c| !c.is_empty())
+                        .collect(),
+                });
+            }
+
+            // Collect container-level row-level security policies
+            if attr.ns() == Some("dibs")
+                && attr.key() == "policy"
+                && let Some(Attr::Policy(policy)) = attr.get_as::<Attr>()
+            {
+                let using = policy.using.map(unescape_rust_string_escapes);
+                let with_check = policy.with_check.map(unescape_rust_string_escapes);
+                policies.push(RlsPolicy {
+                    name: policy.name.to_string(),
+                    command: policy.command.unwrap_or("all").trim().to_uppercase(),
+                    roles: policy
+                        .roles
+                        .map(|r| {
+                            r.split(',')
+                                .map(|s| s.trim().to_string())
+                                // PUBLIC is the default, so store it as "no roles"
+                                .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("public"))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    expr_hash: Some(policy_expr_hash(using.as_deref(), with_check.as_deref())),
+                    using,
+                    with_check,
+                });
+            }
         }
 
+        let mut created_at = None;
+        let mut updated_at = None;
+
         for field in struct_type.fields {
             let field_shape = field.shape.get();
 
@@ -716,18 +1348,25 @@ impl TableDef {
             // Determine if nullable (Option<T> types)
             let (inner_shape, nullable) = unwrap_option(field_shape);
 
-            // Map type to Postgres
-            let pg_type = match shape_to_pg_type(inner_shape) {
+            // Map type to Postgres (newtypes declared as domains map to their
+            // base type, composite types are referred to by name)
+            let domain = shape_to_domain(inner_shape);
+            let composite = shape_to_composite(inner_shape);
+            let pg_type = match domain
+                .as_ref()
+                .map(|d| d.base_type)
+                .or(composite.as_ref().map(|_| PgType::Composite))
+                .or_else(|| shape_to_pg_type(inner_shape))
+            {
                 Some(pg_type) => pg_type,
                 None => {
-                    eprintln!(
+                    return Err(format!(
                         "dibs: unsupported type '{}' for column '{}' in table '{}' ({})",
                         inner_shape,
                         field.name,
                         table_name,
                         self.shape.source_file.unwrap_or("<unknown>")
-                    );
-                    return None;
+                    ));
                 }
             };
 
@@ -737,15 +1376,21 @@ impl TableDef {
             // Check for unique
             let unique = field_has_dibs_attr(field, "unique");
 
-            // Check for default
-            let default = field_get_dibs_attr_str(field, "default").map(|s| s.to_string());
+            // Check for default; timestamp columns default to the current time
+            let is_created_at = field_has_dibs_attr(field, "created_at");
+            let is_updated_at = field_has_dibs_attr(field, "updated_at");
+            let default = field_get_dibs_attr_str(field, "default")
+                .map(|s| s.to_string())
+                .or_else(|| (is_created_at || is_updated_at).then(|| "now()".to_string()));
+            if is_created_at {
+                created_at = Some(col_name.clone());
+            }
+            if is_updated_at {
+                updated_at = Some(col_name.clone());
+            }
 
             // Extract doc comment from field
-            let doc = if field.doc.is_empty() {
-                None
-            } else {
-                Some(field.doc.join("\n"))
-            };
+            let doc = join_doc_lines(field.doc);
 
             // Detect auto-generated columns from default or annotation
             let auto_generated =
@@ -793,6 +1438,8 @@ impl TableDef {
                 lang,
                 icon,
                 subtype,
+                domain: domain.map(|d| d.name),
+                composite: composite.map(|c| c.name),
             });
 
             // Check for foreign key
@@ -805,6 +1452,7 @@ impl TableDef {
                             columns: vec![field.name.to_string()],
                             references_table: ref_table.to_string(),
                             references_columns: vec![ref_col.to_string()],
+                            deferrable: field_has_dibs_attr(field, "deferrable"),
                         });
                     }
                     None => {
@@ -835,6 +1483,25 @@ impl TableDef {
             }
         }
 
+        if let Some(column) = &updated_at {
+            triggers.push(TableTrigger::updated_at(column));
+        }
+
+        let audited = shape_has_dibs_attr(self.shape, "audited");
+        if audited {
+            let mut pks = columns.iter().filter(|c| c.primary_key);
+            match (pks.next(), pks.next()) {
+                (Some(pk), None) => triggers.push(TableTrigger::audit(&table_name, &pk.name)),
+                _ => {
+                    return Err(format!(
+                        "dibs: audited table '{}' needs a single-column primary key ({})",
+                        table_name,
+                        self.shape.source_file.unwrap_or("<unknown>")
+                    ));
+                }
+            }
+        }
+
         // Extract source location from Shape
         let source = SourceLocation {
             file: self.shape.source_file.map(|s| s.to_string()),
@@ -843,26 +1510,38 @@ impl TableDef {
         };
 
         // Extract doc comment from Shape
-        let doc = if self.shape.doc.is_empty() {
-            None
-        } else {
-            Some(self.shape.doc.join("\n"))
-        };
+        let doc = join_doc_lines(self.shape.doc);
+
+        let rls = self.shape.attributes.iter().any(|attr| {
+            attr.ns() == Some("dibs")
+                && attr.key() == "rls"
+                && matches!(attr.get_as::<Attr>(), Some(Attr::Rls(enabled)) if enabled.unwrap_or(true))
+        });
+        let soft_delete = shape_get_dibs_attr_str(self.shape, "soft_delete").map(|s| s.to_string());
 
         // Extract container-level icon
         let icon = shape_get_dibs_attr_str(self.shape, "icon").map(|s| s.to_string());
 
-        Some(Table {
+        Ok(Some(Table {
             name: table_name,
             columns,
             check_constraints,
+            exclusion_constraints,
             trigger_checks,
+            triggers,
+            partition_by,
+            rls,
+            policies,
+            soft_delete,
+            created_at,
+            updated_at,
+            audited,
             foreign_keys,
             indices,
             source,
             doc,
             icon,
-        })
+        }))
     }
 }
 
@@ -956,6 +1635,14 @@ fn shape_get_dibs_attr_str(shape: &Shape, key: &str) -> Option<&'static str> {
     })
 }
 
+/// Check if a shape has a dibs attribute.
+fn shape_has_dibs_attr(shape: &Shape, key: &str) -> bool {
+    shape
+        .attributes
+        .iter()
+        .any(|attr| attr.ns() == Some("dibs") && attr.key() == key)
+}
+
 /// Check if a field has a dibs attribute.
 fn field_has_dibs_attr(field: &facet::Field, key: &str) -> bool {
     field
@@ -1003,6 +1690,25 @@ fn is_auto_generated_default(default: &Option<String>) -> bool {
     false
 }
 
+/// Join `///` doc comment lines into a single string.
+///
+/// Rustdoc keeps the space after `///`, so one leading space is stripped from
+/// each line. The result is what gets written to the database as a `COMMENT`,
+/// so it must be stable: blank doc comments become `None`.
+fn join_doc_lines(lines: &[&str]) -> Option<String> {
+    let joined = lines
+        .iter()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let trimmed = joined.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
 /// Extract enum variants from a shape if it's an enum type.
 fn extract_enum_variants(shape: &'static Shape) -> Vec<String> {
     if let Type::User(UserType::Enum(enum_type)) = shape.ty {
@@ -1079,6 +1785,83 @@ pub fn parse_fk_reference(fk_ref: &str) -> Option<(&str, &str)> {
     None
 }
 
+/// Get the domain declared with `dibs::domain` on a newtype struct.
+///
+/// Returns `None` if the shape has no domain attribute, isn't a single-field
+/// struct, or its inner type has no Postgres mapping.
+pub fn shape_to_domain(shape: &Shape) -> Option<DomainType> {
+    let domain = shape.attributes.iter().find_map(|attr| {
+        if attr.ns() == Some("dibs")
+            && attr.key() == "domain"
+            && let Some(Attr::Domain(domain)) = attr.get_as::<Attr>()
+        {
+            Some(domain)
+        } else {
+            None
+        }
+    })?;
+    let Type::User(UserType::Struct(struct_type)) = &shape.ty else {
+        return None;
+    };
+    let [field] = struct_type.fields else {
+        return None;
+    };
+    let base_type = shape_to_pg_type(field.shape.get())?;
+    let check = domain.check.map(|expr| {
+        let expr = unescape_rust_string_escapes(expr);
+        CheckConstraint {
+            name: check_constraint_name(domain.name, &expr),
+            expr,
+        }
+    });
+    Some(DomainType {
+        name: domain.name.to_string(),
+        base_type,
+        check,
+    })
+}
+
+/// Get the composite type declared with `dibs::composite` on a struct.
+///
+/// Returns `None` if the shape has no composite attribute, isn't a struct with
+/// named fields, or one of its fields has no Postgres mapping.
+pub fn shape_to_composite(shape: &Shape) -> Option<CompositeType> {
+    let composite = shape.attributes.iter().find_map(|attr| {
+        if attr.ns() == Some("dibs")
+            && attr.key() == "composite"
+            && let Some(Attr::Composite(composite)) = attr.get_as::<Attr>()
+        {
+            Some(composite)
+        } else {
+            None
+        }
+    })?;
+    let Type::User(UserType::Struct(struct_type)) = &shape.ty else {
+        return None;
+    };
+    let fields = struct_type
+        .fields
+        .iter()
+        .map(|field| {
+            // Attributes of a composite type are always nullable
+            let (inner_shape, _) = unwrap_option(field.shape.get());
+            Some(CompositeField {
+                name: field_get_dibs_attr_str(field, "column")
+                    .unwrap_or(field.name)
+                    .to_string(),
+                pg_type: shape_to_pg_type(inner_shape)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if fields.is_empty() {
+        return None;
+    }
+    Some(CompositeType {
+        name: composite.name.to_string(),
+        fields,
+    })
+}
+
 /// Map a Rust type to a Postgres type.
 ///
 /// Takes a Shape to properly handle generic types like `Vec<u8>` and `Jsonb<T>`.
@@ -1169,5 +1952,22 @@ pub fn rust_type_to_pg(shape: &Shape) -> Option<PgType> {
 // Register TableDef with inventory so it can be collected across crates
 inventory::collect!(TableDef);
 
+/// A registered Postgres extension.
+///
+/// Submitted to inventory by the `dibs::extension!` macro.
+pub struct ExtensionDef {
+    /// The extension name, as passed to `CREATE EXTENSION`.
+    pub name: &'static str,
+}
+
+impl ExtensionDef {
+    /// Create a new extension definition.
+    pub const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+inventory::collect!(ExtensionDef);
+
 #[cfg(test)]
 mod tests;
@@ -180,3 +180,30 @@ fn test_index_column_parse_asc_nulls_first() {
     assert_eq!(col.order, SortOrder::Asc);
     assert_eq!(col.nulls, NullsOrder::First);
 }
+
+#[test]
+fn test_join_doc_lines() {
+    assert_eq!(join_doc_lines(&[]), None);
+    assert_eq!(join_doc_lines(&["", " "]), None);
+    assert_eq!(
+        join_doc_lines(&[" A registered user.", "", " Deleted users are kept."]),
+        Some("A registered user.\n\nDeleted users are kept.".to_string())
+    );
+    // Only the single space rustdoc inserts is stripped; indentation survives.
+    assert_eq!(
+        join_doc_lines(&[" Example:", "     indented"]),
+        Some("Example:\n    indented".to_string())
+    );
+}
+
+#[test]
+fn test_trigger_parse_events() {
+    assert_eq!(
+        TableTrigger::parse_events("update, insert"),
+        vec!["INSERT", "UPDATE"]
+    );
+    assert_eq!(
+        TableTrigger::parse_events("INSERT OR delete"),
+        vec!["INSERT", "DELETE"]
+    );
+}
@@ -21,6 +21,13 @@ use unsynn::{LiteralString, Parse, ToTokens, TokenIter};
 ///
 /// Use `MigrationResult` instead of `Result` to enable `#[track_caller]` -
 /// when an error occurs, the exact source location (file:line:column) is captured.
+///
+/// A baseline generated by `dibs squash` lists the migrations it replaces:
+/// `#[dibs::migration("2026_01_18_173711-create_users_squashed", squashes = ["..."])]`.
+///
+/// `timeout = "30s"` sets `statement_timeout` for the migration's transaction
+/// (units: `us`, `ms`, `s`, `min`, `h`, `d`), so a statement stuck behind a
+/// lock fails the migration instead of hanging the deploy.
 #[proc_macro_attribute]
 pub fn migration(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Convert to proc_macro2 and create unsynn TokenIter
@@ -30,6 +37,18 @@ pub fn migration(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Version is optional - if not provided, it will be derived from filename
     let explicit_version = LiteralString::parse(&mut tokens).ok();
 
+    // `squashes = [...]` marks a baseline that replaces earlier migrations,
+    // `timeout = "..."` bounds each of its statements
+    let rest: Vec<proc_macro2::TokenTree> = tokens.collect();
+    let MigrationOptions { squashes, timeout } = match parse_options(&rest) {
+        Ok(options) => options,
+        Err(message) => return quote! { compile_error!(#message); }.into(),
+    };
+    let timeout = match timeout {
+        Some(timeout) => quote! { Some(#timeout) },
+        None => quote! { None },
+    };
+
     let item: proc_macro2::TokenStream = item.into();
 
     // Extract function name from the item
@@ -83,12 +102,227 @@ pub fn migration(attr: TokenStream, item: TokenStream) -> TokenStream {
                 name: stringify!(#fn_ident),
                 run: |ctx| Box::pin(#fn_ident(ctx)),
                 source_file: (env!("CARGO_MANIFEST_DIR"), file!()),
+                squashes: &[#(#squashes),*],
+                timeout: #timeout,
+            }
+        }
+    }
+    .into()
+}
+
+/// Register a function to run around migrations.
+///
+/// The argument says when: `before_all` and `after_all` run once per
+/// migration run, each in a transaction of their own, while `before_each` and
+/// `after_each` run inside every migration's transaction (`ctx.migration()`
+/// says which one). Hooks of the same kind run in order of function name.
+///
+/// # Example
+///
+/// ```ignore
+/// #[dibs::hook(before_all)]
+/// async fn set_lock_timeout(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {
+///     // Plain SET outlives the hook's transaction; SET LOCAL would not
+///     ctx.execute("SET lock_timeout = '5s'").await?;
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn hook(attr: TokenStream, item: TokenStream) -> TokenStream {
+    const EXPECTED: &str = "expected one of `before_all`, `before_each`, `after_each`, `after_all`";
+
+    let attr: proc_macro2::TokenStream = attr.into();
+    let tokens: Vec<proc_macro2::TokenTree> = attr.into_iter().collect();
+    let event = match tokens.as_slice() {
+        [proc_macro2::TokenTree::Ident(event)] => match event.to_string().as_str() {
+            "before_all" => quote! { ::dibs::HookEvent::BeforeAll },
+            "before_each" => quote! { ::dibs::HookEvent::BeforeEach },
+            "after_each" => quote! { ::dibs::HookEvent::AfterEach },
+            "after_all" => quote! { ::dibs::HookEvent::AfterAll },
+            _ => return quote! { compile_error!(#EXPECTED); }.into(),
+        },
+        _ => return quote! { compile_error!(#EXPECTED); }.into(),
+    };
+
+    let item: proc_macro2::TokenStream = item.into();
+    let item_str = item.to_string();
+    let Some(fn_name) = extract_fn_name(&item_str) else {
+        return quote! { compile_error!("expected function"); }.into();
+    };
+    let fn_ident = quote::format_ident!("{}", fn_name);
+
+    quote! {
+        #item
+
+        ::dibs::inventory::submit! {
+            ::dibs::MigrationHook {
+                name: stringify!(#fn_ident),
+                event: #event,
+                run: |ctx| Box::pin(#fn_ident(ctx)),
+                source_file: (env!("CARGO_MANIFEST_DIR"), file!()),
             }
         }
     }
     .into()
 }
 
+/// Register every `.sql` file in a directory as a migration.
+///
+/// The directory is relative to the crate root (`CARGO_MANIFEST_DIR`). Files
+/// are embedded with `include_str!`, so edits trigger a rebuild; adding or
+/// removing a file needs the invoking file to be touched (or a `cargo clean`).
+///
+/// Versions are derived from filenames the same way as for Rust migrations,
+/// so `m_2026_01_18_090000_seed_roles.sql` gets version
+/// `2026_01_18_090000_seed_roles` and runs in order with `#[dibs::migration]`
+/// functions. Each file runs in its own transaction, one statement at a time.
+///
+/// Files named `r_<name>.sql` are repeatable migrations instead: they run
+/// after all versioned ones and again whenever their content changes.
+///
+/// # Example
+///
+/// ```ignore
+/// dibs::sql_migrations!("migrations/");
+/// ```
+#[proc_macro]
+pub fn sql_migrations(input: TokenStream) -> TokenStream {
+    let input: proc_macro2::TokenStream = input.into();
+    let mut tokens = TokenIter::new(input);
+    let Ok(dir) = LiteralString::parse(&mut tokens) else {
+        return quote! { compile_error!("expected a directory, e.g. `sql_migrations!(\"migrations/\")`"); }
+            .into();
+    };
+    let dir = dir.as_str().trim_end_matches('/').to_string();
+
+    let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") else {
+        return quote! { compile_error!("CARGO_MANIFEST_DIR is not set"); }.into();
+    };
+    let entries = match std::fs::read_dir(std::path::Path::new(&manifest_dir).join(&dir)) {
+        Ok(entries) => entries,
+        Err(e) => {
+            let message = format!("cannot read migrations directory `{dir}`: {e}");
+            return quote! { compile_error!(#message); }.into();
+        }
+    };
+    let mut files: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".sql"))
+        .collect();
+    files.sort();
+
+    let migrations = files.iter().map(|file| {
+        let stem = file.trim_end_matches(".sql");
+        let relative = format!("{dir}/{file}");
+        let absolute = format!("/{relative}");
+
+        if let Some(name) = stem.strip_prefix("r_") {
+            return quote! {
+                ::dibs::inventory::submit! {
+                    ::dibs::RepeatableMigration {
+                        name: #name,
+                        sql: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), #absolute)),
+                        source_file: (env!("CARGO_MANIFEST_DIR"), #relative),
+                    }
+                }
+            };
+        }
+
+        let version = stem.strip_prefix("m_").unwrap_or(stem);
+        quote! {
+            ::dibs::inventory::submit! {
+                ::dibs::Migration {
+                    version: #version,
+                    name: #file,
+                    run: |ctx| {
+                        const SQL: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), #absolute));
+                        Box::pin(::dibs::__run_sql_migration(ctx, SQL))
+                    },
+                    source_file: (env!("CARGO_MANIFEST_DIR"), #relative),
+                    squashes: &[],
+                    timeout: None,
+                }
+            }
+        }
+    });
+
+    quote! {
+        #(#migrations)*
+    }
+    .into()
+}
+
+/// Options of `#[dibs::migration]` after the version.
+struct MigrationOptions {
+    squashes: Vec<proc_macro2::Literal>,
+    timeout: Option<proc_macro2::Literal>,
+}
+
+/// Parse the optional `squashes = ["v1", "v2"]` and `timeout = "30s"`
+/// arguments, in any order, after an optional leading comma.
+fn parse_options(tokens: &[proc_macro2::TokenTree]) -> Result<MigrationOptions, String> {
+    use proc_macro2::{Delimiter, TokenTree};
+
+    const EXPECTED: &str = "expected `squashes = [\"version\", ...]` or `timeout = \"30s\"`";
+
+    let mut options = MigrationOptions {
+        squashes: Vec::new(),
+        timeout: None,
+    };
+    for option in tokens.split(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == ',')) {
+        match option {
+            [] => {}
+            [
+                TokenTree::Ident(key),
+                TokenTree::Punct(eq),
+                TokenTree::Group(list),
+            ] if key == "squashes"
+                && eq.as_char() == '='
+                && list.delimiter() == Delimiter::Bracket =>
+            {
+                options.squashes = list
+                    .stream()
+                    .into_iter()
+                    .filter(|t| !matches!(t, TokenTree::Punct(p) if p.as_char() == ','))
+                    .map(|t| match t {
+                        TokenTree::Literal(lit) if lit.to_string().starts_with('"') => Ok(lit),
+                        _ => Err(EXPECTED.to_string()),
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            [
+                TokenTree::Ident(key),
+                TokenTree::Punct(eq),
+                TokenTree::Literal(lit),
+            ] if key == "timeout" && eq.as_char() == '=' => {
+                let text = lit.to_string();
+                let value = text.trim_matches('"');
+                if !text.starts_with('"') || !is_valid_timeout(value) {
+                    return Err(format!(
+                        "invalid timeout {text}: expected a number with a unit, e.g. \"30s\" or \"2min\""
+                    ));
+                }
+                options.timeout = Some(lit.clone());
+            }
+            _ => return Err(EXPECTED.to_string()),
+        }
+    }
+    Ok(options)
+}
+
+/// Whether `value` is a Postgres duration like `500ms` or `2min`.
+fn is_valid_timeout(value: &str) -> bool {
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(unit_start);
+    !number.is_empty()
+        && number.parse::<u64>().is_ok_and(|n| n > 0)
+        && matches!(unit, "us" | "ms" | "s" | "min" | "h" | "d")
+}
+
 fn extract_fn_name(s: &str) -> Option<&str> {
     // Simple extraction: find "fn " and take the next identifier
     let idx = s.find("fn ")?;
@@ -35,6 +35,14 @@ pub struct TableInfo {
     pub doc: Option<String>,
     /// Lucide icon name for display in admin UI
     pub icon: Option<String>,
+    /// Soft-delete timestamp column (rows with it set are treated as deleted)
+    pub soft_delete: Option<String>,
+    /// Column filled with the row's creation time
+    pub created_at: Option<String>,
+    /// Column maintained with the row's last modification time
+    pub updated_at: Option<String>,
+    /// Whether changes are recorded in a `<table>_history` table
+    pub audited: bool,
 }
 
 /// Column information.
@@ -81,6 +89,8 @@ pub struct ForeignKeyInfo {
     pub references_table: String,
     /// Referenced columns
     pub references_columns: Vec<String>,
+    /// Whether the constraint is `DEFERRABLE INITIALLY DEFERRED`
+    pub deferrable: bool,
 }
 
 /// A column in an index with optional sort order and nulls ordering.
@@ -112,6 +122,8 @@ pub struct IndexInfo {
 pub struct SchemaInfo {
     /// All tables in the schema
     pub tables: Vec<TableInfo>,
+    /// Postgres extensions the schema requires
+    pub extensions: Vec<String>,
 }
 
 /// A single schema change.
@@ -160,6 +172,13 @@ pub struct MigrationInfo {
     pub name: String,
     /// Whether this migration has been applied
     pub applied: bool,
+    /// Pending, but older than the newest applied migration
+    pub out_of_order: bool,
+    /// Applied, but no longer defined in code
+    pub missing: bool,
+    /// A repeatable migration, re-run when its SQL changes (`applied` is
+    /// false when it changed since it last ran)
+    pub repeatable: bool,
     /// When it was applied (if applied)
     pub applied_at: Option<String>,
     /// Source file path (if known)
@@ -187,8 +206,184 @@ pub struct MigrationStatusRequest {
 pub struct MigrateRequest {
     /// Database connection URL
     pub database_url: String,
-    /// Specific migration to run (if None, run all pending)
+    /// Run pending migrations up to and including this version (if None, run all pending)
     pub migration: Option<String>,
+    /// Refuse to run if migrations are out of order or missing from the code
+    pub strict: bool,
+}
+
+/// A database (or schema) to migrate as part of a fleet.
+#[derive(Debug, Clone, Facet)]
+pub struct FleetTargetInfo {
+    /// Name used in logs and the report, e.g. the tenant's slug
+    pub name: String,
+    /// Database connection URL
+    pub database_url: String,
+    /// Schema to migrate instead of `public` (schema-per-tenant)
+    pub schema: Option<String>,
+}
+
+/// A query listing the databases of a fleet.
+#[derive(Debug, Clone, Facet)]
+pub struct FleetDiscovery {
+    /// Database the query runs against
+    pub database_url: String,
+    /// Query returning `name`, `database_url` and/or `schema` columns
+    pub query: String,
+}
+
+/// Request to run migrations against many databases.
+#[derive(Debug, Clone, Facet)]
+pub struct FleetMigrateRequest {
+    /// Databases to migrate
+    pub targets: Vec<FleetTargetInfo>,
+    /// Query listing more databases to migrate, after `targets`
+    pub discovery: Option<FleetDiscovery>,
+    /// How many databases to migrate at a time
+    pub concurrency: u32,
+    /// Start no new databases once one fails (otherwise carry on)
+    pub halt_on_failure: bool,
+    /// Run pending migrations up to and including this version (if None, run all pending)
+    pub migration: Option<String>,
+    /// Refuse to migrate databases whose migrations are out of order or missing from the code
+    pub strict: bool,
+}
+
+/// How migrating one database of a fleet ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[repr(u8)]
+pub enum FleetStatus {
+    /// All its pending migrations were applied
+    Migrated = 0,
+    /// Migrating it failed
+    Failed = 1,
+    /// Not attempted, because an earlier database failed
+    Skipped = 2,
+}
+
+/// The result of migrating one database of a fleet.
+#[derive(Debug, Clone, Facet)]
+pub struct FleetDatabaseResult {
+    /// Name of the database
+    pub name: String,
+    /// How it ended
+    pub status: FleetStatus,
+    /// Migrations applied to it in this run
+    pub applied: Vec<RanMigration>,
+    /// Why it failed (if it did)
+    pub error: Option<SqlError>,
+    /// Time spent on it in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Result of running migrations against many databases.
+#[derive(Debug, Clone, Facet)]
+pub struct FleetMigrateResult {
+    /// Every database, in the order they were listed
+    pub databases: Vec<FleetDatabaseResult>,
+    /// Total execution time in milliseconds
+    pub total_time_ms: u64,
+}
+
+/// Rust table definitions and baseline SQL for an existing database.
+#[derive(Debug, Clone, Facet)]
+pub struct ImportResult {
+    /// Rust source declaring the database's tables
+    pub rust: String,
+    /// SQL recreating the database's schema, for the baseline migration
+    pub sql: String,
+    /// Number of tables imported
+    pub table_count: u32,
+}
+
+/// Request to record a migration as applied without running it.
+#[derive(Debug, Clone, Facet)]
+pub struct BaselineRequest {
+    /// Database connection URL
+    pub database_url: String,
+    /// Version of the migration to record
+    pub version: String,
+}
+
+/// Request to squash migrations into a single baseline.
+#[derive(Debug, Clone, Facet)]
+pub struct SquashRequest {
+    /// Database connection URL
+    pub database_url: String,
+    /// Last migration version to squash (inclusive)
+    pub up_to: String,
+}
+
+/// A baseline migration replacing a run of earlier migrations.
+#[derive(Debug, Clone, Facet)]
+pub struct SquashResult {
+    /// Version for the baseline migration
+    pub baseline_version: String,
+    /// SQL recreating the schema as of the last squashed migration
+    pub sql: String,
+    /// The squashed migrations, with their status in the requested database
+    pub squashed: Vec<MigrationInfo>,
+}
+
+/// Request to load which migrations created and modified database objects.
+#[derive(Debug, Clone, Facet)]
+pub struct ProvenanceRequest {
+    /// Database connection URL
+    pub database_url: String,
+}
+
+/// Which migrations created and last modified a table, column or index.
+#[derive(Debug, Clone, Facet)]
+pub struct ProvenanceInfo {
+    /// "table", "column" or "index"
+    pub kind: String,
+    /// Table name
+    pub table: String,
+    /// Column or index name (None for tables)
+    pub name: Option<String>,
+    /// Migration that created it (None if it predates tracking)
+    pub created_by: Option<String>,
+    /// When it was created
+    pub created_at: String,
+    /// Migration that last modified it
+    pub modified_by: Option<String>,
+    /// When it was last modified
+    pub modified_at: String,
+}
+
+/// A seed file sent by the CLI.
+#[derive(Debug, Clone, Facet)]
+pub struct SeedSource {
+    /// Path of the file, used in messages
+    pub path: String,
+    /// Styx source of the file
+    pub content: String,
+}
+
+/// Request to load seed data into a database.
+#[derive(Debug, Clone, Facet)]
+pub struct SeedRequest {
+    /// Database connection URL
+    pub database_url: String,
+    /// Environment to seed ("dev", "test" or "prod")
+    pub env: String,
+    /// Seed files, in the order they're applied
+    pub files: Vec<SeedSource>,
+}
+
+/// Rows loaded into one table by a seed run.
+#[derive(Debug, Clone, Facet)]
+pub struct SeededTableInfo {
+    /// Seed file the rows came from
+    pub file: String,
+    /// Table name
+    pub table: String,
+    /// Rows that didn't exist yet
+    pub inserted: u32,
+    /// Existing rows whose values changed
+    pub updated: u32,
+    /// Existing rows that already matched
+    pub unchanged: u32,
 }
 
 /// A migration that was already applied before this run.
@@ -203,10 +398,12 @@ pub struct AppliedMigration {
 /// A migration that was just run.
 #[derive(Debug, Clone, Facet)]
 pub struct RanMigration {
-    /// Migration version
+    /// Migration version (name, for repeatable migrations)
     pub version: String,
     /// How long it took to run in milliseconds
     pub duration_ms: u64,
+    /// Whether this was a repeatable migration
+    pub repeatable: bool,
 }
 
 /// Result of running migrations.
@@ -233,6 +430,8 @@ pub struct MigrationLog {
     pub message: String,
     /// Migration this log is from (if applicable)
     pub migration: Option<String>,
+    /// Database this log is from, when migrating a fleet
+    pub database: Option<String>,
 }
 
 /// Log level.
@@ -491,6 +690,33 @@ pub trait DibsService {
         request: MigrateRequest,
         logs: vox::Tx<MigrationLog>,
     ) -> Result<MigrateResult, DibsError>;
+
+    /// Run migrations against many databases, streaming each one's logs back.
+    async fn migrate_fleet(
+        &self,
+        request: FleetMigrateRequest,
+        logs: vox::Tx<MigrationLog>,
+    ) -> Result<FleetMigrateResult, DibsError>;
+
+    /// Introspect a live database and render its tables as Rust definitions.
+    async fn import_schema(&self, request: DiffRequest) -> Result<ImportResult, DibsError>;
+
+    /// Record a migration as applied without running it.
+    async fn baseline(&self, request: BaselineRequest) -> Result<(), DibsError>;
+
+    /// Compute the baseline replacing all migrations up to a version, by
+    /// running them against a scratch database.
+    async fn squash(&self, request: SquashRequest) -> Result<SquashResult, DibsError>;
+
+    /// Load which migrations created and last modified each table, column
+    /// and index in a live database.
+    async fn provenance(
+        &self,
+        request: ProvenanceRequest,
+    ) -> Result<Vec<ProvenanceInfo>, DibsError>;
+
+    /// Validate seed files against the schema and upsert their rows.
+    async fn seed(&self, request: SeedRequest) -> Result<Vec<SeededTableInfo>, DibsError>;
 }
 
 /// The Squel service trait - the data plane.
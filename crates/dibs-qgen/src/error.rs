@@ -102,6 +102,32 @@ pub enum QErrorKind {
         reason: String,
     },
 
+    /// An `@or`, `@and` or `@not` filter group has no operands.
+    EmptyFilterGroup {
+        /// The group's tag, like `@or`.
+        group: String,
+    },
+
+    /// An aggregate field (`@sum`, `@count`, ...) can't be used where it is.
+    InvalidAggregate {
+        /// The aggregate's tag, like `@sum`.
+        aggregate: String,
+        /// Why the aggregate is invalid.
+        reason: String,
+    },
+
+    /// A `paginate` clause can't be used as written.
+    InvalidPagination {
+        /// Why the pagination is invalid.
+        reason: String,
+    },
+
+    /// `stream true` can't be used with the rest of the query.
+    InvalidStream {
+        /// Why the query can't be streamed.
+        reason: String,
+    },
+
     /// Invalid arguments for an update expression.
     InvalidUpdateArgCount {
         /// Update expression name.
@@ -174,6 +200,22 @@ impl fmt::Display for QErrorKind {
             QErrorKind::InvalidFilterArgType { filter, reason } => {
                 write!(f, "invalid argument for filter '{}': {}", filter, reason)
             }
+            QErrorKind::EmptyFilterGroup { group } => {
+                write!(
+                    f,
+                    "empty filter group '{}': it needs at least one filter",
+                    group
+                )
+            }
+            QErrorKind::InvalidAggregate { aggregate, reason } => {
+                write!(f, "invalid aggregate '{}': {}", aggregate, reason)
+            }
+            QErrorKind::InvalidPagination { reason } => {
+                write!(f, "invalid pagination: {}", reason)
+            }
+            QErrorKind::InvalidStream { reason } => {
+                write!(f, "invalid stream: {}", reason)
+            }
             QErrorKind::InvalidUpdateArgCount {
                 expression,
                 expected,
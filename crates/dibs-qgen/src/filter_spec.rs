@@ -206,7 +206,7 @@ pub const EQ_BARE_SPEC: FunctionSpec = FunctionSpec {
     args: &[ArgSpec::VariableOrLiteral],
 };
 
-use dibs_query_schema::FilterValue;
+use dibs_query_schema::{FilterValue, Junction};
 
 /// Get the function spec for a filter value, along with its arguments.
 ///
@@ -230,17 +230,40 @@ fn get_spec_and_args(
         FilterValue::Contains(args) => Some((&CONTAINS_SPEC, args)),
         FilterValue::KeyExists(args) => Some((&KEY_EXISTS_SPEC, args)),
         FilterValue::EqBare(_) => None, // EqBare is handled specially (optional single arg)
+        FilterValue::Or(_) | FilterValue::And(_) | FilterValue::Not(_) => None,
     }
 }
 
 /// Validate a filter value's arguments according to its spec.
 ///
 /// Returns the parsed FilterArgs on success, or an error with proper span information.
+/// `@or`/`@and`/`@not` groups are validated recursively and yield no args.
 pub fn validate_filter(
     source: Arc<QSource>,
     filter_span: Span,
     filter_value: &FilterValue,
 ) -> Result<Option<Vec<FilterArg>>, QError> {
+    if let Some((kind, junction)) = filter_value.junction() {
+        if junction.is_empty() {
+            return Err(QError {
+                source,
+                span: filter_span,
+                kind: QErrorKind::EmptyFilterGroup {
+                    group: kind.tag().to_string(),
+                },
+            });
+        }
+        match junction {
+            Junction::Group(group) => validate_where(source, group)?,
+            Junction::Each(filters) => {
+                for filter in filters {
+                    validate_filter(source.clone(), filter_span, filter)?;
+                }
+            }
+        }
+        return Ok(None);
+    }
+
     match get_spec_and_args(filter_value) {
         Some((spec, args)) => {
             let parsed = spec.parse_args(source, filter_span, args)?;
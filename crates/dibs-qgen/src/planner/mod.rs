@@ -8,7 +8,7 @@
 
 mod types;
 
-use dibs_db_schema::Schema;
+use dibs_db_schema::{ForeignKey, Schema, Table};
 use dibs_sql::{ColumnName, TableName};
 pub use types::*;
 
@@ -25,6 +25,22 @@ impl std::fmt::Display for PlanError {
             PlanError::RelationNeedsFrom { relation } => {
                 write!(f, "relation '{}' requires explicit 'from' clause", relation)
             }
+            PlanError::InvalidJunction { junction, from, to } => write!(
+                f,
+                "'{}' can't join {} and {}: it needs a FK to each",
+                junction, from, to
+            ),
+            PlanError::AmbiguousJunction {
+                from,
+                to,
+                candidates,
+            } => write!(
+                f,
+                "{} and {} are linked through several tables ({}); pick one with 'through'",
+                from,
+                to,
+                candidates.join(", ")
+            ),
         }
     }
 }
@@ -106,9 +122,10 @@ impl<'a> QueryPlanner<'a> {
                 .unwrap_or_else(|| name.as_str().into());
 
             // Find FK relationship
-            let relation_alias = plan.next_alias();
+            let through = relation.through.as_ref().map(|m| &m.value);
             let fk_resolution =
-                self.resolve_fk(parent_table, &relation_table, &relation_alias, parent_alias)?;
+                self.resolve_fk(parent_table, &relation_table, through, parent_alias, plan)?;
+            let relation_alias = fk_resolution.join_clause.alias.clone();
 
             // Collect column names for the join (only direct columns, not nested relations)
             let join_select_columns: Vec<ColumnName> = relation
@@ -122,17 +139,11 @@ impl<'a> QueryPlanner<'a> {
             join.first = relation.is_first();
             join.select_columns = join_select_columns;
 
-            // Add extra conditions from relation-level WHERE clause
-            if let Some(where_clause) = &relation.where_clause {
-                for (col_meta, filter_value) in &where_clause.filters {
-                    if let Some(condition) =
-                        Self::filter_to_join_condition(&col_meta.value, filter_value)
-                    {
-                        join.extra_conditions.push(condition);
-                    }
-                }
-            }
+            self.add_relation_filters(&mut join, &relation_table, relation.where_clause.as_ref());
 
+            if let Some(junction) = fk_resolution.junction {
+                plan.add_join(junction);
+            }
             plan.add_join(join);
 
             // Build path for nested fields
@@ -193,6 +204,18 @@ impl<'a> QueryPlanner<'a> {
             plan.add_count(subquery, vec![name.clone()]);
         }
 
+        // Process aggregates over the queried rows
+        for (name_meta, func, column) in select.aggregates() {
+            let name = &name_meta.value;
+            let aggregate = AggregateColumn {
+                func,
+                table_alias: parent_alias.to_string(),
+                column: column.map(|c| c.value.clone()),
+                result_alias: name.clone(),
+            };
+            plan.add_aggregate(aggregate, vec![name.clone()]);
+        }
+
         Ok(())
     }
 
@@ -232,9 +255,10 @@ impl<'a> QueryPlanner<'a> {
                 .map(|m| m.value.clone())
                 .unwrap_or_else(|| name.as_str().into());
 
-            let relation_alias = plan.next_alias();
+            let through = relation.through.as_ref().map(|m| &m.value);
             let fk_resolution =
-                self.resolve_fk(parent_table, &relation_table, &relation_alias, parent_alias)?;
+                self.resolve_fk(parent_table, &relation_table, through, parent_alias, plan)?;
+            let relation_alias = fk_resolution.join_clause.alias.clone();
 
             let join_select_columns: Vec<ColumnName> = relation
                 .fields
@@ -246,17 +270,11 @@ impl<'a> QueryPlanner<'a> {
             join.first = relation.is_first();
             join.select_columns = join_select_columns;
 
-            // Add extra conditions from relation-level WHERE clause
-            if let Some(where_clause) = &relation.where_clause {
-                for (col_meta, filter_value) in &where_clause.filters {
-                    if let Some(condition) =
-                        Self::filter_to_join_condition(&col_meta.value, filter_value)
-                    {
-                        join.extra_conditions.push(condition);
-                    }
-                }
-            }
+            self.add_relation_filters(&mut join, &relation_table, relation.where_clause.as_ref());
 
+            if let Some(junction) = fk_resolution.junction {
+                plan.add_join(junction);
+            }
             plan.add_join(join);
 
             let mut nested_path = path.to_vec();
@@ -299,8 +317,67 @@ impl<'a> QueryPlanner<'a> {
         Ok(())
     }
 
+    /// Add a relation-level WHERE clause to its join: equality filters become
+    /// join conditions, the rest is kept for SQL generation to render. Then
+    /// hide soft-deleted rows unless the relation filters on that column.
+    fn add_relation_filters(
+        &self,
+        join: &mut JoinClause,
+        table: &TableName,
+        where_clause: Option<&crate::Where>,
+    ) {
+        if let Some(where_clause) = where_clause {
+            let mut rest = IndexMap::new();
+            for (col_meta, filter_value) in &where_clause.filters {
+                match Self::filter_to_join_condition(&col_meta.value, filter_value) {
+                    Some(condition) => join.extra_conditions.push(condition),
+                    None => {
+                        rest.insert(col_meta.clone(), filter_value.clone());
+                    }
+                }
+            }
+            if !rest.is_empty() {
+                join.extra_where = Some(crate::Where { filters: rest });
+            }
+        }
+        let soft_delete =
+            self.soft_delete_condition(table, &join.extra_conditions, join.extra_where.as_ref());
+        join.extra_conditions.extend(soft_delete);
+    }
+
+    /// The `IS NULL` condition hiding soft-deleted rows of a joined table,
+    /// unless the join's `conditions` or `filters` already mention the
+    /// soft-delete column.
+    fn soft_delete_condition(
+        &self,
+        table: &TableName,
+        conditions: &[JoinCondition],
+        filters: Option<&crate::Where>,
+    ) -> Option<JoinCondition> {
+        let column = self
+            .schema
+            .tables
+            .get(table.as_str())?
+            .soft_delete
+            .as_deref()?;
+        let filtered = conditions.iter().any(|c| c.column.as_str() == column)
+            || filters.is_some_and(|w| {
+                w.predicates()
+                    .iter()
+                    .any(|(col_meta, _)| col_meta.value.as_str() == column)
+            });
+        if filtered {
+            return None;
+        }
+        Some(JoinCondition {
+            column: column.into(),
+            value: JoinConditionValue::Null,
+        })
+    }
+
     /// Convert a filter value to a JoinCondition for relation-level WHERE.
-    /// Only supports simple equality filters (bare scalars).
+    /// Only simple equality filters (bare scalars) convert; the others are
+    /// rendered from the join's `extra_where` by SQL generation.
     fn filter_to_join_condition(
         column: &ColumnName,
         filter_value: &crate::FilterValue,
@@ -318,19 +395,22 @@ impl<'a> QueryPlanner<'a> {
                     value,
                 })
             }
-            // TODO: Support other filter types if needed
             _ => None,
         }
     }
 
     /// Resolve FK relationship between two tables.
     /// Returns the FkResolution with JoinClause, direction, and parent key column.
+    ///
+    /// Without a direct FK, the tables are joined through a junction table:
+    /// `through` if given, otherwise the only table with FKs to both.
     fn resolve_fk(
         &self,
         from_table: &TableName,
         to_table: &TableName,
-        alias: &str,
+        through: Option<&TableName>,
         parent_alias: &str,
+        plan: &mut QueryPlan,
     ) -> Result<FkResolution, PlanError> {
         let to_table_info =
             self.schema
@@ -340,25 +420,32 @@ impl<'a> QueryPlanner<'a> {
                     table: to_table.to_string(),
                 })?;
 
+        if let Some(junction) = through {
+            return self.resolve_junction(from_table, to_table, junction, parent_alias, plan);
+        }
+
         // Check if to_table has FK pointing to from_table (reverse/has-many)
         for fk in &to_table_info.foreign_keys {
             if fk.references_table == from_table.as_str() {
                 // Found: to_table.fk_col -> from_table.ref_col
                 // JOIN to_table ON from_table.ref_col = to_table.fk_col
+                let alias = plan.next_alias();
                 let parent_key_column: ColumnName = fk.references_columns[0].clone().into();
                 return Ok(FkResolution {
                     join_clause: JoinClause {
                         join_type: JoinType::Left,
                         table: to_table.clone(),
-                        alias: alias.to_string(),
+                        alias: alias.clone(),
                         on_condition: (
                             format!("{}.{}", parent_alias, parent_key_column),
                             format!("{}.{}", alias, fk.columns[0]),
                         ),
                         extra_conditions: vec![],
+                        extra_where: None,
                         first: false,
                         select_columns: vec![],
                     },
+                    junction: None,
                     _direction: FkDirection::Reverse,
                     parent_key_column,
                 });
@@ -377,33 +464,161 @@ impl<'a> QueryPlanner<'a> {
                 // Found: from_table.fk_col -> to_table.ref_col
                 // JOIN to_table ON from_table.fk_col = to_table.ref_col
                 // For forward (belongs-to), parent key is the FK column in from_table
+                let alias = plan.next_alias();
                 let parent_key_column: ColumnName = fk.columns[0].clone().into();
                 return Ok(FkResolution {
                     join_clause: JoinClause {
                         join_type: JoinType::Left,
                         table: to_table.clone(),
-                        alias: alias.to_string(),
+                        alias: alias.clone(),
                         on_condition: (
                             format!("{}.{}", parent_alias, parent_key_column),
                             format!("{}.{}", alias, fk.references_columns[0]),
                         ),
                         extra_conditions: vec![],
+                        extra_where: None,
                         first: false,
                         select_columns: vec![],
                     },
+                    junction: None,
                     _direction: FkDirection::Forward,
                     parent_key_column,
                 });
             }
         }
 
-        Err(PlanError::NoForeignKey {
-            from: from_table.to_string(),
-            to: to_table.to_string(),
+        // Look for a junction table (many-to-many)
+        let mut candidates: Vec<&str> = self
+            .schema
+            .tables
+            .values()
+            .filter(|table| {
+                from_table != to_table
+                    && table.foreign_keys.len() == 2
+                    && junction_fks(table, from_table, to_table).is_some()
+            })
+            .map(|table| table.name.as_str())
+            .collect();
+        match candidates.len() {
+            0 => Err(PlanError::NoForeignKey {
+                from: from_table.to_string(),
+                to: to_table.to_string(),
+            }),
+            1 => {
+                let junction: TableName = candidates[0].into();
+                self.resolve_junction(from_table, to_table, &junction, parent_alias, plan)
+            }
+            _ => {
+                candidates.sort();
+                Err(PlanError::AmbiguousJunction {
+                    from: from_table.to_string(),
+                    to: to_table.to_string(),
+                    candidates: candidates.into_iter().map(String::from).collect(),
+                })
+            }
+        }
+    }
+
+    /// Join `to_table` through the `junction` table of a many-to-many relation:
+    /// `from_table.key = junction.from_fk` then `junction.to_fk = to_table.key`.
+    fn resolve_junction(
+        &self,
+        from_table: &TableName,
+        to_table: &TableName,
+        junction: &TableName,
+        parent_alias: &str,
+        plan: &mut QueryPlan,
+    ) -> Result<FkResolution, PlanError> {
+        let junction_info =
+            self.schema
+                .tables
+                .get(junction.as_str())
+                .ok_or_else(|| PlanError::TableNotFound {
+                    table: junction.to_string(),
+                })?;
+        let (from_fk, to_fk) =
+            junction_fks(junction_info, from_table, to_table).ok_or_else(|| {
+                PlanError::InvalidJunction {
+                    junction: junction.to_string(),
+                    from: from_table.to_string(),
+                    to: to_table.to_string(),
+                }
+            })?;
+
+        let junction_alias = plan.next_alias();
+        let alias = plan.next_alias();
+        let parent_key_column: ColumnName = from_fk.references_columns[0].clone().into();
+        Ok(FkResolution {
+            join_clause: JoinClause {
+                join_type: JoinType::Left,
+                table: to_table.clone(),
+                alias: alias.clone(),
+                on_condition: (
+                    format!("{}.{}", junction_alias, to_fk.columns[0]),
+                    format!("{}.{}", alias, to_fk.references_columns[0]),
+                ),
+                extra_conditions: vec![],
+                extra_where: None,
+                first: false,
+                select_columns: vec![],
+            },
+            junction: Some(JoinClause {
+                join_type: JoinType::Left,
+                table: junction.clone(),
+                alias: junction_alias.clone(),
+                on_condition: (
+                    format!("{}.{}", parent_alias, parent_key_column),
+                    format!("{}.{}", junction_alias, from_fk.columns[0]),
+                ),
+                extra_conditions: self
+                    .soft_delete_condition(junction, &[], None)
+                    .into_iter()
+                    .collect(),
+                extra_where: None,
+                first: false,
+                select_columns: vec![],
+            }),
+            _direction: FkDirection::Reverse,
+            parent_key_column,
         })
     }
 }
 
+/// The FKs of a junction table pointing to `from_table` and `to_table`, in
+/// that order.
+fn junction_fks<'t>(
+    junction: &'t Table,
+    from_table: &TableName,
+    to_table: &TableName,
+) -> Option<(&'t ForeignKey, &'t ForeignKey)> {
+    let fks = &junction.foreign_keys;
+    let from_idx = fks
+        .iter()
+        .position(|fk| fk.references_table == from_table.as_str())?;
+    let to_fk = fks
+        .iter()
+        .enumerate()
+        .find(|(idx, fk)| *idx != from_idx && fk.references_table == to_table.as_str())?
+        .1;
+    Some((&fks[from_idx], to_fk))
+}
+
+/// The soft-delete column of `table` that a query should filter on, if the
+/// table declares one and the query's WHERE doesn't already mention it.
+pub(crate) fn soft_delete_column(
+    schema: &Schema,
+    table: &TableName,
+    where_clause: Option<&crate::Where>,
+) -> Option<ColumnName> {
+    let column = schema.tables.get(table.as_str())?.soft_delete.as_deref()?;
+    let filtered = where_clause.is_some_and(|w| {
+        w.predicates()
+            .iter()
+            .any(|(col_meta, _)| col_meta.value.as_str() == column)
+    });
+    (!filtered).then(|| column.into())
+}
+
 impl QueryPlan {
     /// Generate SQL SELECT clause.
     pub fn select_sql(&self) -> String {
@@ -441,6 +656,9 @@ impl QueryPlan {
 
     /// Generate SQL FROM clause with JOINs, tracking parameter order.
     ///
+    /// Only equality join conditions are rendered here; relation filters kept
+    /// in `extra_where` need [`crate::generate_select_sql`].
+    ///
     /// Returns the SQL and appends any parameter names to `param_order`.
     /// `param_idx` is updated to track the next $N placeholder.
     #[allow(clippy::wrong_self_convention)]
@@ -466,6 +684,10 @@ impl QueryPlan {
             // Add extra conditions from relation-level WHERE
             for cond in &join.extra_conditions {
                 let value_sql = match &cond.value {
+                    JoinConditionValue::Null => {
+                        on_parts.push(format!("\"{}\".\"{}\" IS NULL", join.alias, cond.column));
+                        continue;
+                    }
                     JoinConditionValue::Literal(lit) => format!("'{}'", lit),
                     JoinConditionValue::Param(param_name) => {
                         param_order.push(param_name.clone());
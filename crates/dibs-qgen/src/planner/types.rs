@@ -2,6 +2,7 @@
 
 use indexmap::IndexMap;
 
+use dibs_query_schema::AggregateFn;
 use dibs_sql::{ColumnName, TableName};
 
 /// A planned query with JOINs resolved.
@@ -17,6 +18,8 @@ pub struct QueryPlan {
     pub select_columns: Vec<SelectColumn>,
     /// COUNT subqueries
     pub count_subqueries: Vec<CountSubquery>,
+    /// Aggregates over the queried rows (SUM, AVG, ...)
+    pub aggregates: Vec<AggregateColumn>,
     /// Mapping from result columns to nested struct paths
     pub result_mapping: ResultMapping,
     /// Counter for generating unique table aliases
@@ -32,6 +35,7 @@ impl QueryPlan {
             joins: Vec::new(),
             select_columns: Vec::new(),
             count_subqueries: Vec::new(),
+            aggregates: Vec::new(),
             result_mapping: ResultMapping::default(),
             alias_counter: 1, // t0 is already used for the base table
         }
@@ -74,6 +78,13 @@ impl QueryPlan {
         self.result_mapping.columns.insert(alias, path);
     }
 
+    /// Add an aggregate column.
+    pub fn add_aggregate(&mut self, aggregate: AggregateColumn, path: Vec<ColumnName>) {
+        let alias = aggregate.result_alias.clone();
+        self.aggregates.push(aggregate);
+        self.result_mapping.columns.insert(alias, path);
+    }
+
     /// Add a relation mapping at the top level.
     pub fn add_relation(&mut self, name: ColumnName, mapping: RelationMapping) {
         self.result_mapping.relations.insert(name, mapping);
@@ -93,6 +104,9 @@ pub struct JoinClause {
     pub on_condition: (String, String),
     /// Additional conditions for the ON clause (from relation-level WHERE)
     pub extra_conditions: Vec<JoinCondition>,
+    /// Relation-level filters that aren't plain equality (`@or`, `@gt`,
+    /// `@null`, ...), ANDed into the ON clause by SQL generation
+    pub extra_where: Option<crate::Where>,
     /// Whether this is a first:true relation (affects LATERAL generation)
     pub first: bool,
     /// Columns selected from this join (needed for LATERAL subquery)
@@ -115,6 +129,8 @@ pub enum JoinConditionValue {
     Literal(String),
     /// A parameter reference (e.g., $1)
     Param(dibs_sql::ParamName),
+    /// NULL (rendered as `IS NULL`, e.g. for soft-deleted rows)
+    Null,
 }
 
 /// JOIN type.
@@ -150,6 +166,19 @@ pub struct CountSubquery {
     pub parent_key: ColumnName,
 }
 
+/// An aggregate in the SELECT clause (e.g. `SUM("t0"."amount") AS "total"`).
+#[derive(Debug, Clone)]
+pub struct AggregateColumn {
+    /// Aggregate function
+    pub func: AggregateFn,
+    /// Table alias
+    pub table_alias: String,
+    /// Aggregated column (None for `COUNT(*)`)
+    pub column: Option<ColumnName>,
+    /// Result alias (for AS clause)
+    pub result_alias: ColumnName,
+}
+
 /// Mapping of result columns to nested struct paths.
 #[derive(Debug, Clone, Default)]
 pub struct ResultMapping {
@@ -186,6 +215,18 @@ pub enum PlanError {
     /// Relation requires explicit 'from' clause
     #[allow(dead_code)]
     RelationNeedsFrom { relation: String },
+    /// The `through` table of a relation lacks a FK to one of the sides
+    InvalidJunction {
+        junction: String,
+        from: String,
+        to: String,
+    },
+    /// Several junction tables link the two sides of a relation
+    AmbiguousJunction {
+        from: String,
+        to: String,
+        candidates: Vec<String>,
+    },
 }
 
 /// Direction of FK relationship.
@@ -202,6 +243,8 @@ pub enum FkDirection {
 pub struct FkResolution {
     /// The JOIN clause
     pub join_clause: JoinClause,
+    /// JOIN of the junction table, preceding `join_clause` (many-to-many)
+    pub junction: Option<JoinClause>,
     /// Direction of the relationship (reserved for future use)
     pub _direction: FkDirection,
     /// Parent's primary key column (used for grouping Vec relations)
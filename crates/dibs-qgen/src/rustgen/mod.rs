@@ -14,10 +14,10 @@ use crate::error::QErrorKind;
 use crate::sqlgen::SqlGenContext;
 use crate::{QError, QSource};
 use codegen::{Block, Function, Scope, Struct};
-use dibs_db_schema::{Schema, Table};
+use dibs_db_schema::{PgType, Schema, Table};
 use dibs_query_schema::{
-    Decl, Delete, FieldDef, Insert, InsertMany, Meta, Params, QueryFile, Returning, Returns,
-    Select, SelectFields, Span, Update, Upsert, UpsertMany,
+    AggregateFn, Decl, Delete, FieldDef, Insert, InsertMany, Keyset, Meta, ParamType, Params,
+    QueryFile, Returning, Returns, Select, SelectFields, Span, Update, Upsert, UpsertMany,
 };
 use std::sync::Arc;
 
@@ -110,8 +110,29 @@ impl CodegenContext<'_> {
     /// empty (e.g. a build script that forgot to link its table definitions);
     /// silently falling back to `String` there generates wrong-typed structs
     /// that compile fine and corrupt data at runtime.
+    ///
+    /// Composite type columns are an error too: nothing decodes a Postgres
+    /// record into a Rust value yet.
     fn column_type_at(&self, table: &str, column: &str, span: Span) -> Result<String, QError> {
         let table_info = self.require_table(table, span)?;
+        if let Some(composite) = table_info
+            .columns
+            .iter()
+            .find(|c| c.name == column)
+            .and_then(|c| c.composite.as_ref())
+        {
+            return Err(QError {
+                source: self.source.clone(),
+                span,
+                kind: QErrorKind::SchemaMismatch {
+                    table: table.to_string(),
+                    column: column.to_string(),
+                    reason: format!(
+                        "columns of composite type '{composite}' can't be read by generated queries yet"
+                    ),
+                },
+            });
+        }
         if let Some(ty) = schema_column_type(self.schema, table, column) {
             return Ok(ty);
         }
@@ -126,6 +147,76 @@ impl CodegenContext<'_> {
         })
     }
 
+    /// Look up the Rust type for a non-relation field with a definition: a
+    /// related-row count (`@count(table)`) or an aggregate over `table`.
+    ///
+    /// `grouped` is whether the query has a GROUP BY. Without one, an
+    /// aggregate other than COUNT over zero rows is NULL.
+    fn computed_field_type(
+        &self,
+        table: &str,
+        field_def: &FieldDef,
+        grouped: bool,
+    ) -> Result<String, QError> {
+        let Some((func, column)) = field_def.aggregate() else {
+            return Ok("i64".to_string());
+        };
+        let Some(column) = column else {
+            // COUNT(*)
+            return Ok("i64".to_string());
+        };
+
+        let table_info = self.require_table(table, column.span)?;
+        let Some(col) = table_info
+            .columns
+            .iter()
+            .find(|c| c.name == column.as_str())
+        else {
+            // Reports the missing column
+            return self.column_type_at(table, column.as_str(), column.span);
+        };
+        if func == AggregateFn::Count {
+            return Ok("i64".to_string());
+        }
+
+        let rust_type = match (func, col.pg_type) {
+            (AggregateFn::Min | AggregateFn::Max, pg_type) => col
+                .rust_type
+                .clone()
+                .unwrap_or_else(|| pg_type.to_rust_type().to_string()),
+            // SUM(integer) is bigint; SUM(bigint) and AVG of any integer are numeric
+            (AggregateFn::Sum, PgType::SmallInt | PgType::Integer) => "i64".to_string(),
+            (
+                AggregateFn::Sum | AggregateFn::Avg,
+                PgType::SmallInt | PgType::Integer | PgType::BigInt | PgType::Numeric,
+            ) => "Decimal".to_string(),
+            (AggregateFn::Sum, PgType::Real) => "f32".to_string(),
+            (AggregateFn::Sum | AggregateFn::Avg, PgType::Real | PgType::DoublePrecision) => {
+                "f64".to_string()
+            }
+            (_, pg_type) => {
+                return Err(QError {
+                    source: self.source.clone(),
+                    span: column.span,
+                    kind: QErrorKind::InvalidAggregate {
+                        aggregate: func.tag().to_string(),
+                        reason: format!(
+                            "it needs a numeric column, '{}' is {}",
+                            column.as_str(),
+                            pg_type
+                        ),
+                    },
+                });
+            }
+        };
+
+        if grouped && !col.nullable {
+            Ok(rust_type)
+        } else {
+            Ok(format!("Option<{}>", rust_type))
+        }
+    }
+
     /// Create an SqlGenContext for this codegen context.
     fn sqlgen_ctx(&self) -> SqlGenContext<'_> {
         SqlGenContext::new(self.schema, self.source.clone())
@@ -181,6 +272,11 @@ pub fn generate_rust_code(
         }
     }
 
+    // Every audited table gets a helper fetching a row's history
+    for table in schema.tables.values().filter(|t| t.audited) {
+        generate_history_code(&ctx, table, &mut scope);
+    }
+
     Ok(GeneratedCode {
         code: scope.to_string(),
     })
@@ -211,8 +307,46 @@ fn generate_select_code(
         generate_raw_sql_result_struct(&struct_name, returns, scope);
     }
 
+    // Keyset-paginated queries return a cursor for the next page
+    if let (Some(keyset), Some(from)) = (select.keyset(), &select.from) {
+        generate_cursor_struct(ctx, name, keyset, from, scope)?;
+    }
+
     // Generate query function
-    generate_select_function(ctx, name_meta, select, &struct_name, scope)?;
+    if select.is_stream() {
+        generate_stream_function(ctx, name_meta, select, &struct_name, scope)?;
+    } else {
+        generate_select_function(ctx, name_meta, select, &struct_name, scope)?;
+    }
+    Ok(())
+}
+
+/// Generate the opaque `{Name}Cursor` of a keyset-paginated query: the
+/// keyset column values of the last row of a page.
+fn generate_cursor_struct(
+    ctx: &CodegenContext,
+    name: &str,
+    keyset: &Keyset,
+    table: &Meta<dibs_sql::TableName>,
+    scope: &mut Scope,
+) -> Result<(), QError> {
+    let mut st = Struct::new(format!("{name}Cursor"));
+    st.doc(format!(
+        "Where the next page of [`{}`] starts. Returned with each full page.",
+        to_snake_case(name)
+    ));
+    st.vis("pub");
+    st.derive("Debug");
+    st.derive("Clone");
+    st.derive("Facet");
+    st.attr("facet(crate = dibs_runtime::facet)");
+
+    for col in keyset.by.columns.keys() {
+        let rust_ty = ctx.column_type_at(table.value.as_str(), col.as_str(), col.span)?;
+        st.field(col.as_str(), &rust_ty);
+    }
+
+    scope.push_struct(st);
     Ok(())
 }
 
@@ -318,14 +452,15 @@ fn add_flat_fields_for_select(
                     add_flat_fields_for_select(ctx, st, rel_table, &new_prefix, rel_fields)?;
                 }
             }
-            Some(FieldDef::Count(_)) => {
-                // COUNT subquery result
+            Some(field_def) => {
+                // COUNT subquery or aggregate result
                 let flat_field_name = if prefix.is_empty() {
                     field_name.to_string()
                 } else {
                     format!("{}_{}", prefix, field_name)
                 };
-                st.field(&flat_field_name, "i64");
+                let rust_ty = ctx.computed_field_type(table_name, field_def, false)?;
+                st.field(&flat_field_name, &rust_ty);
             }
         }
     }
@@ -390,8 +525,10 @@ fn generate_result_struct(
                     };
                     st.field(format!("pub {}", field_name), &ty);
                 }
-                Some(FieldDef::Count(_)) => {
-                    st.field(format!("pub {}", field_name), "i64");
+                Some(field_def) => {
+                    let rust_ty =
+                        ctx.computed_field_type(table_name, field_def, select.group_by.is_some())?;
+                    st.field(format!("pub {}", field_name), &rust_ty);
                 }
             }
         }
@@ -459,8 +596,9 @@ fn generate_nested_structs(
                             };
                             nested_st.field(format!("pub {}", rel_field_name), &ty);
                         }
-                        Some(FieldDef::Count(_)) => {
-                            nested_st.field(format!("pub {}", rel_field_name), "i64");
+                        Some(field_def) => {
+                            let rust_ty = ctx.computed_field_type(rel_table, field_def, false)?;
+                            nested_st.field(format!("pub {}", rel_field_name), &rust_ty);
                         }
                     }
                 }
@@ -487,8 +625,11 @@ fn generate_select_function(
     let name = &name_meta.value;
     let fn_name = to_snake_case(name);
 
+    let keyset = query.paginate.is_some() && query.sql.is_none();
     let return_ty = if query.first.is_some() {
         format!("Result<Option<{}>, QueryError>", struct_name)
+    } else if keyset {
+        format!("Result<(Vec<{struct_name}>, Option<{name}Cursor>), QueryError>")
     } else {
         format!("Result<Vec<{}>, QueryError>", struct_name)
     };
@@ -509,6 +650,9 @@ fn generate_select_function(
     func.attr("allow(clippy::clone_on_copy)");
 
     add_params_to_function(&mut func, query.params.as_ref());
+    if keyset {
+        func.arg("cursor", format!("Option<&{name}Cursor>"));
+    }
 
     func.ret(&return_ty);
     func.bound("C", "tokio_postgres::GenericClient");
@@ -525,6 +669,116 @@ fn generate_select_function(
     Ok(())
 }
 
+/// Generate the function of a `stream true` query, yielding results as rows
+/// arrive instead of collecting them.
+///
+/// Queries with relations stream their flat rows through `group_rows`, which
+/// assembles a parent once all of its rows (adjacent, as sqlgen orders by the
+/// parent key) have been seen.
+fn generate_stream_function(
+    ctx: &CodegenContext,
+    name_meta: &Meta<String>,
+    query: &Select,
+    struct_name: &str,
+    scope: &mut Scope,
+) -> Result<(), QError> {
+    let name = &name_meta.value;
+    let fn_name = to_snake_case(name);
+
+    let mut func = Function::new(&fn_name);
+    if let Some(doc) = &name_meta.doc {
+        let doc_str = doc.join("\n");
+        func.doc(&doc_str);
+    }
+    func.vis("pub");
+    func.attr("allow(clippy::too_many_arguments)");
+    func.attr("allow(clippy::clone_on_copy)");
+    func.generic("'a");
+    func.generic("C");
+    func.arg("client", "&'a C");
+    if let Some(params) = &query.params {
+        for (param_name_meta, param_type) in &params.params {
+            let rust_ty = param_type_to_function_arg_rust(param_type);
+            func.arg(param_name_meta.value.as_str(), format!("&'a {rust_ty}"));
+        }
+    }
+    func.ret(format!(
+        "impl Stream<Item = Result<{struct_name}, QueryError>> + 'a"
+    ));
+    func.bound("C", "tokio_postgres::GenericClient");
+
+    let (sql, params): (String, Vec<String>) = if let Some(raw_sql_meta) = &query.sql {
+        let params = query
+            .params
+            .iter()
+            .flat_map(|params| params.iter().map(|(meta, _)| meta.value.to_string()))
+            .collect();
+        (clean_raw_sql(&raw_sql_meta.value), params)
+    } else {
+        let generated = crate::sqlgen::generate_select_sql(&ctx.sqlgen_ctx(), query)?;
+        let params = generated
+            .param_order
+            .iter()
+            .filter(|p| !p.as_str().starts_with("__literal_"))
+            .map(|p| p.as_str().to_string())
+            .collect();
+        (generated.sql, params)
+    };
+
+    let mut block = Block::new("");
+    block.line(format!("const SQL: &str = r#\"{sql}\"#;"));
+    block.line("");
+
+    let flat_struct_name = format!("{name}Row");
+    let grouped = query.sql.is_none() && query.has_relations();
+    let mut rows_block = if grouped {
+        Block::new(format!(
+            "let flat_rows = dibs_runtime::stream_rows::<{flat_struct_name}, _>(\"{fn_name}\", async move"
+        ))
+    } else {
+        Block::new(format!(
+            "dibs_runtime::stream_rows(\"{fn_name}\", async move"
+        ))
+    };
+    rows_block.line(format!(
+        "let params: [&(dyn tokio_postgres::types::ToSql + Sync); {}] = [{}];",
+        params.len(),
+        bind_param_list(params.iter().map(String::as_str))
+    ));
+    rows_block.line("client.query_raw(SQL, params).await");
+    rows_block.after(if grouped { ");" } else { ")" });
+    block.push_block(rows_block);
+
+    if grouped && let Some(select_fields) = &query.fields {
+        let id_column =
+            crate::sqlgen::stream_parent_key(&ctx.sqlgen_ctx(), query, select_fields)?.as_str();
+        let root_table = query
+            .from
+            .as_ref()
+            .map(|m| m.value.as_str())
+            .unwrap_or("unknown");
+        let transform =
+            generate_flat_to_nested_transform(ctx, select_fields, struct_name, root_table, true)?;
+
+        block.line("");
+        let mut group_block = Block::new(format!(
+            "dibs_runtime::group_rows(flat_rows, |flat_row: &{flat_struct_name}| flat_row.{id_column}.clone(), |flat_rows: Vec<{flat_struct_name}>|"
+        ));
+        group_block.line(format!(
+            "let parent: Result<Option<{struct_name}>, QueryError> = {{\n{transform}\n}};"
+        ));
+        group_block.line(
+            "parent?.ok_or_else(|| QueryError::Assemble(\"a group has no rows\".to_string()))",
+        );
+        group_block.after(")");
+        block.push_block(group_block);
+    }
+
+    func.line(block_to_string(&block));
+    scope.push_fn(func);
+    Ok(())
+}
+
 /// Generate query body for all queries (with or without JOINs).
 ///
 /// For queries without relations: use `from_row()` directly into the result struct.
@@ -535,38 +789,71 @@ fn generate_query_body(
     struct_name: &str,
 ) -> Result<String, QError> {
     let sqlgen_ctx = ctx.sqlgen_ctx();
-    let generated = match crate::sqlgen::generate_select_sql(&sqlgen_ctx, query) {
-        Ok(g) => g,
-        Err(e) => {
-            panic!("SELECT SQL generation failed: {}", e);
-        }
-    };
+    let generated = crate::sqlgen::generate_select_sql(&sqlgen_ctx, query)?;
 
     let mut block = Block::new("");
 
     // SQL constant
     block.line(format!("const SQL: &str = r#\"{}\"#;", generated.sql));
+    if let Some(first_page) = &generated.first_page {
+        block.line(format!(
+            "const FIRST_PAGE_SQL: &str = r#\"{}\"#;",
+            first_page.sql
+        ));
+    }
     block.line("");
 
     // Build params array - filter out literal placeholders
-    let params: Vec<_> = generated
-        .param_order
-        .iter()
-        .filter(|p| !p.as_str().starts_with("__literal_"))
-        .collect();
+    let bound = |param_order: &[dibs_sql::ParamName]| {
+        bind_param_list(
+            param_order
+                .iter()
+                .map(|p| p.as_str())
+                .filter(|p| !p.starts_with("__literal_")),
+        )
+    };
 
-    if params.is_empty() {
-        block.line("let rows = client.query(SQL, &[]).await?;");
+    // Keyset pagination runs a separate statement for the first page, which
+    // has no cursor to seek past
+    let keyset = query.keyset();
+    if let (Some(keyset), Some(first_page)) = (keyset, &generated.first_page) {
+        let mut match_block = Block::new("let rows = match cursor");
+        let mut next_page = Block::new("Some(cursor) =>");
+        for col in keyset.by.columns.keys() {
+            next_page.line(format!(
+                "let {} = &cursor.{};",
+                crate::sqlgen::keyset_cursor_param(&col.value),
+                col.as_str()
+            ));
+        }
+        next_page.line(format!(
+            "client.query(SQL, &[{}]).await?",
+            bound(&generated.param_order)
+        ));
+        match_block.push_block(next_page);
+        match_block.line(format!(
+            "None => client.query(FIRST_PAGE_SQL, &[{}]).await?,",
+            bound(&first_page.params)
+        ));
+        match_block.after(";");
+        block.push_block(match_block);
     } else {
-        let params_str = bind_param_list(params.iter().map(|p| p.as_str()));
         block.line(format!(
             "let rows = client.query(SQL, &[{}]).await?;",
-            params_str
+            bound(&generated.param_order)
         ));
     }
 
     // If no relations, use from_row() directly into the result struct
-    if !query.has_relations() {
+    if let Some(keyset) = keyset
+        && !query.has_relations()
+    {
+        block.line(format!(
+            "let results: Vec<{struct_name}> = rows.iter().map(from_row).collect::<Result<_, _>>()?;"
+        ));
+        generate_next_cursor(&mut block, query, keyset, struct_name);
+        return Ok(block_to_string(&block));
+    } else if !query.has_relations() {
         if query.first.is_some() {
             let mut match_block = Block::new("match rows.into_iter().next()");
             match_block.line("Some(row) => Ok(Some(from_row(&row)?)),");
@@ -603,17 +890,62 @@ fn generate_query_body(
         .unwrap_or("unknown");
     let is_first = query.is_first();
 
-    block.line(generate_flat_to_nested_transform(
-        ctx,
-        select_fields,
-        struct_name,
-        root_table,
-        is_first,
-    )?);
+    let transform =
+        generate_flat_to_nested_transform(ctx, select_fields, struct_name, root_table, is_first)?;
+    if let Some(keyset) = keyset {
+        block.line(format!(
+            "let results: Result<Vec<{struct_name}>, QueryError> = {{\n{transform}\n}};"
+        ));
+        block.line("let results = results?;");
+        generate_next_cursor(&mut block, query, keyset, struct_name);
+    } else {
+        block.line(transform);
+    }
 
     Ok(block_to_string(&block))
 }
 
+/// Finish a keyset-paginated query body: a full page yields the cursor after
+/// its last row, a shorter one is the last page.
+fn generate_next_cursor(block: &mut Block, query: &Select, keyset: &Keyset, struct_name: &str) {
+    let query_name = struct_name.strip_suffix("Result").unwrap_or(struct_name);
+    let page_size = match query.limit.as_ref().map(|l| l.value.as_str()) {
+        Some(limit) => match limit.strip_prefix('$') {
+            Some(param) => {
+                let param_type = query.params.as_ref().and_then(|params| {
+                    params
+                        .params
+                        .iter()
+                        .find(|(name, _)| name.as_str() == param)
+                        .map(|(_, ty)| ty)
+                });
+                match param_type {
+                    Some(ParamType::Int) => format!("*{param}"),
+                    _ => format!("i64::from(*{param})"),
+                }
+            }
+            None => limit.to_string(),
+        },
+        // sqlgen rejects keyset pagination without a limit
+        None => "i64::MAX".to_string(),
+    };
+
+    block.line("");
+    let mut match_block = Block::new("let next_cursor = match results.last()");
+    let mut cursor_block = Block::new(format!(
+        "Some(last) if results.len() as i64 == {page_size} => Some({query_name}Cursor"
+    ));
+    for col in keyset.by.columns.keys() {
+        cursor_block.line(format!("{0}: last.{0}.clone(),", col.as_str()));
+    }
+    cursor_block.after("),");
+    match_block.push_block(cursor_block);
+    match_block.line("_ => None,");
+    match_block.after(";");
+    block.push_block(match_block);
+    block.line("Ok((results, next_cursor))");
+}
+
 /// Generate code to transform flat rows into nested result structs.
 fn generate_flat_to_nested_transform(
     ctx: &CodegenContext,
@@ -670,7 +1002,7 @@ fn generate_flat_to_nested_transform(
                         entry_block.line(format!("{field_name}: Vec::new(),"));
                     }
                 }
-                Some(FieldDef::Count(_)) => {
+                Some(_) => {
                     entry_block.line(format!("{field_name}: flat_row.{field_name},"));
                 }
             }
@@ -763,7 +1095,7 @@ fn generate_flat_to_nested_transform(
                         result_block.line(format!("{field_name}: Vec::new(),"));
                     }
                 }
-                Some(FieldDef::Count(_)) => {
+                Some(_) => {
                     result_block.line(format!("{field_name}: flat_row.{field_name},"));
                 }
             }
@@ -959,7 +1291,7 @@ fn generate_relation_fields(
                     ));
                 }
             }
-            Some(FieldDef::Count(_)) => {
+            Some(_) => {
                 block.line(format!("{field_name}: flat_row.{alias},"));
             }
         }
@@ -967,17 +1299,25 @@ fn generate_relation_fields(
     Ok(())
 }
 
-fn generate_raw_query_body(query: &Select, raw_sql: &str) -> Block {
-    let cleaned: String = raw_sql
+/// Trim the indentation of a raw SQL block.
+fn clean_raw_sql(raw_sql: &str) -> String {
+    raw_sql
         .lines()
         .map(|l| l.trim())
         .collect::<Vec<_>>()
-        .join("\n");
+        .join("\n")
+        .trim()
+        .to_string()
+}
 
+fn generate_raw_query_body(query: &Select, raw_sql: &str) -> Block {
     let mut block = Block::new("");
 
     // SQL constant
-    block.line(format!("const SQL: &str = r#\"{}\"#;", cleaned.trim()));
+    block.line(format!(
+        "const SQL: &str = r#\"{}\"#;",
+        clean_raw_sql(raw_sql)
+    ));
     block.line("");
 
     // Query execution
@@ -1068,6 +1408,68 @@ fn bind_param_list<'a>(params: impl IntoIterator<Item = &'a str>) -> String {
         .join(", ")
 }
 
+/// Generate the `<table>_history` result struct and the function returning a
+/// row's recorded changes, newest first.
+fn generate_history_code(ctx: &CodegenContext, table: &Table, scope: &mut Scope) {
+    let history_name = Table::history_table_name(&table.name);
+    let Some(history) = ctx.schema.get_table(&history_name) else {
+        return;
+    };
+    let Some(row_id_type) = ctx.column_type(&history_name, "row_id") else {
+        return;
+    };
+
+    let struct_name = format!("{}HistoryEntry", to_pascal_case(&table.name));
+    let mut st = Struct::new(&struct_name);
+    st.vis("pub");
+    st.derive("Debug");
+    st.derive("Clone");
+    st.derive("Facet");
+    st.attr("facet(crate = dibs_runtime::facet)");
+    for col in &history.columns {
+        if let Some(ty) = ctx.column_type(&history_name, &col.name) {
+            st.field(format!("pub {}", col.name), &ty);
+        }
+    }
+    scope.push_struct(st);
+
+    let fn_name = format!("{}_history", to_snake_case(&table.name));
+    let mut func = Function::new(&fn_name);
+    func.doc(format!(
+        "Recorded changes of the `{}` row with the given primary key, newest first.",
+        table.name
+    ));
+    func.vis("pub");
+    func.set_async(true);
+    func.generic("C");
+    func.arg("client", "&C");
+    let arg_type = match row_id_type.as_str() {
+        "String" => "str".to_string(),
+        other => other.to_string(),
+    };
+    func.arg("row_id", format!("&{arg_type}"));
+    func.ret(format!("Result<Vec<{struct_name}>, QueryError>"));
+    func.bound("C", "tokio_postgres::GenericClient");
+
+    let columns: Vec<String> = history
+        .columns
+        .iter()
+        .map(|c| format!("\"{}\"", c.name))
+        .collect();
+    let mut block = Block::new("");
+    block.line(format!(
+        "const SQL: &str = r#\"SELECT {} FROM \"{}\" WHERE \"row_id\" = $1 ORDER BY \"changed_at\" DESC, \"id\" DESC\"#;",
+        columns.join(", "),
+        history_name
+    ));
+    block.line("");
+    block.line("let rows = client.query(SQL, &[&row_id]).await?;");
+    block.line("rows.iter().map(|row| Ok(from_row(row)?)).collect()");
+    func.line(wrap_with_trace_err(&block_to_string(&block), &fn_name));
+
+    scope.push_fn(func);
+}
+
 /// Helper to format a Block to a String.
 fn block_to_string(block: &Block) -> String {
     let mut output = String::new();
@@ -1123,7 +1525,7 @@ fn generate_insert_code(
 ) -> Result<(), QError> {
     let name = &name_meta.value;
     let fn_name = to_snake_case(name);
-    let generated = crate::sqlgen::generate_insert_sql(insert);
+    let generated = crate::sqlgen::generate_insert_sql(&_ctx.sqlgen_ctx(), insert);
 
     // Generate result struct if RETURNING is used
     let has_returning = insert.returning.is_some();
@@ -1230,7 +1632,7 @@ fn generate_insert_many_code(
 ) -> Result<(), QError> {
     let name = &name_meta.value;
     let fn_name = to_snake_case(name);
-    let generated = crate::sqlgen::generate_insert_many_sql(insert);
+    let generated = crate::sqlgen::generate_insert_many_sql(&ctx.sqlgen_ctx(), insert);
 
     // Generate params struct
     let params_struct_name = format!("{}Params", name);
@@ -1295,7 +1697,7 @@ fn generate_upsert_many_code(
 ) -> Result<(), QError> {
     let name = &name_meta.value;
     let fn_name = to_snake_case(name);
-    let generated = crate::sqlgen::generate_upsert_many_sql(upsert);
+    let generated = crate::sqlgen::generate_upsert_many_sql(&ctx.sqlgen_ctx(), upsert);
 
     // Generate params struct
     let params_struct_name = format!("{}Params", name);
@@ -32,10 +32,21 @@ fn make_test_table(name: &str, columns: &[ColDef], fks: Vec<ForeignKey>) -> Tabl
                 icon: None,
                 lang: None,
                 subtype: None,
+                domain: None,
+                composite: None,
             })
             .collect(),
         check_constraints: vec![],
+        exclusion_constraints: Vec::new(),
         trigger_checks: vec![],
+        triggers: Vec::new(),
+        partition_by: None,
+        rls: false,
+        policies: vec![],
+        soft_delete: None,
+        created_at: None,
+        updated_at: None,
+        audited: false,
         foreign_keys: fks,
         indices: vec![],
         source: SourceLocation::default(),
@@ -47,6 +58,9 @@ fn make_test_table(name: &str, columns: &[ColDef], fks: Vec<ForeignKey>) -> Tabl
 fn make_test_schema(tables: Vec<Table>) -> Schema {
     Schema {
         tables: tables.into_iter().map(|t| (t.name.clone(), t)).collect(),
+        extensions: Vec::new(),
+        domains: Vec::new(),
+        composites: Vec::new(),
     }
 }
 
@@ -176,6 +190,7 @@ ProductListing @select{
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         ),
     ]);
@@ -322,6 +337,7 @@ ProductWithTranslation @select{
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         ),
     ]);
@@ -397,6 +413,7 @@ ProductWithVariants @select{
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         ),
     ]);
@@ -447,6 +464,127 @@ ProductWithVariants @select{
     );
 }
 
+fn make_post_tag_schema(extra: Vec<Table>) -> Schema {
+    let fk = |column: &str, table: &str| ForeignKey {
+        columns: vec![column.to_string()],
+        references_table: table.to_string(),
+        references_columns: vec!["id".to_string()],
+        deferrable: false,
+    };
+    let mut tables = vec![
+        make_test_table(
+            "post",
+            &[
+                ("id", PgType::BigInt, false),
+                ("title", PgType::Text, false),
+            ],
+            vec![],
+        ),
+        make_test_table(
+            "tag",
+            &[("id", PgType::BigInt, false), ("name", PgType::Text, false)],
+            vec![],
+        ),
+        make_test_table(
+            "post_tag",
+            &[
+                ("post_id", PgType::BigInt, false),
+                ("tag_id", PgType::BigInt, false),
+            ],
+            vec![fk("post_id", "post"), fk("tag_id", "tag")],
+        ),
+    ];
+    tables.extend(extra);
+    make_test_schema(tables)
+}
+
+#[test]
+fn test_generate_many_to_many_relation_query() {
+    for through in ["", "through post_tag"] {
+        let source = format!(
+            r#"
+PostWithTags @select{{
+  from post
+  fields {{
+    id, title, tags @rel{{
+      from tag
+      {through}
+      fields {{ id, name }}
+    }}
+  }}
+}}
+"#
+        );
+        let (file, qsource) = parse_test(&source);
+        let code = generate_rust_code(&file, &make_post_tag_schema(vec![]), qsource).unwrap();
+
+        tracing::info!("Generated code:\n{}", code.code);
+
+        assert!(
+            code.code.contains("pub tags: Vec<PostWithTagsTags>"),
+            "Should have Vec tags field"
+        );
+        assert!(
+            code.code
+                .contains(r#"LEFT JOIN "post_tag" "t1" ON "t0"."id" = "t1"."post_id""#),
+            "Should join the junction table"
+        );
+        assert!(
+            code.code
+                .contains(r#"LEFT JOIN "tag" "t2" ON "t1"."tag_id" = "t2"."id""#),
+            "Should join tag through the junction table"
+        );
+        assert!(
+            code.code.contains("entry.tags.push"),
+            "Should append to tags"
+        );
+    }
+}
+
+#[test]
+fn test_generate_many_to_many_ambiguous_junction() {
+    let source = r#"
+PostWithTags @select{
+  from post
+  fields {
+    id, tags @rel{
+      from tag
+      fields { id, name }
+    }
+  }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let schema = make_post_tag_schema(vec![make_test_table(
+        "post_featured_tag",
+        &[
+            ("post_id", PgType::BigInt, false),
+            ("tag_id", PgType::BigInt, false),
+        ],
+        vec![
+            ForeignKey {
+                columns: vec!["post_id".to_string()],
+                references_table: "post".to_string(),
+                references_columns: vec!["id".to_string()],
+                deferrable: false,
+            },
+            ForeignKey {
+                columns: vec!["tag_id".to_string()],
+                references_table: "tag".to_string(),
+                references_columns: vec!["id".to_string()],
+                deferrable: false,
+            },
+        ],
+    )]);
+
+    let err = generate_rust_code(&file, &schema, qsource).unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("post_featured_tag, post_tag"),
+        "Should list the candidate junctions: {message}"
+    );
+}
+
 #[test]
 fn test_generate_count_query() {
     let source = r#"
@@ -477,6 +615,7 @@ ProductWithVariantCount @select{
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         ),
     ]);
@@ -508,6 +647,395 @@ ProductWithVariantCount @select{
     );
 }
 
+#[test]
+fn test_generate_stream_query() {
+    let source = r#"
+ExportProducts @select{
+  params {status @string}
+  from product
+  where {status $status}
+  stream true
+  fields { id, handle }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let schema = make_test_schema(vec![make_test_table(
+        "product",
+        &[
+            ("id", PgType::BigInt, false),
+            ("handle", PgType::Text, false),
+            ("status", PgType::Text, false),
+        ],
+        vec![],
+    )]);
+
+    let code = generate_rust_code(&file, &schema, qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    assert!(
+        code.code.contains(
+            "pub fn export_products<'a, C>(client: &'a C, status: &'a str) -> impl Stream<Item = Result<ExportProductsResult, QueryError>> + 'a"
+        ),
+        "Should return a stream instead of an async Vec"
+    );
+    assert!(
+        code.code
+            .contains("let params: [&(dyn tokio_postgres::types::ToSql + Sync); 1] = [&status];"),
+        "Should bind params for query_raw"
+    );
+    assert!(
+        code.code.contains("client.query_raw(SQL, params).await"),
+        "Should use query_raw"
+    );
+    assert!(
+        !code.code.contains("group_rows"),
+        "Queries without relations stream rows directly"
+    );
+}
+
+#[test]
+fn test_generate_stream_query_with_vec_relation() {
+    let source = r#"
+ExportProducts @select{
+  from product
+  order-by {handle desc}
+  stream true
+  fields {
+    id
+    handle
+    variants @rel{
+      from product_variant
+      fields { id, sku }
+    }
+  }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let schema = make_test_schema(vec![
+        make_test_table(
+            "product",
+            &[
+                ("id", PgType::BigInt, false),
+                ("handle", PgType::Text, false),
+            ],
+            vec![],
+        ),
+        make_test_table(
+            "product_variant",
+            &[
+                ("id", PgType::BigInt, false),
+                ("product_id", PgType::BigInt, false),
+                ("sku", PgType::Text, false),
+            ],
+            vec![ForeignKey {
+                columns: vec!["product_id".to_string()],
+                references_table: "product".to_string(),
+                references_columns: vec!["id".to_string()],
+                deferrable: false,
+            }],
+        ),
+    ]);
+
+    let code = generate_rust_code(&file, &schema, qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    assert!(
+        code.code
+            .contains("ORDER BY \"t0\".\"handle\" DESC, \"t0\".\"id\" ASC"),
+        "Should order by the parent key so a parent's rows are adjacent"
+    );
+    assert!(
+        code.code.contains(
+            "dibs_runtime::stream_rows::<ExportProductsRow, _>(\"export_products\", async move {"
+        ),
+        "Should stream flat rows"
+    );
+    assert!(
+        code.code.contains(
+            "dibs_runtime::group_rows(flat_rows, |flat_row: &ExportProductsRow| flat_row.id.clone(), |flat_rows: Vec<ExportProductsRow>| {"
+        ),
+        "Should group flat rows by parent key"
+    );
+    assert!(
+        code.code
+            .contains("entry.variants.push(ExportProductsVariants {"),
+        "Should assemble the relation per parent"
+    );
+    assert!(
+        code.code
+            .contains("parent?.ok_or_else(|| QueryError::Assemble("),
+        "An empty group should be an error, not a panic"
+    );
+}
+
+#[test]
+fn test_generate_stream_relation_without_parent_key_errors() {
+    let source = r#"
+ExportVariants @select{
+  from product
+  stream true
+  fields {
+    variants @rel{
+      from product_variant
+      fields { id, sku }
+    }
+  }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let schema = make_test_schema(vec![
+        make_test_table("product", &[("id", PgType::BigInt, false)], vec![]),
+        make_test_table(
+            "product_variant",
+            &[
+                ("id", PgType::BigInt, false),
+                ("product_id", PgType::BigInt, false),
+                ("sku", PgType::Text, false),
+            ],
+            vec![ForeignKey {
+                columns: vec!["product_id".to_string()],
+                references_table: "product".to_string(),
+                references_columns: vec!["id".to_string()],
+                deferrable: false,
+            }],
+        ),
+    ]);
+
+    let err = generate_rust_code(&file, &schema, qsource).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("the query must select a column such as `id`"),
+        "Should not guess the parent key: {err}"
+    );
+}
+
+#[test]
+fn test_generate_keyset_paginated_query() {
+    let source = r#"
+PostsPage @select{
+  params {limit @int}
+  from post
+  paginate @keyset{by {created_at desc, id desc}}
+  limit $limit
+  fields { id, title, created_at }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+
+    let schema = make_test_schema(vec![make_test_table(
+        "post",
+        &[
+            ("id", PgType::BigInt, false),
+            ("title", PgType::Text, false),
+            ("created_at", PgType::Timestamptz, false),
+        ],
+        vec![],
+    )]);
+
+    let code = generate_rust_code(&file, &schema, qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    assert!(
+        code.code.contains("pub struct PostsPageCursor"),
+        "Should generate a cursor struct"
+    );
+    assert!(
+        code.code.contains("    created_at: Timestamp,") && code.code.contains("    id: i64,"),
+        "Cursor fields should be private and typed from the schema"
+    );
+    assert!(
+        code.code.contains("cursor: Option<&PostsPageCursor>"),
+        "Should take the cursor of the previous page"
+    );
+    assert!(
+        code.code
+            .contains("Result<(Vec<PostsPageResult>, Option<PostsPageCursor>), QueryError>"),
+        "Should return the page with the next cursor"
+    );
+    assert!(
+        code.code
+            .contains("let cursor_created_at = &cursor.created_at;"),
+        "Should bind the cursor values"
+    );
+    assert!(
+        code.code
+            .contains("client.query(SQL, &[&cursor_created_at, &cursor_id, &limit])"),
+        "Should pass the cursor params in SQL order"
+    );
+    assert!(
+        code.code
+            .contains("None => client.query(FIRST_PAGE_SQL, &[&limit]).await?,"),
+        "Should run the first page without a cursor"
+    );
+    assert!(
+        !code.code.contains("IS NULL"),
+        "Neither statement should test the cursor for NULL"
+    );
+    assert!(
+        code.code
+            .contains("Some(last) if results.len() as i64 == *limit => Some(PostsPageCursor {"),
+        "Should only return a cursor for a full page"
+    );
+}
+
+#[test]
+fn test_generate_composite_column_errors() {
+    let source = r#"
+Shipments @select{
+  from shipment
+  fields { id, destination }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+
+    let mut shipment = make_test_table(
+        "shipment",
+        &[
+            ("id", PgType::BigInt, false),
+            ("destination", PgType::Composite, false),
+        ],
+        vec![],
+    );
+    shipment.columns[1].composite = Some("postal_address".to_string());
+    let schema = make_test_schema(vec![shipment]);
+
+    let err = generate_rust_code(&file, &schema, qsource).unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "'shipment.destination': columns of composite type 'postal_address' can't be read"
+        ),
+        "Should reject the composite column: {err}"
+    );
+}
+
+#[test]
+fn test_generate_keyset_on_non_unique_column_errors() {
+    let source = r#"
+PostsPage @select{
+  from post
+  paginate @keyset{by {created_at desc}}
+  limit 20
+  fields { id, created_at }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+
+    let schema = make_test_schema(vec![make_test_table(
+        "post",
+        &[
+            ("id", PgType::BigInt, false),
+            ("created_at", PgType::Timestamptz, false),
+        ],
+        vec![],
+    )]);
+
+    let err = generate_rust_code(&file, &schema, qsource).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("the last keyset column 'created_at' must be unique"),
+        "Should reject a keyset that doesn't end in a unique column: {err}"
+    );
+}
+
+#[test]
+fn test_generate_aggregate_query() {
+    let source = r#"
+SalesByCategory @select{
+  from order_item
+  group-by {category_id}
+  fields {
+    category_id
+    items @count
+    quantity @sum{column quantity}
+    revenue @sum{column amount}
+    avg_amount @avg{column amount}
+    avg_weight @avg{column weight}
+    first_sold @min{column sold_at}
+    biggest_discount @max{column discount}
+  }
+}
+
+OrderTotals @select{
+  from order_item
+  first true
+  fields { total @sum{column amount}, orders @count{column category_id} }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+
+    let schema = make_test_schema(vec![make_test_table(
+        "order_item",
+        &[
+            ("id", PgType::BigInt, false),
+            ("category_id", PgType::BigInt, false),
+            ("quantity", PgType::Integer, false),
+            ("amount", PgType::BigInt, false),
+            ("weight", PgType::DoublePrecision, false),
+            ("sold_at", PgType::Timestamptz, false),
+            ("discount", PgType::Numeric, true),
+        ],
+        vec![],
+    )]);
+
+    let code = generate_rust_code(&file, &schema, qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    for field in [
+        "pub category_id: i64",
+        "pub items: i64",
+        "pub quantity: i64",
+        "pub revenue: Decimal",
+        "pub avg_amount: Decimal",
+        "pub avg_weight: f64",
+        "pub first_sold: Timestamp",
+        "pub biggest_discount: Option<Decimal>",
+    ] {
+        assert!(code.code.contains(field), "Should have `{field}`");
+    }
+    assert!(
+        code.code.contains("GROUP BY \"t0\".\"category_id\""),
+        "Should generate GROUP BY"
+    );
+
+    // Without GROUP BY, SUM over no rows is NULL
+    assert!(
+        code.code.contains("pub total: Option<Decimal>"),
+        "Ungrouped SUM should be optional"
+    );
+    assert!(code.code.contains("pub orders: i64"), "COUNT is never NULL");
+}
+
+#[test]
+fn test_generate_aggregate_on_text_column_errors() {
+    let source = r#"
+HandleSum @select{
+  from product
+  fields { total @sum{column handle} }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+
+    let schema = make_test_schema(vec![make_test_table(
+        "product",
+        &[
+            ("id", PgType::BigInt, false),
+            ("handle", PgType::Text, false),
+        ],
+        vec![],
+    )]);
+
+    let err = generate_rust_code(&file, &schema, qsource).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid aggregate '@sum': it needs a numeric column, 'handle' is TEXT"),
+        "Should reject SUM of text: {err}"
+    );
+}
+
 #[test]
 fn test_generate_nested_vec_relation_query() {
     let source = r#"
@@ -546,6 +1074,7 @@ ProductWithVariantsAndPrices @select{
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         ),
         make_test_table(
@@ -560,6 +1089,7 @@ ProductWithVariantsAndPrices @select{
                 columns: vec!["variant_id".to_string()],
                 references_table: "product_variant".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         ),
     ]);
@@ -799,6 +1329,364 @@ DeleteUser @delete{
     assert!(code.code.contains("WHERE"));
 }
 
+fn make_soft_delete_schema() -> Schema {
+    let mut product = make_test_table(
+        "product",
+        &[
+            ("id", PgType::BigInt, false),
+            ("handle", PgType::Text, false),
+            ("deleted_at", PgType::Timestamptz, true),
+        ],
+        vec![],
+    );
+    product.soft_delete = Some("deleted_at".to_string());
+    let mut variant = make_test_table(
+        "product_variant",
+        &[
+            ("id", PgType::BigInt, false),
+            ("product_id", PgType::BigInt, false),
+            ("sku", PgType::Text, false),
+            ("deleted_at", PgType::Timestamptz, true),
+        ],
+        vec![ForeignKey {
+            columns: vec!["product_id".to_string()],
+            references_table: "product".to_string(),
+            references_columns: vec!["id".to_string()],
+            deferrable: false,
+        }],
+    );
+    variant.soft_delete = Some("deleted_at".to_string());
+    make_test_schema(vec![product, variant])
+}
+
+#[test]
+fn test_generate_soft_delete_select() {
+    let source = r#"
+ProductWithVariants @select{
+  params { handle @string }
+  from product
+  where { handle $handle }
+  fields {
+    id, handle, variants @rel{
+      from product_variant
+      fields { id, sku }
+    }
+  }
+}
+
+DeletedProducts @select{
+  from product
+  where { deleted_at @not-null }
+  fields { id }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let code = generate_rust_code(&file, &make_soft_delete_schema(), qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    assert!(
+        code.code.contains(r#"AND "t1"."deleted_at" IS NULL"#),
+        "Should hide soft-deleted variants in the join"
+    );
+    assert!(
+        code.code
+            .contains(r#"WHERE "t0"."handle" = $1 AND "t0"."deleted_at" IS NULL"#),
+        "Should hide soft-deleted products"
+    );
+    assert!(
+        code.code.contains(r#"WHERE "t0"."deleted_at" IS NOT NULL"#),
+        "Explicit filters on the soft-delete column win"
+    );
+}
+
+#[test]
+fn test_generate_soft_delete_relation_filters() {
+    // An equality filter on the soft-delete column replaces the implicit one
+    let source = r#"
+ProductWithVariantsDeletedAt @select{
+  params { handle @string, at @string }
+  from product
+  where { handle $handle }
+  fields {
+    id, variants @rel{
+      from product_variant
+      where { deleted_at $at }
+      fields { id }
+    }
+  }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let code = generate_rust_code(&file, &make_soft_delete_schema(), qsource).unwrap();
+    assert!(
+        code.code.contains(r#"AND "t1"."deleted_at" = $1"#),
+        "Should filter the join on the parameter"
+    );
+    assert!(
+        !code.code.contains(r#""t1"."deleted_at" IS NULL"#),
+        "The explicit filter replaces the implicit one"
+    );
+
+    // Other filters on the soft-delete column also replace the implicit one
+    let source = r#"
+ProductWithDeletedVariants @select{
+  from product
+  fields {
+    id, variants @rel{
+      from product_variant
+      where { deleted_at @not-null }
+      fields { id }
+    }
+  }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let code = generate_rust_code(&file, &make_soft_delete_schema(), qsource).unwrap();
+    assert!(
+        code.code
+            .contains(r#"ON "t0"."id" = "t1"."product_id" AND "t1"."deleted_at" IS NOT NULL"#),
+        "Should filter the join on deleted variants only: {}",
+        code.code
+    );
+    assert!(!code.code.contains(r#""t1"."deleted_at" IS NULL"#));
+}
+
+#[test]
+fn test_generate_relation_filter_groups() {
+    let source = r#"
+ProductWithMatchingVariants @select{
+  params { handle @string, q @string }
+  from product
+  where { handle $handle }
+  fields {
+    id, variants @rel{
+      from product_variant
+      where {
+        match @or{ sku @ilike($q), id 1 }
+      }
+      fields { id, sku }
+    }
+  }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let code = generate_rust_code(&file, &make_soft_delete_schema(), qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    assert!(
+        code.code.contains(
+            r#"ON "t0"."id" = "t1"."product_id" AND "t1"."deleted_at" IS NULL AND ("t1"."sku" ILIKE $1 OR "t1"."id" = 1)"#
+        ),
+        "Should qualify the group with the relation's alias: {}",
+        code.code
+    );
+    assert!(
+        code.code.contains("client.query(SQL, &[&q, &handle])"),
+        "Join params bind before WHERE params"
+    );
+}
+
+#[test]
+fn test_generate_filter_groups_select() {
+    let source = r#"
+ProductsByPriceRange @select{
+  params { min_price @int, max_price @int, q @string }
+  from product
+  where {
+    price @and(@gte($min_price) @lte($max_price))
+    search @or{ handle @ilike($q), status "featured" }
+  }
+  fields { id, handle }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let code = generate_rust_code(&file, &make_soft_delete_schema(), qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    assert!(
+        code.code.contains(
+            r#"WHERE "t0"."price" >= $1 AND "t0"."price" <= $2 AND ("t0"."handle" ILIKE $3 OR "t0"."status" = 'featured') AND "t0"."deleted_at" IS NULL"#
+        ),
+        "Should qualify and parenthesize grouped filters"
+    );
+    assert!(
+        code.code
+            .contains("client.query(SQL, &[&min_price, &max_price, &q])"),
+        "Params should bind in filter order"
+    );
+}
+
+#[test]
+fn test_generate_soft_delete_delete() {
+    let source = r#"
+DeleteProduct @delete{
+  params { id @int }
+  from product
+  where { id $id }
+  returning { id }
+}
+
+PurgeProduct @delete{
+  params { id @int }
+  from product
+  where { id $id }
+  hard true
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let code = generate_rust_code(&file, &make_soft_delete_schema(), qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    assert!(
+        code.code.contains(r#"SET "deleted_at" = NOW()"#),
+        "Should soft-delete by default"
+    );
+    assert!(
+        code.code
+            .contains(r#"WHERE "id" = $1 AND "deleted_at" IS NULL"#),
+        "Should only soft-delete live rows"
+    );
+    assert!(
+        code.code.contains(r#"DELETE FROM "product""#),
+        "hard true should really delete"
+    );
+}
+
+#[test]
+fn test_generate_fills_timestamp_columns() {
+    let source = r#"
+CreatePost @insert{
+  params { title @string }
+  into post
+  values { title $title }
+}
+
+ImportPost @insert{
+  params { title @string, created_at @timestamp }
+  into post
+  values { title $title, created_at $created_at }
+}
+
+RenamePost @update{
+  params { id @int, title @string }
+  table post
+  set { title $title }
+  where { id $id }
+}
+
+UpsertPost @upsert{
+  params { id @int, title @string }
+  into post
+  on-conflict {
+    target { id }
+    update { title }
+  }
+  values { id $id, title $title }
+}
+
+BulkCreatePosts @insert-many{
+  params { title @string }
+  into post
+  values { title }
+}
+
+BulkUpsertPosts @upsert-many{
+  params { id @int, title @string }
+  into post
+  on-conflict {
+    target { id }
+    update { title }
+  }
+  values { id, title }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let mut post = make_test_table(
+        "post",
+        &[
+            ("id", PgType::BigInt, false),
+            ("title", PgType::Text, false),
+            ("created_at", PgType::Timestamptz, false),
+            ("updated_at", PgType::Timestamptz, false),
+        ],
+        vec![],
+    );
+    post.created_at = Some("created_at".to_string());
+    post.updated_at = Some("updated_at".to_string());
+    let code = generate_rust_code(&file, &make_test_schema(vec![post]), qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    assert!(
+        code.code
+            .contains(r#"INSERT INTO "post" ("title", "created_at", "updated_at")"#),
+        "Should fill both timestamps on insert"
+    );
+    assert!(
+        code.code.contains(r#"VALUES ($1, $2, NOW())"#),
+        "Explicit values win over generated ones"
+    );
+    assert!(
+        code.code
+            .contains(r#"SET "title" = $1, "updated_at" = NOW()"#),
+        "Should touch updated_at on update"
+    );
+    assert!(
+        code.code.contains(r#""updated_at" = NOW()"#) && code.code.contains("DO UPDATE SET"),
+        "Should touch updated_at on conflict"
+    );
+    assert!(
+        code.code
+            .contains(r#"SELECT title, NOW(), NOW() FROM UNNEST"#),
+        "Should fill timestamps on bulk insert"
+    );
+    assert!(
+        code.code
+            .contains(r#"DO UPDATE SET "title" = EXCLUDED."title", "updated_at" = NOW()"#),
+        "Should touch updated_at on bulk upsert"
+    );
+}
+
+#[test]
+fn test_generate_history_helper() {
+    let source = r#"
+AllPosts @select{
+  from post
+  fields { id }
+}
+"#;
+    let (file, qsource) = parse_test(source);
+    let mut post = make_test_table(
+        "post",
+        &[
+            ("id", PgType::BigInt, false),
+            ("title", PgType::Text, false),
+        ],
+        vec![],
+    );
+    post.audited = true;
+    let history = post.history_table().expect("history table");
+    let code = generate_rust_code(&file, &make_test_schema(vec![post, history]), qsource).unwrap();
+
+    tracing::info!("Generated code:\n{}", code.code);
+
+    assert!(code.code.contains("pub struct PostHistoryEntry"));
+    assert!(code.code.contains("pub row_id: i64"));
+    assert!(
+        code.code
+            .contains("pub old_row: Option<Jsonb<facet_value::Value>>")
+    );
+    assert!(code.code.contains("pub async fn post_history"));
+    assert!(code.code.contains("row_id: &i64"));
+    assert!(code.code.contains(
+        r#"FROM "post_history" WHERE "row_id" = $1 ORDER BY "changed_at" DESC, "id" DESC"#
+    ));
+}
+
 #[test]
 fn test_generate_insert_without_returning() {
     let source = r#"
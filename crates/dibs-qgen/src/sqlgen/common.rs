@@ -7,7 +7,8 @@ use crate::filter_spec::{
 };
 use crate::{FilterArg, QError, QErrorKind};
 use dibs_query_schema::{
-    FilterValue, Meta, ParamType, Params, Payload, Span, UpdateValue, ValueExpr, Where,
+    FilterValue, Junction, JunctionKind, Meta, ParamType, Params, Payload, Span, UpdateValue,
+    ValueExpr, Where,
 };
 use dibs_sql::{BinOp, ColumnName, Expr, TableName};
 
@@ -90,10 +91,50 @@ pub fn filter_value_to_expr_validated(
     filter: &FilterValue,
     filter_span: Span,
 ) -> Result<Option<Expr>, QError> {
-    let col = column_name_to_expr(column);
+    filter_value_to_expr_with(ctx, column, filter, filter_span, &column_name_to_expr)
+}
+
+/// Like [`filter_value_to_expr_validated`], but `resolve` turns the filtered
+/// name into the expression being filtered (e.g. an aggregate in HAVING).
+pub fn filter_value_to_expr_with(
+    ctx: &SqlGenContext,
+    column: &ColumnName,
+    filter: &FilterValue,
+    filter_span: Span,
+    resolve: &dyn Fn(&ColumnName) -> Expr,
+) -> Result<Option<Expr>, QError> {
+    let col = resolve(column);
     let unqualified_name = extract_column_name(column);
 
     match filter {
+        FilterValue::Or(junction) => junction_to_expr_validated(
+            ctx,
+            column,
+            JunctionKind::Or,
+            junction,
+            filter_span,
+            resolve,
+        )
+        .map(Some),
+        FilterValue::And(junction) => junction_to_expr_validated(
+            ctx,
+            column,
+            JunctionKind::And,
+            junction,
+            filter_span,
+            resolve,
+        )
+        .map(Some),
+        FilterValue::Not(junction) => junction_to_expr_validated(
+            ctx,
+            column,
+            JunctionKind::Not,
+            junction,
+            filter_span,
+            resolve,
+        )
+        .map(Some),
+
         FilterValue::Null => Ok(Some(col.is_null())),
         FilterValue::NotNull => Ok(Some(col.is_not_null())),
 
@@ -208,6 +249,77 @@ pub fn filter_value_to_expr_validated(
     }
 }
 
+/// Convert an `@or`/`@and`/`@not` filter on `column` to a dibs_sql::Expr.
+///
+/// For a `{...}` group, `column` is only a label, but its table qualifier (if
+/// any) is carried over to the group's columns. The renderer parenthesizes
+/// the result where precedence requires it.
+fn junction_to_expr_validated(
+    ctx: &SqlGenContext,
+    column: &ColumnName,
+    kind: JunctionKind,
+    junction: &Junction,
+    filter_span: Span,
+    resolve: &dyn Fn(&ColumnName) -> Expr,
+) -> Result<Expr, QError> {
+    if junction.is_empty() {
+        return Err(QError {
+            source: ctx.source.clone(),
+            span: filter_span,
+            kind: QErrorKind::EmptyFilterGroup {
+                group: kind.tag().to_string(),
+            },
+        });
+    }
+
+    let mut operands = vec![];
+    match junction {
+        Junction::Group(group) => {
+            let qualifier = column.as_str().split_once('.').map(|(table, _)| table);
+            for (col_meta, filter_value) in &group.filters {
+                let col_name: ColumnName = match qualifier {
+                    Some(table) => format!("{table}.{}", col_meta.value).into(),
+                    None => col_meta.value.clone(),
+                };
+                operands.extend(filter_value_to_expr_with(
+                    ctx,
+                    &col_name,
+                    filter_value,
+                    col_meta.span,
+                    resolve,
+                )?);
+            }
+        }
+        Junction::Each(filters) => {
+            for filter_value in filters {
+                operands.extend(filter_value_to_expr_with(
+                    ctx,
+                    column,
+                    filter_value,
+                    filter_span,
+                    resolve,
+                )?);
+            }
+        }
+    }
+
+    let op = match kind {
+        JunctionKind::Or => BinOp::Or,
+        JunctionKind::And | JunctionKind::Not => BinOp::And,
+    };
+    let mut iter = operands.into_iter();
+    let first = iter.next().expect("junction has operands");
+    let combined = iter.fold(first, |acc, expr| Expr::BinOp {
+        left: Box::new(acc),
+        op,
+        right: Box::new(expr),
+    });
+    Ok(match kind {
+        JunctionKind::Not => combined.negate(),
+        JunctionKind::Or | JunctionKind::And => combined,
+    })
+}
+
 /// Convert a WHERE clause to a dibs_sql::Expr with validation.
 ///
 /// Validates all filter arguments using FunctionSpec and returns rich errors.
@@ -426,3 +538,43 @@ pub fn update_value_to_expr(
     };
     Ok(cast_for_jsonb_param(raw, params))
 }
+
+/// Timestamp columns an INSERT into `table` fills with `NOW()` because the
+/// schema marks them `created_at`/`updated_at` and the query doesn't set them.
+pub fn insert_timestamp_columns<'c>(
+    ctx: &SqlGenContext,
+    table: &TableName,
+    columns: impl IntoIterator<Item = &'c ColumnName> + Clone,
+) -> Vec<ColumnName> {
+    let Some(table) = ctx.schema.tables.get(table.as_str()) else {
+        return vec![];
+    };
+    [&table.created_at, &table.updated_at]
+        .into_iter()
+        .flatten()
+        .filter(|auto| {
+            !columns
+                .clone()
+                .into_iter()
+                .any(|c| c.as_str() == auto.as_str())
+        })
+        .map(|auto| auto.as_str().into())
+        .collect()
+}
+
+/// The `updated_at` column an UPDATE of `table` sets to `NOW()`, unless the
+/// query already assigns it.
+pub fn update_timestamp_column<'c>(
+    ctx: &SqlGenContext,
+    table: &TableName,
+    columns: impl IntoIterator<Item = &'c ColumnName>,
+) -> Option<ColumnName> {
+    let column = ctx
+        .schema
+        .tables
+        .get(table.as_str())?
+        .updated_at
+        .as_deref()?;
+    let assigned = columns.into_iter().any(|c| c.as_str() == column);
+    (!assigned).then(|| column.into())
+}
@@ -4,7 +4,7 @@ use super::SqlGenContext;
 use super::common::where_to_expr_validated;
 use crate::QError;
 use dibs_query_schema::Delete;
-use dibs_sql::{ColumnName, DeleteStmt, ParamName, render};
+use dibs_sql::{ColumnName, DeleteStmt, Expr, ParamName, UpdateStmt, render};
 
 /// Generated SQL with parameter info.
 #[derive(Debug, Clone)]
@@ -20,18 +20,17 @@ pub struct GeneratedDelete {
 }
 
 /// Generate SQL for a DELETE statement.
+///
+/// On a soft-delete table this becomes an UPDATE that sets the soft-delete
+/// column on rows that don't have it yet, unless the query says `hard true`.
 pub fn generate_delete_sql(
     ctx: &SqlGenContext,
     delete: &Delete,
 ) -> Result<GeneratedDelete, QError> {
-    let mut stmt = DeleteStmt::new(delete.from.value.clone());
-
-    // WHERE clause
-    if let Some(where_clause) = &delete.where_clause
-        && let Some(expr) = where_to_expr_validated(ctx, where_clause)?
-    {
-        stmt = stmt.where_(expr);
-    }
+    let where_expr = match &delete.where_clause {
+        Some(where_clause) => where_to_expr_validated(ctx, where_clause)?,
+        None => None,
+    };
 
     // RETURNING clause
     let returning_columns: Vec<ColumnName> = if let Some(returning) = &delete.returning {
@@ -40,11 +39,37 @@ pub fn generate_delete_sql(
         vec![]
     };
 
-    for col in &returning_columns {
-        stmt = stmt.returning([col.clone()]);
-    }
-
-    let rendered = render(&stmt);
+    let hard = delete.hard.as_ref().is_some_and(|m| m.value);
+    let soft_delete_column = ctx
+        .schema
+        .tables
+        .get(delete.from.value.as_str())
+        .and_then(|t| t.soft_delete.as_deref())
+        .filter(|_| !hard);
+
+    let rendered = if let Some(column) = soft_delete_column {
+        let column: ColumnName = column.into();
+        let not_deleted = Expr::column(column.clone()).is_null();
+        let mut stmt = UpdateStmt::new(delete.from.value.clone())
+            .set(column, Expr::Now)
+            .where_(match where_expr {
+                Some(expr) => expr.and(not_deleted),
+                None => not_deleted,
+            });
+        for col in &returning_columns {
+            stmt = stmt.returning([col.clone()]);
+        }
+        render(&stmt)
+    } else {
+        let mut stmt = DeleteStmt::new(delete.from.value.clone());
+        if let Some(expr) = where_expr {
+            stmt = stmt.where_(expr);
+        }
+        for col in &returning_columns {
+            stmt = stmt.returning([col.clone()]);
+        }
+        render(&stmt)
+    };
 
     Ok(GeneratedDelete {
         sql: rendered.sql,
@@ -118,6 +143,49 @@ DeleteUserPosts @delete{
         insta::assert_snapshot!(result.sql);
     }
 
+    #[test]
+    fn test_delete_filter_groups() {
+        let source = r#"
+DeleteStaleSessions @delete{
+    params {user_id @int, now @timestamp, min @int, max @int, kept @string}
+    from sessions
+    where {
+        user_id $user_id
+        stale @or{
+            expired_at @lt($now)
+            revoked true
+        }
+        attempts @and(@gte($min) @lte($max))
+        keep @not{kind $kept, pinned true}
+    }
+}
+"#;
+        let (delete, qsource) = get_first_delete(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_delete_sql(&ctx, &delete).unwrap();
+        insta::assert_snapshot!(result.sql);
+    }
+
+    #[test]
+    fn test_delete_empty_filter_group_produces_error() {
+        let source = r#"
+DeleteNothing @delete{
+    from records
+    where {id $id, either @or{}}
+}
+"#;
+        let (delete, qsource) = get_first_delete(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let err = generate_delete_sql(&ctx, &delete).unwrap_err();
+        assert!(
+            err.to_string().contains("empty filter group '@or'"),
+            "Error should name the empty group: {}",
+            err
+        );
+    }
+
     #[test]
     fn test_delete_invalid_filter_args_produces_error() {
         // @lt requires exactly 1 argument, but we provide 2 (whitespace-separated in styx)
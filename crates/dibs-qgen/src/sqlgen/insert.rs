@@ -1,8 +1,9 @@
 //! SQL generation for INSERT statements.
 
-use super::common::value_expr_to_expr;
+use super::SqlGenContext;
+use super::common::{insert_timestamp_columns, value_expr_to_expr};
 use dibs_query_schema::Insert;
-use dibs_sql::{ColumnName, InsertStmt, ParamName, render};
+use dibs_sql::{ColumnName, Expr, InsertStmt, ParamName, render};
 
 /// Generated SQL with parameter info.
 #[derive(Debug, Clone)]
@@ -18,7 +19,7 @@ pub struct GeneratedInsert {
 }
 
 /// Generate SQL for an INSERT statement.
-pub fn generate_insert_sql(insert: &Insert) -> GeneratedInsert {
+pub fn generate_insert_sql(ctx: &SqlGenContext, insert: &Insert) -> GeneratedInsert {
     let mut stmt = InsertStmt::new(insert.into.value.clone());
 
     // VALUES clause
@@ -28,6 +29,12 @@ pub fn generate_insert_sql(insert: &Insert) -> GeneratedInsert {
         stmt = stmt.column(col_name.clone(), expr);
     }
 
+    // Schema-managed timestamps the query leaves out
+    let listed = insert.values.columns.keys().map(|k| &k.value);
+    for col in insert_timestamp_columns(ctx, &insert.into.value, listed) {
+        stmt = stmt.column(col, Expr::Now);
+    }
+
     // RETURNING clause
     let returning_columns: Vec<ColumnName> = if let Some(returning) = &insert.returning {
         returning.columns.keys().map(|k| k.value.clone()).collect()
@@ -52,12 +59,13 @@ pub fn generate_insert_sql(insert: &Insert) -> GeneratedInsert {
 mod tests {
     use super::*;
     use crate::parse_query_file;
+    use dibs_db_schema::Schema;
 
-    fn get_first_insert(source: &str) -> Insert {
-        let (file, _source) = parse_query_file(camino::Utf8Path::new("<test>"), source).unwrap();
+    fn get_first_insert(source: &str) -> (Insert, crate::QSource) {
+        let (file, qsource) = parse_query_file(camino::Utf8Path::new("<test>"), source).unwrap();
         for (_, decl) in file.0.iter() {
             if let dibs_query_schema::Decl::Insert(i) = decl {
-                return i.clone();
+                return (i.clone(), (*qsource).clone());
             }
         }
         panic!("No insert found in source");
@@ -73,8 +81,10 @@ CreateUser @insert{
     returning {id}
 }
 "#;
-        let insert = get_first_insert(source);
-        let result = generate_insert_sql(&insert);
+        let (insert, qsource) = get_first_insert(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_insert_sql(&ctx, &insert);
         insta::assert_snapshot!(result.sql);
     }
 
@@ -88,8 +98,10 @@ CreateUser @insert{
     returning {id, name, email, created_at}
 }
 "#;
-        let insert = get_first_insert(source);
-        let result = generate_insert_sql(&insert);
+        let (insert, qsource) = get_first_insert(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_insert_sql(&ctx, &insert);
         insta::assert_snapshot!(result.sql);
     }
 
@@ -103,8 +115,10 @@ CreateProduct @insert{
     returning {id, name, status}
 }
 "#;
-        let insert = get_first_insert(source);
-        let result = generate_insert_sql(&insert);
+        let (insert, qsource) = get_first_insert(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_insert_sql(&ctx, &insert);
         insta::assert_snapshot!(result.sql);
     }
 
@@ -118,8 +132,10 @@ CreateUser @insert{
     returning {id}
 }
 "#;
-        let insert = get_first_insert(source);
-        let result = generate_insert_sql(&insert);
+        let (insert, qsource) = get_first_insert(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_insert_sql(&ctx, &insert);
         insta::assert_snapshot!(result.sql);
     }
 
@@ -137,8 +153,10 @@ RecordEvent @insert{
     returning {id}
 }
 "#;
-        let insert = get_first_insert(source);
-        let result = generate_insert_sql(&insert);
+        let (insert, qsource) = get_first_insert(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_insert_sql(&ctx, &insert);
         insta::assert_snapshot!(result.sql);
     }
 }
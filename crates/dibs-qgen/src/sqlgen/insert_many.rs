@@ -8,6 +8,8 @@
 //! RETURNING id, handle, status
 //! ```
 
+use super::SqlGenContext;
+use super::common::insert_timestamp_columns;
 use dibs_query_schema::{InsertMany, ParamType, Payload, ValueExpr};
 use dibs_sql::{ColumnName, ParamName};
 
@@ -25,7 +27,7 @@ pub struct GeneratedInsertMany {
 }
 
 /// Generate SQL for a bulk INSERT statement.
-pub fn generate_insert_many_sql(insert: &InsertMany) -> GeneratedInsertMany {
+pub fn generate_insert_many_sql(ctx: &SqlGenContext, insert: &InsertMany) -> GeneratedInsertMany {
     let mut sql = String::new();
     let mut params: Vec<ParamName> = Vec::new();
 
@@ -41,11 +43,19 @@ pub fn generate_insert_many_sql(insert: &InsertMany) -> GeneratedInsertMany {
     sql.push_str(insert.into.value.as_str());
     sql.push_str("\" (");
 
+    // Schema-managed timestamps the query leaves out are filled with NOW()
+    let timestamp_columns = insert_timestamp_columns(
+        ctx,
+        &insert.into.value,
+        insert.values.columns.keys().map(|k| &k.value),
+    );
+
     let columns: Vec<&str> = insert
         .values
         .columns
         .keys()
         .map(|k| k.value.as_str())
+        .chain(timestamp_columns.iter().map(|c| c.as_str()))
         .collect();
     sql.push_str(
         &columns
@@ -64,6 +74,7 @@ pub fn generate_insert_many_sql(insert: &InsertMany) -> GeneratedInsertMany {
         .columns
         .iter()
         .map(|(col_meta, expr)| value_expr_to_unnest_select(&col_meta.value, expr, &param_names))
+        .chain(timestamp_columns.iter().map(|_| "NOW()".to_string()))
         .collect();
     sql.push_str(&select_exprs.join(", "));
 
@@ -209,12 +220,13 @@ fn param_type_to_pg_array(ty: &ParamType) -> &'static str {
 mod tests {
     use super::*;
     use crate::parse_query_file;
+    use dibs_db_schema::Schema;
 
-    fn get_first_insert_many(source: &str) -> InsertMany {
-        let (file, _source) = parse_query_file(camino::Utf8Path::new("<test>"), source).unwrap();
+    fn get_first_insert_many(source: &str) -> (InsertMany, crate::QSource) {
+        let (file, qsource) = parse_query_file(camino::Utf8Path::new("<test>"), source).unwrap();
         for (_, decl) in file.0.iter() {
             if let dibs_query_schema::Decl::InsertMany(im) = decl {
-                return im.clone();
+                return (im.clone(), (*qsource).clone());
             }
         }
         panic!("No insert-many found in source");
@@ -230,8 +242,10 @@ BulkCreateProducts @insert-many{
     returning {id, handle, status}
 }
 "#;
-        let insert_many = get_first_insert_many(source);
-        let result = generate_insert_many_sql(&insert_many);
+        let (insert_many, qsource) = get_first_insert_many(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_insert_many_sql(&ctx, &insert_many);
         insta::assert_snapshot!(result.sql);
     }
 
@@ -245,8 +259,10 @@ BulkCreateProducts @insert-many{
     returning {id, handle, status, created_at}
 }
 "#;
-        let insert_many = get_first_insert_many(source);
-        let result = generate_insert_many_sql(&insert_many);
+        let (insert_many, qsource) = get_first_insert_many(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_insert_many_sql(&ctx, &insert_many);
         insta::assert_snapshot!(result.sql);
     }
 
@@ -260,8 +276,10 @@ BulkCreateUsers @insert-many{
     returning {id}
 }
 "#;
-        let insert_many = get_first_insert_many(source);
-        let result = generate_insert_many_sql(&insert_many);
+        let (insert_many, qsource) = get_first_insert_many(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_insert_many_sql(&ctx, &insert_many);
         insta::assert_snapshot!(result.sql);
     }
 }
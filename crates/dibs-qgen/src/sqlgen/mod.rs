@@ -18,6 +18,7 @@ pub use delete::{GeneratedDelete, generate_delete_sql};
 pub use insert::{GeneratedInsert, generate_insert_sql};
 pub use insert_many::{GeneratedInsertMany, generate_insert_many_sql};
 pub use select::{GeneratedSelect, generate_select_sql};
+pub(crate) use select::{keyset_cursor_param, stream_parent_key};
 pub use update::{GeneratedUpdate, generate_update_sql};
 pub use upsert::{GeneratedUpsert, generate_upsert_sql};
 pub use upsert_many::{GeneratedUpsertMany, generate_upsert_many_sql};
@@ -1,12 +1,17 @@
 //! SQL generation for SELECT statements.
 
 use super::SqlGenContext;
-use super::common::{filter_value_to_expr_validated, meta_string_to_expr};
-use crate::{QError, QueryPlan, QueryPlanner};
-use dibs_query_schema::{OrderBy as QueryOrderBy, Select, Where};
+use super::common::{
+    filter_value_to_expr_validated, filter_value_to_expr_with, meta_string_to_expr,
+};
+use crate::planner::AggregateColumn;
+use crate::{QError, QErrorKind, QueryPlan, QueryPlanner};
+use dibs_query_schema::{
+    AggregateFn, Keyset, Meta, OrderBy as QueryOrderBy, Select, SelectFields, Span, Where,
+};
 use dibs_sql::{
-    ColumnName, Expr, FromClause, Join, JoinKind, OrderBy, ParamName, SelectColumn, SelectStmt,
-    render,
+    BinOp, ColumnName, Expr, FromClause, Join, JoinKind, OrderBy, ParamName, RenderedSql,
+    SelectColumn, SelectStmt, render,
 };
 use std::collections::HashMap;
 
@@ -22,14 +27,28 @@ pub struct GeneratedSelect {
     /// Column names in SELECT order (for index-based access).
     /// Maps column names to their index in the result set.
     pub column_order: HashMap<ColumnName, usize>,
+    /// For keyset-paginated queries, the SQL of the first page: the same
+    /// query without the cursor predicate, with its own parameter order.
+    pub first_page: Option<RenderedSql>,
 }
 
 /// Generate SQL for a SELECT query using the planner.
 pub fn generate_select_sql(ctx: &SqlGenContext, query: &Select) -> Result<GeneratedSelect, QError> {
+    if let Some(fields) = &query.fields {
+        validate_aggregates(ctx, fields)?;
+    }
+    let keyset = query.keyset();
+    if let Some(keyset) = keyset {
+        validate_keyset(ctx, query, keyset)?;
+    }
+    if query.is_stream() {
+        validate_stream(ctx, query)?;
+    }
+
     // Plan the query
     let planner = QueryPlanner::new(ctx.schema);
     let plan = planner.plan(query).map_err(|e| {
-        use crate::{QErrorKind, planner::PlanError};
+        use crate::planner::PlanError;
         let kind = match e {
             PlanError::TableNotFound { table } => {
                 let mut available: Vec<String> = ctx.schema.tables.keys().cloned().collect();
@@ -42,6 +61,11 @@ pub fn generate_select_sql(ctx: &SqlGenContext, query: &Select) -> Result<Genera
             PlanError::RelationNeedsFrom { relation } => QErrorKind::PlanMissing {
                 reason: format!("relation '{relation}' requires explicit 'from' clause"),
             },
+            e @ (PlanError::InvalidJunction { .. } | PlanError::AmbiguousJunction { .. }) => {
+                QErrorKind::PlanMissing {
+                    reason: e.to_string(),
+                }
+            }
         };
         crate::QError {
             source: ctx.source.clone(),
@@ -65,6 +89,10 @@ pub fn generate_select_sql(ctx: &SqlGenContext, query: &Select) -> Result<Genera
         column_order.insert(count.result_alias.clone(), col_idx);
         col_idx += 1;
     }
+    for aggregate in &plan.aggregates {
+        column_order.insert(aggregate.result_alias.clone(), col_idx);
+        col_idx += 1;
+    }
 
     // Build SelectStmt using builder API
     let mut stmt = SelectStmt::new();
@@ -103,6 +131,14 @@ pub fn generate_select_sql(ctx: &SqlGenContext, query: &Select) -> Result<Genera
         ));
     }
 
+    // Aggregates (from plan)
+    let mut aggregate_exprs: HashMap<ColumnName, Expr> = HashMap::new();
+    for aggregate in &plan.aggregates {
+        let expr = aggregate_to_expr(aggregate);
+        aggregate_exprs.insert(aggregate.result_alias.clone(), expr.clone());
+        stmt = stmt.column(SelectColumn::aliased(expr, aggregate.result_alias.clone()));
+    }
+
     // FROM (from plan)
     stmt = stmt.from(FromClause::aliased(
         plan.from_table.clone(),
@@ -120,14 +156,19 @@ pub fn generate_select_sql(ctx: &SqlGenContext, query: &Select) -> Result<Genera
 
         // Add extra conditions from relation-level WHERE
         for cond in &join_clause.extra_conditions {
-            let value_expr = match &cond.value {
-                crate::planner::JoinConditionValue::Param(p) => Expr::param(p.clone()),
-                crate::planner::JoinConditionValue::Literal(lit) => Expr::string(lit),
+            let column =
+                Expr::qualified_column(join_clause.alias.as_str().into(), cond.column.clone());
+            let condition = match &cond.value {
+                crate::planner::JoinConditionValue::Param(p) => column.eq(Expr::param(p.clone())),
+                crate::planner::JoinConditionValue::Literal(lit) => column.eq(Expr::string(lit)),
+                crate::planner::JoinConditionValue::Null => column.is_null(),
             };
-            on_expr = on_expr.and(
-                Expr::qualified_column(join_clause.alias.as_str().into(), cond.column.clone())
-                    .eq(value_expr),
-            );
+            on_expr = on_expr.and(condition);
+        }
+        if let Some(filters) = &join_clause.extra_where
+            && let Some(expr) = where_to_qualified_expr_validated(ctx, filters, &join_clause.alias)?
+        {
+            on_expr = on_expr.and(expr);
         }
 
         let kind = match join_clause.join_type {
@@ -143,20 +184,71 @@ pub fn generate_select_sql(ctx: &SqlGenContext, query: &Select) -> Result<Genera
         });
     }
 
-    // WHERE
-    if let Some(where_clause) = &query.where_clause
-        && let Some(expr) = where_to_qualified_expr_validated(ctx, where_clause, "t0")?
+    // WHERE, skipping soft-deleted rows unless the query filters on them
+    let mut where_expr = match &query.where_clause {
+        Some(where_clause) => where_to_qualified_expr_validated(ctx, where_clause, "t0")?,
+        None => None,
+    };
+    if let Some(column) = crate::planner::soft_delete_column(
+        ctx.schema,
+        &plan.from_table,
+        query.where_clause.as_ref(),
+    ) {
+        let not_deleted = Expr::qualified_column("t0".into(), column).is_null();
+        where_expr = Some(match where_expr {
+            Some(expr) => expr.and(not_deleted),
+            None => not_deleted,
+        });
+    }
+    // The cursor predicate is added last, once the first page is rendered
+    let after_cursor = keyset.map(|keyset| keyset_to_expr(keyset, "t0"));
+
+    // GROUP BY
+    if let Some(group_by) = &query.group_by {
+        stmt = stmt.group_by(
+            group_by
+                .columns
+                .keys()
+                .map(|col| Expr::qualified_column("t0".into(), col.value.clone())),
+        );
+    }
+
+    // HAVING
+    if let Some(having) = &query.having
+        && let Some(expr) = having_to_expr_validated(ctx, having, &aggregate_exprs)?
     {
-        stmt = stmt.where_(expr);
+        stmt = stmt.having(expr);
     }
 
-    // ORDER BY
-    if let Some(order_by) = &query.order_by {
+    // ORDER BY (keyset pagination orders by its own columns)
+    if let Some(keyset) = keyset {
+        for order in order_by_to_ast(&keyset.by, "t0") {
+            stmt = stmt.order_by(order);
+        }
+    } else if let Some(order_by) = &query.order_by {
         for order in order_by_to_ast(order_by, "t0") {
             stmt = stmt.order_by(order);
         }
     }
 
+    // Streamed has-many relations are grouped as rows arrive, so each
+    // parent's rows must be adjacent: break ties by the parent key.
+    if query.is_stream()
+        && let Some(fields) = query.fields.as_ref().filter(|f| f.has_vec_relations())
+    {
+        let parent_key = stream_parent_key(ctx, query, fields)?.clone();
+        let already_ordered = query
+            .order_by
+            .as_ref()
+            .is_some_and(|o| o.columns.keys().any(|c| c.value == parent_key));
+        if !already_ordered {
+            stmt = stmt.order_by(OrderBy::asc(Expr::qualified_column(
+                "t0".into(),
+                parent_key,
+            )));
+        }
+    }
+
     // LIMIT
     if let Some(limit) = &query.limit {
         stmt = stmt.limit(meta_string_to_expr(limit));
@@ -167,7 +259,21 @@ pub fn generate_select_sql(ctx: &SqlGenContext, query: &Select) -> Result<Genera
         stmt = stmt.offset(meta_string_to_expr(offset));
     }
 
-    // Render once
+    // Keyset pagination renders twice: the first page has no cursor to
+    // compare against, and a separate statement keeps the predicate sargable
+    let first_page = after_cursor.is_some().then(|| match &where_expr {
+        Some(expr) => render(&stmt.clone().where_(expr.clone())),
+        None => render(&stmt),
+    });
+    if let Some(after_cursor) = after_cursor {
+        where_expr = Some(match where_expr {
+            Some(expr) => expr.and(after_cursor),
+            None => after_cursor,
+        });
+    }
+    if let Some(expr) = where_expr {
+        stmt = stmt.where_(expr);
+    }
     let rendered = render(&stmt);
 
     Ok(GeneratedSelect {
@@ -175,6 +281,7 @@ pub fn generate_select_sql(ctx: &SqlGenContext, query: &Select) -> Result<Genera
         param_order: rendered.params,
         plan,
         column_order,
+        first_page,
     })
 }
 
@@ -214,6 +321,310 @@ fn where_to_qualified_expr_validated(
         .map(|first| iter.fold(first, |acc, expr| acc.and(expr))))
 }
 
+/// Check that aggregates only appear on the queried table, without relations.
+///
+/// Aggregates group the queried table's rows; joined relation rows would be
+/// folded into the same groups.
+fn validate_aggregates(ctx: &SqlGenContext, fields: &SelectFields) -> Result<(), QError> {
+    let error = |name: &Meta<ColumnName>, func: AggregateFn, reason: &str| QError {
+        source: ctx.source.clone(),
+        span: name.span,
+        kind: QErrorKind::InvalidAggregate {
+            aggregate: func.tag().to_string(),
+            reason: reason.to_string(),
+        },
+    };
+
+    if let Some((name, func, _)) = fields.aggregates().next()
+        && fields.has_relations()
+    {
+        return Err(error(
+            name,
+            func,
+            "aggregates can't be combined with relations",
+        ));
+    }
+
+    fn nested_aggregate(fields: &SelectFields) -> Option<(&Meta<ColumnName>, AggregateFn)> {
+        fields.relations().find_map(|(_, rel)| {
+            let rel_fields = rel.fields.as_ref()?;
+            rel_fields
+                .aggregates()
+                .next()
+                .map(|(name, func, _)| (name, func))
+                .or_else(|| nested_aggregate(rel_fields))
+        })
+    }
+    if let Some((name, func)) = nested_aggregate(fields) {
+        return Err(error(
+            name,
+            func,
+            "aggregates are only supported on the queried table, not in relations",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that keyset pagination can page through `query` without skipping or
+/// repeating rows.
+fn validate_keyset(ctx: &SqlGenContext, query: &Select, keyset: &Keyset) -> Result<(), QError> {
+    let error = |span: Span, reason: String| QError {
+        source: ctx.source.clone(),
+        span,
+        kind: QErrorKind::InvalidPagination { reason },
+    };
+
+    let Some((first_col, _)) = keyset.by.columns.first() else {
+        return Err(error(
+            query.from.as_ref().map(|f| f.span).unwrap_or_default(),
+            "keyset pagination needs at least one column in `by`".to_string(),
+        ));
+    };
+    if let Some((col, _)) = query.order_by.as_ref().and_then(|o| o.columns.first()) {
+        return Err(error(
+            col.span,
+            "`order-by` can't be combined with keyset pagination, which orders by its `by` columns"
+                .to_string(),
+        ));
+    }
+    if let Some(offset) = &query.offset {
+        return Err(error(
+            offset.span,
+            "`offset` can't be combined with keyset pagination".to_string(),
+        ));
+    }
+    if let Some(first) = &query.first {
+        return Err(error(
+            first.span,
+            "`first` queries return a single row and can't be paginated".to_string(),
+        ));
+    }
+    if query.limit.is_none() {
+        return Err(error(
+            first_col.span,
+            "keyset pagination needs a `limit` (the page size)".to_string(),
+        ));
+    }
+    if query.group_by.is_some() || query.has_vec_relations() {
+        return Err(error(
+            first_col.span,
+            "keyset pagination needs one result per row, so it can't be combined with \
+             `group-by` or has-many relations"
+                .to_string(),
+        ));
+    }
+
+    for (col, dir) in &keyset.by.columns {
+        let selected = query
+            .fields
+            .as_ref()
+            .is_some_and(|fields| fields.columns().any(|(name, _)| name.value == col.value));
+        if !selected {
+            return Err(error(
+                col.span,
+                format!(
+                    "keyset column '{}' must be selected in `fields` to build the next cursor",
+                    col.value
+                ),
+            ));
+        }
+        if let Some(dir) = dir
+            && !dir.eq_ignore_ascii_case("asc")
+            && !dir.eq_ignore_ascii_case("desc")
+        {
+            return Err(error(
+                dir.span,
+                format!("unknown direction '{}', expected asc or desc", dir.value),
+            ));
+        }
+    }
+
+    // Without a schema for the table, column properties can't be checked.
+    let Some(table) = query
+        .from
+        .as_ref()
+        .and_then(|from| ctx.schema.get_table(from.value.as_str()))
+    else {
+        return Ok(());
+    };
+    for (col, _) in &keyset.by.columns {
+        if let Some(column) = table.columns.iter().find(|c| c.name == col.as_str())
+            && column.nullable
+        {
+            return Err(error(
+                col.span,
+                format!(
+                    "keyset column '{}' is nullable; NULLs can't be compared with a cursor",
+                    col.value
+                ),
+            ));
+        }
+    }
+    let (last_col, _) = keyset.by.columns.last().unwrap_or((first_col, &None));
+    let unique = table
+        .columns
+        .iter()
+        .find(|c| c.name == last_col.as_str())
+        .is_some_and(|c| c.primary_key || c.unique);
+    if !unique {
+        return Err(error(
+            last_col.span,
+            format!(
+                "the last keyset column '{}' must be unique (e.g. the primary key), or rows \
+                 sharing a value would be skipped between pages",
+                last_col.value
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that a `stream true` query returns rows that can be streamed.
+fn validate_stream(ctx: &SqlGenContext, query: &Select) -> Result<(), QError> {
+    let error = |span: Span, reason: &str| QError {
+        source: ctx.source.clone(),
+        span,
+        kind: QErrorKind::InvalidStream {
+            reason: reason.to_string(),
+        },
+    };
+    let span = query.stream.as_ref().map(|s| s.span).unwrap_or_default();
+
+    if query.is_first() {
+        return Err(error(
+            span,
+            "`first` queries return a single row, there is nothing to stream",
+        ));
+    }
+    if query.paginate.is_some() {
+        return Err(error(
+            span,
+            "paginated queries return one page at a time and can't be streamed",
+        ));
+    }
+    if let Some(fields) = query.fields.as_ref().filter(|f| f.has_relations()) {
+        stream_parent_key(ctx, query, fields)?;
+    }
+    Ok(())
+}
+
+/// The column a streamed query with relations groups its rows by.
+///
+/// Rows are grouped into parents as they arrive, so the parent needs a plain
+/// column to tell one from the next; without one the query can't be streamed.
+pub(crate) fn stream_parent_key<'a>(
+    ctx: &SqlGenContext,
+    query: &Select,
+    fields: &'a SelectFields,
+) -> Result<&'a ColumnName, QError> {
+    fields.id_column().ok_or_else(|| QError {
+        source: ctx.source.clone(),
+        span: query.stream.as_ref().map(|s| s.span).unwrap_or_default(),
+        kind: QErrorKind::InvalidStream {
+            reason: "streamed rows are grouped by the parent's key, so the query must select a column such as `id`".to_string(),
+        },
+    })
+}
+
+/// Build the predicate that selects rows after the cursor.
+///
+/// With a single direction this is a row comparison,
+/// `("t0"."created_at", "t0"."id") < ($cursor_created_at, $cursor_id)`;
+/// mixed directions expand to
+/// `a < $a OR (a = $a AND b > $b) OR ...`. The first page has no cursor
+/// and is rendered without this predicate.
+fn keyset_to_expr(keyset: &Keyset, table_alias: &str) -> Expr {
+    let columns: Vec<(Expr, Expr, BinOp)> = keyset
+        .by
+        .columns
+        .iter()
+        .map(|(col, dir)| {
+            let column = Expr::qualified_column(table_alias.into(), col.value.clone());
+            let cursor = Expr::param(keyset_cursor_param(&col.value));
+            let desc = dir.as_ref().is_some_and(|d| d.eq_ignore_ascii_case("desc"));
+            (column, cursor, if desc { BinOp::Lt } else { BinOp::Gt })
+        })
+        .collect();
+
+    let compare = |left: Expr, op: BinOp, right: Expr| Expr::BinOp {
+        left: Box::new(left),
+        op,
+        right: Box::new(right),
+    };
+
+    let op = columns[0].2;
+    if columns.iter().all(|(_, _, o)| *o == op) {
+        let (cols, cursors) = columns
+            .iter()
+            .map(|(c, p, _)| (c.clone(), p.clone()))
+            .unzip();
+        compare(Expr::Row(cols), op, Expr::Row(cursors))
+    } else {
+        let mut alternatives = (0..columns.len()).map(|i| {
+            let (column, cursor, op) = &columns[i];
+            columns[..i].iter().rev().fold(
+                compare(column.clone(), *op, cursor.clone()),
+                |acc, (c, p, _)| c.clone().eq(p.clone()).and(acc),
+            )
+        });
+        let first = alternatives.next().expect("keyset has at least one column");
+        alternatives.fold(first, |acc, expr| acc.or(expr))
+    }
+}
+
+/// Name of the param carrying a keyset column's cursor value.
+pub(crate) fn keyset_cursor_param(column: &ColumnName) -> ParamName {
+    format!("cursor_{}", column.as_str()).into()
+}
+
+/// Build the SQL for an aggregate column, e.g. `SUM("t0"."amount")`.
+fn aggregate_to_expr(aggregate: &AggregateColumn) -> Expr {
+    let arg = match &aggregate.column {
+        Some(column) => {
+            Expr::qualified_column(aggregate.table_alias.as_str().into(), column.clone())
+        }
+        None => Expr::Raw("*".to_string()),
+    };
+    Expr::FnCall {
+        name: aggregate.func.sql_name().to_string(),
+        args: vec![arg],
+    }
+}
+
+/// Convert a HAVING clause to a dibs_sql::Expr with validation.
+///
+/// Keys naming an aggregate field filter on the aggregate itself, so
+/// `total @gt(1000)` becomes `SUM("t0"."amount") > 1000`; other keys are
+/// columns of the queried table.
+fn having_to_expr_validated(
+    ctx: &SqlGenContext,
+    having: &Where,
+    aggregates: &HashMap<ColumnName, Expr>,
+) -> Result<Option<Expr>, QError> {
+    let resolve = |name: &ColumnName| {
+        aggregates
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| Expr::qualified_column("t0".into(), name.clone()))
+    };
+
+    let mut exprs: Vec<Expr> = vec![];
+    for (col_meta, filter_value) in &having.filters {
+        if let Some(expr) =
+            filter_value_to_expr_with(ctx, &col_meta.value, filter_value, col_meta.span, &resolve)?
+        {
+            exprs.push(expr);
+        }
+    }
+
+    let mut iter = exprs.into_iter();
+    Ok(iter
+        .next()
+        .map(|first| iter.fold(first, |acc, expr| acc.and(expr))))
+}
+
 /// Convert ORDER BY clause to AST OrderBy items.
 fn order_by_to_ast(order_by: &QueryOrderBy, table_alias: &str) -> Vec<OrderBy> {
     order_by
@@ -230,3 +641,190 @@ fn order_by_to_ast(order_by: &QueryOrderBy, table_alias: &str) -> Vec<OrderBy> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_query_file;
+    use dibs_db_schema::Schema;
+    use dibs_query_schema::{Decl, QueryFile};
+
+    fn generate_first_select(source: &str) -> Result<GeneratedSelect, QError> {
+        let (file, qsource): (QueryFile, _) =
+            parse_query_file(camino::Utf8Path::new("<test>"), source).unwrap();
+        let select = file
+            .0
+            .values()
+            .find_map(|decl| match decl {
+                Decl::Select(s) => Some(s),
+                _ => None,
+            })
+            .expect("No select found in source");
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, qsource);
+        generate_select_sql(&ctx, select)
+    }
+
+    #[test]
+    fn test_select_group_by_having() {
+        let source = r#"
+SalesByCategory @select{
+    params {status @string, min_total @int}
+    from order_item
+    where {status $status}
+    group-by {category_id}
+    having {total @gt($min_total), count @gte(2)}
+    order-by {category_id asc}
+    fields {
+        category_id
+        total @sum{column amount}
+        count @count
+        avg_amount @avg{column amount}
+        largest @max{column amount}
+    }
+}
+"#;
+        let result = generate_first_select(source).unwrap();
+        insta::assert_snapshot!(result.sql);
+        assert_eq!(
+            result.param_order,
+            vec![ParamName::from("status"), ParamName::from("min_total")]
+        );
+        assert_eq!(result.column_order[&ColumnName::from("avg_amount")], 3);
+    }
+
+    #[test]
+    fn test_select_aggregates_without_group_by() {
+        let source = r#"
+OrderStats @select{
+    from order_item
+    first true
+    fields {
+        items @count
+        priced @count{column amount}
+        smallest @min{column amount}
+    }
+}
+"#;
+        let result = generate_first_select(source).unwrap();
+        insta::assert_snapshot!(result.sql);
+    }
+
+    #[test]
+    fn test_select_aggregate_with_relation_produces_error() {
+        let source = r#"
+ProductTotals @select{
+    from product
+    fields {
+        total @sum{column price}
+        variants @rel{fields {id}}
+    }
+}
+"#;
+        let err = generate_first_select(source).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("invalid aggregate '@sum': aggregates can't be combined with relations"),
+            "Error should reject the aggregate: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_select_keyset_pagination() {
+        let source = r#"
+PostsPage @select{
+    params {author_id @int, limit @int}
+    from post
+    where {author_id $author_id}
+    paginate @keyset{by {created_at desc, id desc}}
+    limit $limit
+    fields {id, title, created_at}
+}
+"#;
+        let result = generate_first_select(source).unwrap();
+        insta::assert_snapshot!(result.sql);
+        assert_eq!(
+            result.param_order,
+            vec![
+                ParamName::from("author_id"),
+                ParamName::from("cursor_created_at"),
+                ParamName::from("cursor_id"),
+                ParamName::from("limit"),
+            ]
+        );
+
+        let first_page = result.first_page.expect("first page SQL");
+        insta::assert_snapshot!("select_keyset_pagination_first_page", first_page.sql);
+        assert_eq!(
+            first_page.params,
+            vec![ParamName::from("author_id"), ParamName::from("limit")]
+        );
+    }
+
+    #[test]
+    fn test_select_keyset_pagination_mixed_directions() {
+        let source = r#"
+ProductsByPrice @select{
+    from product
+    paginate @keyset{by {price desc, id asc}}
+    limit 50
+    fields {id, price}
+}
+"#;
+        let result = generate_first_select(source).unwrap();
+        insta::assert_snapshot!(result.sql);
+    }
+
+    #[test]
+    fn test_select_keyset_pagination_needs_selected_columns_and_limit() {
+        let source = r#"
+PostsPage @select{
+    from post
+    paginate @keyset{by {created_at desc, id desc}}
+    limit 20
+    fields {id, title}
+}
+"#;
+        let err = generate_first_select(source).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("keyset column 'created_at' must be selected"),
+            "Error should require the keyset column in fields: {}",
+            err
+        );
+
+        let source = r#"
+PostsPage @select{
+    from post
+    paginate @keyset{by {id desc}}
+    fields {id, title}
+}
+"#;
+        let err = generate_first_select(source).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("keyset pagination needs a `limit`"),
+            "Error should require a limit: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_select_stream_first_produces_error() {
+        let source = r#"
+OneProduct @select{
+    from product
+    first true
+    stream true
+    fields {id}
+}
+"#;
+        let err = generate_first_select(source).unwrap_err();
+        assert!(
+            err.to_string().contains("invalid stream: `first` queries"),
+            "Error should reject streaming a single row: {}",
+            err
+        );
+    }
+}
@@ -1,10 +1,10 @@
 //! SQL generation for UPDATE statements.
 
 use super::SqlGenContext;
-use super::common::{update_set_value_to_expr, where_to_expr_validated};
+use super::common::{update_set_value_to_expr, update_timestamp_column, where_to_expr_validated};
 use crate::QError;
 use dibs_query_schema::Update;
-use dibs_sql::{ColumnName, ParamName, UpdateStmt, render};
+use dibs_sql::{ColumnName, Expr, ParamName, UpdateStmt, render};
 
 /// Generated SQL with parameter info.
 #[derive(Debug, Clone)]
@@ -39,6 +39,12 @@ pub fn generate_update_sql(
         stmt = stmt.set(col_name.clone(), expr);
     }
 
+    // Keep a schema-managed `updated_at` current even if the query forgets it
+    let assigned = update.set.columns.keys().map(|k| &k.value);
+    if let Some(col) = update_timestamp_column(ctx, &update.table.value, assigned) {
+        stmt = stmt.set(col, Expr::Now);
+    }
+
     // WHERE clause
     if let Some(where_clause) = &update.where_clause
         && let Some(expr) = where_to_expr_validated(ctx, where_clause)?
@@ -208,7 +214,7 @@ InsertCounter @insert{
     values {refcount @add(1)}
 }
 "#;
-        let (file, _) = parse_query_file(camino::Utf8Path::new("<test>"), source).unwrap();
+        let (file, qsource) = parse_query_file(camino::Utf8Path::new("<test>"), source).unwrap();
         let insert = file
             .0
             .values()
@@ -217,7 +223,9 @@ InsertCounter @insert{
                 _ => None,
             })
             .unwrap();
-        let result = crate::sqlgen::generate_insert_sql(insert);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, qsource);
+        let result = crate::sqlgen::generate_insert_sql(&ctx, insert);
         assert!(result.sql.contains(r#"VALUES (ADD(1))"#));
         assert!(!result.sql.contains(r#"\"refcount\" + 1"#));
     }
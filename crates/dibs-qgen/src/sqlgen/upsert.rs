@@ -1,11 +1,13 @@
 //! SQL generation for UPSERT statements (INSERT ... ON CONFLICT ... DO UPDATE).
 
 use super::SqlGenContext;
-use super::common::{update_value_to_expr, value_expr_to_expr};
+use super::common::{
+    insert_timestamp_columns, update_timestamp_column, update_value_to_expr, value_expr_to_expr,
+};
 use crate::QError;
 use dibs_query_schema::Upsert;
 use dibs_sql::{
-    ColumnName, ConflictAction, InsertStmt, OnConflict, ParamName, UpdateAssignment, render,
+    ColumnName, ConflictAction, Expr, InsertStmt, OnConflict, ParamName, UpdateAssignment, render,
 };
 
 /// Generated SQL with parameter info.
@@ -35,6 +37,12 @@ pub fn generate_upsert_sql(
         stmt = stmt.column(col_name.clone(), expr);
     }
 
+    // Schema-managed timestamps the query leaves out
+    let listed = upsert.values.columns.keys().map(|k| &k.value);
+    for col in insert_timestamp_columns(ctx, &upsert.into.value, listed) {
+        stmt = stmt.column(col, Expr::Now);
+    }
+
     // ON CONFLICT clause
     let conflict_columns: Vec<ColumnName> = upsert
         .on_conflict
@@ -45,7 +53,7 @@ pub fn generate_upsert_sql(
         .collect();
 
     // Build update assignments from on_conflict.update
-    let update_assignments: Vec<UpdateAssignment> = upsert
+    let mut update_assignments: Vec<UpdateAssignment> = upsert
         .on_conflict
         .update
         .columns
@@ -62,6 +70,10 @@ pub fn generate_upsert_sql(
             Ok(UpdateAssignment::new(col_name.clone(), expr))
         })
         .collect::<Result<_, QError>>()?;
+    let assigned = upsert.on_conflict.update.columns.keys().map(|k| &k.value);
+    if let Some(col) = update_timestamp_column(ctx, &upsert.into.value, assigned) {
+        update_assignments.push(UpdateAssignment::new(col, Expr::Now));
+    }
 
     stmt = stmt.on_conflict(OnConflict {
         columns: conflict_columns,
@@ -9,6 +9,8 @@
 //! RETURNING id, handle, status
 //! ```
 
+use super::SqlGenContext;
+use super::common::{insert_timestamp_columns, update_timestamp_column};
 use dibs_query_schema::{ParamType, Payload, UpdateValue, UpsertMany, ValueExpr};
 use dibs_sql::{ColumnName, ParamName};
 
@@ -26,7 +28,7 @@ pub struct GeneratedUpsertMany {
 }
 
 /// Generate SQL for a bulk UPSERT statement.
-pub fn generate_upsert_many_sql(upsert: &UpsertMany) -> GeneratedUpsertMany {
+pub fn generate_upsert_many_sql(ctx: &SqlGenContext, upsert: &UpsertMany) -> GeneratedUpsertMany {
     let mut sql = String::new();
     let mut params: Vec<ParamName> = Vec::new();
 
@@ -42,11 +44,19 @@ pub fn generate_upsert_many_sql(upsert: &UpsertMany) -> GeneratedUpsertMany {
     sql.push_str(upsert.into.value.as_str());
     sql.push_str("\" (");
 
+    // Schema-managed timestamps the query leaves out are filled with NOW()
+    let timestamp_columns = insert_timestamp_columns(
+        ctx,
+        &upsert.into.value,
+        upsert.values.columns.keys().map(|k| &k.value),
+    );
+
     let columns: Vec<&str> = upsert
         .values
         .columns
         .keys()
         .map(|k| k.value.as_str())
+        .chain(timestamp_columns.iter().map(|c| c.as_str()))
         .collect();
     sql.push_str(
         &columns
@@ -65,6 +75,7 @@ pub fn generate_upsert_many_sql(upsert: &UpsertMany) -> GeneratedUpsertMany {
         .columns
         .iter()
         .map(|(col_meta, expr)| value_expr_to_unnest_select(&col_meta.value, expr, &param_names))
+        .chain(timestamp_columns.iter().map(|_| "NOW()".to_string()))
         .collect();
     sql.push_str(&select_exprs.join(", "));
 
@@ -109,7 +120,7 @@ pub fn generate_upsert_many_sql(upsert: &UpsertMany) -> GeneratedUpsertMany {
     sql.push_str(") DO UPDATE SET ");
 
     // Build update assignments
-    let update_assignments: Vec<String> = upsert
+    let mut update_assignments: Vec<String> = upsert
         .on_conflict
         .update
         .columns
@@ -120,6 +131,10 @@ pub fn generate_upsert_many_sql(upsert: &UpsertMany) -> GeneratedUpsertMany {
             format!("\"{}\" = {}", col, value)
         })
         .collect();
+    let assigned = upsert.on_conflict.update.columns.keys().map(|k| &k.value);
+    if let Some(col) = update_timestamp_column(ctx, &upsert.into.value, assigned) {
+        update_assignments.push(format!("\"{}\" = NOW()", col));
+    }
     sql.push_str(&update_assignments.join(", "));
 
     // RETURNING
@@ -301,12 +316,13 @@ fn param_type_to_pg_array(ty: &ParamType) -> &'static str {
 mod tests {
     use super::*;
     use crate::parse_query_file;
+    use dibs_db_schema::Schema;
 
-    fn get_first_upsert_many(source: &str) -> UpsertMany {
-        let (file, _source) = parse_query_file(camino::Utf8Path::new("<test>"), source).unwrap();
+    fn get_first_upsert_many(source: &str) -> (UpsertMany, crate::QSource) {
+        let (file, qsource) = parse_query_file(camino::Utf8Path::new("<test>"), source).unwrap();
         for (_, decl) in file.0.iter() {
             if let dibs_query_schema::Decl::UpsertMany(um) = decl {
-                return um.clone();
+                return (um.clone(), (*qsource).clone());
             }
         }
         panic!("No upsert-many found in source");
@@ -326,8 +342,10 @@ BulkUpsertProducts @upsert-many{
     returning {id, handle, status}
 }
 "#;
-        let upsert_many = get_first_upsert_many(source);
-        let result = generate_upsert_many_sql(&upsert_many);
+        let (upsert_many, qsource) = get_first_upsert_many(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_upsert_many_sql(&ctx, &upsert_many);
         insta::assert_snapshot!(result.sql);
     }
 
@@ -345,8 +363,10 @@ BulkUpsertProducts @upsert-many{
     returning {id, handle, status}
 }
 "#;
-        let upsert_many = get_first_upsert_many(source);
-        let result = generate_upsert_many_sql(&upsert_many);
+        let (upsert_many, qsource) = get_first_upsert_many(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_upsert_many_sql(&ctx, &upsert_many);
         insta::assert_snapshot!(result.sql);
     }
 
@@ -364,8 +384,10 @@ BulkUpsertTranslations @upsert-many{
     returning {id}
 }
 "#;
-        let upsert_many = get_first_upsert_many(source);
-        let result = generate_upsert_many_sql(&upsert_many);
+        let (upsert_many, qsource) = get_first_upsert_many(source);
+        let schema = Schema::default();
+        let ctx = SqlGenContext::new(&schema, std::sync::Arc::new(qsource));
+        let result = generate_upsert_many_sql(&ctx, &upsert_many);
         insta::assert_snapshot!(result.sql);
     }
 }
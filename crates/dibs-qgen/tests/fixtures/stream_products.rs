@@ -0,0 +1,85 @@
+use dibs_runtime::prelude::*;
+use dibs_runtime::tokio_postgres;
+
+// Generated by dibs-qgen. Do not edit.
+
+
+
+#[derive(Debug, Clone, Facet)]
+#[facet(crate = dibs_runtime::facet)]
+pub struct StreamProductsResult {
+    pub id: i64,
+    pub handle: String,
+    pub variants: Vec<StreamProductsVariants>,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(crate = dibs_runtime::facet)]
+pub struct StreamProductsVariants {
+    pub id: i64,
+    pub sku: String,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(crate = dibs_runtime::facet)]
+struct StreamProductsRow {
+    id: i64,
+    handle: String,
+    variants_id: Option<i64>,
+    variants_sku: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::clone_on_copy)]
+pub fn stream_products<'a, C>(client: &'a C) -> impl Stream<Item = Result<StreamProductsResult, QueryError>> + 'a
+where C: tokio_postgres::GenericClient,
+{
+    {
+        const SQL: &str = r#"SELECT "t0"."id" AS "id", "t0"."handle" AS "handle", "t1"."id" AS "variants_id", "t1"."sku" AS "variants_sku"
+        FROM "product" "t0"
+        LEFT JOIN "product_variant" "t1" ON "t0"."id" = "t1"."product_id"
+        ORDER BY "t0"."handle" DESC, "t0"."id" ASC"#;
+
+        let flat_rows = dibs_runtime::stream_rows::<StreamProductsRow, _>("stream_products", async move {
+            let params: [&(dyn tokio_postgres::types::ToSql + Sync); 0] = [];
+            client.query_raw(SQL, params).await
+        });
+
+        dibs_runtime::group_rows(flat_rows, |flat_row: &StreamProductsRow| flat_row.id.clone(), |flat_rows: Vec<StreamProductsRow>| {
+            let parent: Result<Option<StreamProductsResult>, QueryError> = {
+            {
+                // Group flat rows by parent ID and assemble nested structs
+                let mut grouped: std::collections::HashMap<i64, StreamProductsResult> = std::collections::HashMap::new();
+                let mut seen_variants: std::collections::HashSet<(i64, i64)> = std::collections::HashSet::new();
+
+                for flat_row in flat_rows {
+                    let parent_id = flat_row.id.clone();
+
+                    let entry = grouped.entry(parent_id.clone()).or_insert_with(|| StreamProductsResult {
+                        id: flat_row.id.clone(),
+                        handle: flat_row.handle.clone(),
+                        variants: Vec::new(),
+                    });
+
+                    // Append to variants (Vec relation)
+                    if let Some(ref rel_id) = flat_row.variants_id {
+                        let key = (parent_id.clone(), rel_id.clone());
+                        if seen_variants.insert(key) {
+                            entry.variants.push(StreamProductsVariants {
+                                id: flat_row.variants_id.clone().expect("non-null from LEFT JOIN"),
+                                sku: flat_row.variants_sku.clone().expect("non-null from LEFT JOIN"),
+                            });
+                        }
+                    }
+
+                }
+
+                Ok(grouped.into_values().next())
+            }
+
+            };
+            parent?.ok_or_else(|| QueryError::Assemble("a group has no rows".to_string()))
+        })
+    }
+
+}
\ No newline at end of file
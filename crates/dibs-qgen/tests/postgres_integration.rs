@@ -350,6 +350,8 @@ fn col(name: &str, pg_type: PgType, rust_type: &str, nullable: bool) -> Column {
         lang: None,
         icon: None,
         subtype: None,
+        domain: None,
+        composite: None,
     }
 }
 
@@ -359,7 +361,16 @@ fn table(name: &str, columns: Vec<Column>, foreign_keys: Vec<ForeignKey>) -> Tab
         name: name.to_string(),
         columns,
         check_constraints: vec![],
+        exclusion_constraints: Vec::new(),
         trigger_checks: vec![],
+        triggers: Vec::new(),
+        partition_by: None,
+        rls: false,
+        policies: vec![],
+        soft_delete: None,
+        created_at: None,
+        updated_at: None,
+        audited: false,
         foreign_keys,
         indices: vec![],
         source: SourceLocation::default(),
@@ -398,6 +409,7 @@ fn build_test_schema() -> Schema {
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         ),
     );
@@ -417,11 +429,17 @@ fn build_test_schema() -> Schema {
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         ),
     );
 
-    Schema { tables }
+    Schema {
+        tables,
+        extensions: Vec::new(),
+        domains: Vec::new(),
+        composites: Vec::new(),
+    }
 }
 
 #[tokio::test]
@@ -590,6 +608,179 @@ ProductWithVariants @select{
     );
 }
 
+/// A streamed has-many query; its generated code is checked in at
+/// `STREAM_FIXTURE` so it can be compiled into this test.
+const STREAM_SOURCE: &str = r#"
+StreamProducts @select{
+  from product
+  order-by {handle desc}
+  stream true
+  fields {
+    id
+    handle
+    variants @rel{
+      from product_variant
+      fields { id, sku }
+    }
+  }
+}
+"#;
+
+const STREAM_FIXTURE: &str = "tests/fixtures/stream_products.rs";
+
+mod stream_products {
+    include!("fixtures/stream_products.rs");
+}
+
+#[test]
+fn test_stream_fixture_is_up_to_date() {
+    let (file, qsource) = parse_test_query(STREAM_SOURCE);
+    let code = generate_rust_code(&file, &build_test_schema(), qsource).unwrap();
+
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(STREAM_FIXTURE);
+    if std::env::var_os("DIBS_UPDATE_FIXTURES").is_some() {
+        std::fs::write(&path, &code.code).unwrap();
+    }
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        code.code,
+        "{STREAM_FIXTURE} is stale; rerun with DIBS_UPDATE_FIXTURES=1"
+    );
+}
+
+#[tokio::test]
+async fn test_stream_vec_relation_against_postgres() {
+    use futures_util::TryStreamExt;
+
+    let (_container, client) = setup_postgres().await;
+    create_test_tables(&client).await;
+    insert_test_data(&client).await;
+
+    let products: Vec<stream_products::StreamProductsResult> =
+        stream_products::stream_products(&client)
+            .try_collect()
+            .await
+            .unwrap();
+
+    let summary: Vec<(&str, Vec<&str>)> = products
+        .iter()
+        .map(|p| {
+            let mut skus: Vec<&str> = p.variants.iter().map(|v| v.sku.as_str()).collect();
+            skus.sort();
+            (p.handle.as_str(), skus)
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("widget", vec!["WIDGET-L", "WIDGET-M", "WIDGET-S"]),
+            ("gizmo", vec![]),
+            ("gadget", vec!["GADGET-1"]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_many_to_many_relation_against_postgres() {
+    let (_container, client) = setup_postgres().await;
+    create_test_tables(&client).await;
+    insert_test_data(&client).await;
+    client
+        .batch_execute(
+            r#"
+            CREATE TABLE tag (
+                id BIGSERIAL PRIMARY KEY,
+                name TEXT NOT NULL
+            );
+
+            CREATE TABLE product_tag (
+                product_id BIGINT NOT NULL REFERENCES product(id),
+                tag_id BIGINT NOT NULL REFERENCES tag(id),
+                PRIMARY KEY (product_id, tag_id)
+            );
+
+            INSERT INTO tag (id, name) VALUES (1, 'new'), (2, 'sale');
+            INSERT INTO product_tag (product_id, tag_id) VALUES (1, 1), (1, 2), (2, 2);
+            "#,
+        )
+        .await
+        .unwrap();
+
+    let mut schema = build_test_schema();
+    schema.tables.insert(
+        "tag".to_string(),
+        table(
+            "tag",
+            vec![
+                col("id", PgType::BigInt, "i64", false),
+                col("name", PgType::Text, "String", false),
+            ],
+            vec![],
+        ),
+    );
+    schema.tables.insert(
+        "product_tag".to_string(),
+        table(
+            "product_tag",
+            vec![
+                col("product_id", PgType::BigInt, "i64", false),
+                col("tag_id", PgType::BigInt, "i64", false),
+            ],
+            vec![
+                ForeignKey {
+                    columns: vec!["product_id".to_string()],
+                    references_table: "product".to_string(),
+                    references_columns: vec!["id".to_string()],
+                    deferrable: false,
+                },
+                ForeignKey {
+                    columns: vec!["tag_id".to_string()],
+                    references_table: "tag".to_string(),
+                    references_columns: vec!["id".to_string()],
+                    deferrable: false,
+                },
+            ],
+        ),
+    );
+
+    // No `through`: product_tag is the only junction between product and tag
+    let source = r#"
+ProductWithTags @select{
+  from product
+  order-by {id asc}
+  fields {
+    id
+    tags @rel{
+      from tag
+      fields { id, name }
+    }
+  }
+}
+"#;
+    let (file, qsource) = parse_test_query(source);
+    let query = first_select(&file);
+
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = generate_select_sql(&ctx, query).unwrap();
+    tracing::info!("Generated SQL: {}", generated.sql);
+
+    let rows: Vec<Row> = client.query(&generated.sql, &[]).await.unwrap();
+    let pairs: Vec<(i64, Option<String>)> = rows
+        .iter()
+        .map(|row| (row.get("id"), row.get("tags_name")))
+        .collect();
+
+    // widget has two tags, gadget one, gizmo none (one row of NULLs)
+    assert_eq!(pairs.len(), 4);
+    assert_eq!(
+        pairs.iter().filter(|(id, _)| *id == 1).count(),
+        2,
+        "widget should have 2 tags"
+    );
+    assert!(pairs.contains(&(2, Some("sale".to_string()))));
+    assert!(pairs.contains(&(3, None)));
+}
+
 #[tokio::test]
 async fn test_filtered_query_with_params() {
     let (_container, client) = setup_postgres().await;
@@ -954,11 +1145,13 @@ CreateProduct @insert{
     returning {id, handle, status}
 }
 "#;
-    let (file, _qsource) = parse_test_query(source);
+    let (file, qsource) = parse_test_query(source);
     let insert = first_insert(&file);
 
+    let schema = Schema::default();
+    let ctx = SqlGenContext::new(&schema, qsource);
     // Generate SQL
-    let generated = dibs_qgen::generate_insert_sql(insert);
+    let generated = dibs_qgen::generate_insert_sql(&ctx, insert);
     tracing::info!("Generated INSERT SQL: {}", generated.sql);
 
     // Verify SQL structure
@@ -1016,10 +1209,12 @@ CreateProductWithDefault @insert{
     returning {id, handle, status}
 }
 "#;
-    let (file, _qsource) = parse_test_query(source);
+    let (file, qsource) = parse_test_query(source);
     let insert = first_insert(&file);
 
-    let generated = dibs_qgen::generate_insert_sql(insert);
+    let schema = Schema::default();
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = dibs_qgen::generate_insert_sql(&ctx, insert);
     tracing::info!("Generated INSERT SQL: {}", generated.sql);
 
     // Should use DEFAULT keyword
@@ -1283,10 +1478,12 @@ CreateProductNoReturn @insert{
     values {handle $handle, status $status}
 }
 "#;
-    let (file, _qsource) = parse_test_query(source);
+    let (file, qsource) = parse_test_query(source);
     let insert = first_insert(&file);
 
-    let generated = dibs_qgen::generate_insert_sql(insert);
+    let schema = Schema::default();
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = dibs_qgen::generate_insert_sql(&ctx, insert);
     tracing::info!("Generated INSERT SQL: {}", generated.sql);
 
     // Should not have RETURNING clause
@@ -1330,11 +1527,13 @@ BulkCreateProducts @insert-many{
     returning {id, handle, status}
 }
 "#;
-    let (file, _qsource) = parse_test_query(source);
+    let (file, qsource) = parse_test_query(source);
     let insert_many = first_insert_many(&file);
 
+    let schema = Schema::default();
+    let ctx = SqlGenContext::new(&schema, qsource);
     // Generate SQL
-    let generated = dibs_qgen::generate_insert_many_sql(insert_many);
+    let generated = dibs_qgen::generate_insert_many_sql(&ctx, insert_many);
     tracing::info!("Generated INSERT MANY SQL: {}", generated.sql);
 
     // Verify SQL structure uses UNNEST
@@ -1409,10 +1608,12 @@ BulkCreateProductsNoReturn @insert-many{
     values {handle $handle, status $status}
 }
 "#;
-    let (file, _qsource) = parse_test_query(source);
+    let (file, qsource) = parse_test_query(source);
     let insert_many = first_insert_many(&file);
 
-    let generated = dibs_qgen::generate_insert_many_sql(insert_many);
+    let schema = Schema::default();
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = dibs_qgen::generate_insert_many_sql(&ctx, insert_many);
     tracing::info!(
         "Generated INSERT MANY SQL (no returning): {}",
         generated.sql
@@ -1457,10 +1658,12 @@ BulkUpsertProducts @upsert-many{
     returning {id, handle, status}
 }
 "#;
-    let (file, _qsource) = parse_test_query(source);
+    let (file, qsource) = parse_test_query(source);
     let upsert_many = first_upsert_many(&file);
 
-    let generated = dibs_qgen::generate_upsert_many_sql(upsert_many);
+    let schema = Schema::default();
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = dibs_qgen::generate_upsert_many_sql(&ctx, upsert_many);
     tracing::info!("Generated UPSERT MANY SQL: {}", generated.sql);
 
     // Verify SQL structure
@@ -1535,9 +1738,12 @@ BulkUpsertProducts @upsert-many{
     returning {id, handle, status}
 }
 "#;
-    let (file, _qsource) = parse_test_query(source);
+    let (file, qsource) = parse_test_query(source);
     let upsert_many = first_upsert_many(&file);
-    let generated = dibs_qgen::generate_upsert_many_sql(upsert_many);
+
+    let schema = Schema::default();
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = dibs_qgen::generate_upsert_many_sql(&ctx, upsert_many);
 
     // Upsert with mix of existing and new
     let handles = vec![
@@ -1597,9 +1803,12 @@ BulkCreateProducts @insert-many{
     returning {id, handle, status}
 }
 "#;
-    let (file, _qsource) = parse_test_query(source);
+    let (file, qsource) = parse_test_query(source);
     let insert_many = first_insert_many(&file);
-    let generated = dibs_qgen::generate_insert_many_sql(insert_many);
+
+    let schema = Schema::default();
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = dibs_qgen::generate_insert_many_sql(&ctx, insert_many);
 
     // Execute with empty arrays
     let handles: Vec<String> = vec![];
@@ -1969,6 +2178,125 @@ ComplexJsonQuery @select{
     assert!(handles.contains(&"multi-gizmo".to_string()));
 }
 
+#[tokio::test]
+async fn test_filter_groups_against_postgres() {
+    let (_container, client) = setup_postgres().await;
+    create_jsonb_test_tables(&client).await;
+    insert_jsonb_test_data(&client).await;
+
+    let source = r#"
+DraftsOrBare @select{
+    from product_with_metadata
+    fields {id, handle}
+    where {
+        id @and(@gte(2) @lte(4))
+        either @or{status "draft", metadata @null}
+        hidden @not{handle "premium-widget"}
+    }
+    order-by {id asc}
+}
+"#;
+    let (file, qsource) = parse_test_query(source);
+    let query = first_select(&file);
+
+    let schema = build_jsonb_test_schema();
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = generate_select_sql(&ctx, query).unwrap();
+
+    tracing::info!("Generated SQL: {}", generated.sql);
+
+    assert!(
+        generated
+            .sql
+            .contains(r#"("t0"."status" = 'draft' OR "t0"."metadata" IS NULL)"#),
+        "SQL should parenthesize the @or group: {}",
+        generated.sql
+    );
+
+    let rows: Vec<Row> = client.query(&generated.sql, &[]).await.unwrap();
+    let handles: Vec<String> = rows.iter().map(|r| r.get::<_, String>(1)).collect();
+    assert_eq!(handles, ["basic-gadget", "no-meta"]);
+}
+
+#[tokio::test]
+async fn test_group_by_having_against_postgres() {
+    let (_container, client) = setup_postgres().await;
+    create_jsonb_test_tables(&client).await;
+    insert_jsonb_test_data(&client).await;
+
+    let source = r#"
+ProductsPerStatus @select{
+    from product_with_metadata
+    group-by {status}
+    having {products @gte(2)}
+    fields {
+        status
+        products @count
+        with_metadata @count{column metadata}
+        newest @max{column id}
+    }
+}
+"#;
+    let (file, qsource) = parse_test_query(source);
+    let query = first_select(&file);
+
+    let schema = build_jsonb_test_schema();
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = generate_select_sql(&ctx, query).unwrap();
+
+    tracing::info!("Generated SQL: {}", generated.sql);
+
+    let rows: Vec<Row> = client.query(&generated.sql, &[]).await.unwrap();
+    assert_eq!(rows.len(), 1, "Only 'active' has two or more products");
+    assert_eq!(rows[0].get::<_, String>(0), "active");
+    assert_eq!(rows[0].get::<_, i64>(1), 4);
+    assert_eq!(rows[0].get::<_, i64>(2), 3);
+    assert_eq!(rows[0].get::<_, i64>(3), 5);
+}
+
+#[tokio::test]
+async fn test_keyset_pagination_against_postgres() {
+    let (_container, client) = setup_postgres().await;
+    create_jsonb_test_tables(&client).await;
+    insert_jsonb_test_data(&client).await;
+
+    let source = r#"
+ProductsPage @select{
+    from product_with_metadata
+    paginate @keyset{by {status asc, id desc}}
+    limit 2
+    fields {id, status}
+}
+"#;
+    let (file, qsource) = parse_test_query(source);
+    let query = first_select(&file);
+
+    let schema = build_jsonb_test_schema();
+    let ctx = SqlGenContext::new(&schema, qsource);
+    let generated = generate_select_sql(&ctx, query).unwrap();
+
+    tracing::info!("Generated SQL: {}", generated.sql);
+
+    // Walk the pages, feeding each page's last row back in as the cursor
+    let mut cursor: Option<(String, i64)> = None;
+    let mut pages: Vec<Vec<i64>> = vec![];
+    let first_page = generated.first_page.as_ref().expect("first page SQL");
+    assert!(first_page.params.is_empty());
+    loop {
+        let rows: Vec<Row> = match &cursor {
+            Some((status, id)) => client.query(&generated.sql, &[status, id]).await.unwrap(),
+            None => client.query(&first_page.sql, &[]).await.unwrap(),
+        };
+        pages.push(rows.iter().map(|r| r.get::<_, i64>(0)).collect());
+        match rows.last() {
+            Some(last) if rows.len() == 2 => cursor = Some((last.get(1), last.get(0))),
+            _ => break,
+        }
+    }
+
+    assert_eq!(pages, vec![vec![5, 4], vec![3, 1], vec![2]]);
+}
+
 #[tokio::test]
 async fn test_jsonb_null_handling() {
     let (_container, client) = setup_postgres().await;
@@ -2023,5 +2351,10 @@ fn build_jsonb_test_schema() -> Schema {
         ),
     );
 
-    Schema { tables }
+    Schema {
+        tables,
+        extensions: Vec::new(),
+        domains: Vec::new(),
+        composites: Vec::new(),
+    }
 }
@@ -282,6 +282,9 @@ pub struct Select {
     /// Return only the first result.
     pub first: Option<Meta<bool>>,
 
+    /// Stream rows as they arrive instead of collecting them into a `Vec`.
+    pub stream: Option<Meta<bool>>,
+
     /// Use DISTINCT to return only unique rows.
     pub distinct: Option<Meta<bool>>,
 
@@ -291,6 +294,16 @@ pub struct Select {
     /// Order by clause.
     pub order_by: Option<OrderBy>,
 
+    /// GROUP BY clause.
+    pub group_by: Option<GroupBy>,
+
+    /// Filter conditions on groups (HAVING). Keys are aggregate fields from
+    /// `fields` or grouped columns.
+    pub having: Option<Where>,
+
+    /// Pagination mode, e.g. `paginate @keyset{by {created_at desc, id desc}}`.
+    pub paginate: Option<Paginate>,
+
     /// Limit clause (number or param reference like $limit).
     pub limit: Option<Meta<String>>,
 
@@ -327,13 +340,72 @@ pub struct OrderBy {
     pub columns: IndexMap<Meta<ColumnName>, Option<Meta<String>>>,
 }
 
-/// WHERE clause - filter conditions.
+/// How a query is paginated.
+#[derive(Debug, Facet)]
+#[facet(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum Paginate {
+    /// Keyset (cursor) pagination (`@keyset{by {created_at desc, id desc}}`).
+    Keyset(Keyset),
+}
+
+/// Keyset pagination: rows are ordered by `by`, and each page starts after
+/// the last row of the previous one.
+#[derive(Debug, Facet)]
+pub struct Keyset {
+    /// Columns to order and seek by; the last one must be unique.
+    pub by: OrderBy,
+}
+
+/// GROUP BY clause - columns of the `from` table.
+#[derive(Debug, Facet)]
+pub struct GroupBy {
+    #[facet(flatten)]
+    pub columns: IndexMap<Meta<ColumnName>, ()>,
+}
+
+/// WHERE clause - filter conditions, ANDed together.
+///
+/// Keys are column names, except for entries whose value is a `{...}` group
+/// (`@or{...}`, `@and{...}`, `@not{...}`): there the key is only a label.
 #[derive(Debug, Clone, Facet)]
 pub struct Where {
     #[facet(flatten)]
     pub filters: IndexMap<Meta<ColumnName>, FilterValue>,
 }
 
+impl Where {
+    /// Every leaf predicate with the column it applies to, including those
+    /// nested in `@or`/`@and`/`@not` groups, in source order.
+    pub fn predicates(&self) -> Vec<(&Meta<ColumnName>, &FilterValue)> {
+        fn visit<'a>(
+            column: &'a Meta<ColumnName>,
+            filter: &'a FilterValue,
+            out: &mut Vec<(&'a Meta<ColumnName>, &'a FilterValue)>,
+        ) {
+            match filter.junction() {
+                Some((_, Junction::Group(group))) => {
+                    for (column, filter) in &group.filters {
+                        visit(column, filter, out);
+                    }
+                }
+                Some((_, Junction::Each(filters))) => {
+                    for filter in filters {
+                        visit(column, filter, out);
+                    }
+                }
+                None => out.push((column, filter)),
+            }
+        }
+
+        let mut out = Vec::new();
+        for (column, filter) in &self.filters {
+            visit(column, filter, &mut out);
+        }
+        out
+    }
+}
+
 /// A filter value - tagged operators or bare scalars for where clauses.
 ///
 /// Tagged operators:
@@ -346,6 +418,7 @@ pub struct Where {
 /// - `@json-get-text($param)` for JSONB `->>` operator (get JSON value as text)
 /// - `@contains($param)` for `@>` operator (contains, typically JSONB)
 /// - `@key-exists($param)` for `?` operator (key exists, typically JSONB)
+/// - `@or`, `@and`, `@not` for boolean combinations, see [`Junction`]
 ///
 /// Bare scalars (like `$handle`) are treated as equality filters via `#[facet(other)]`.
 #[derive(Debug, Clone, Facet)]
@@ -383,11 +456,89 @@ pub enum FilterValue {
     KeyExists(Vec<Meta<String>>),
     /// Explicit equality (@eq($param) or @eq(value))
     Eq(Vec<Meta<String>>),
+    /// Any of the filters must match (@or{...} or @or(...))
+    Or(Junction),
+    /// All of the filters must match (@and{...} or @and(...))
+    And(Junction),
+    /// None of the filters may match all at once (@not{...} or @not(...))
+    Not(Junction),
     /// Equality - bare scalar fallback (e.g., `$handle` or `"value"`)
     #[facet(other)]
     EqBare(Option<Meta<String>>),
 }
 
+impl FilterValue {
+    /// The boolean operator and operands of an `@or`, `@and` or `@not` filter.
+    pub fn junction(&self) -> Option<(JunctionKind, &Junction)> {
+        match self {
+            FilterValue::Or(junction) => Some((JunctionKind::Or, junction)),
+            FilterValue::And(junction) => Some((JunctionKind::And, junction)),
+            FilterValue::Not(junction) => Some((JunctionKind::Not, junction)),
+            _ => None,
+        }
+    }
+}
+
+/// The operands of an `@or`, `@and` or `@not` filter.
+///
+/// ```styx
+/// where {
+///     deleted_at @null
+///     price @and(@gte($min_price) @lte($max_price))
+///     search @or{
+///         handle @ilike($q)
+///         status @not(@in($hidden))
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Facet)]
+#[facet(untagged)]
+#[repr(u8)]
+pub enum Junction {
+    /// Filters on other columns (`label @or{a $a, b $b}`); the entry's key is
+    /// only a label.
+    Group(Where),
+    /// Several filters on the entry's own column (`price @and(@gte($a) @lte($b))`).
+    Each(Vec<FilterValue>),
+}
+
+impl Junction {
+    /// Number of operands.
+    pub fn len(&self) -> usize {
+        match self {
+            Junction::Group(group) => group.filters.len(),
+            Junction::Each(filters) => filters.len(),
+        }
+    }
+
+    /// Whether there are no operands.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Which boolean operator a [`Junction`] applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JunctionKind {
+    /// `@or`: any operand matches.
+    Or,
+    /// `@and`: every operand matches.
+    And,
+    /// `@not`: the operands, ANDed, don't match.
+    Not,
+}
+
+impl JunctionKind {
+    /// The tag as written in query files.
+    pub fn tag(self) -> &'static str {
+        match self {
+            JunctionKind::Or => "@or",
+            JunctionKind::And => "@and",
+            JunctionKind::Not => "@not",
+        }
+    }
+}
+
 /// Query parameters.
 #[derive(Debug, Clone, Facet)]
 pub struct Params {
@@ -439,8 +590,89 @@ pub struct SelectFields {
 pub enum FieldDef {
     /// A relation field (`@rel{...}`).
     Rel(Relation),
-    /// A count aggregation (`@count(table_name)`).
-    Count(Vec<Meta<TableName>>),
+    /// A count: `@count` (`COUNT(*)`), `@count{column x}` (`COUNT(x)`), or
+    /// related rows with `@count(table_name)`.
+    Count(Option<Count>),
+    /// `SUM(x)` (`@sum{column x}`).
+    Sum(Aggregate),
+    /// `AVG(x)` (`@avg{column x}`).
+    Avg(Aggregate),
+    /// `MIN(x)` (`@min{column x}`).
+    Min(Aggregate),
+    /// `MAX(x)` (`@max{column x}`).
+    Max(Aggregate),
+}
+
+impl FieldDef {
+    /// The aggregate function and its column, if this field is an aggregate
+    /// over the queried rows. `None` as column means `COUNT(*)`.
+    ///
+    /// Related-row counts (`@count(table)`) are subqueries, not aggregates.
+    pub fn aggregate(&self) -> Option<(AggregateFn, Option<&Meta<ColumnName>>)> {
+        match self {
+            FieldDef::Rel(_) | FieldDef::Count(Some(Count::Related(_))) => None,
+            FieldDef::Count(None) => Some((AggregateFn::Count, None)),
+            FieldDef::Count(Some(Count::Column(agg))) => {
+                Some((AggregateFn::Count, Some(&agg.column)))
+            }
+            FieldDef::Sum(agg) => Some((AggregateFn::Sum, Some(&agg.column))),
+            FieldDef::Avg(agg) => Some((AggregateFn::Avg, Some(&agg.column))),
+            FieldDef::Min(agg) => Some((AggregateFn::Min, Some(&agg.column))),
+            FieldDef::Max(agg) => Some((AggregateFn::Max, Some(&agg.column))),
+        }
+    }
+}
+
+/// The payload of a `@count` field.
+#[derive(Debug, Facet)]
+#[facet(untagged)]
+#[repr(u8)]
+pub enum Count {
+    /// Rows of a related table (`@count(table_name)`).
+    Related(Vec<Meta<TableName>>),
+    /// Non-null values of a column (`@count{column x}`).
+    Column(Aggregate),
+}
+
+/// The payload of an aggregate field (`@sum{column amount}`).
+#[derive(Debug, Facet)]
+pub struct Aggregate {
+    /// Column of the `from` table to aggregate.
+    pub column: Meta<ColumnName>,
+}
+
+/// An SQL aggregate function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateFn {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFn {
+    /// The SQL function name.
+    pub fn sql_name(self) -> &'static str {
+        match self {
+            AggregateFn::Count => "COUNT",
+            AggregateFn::Sum => "SUM",
+            AggregateFn::Avg => "AVG",
+            AggregateFn::Min => "MIN",
+            AggregateFn::Max => "MAX",
+        }
+    }
+
+    /// The tag as written in query files.
+    pub fn tag(self) -> &'static str {
+        match self {
+            AggregateFn::Count => "@count",
+            AggregateFn::Sum => "@sum",
+            AggregateFn::Avg => "@avg",
+            AggregateFn::Min => "@min",
+            AggregateFn::Max => "@max",
+        }
+    }
 }
 
 /// A relation definition (nested query on related table).
@@ -450,6 +682,11 @@ pub struct Relation {
     /// Optional explicit table name.
     pub from: Option<Meta<TableName>>,
 
+    /// Junction table of a many-to-many relation (e.g. `post_tag` between
+    /// `post` and `tag`). Detected automatically when a single table has
+    /// foreign keys to both sides.
+    pub through: Option<Meta<TableName>>,
+
     /// Filter conditions.
     #[facet(rename = "where")]
     pub where_clause: Option<Where>,
@@ -579,6 +816,8 @@ pub struct Delete {
     pub where_clause: Option<Where>,
     /// Columns to return.
     pub returning: Option<Returning>,
+    /// Remove rows even if the table is soft-deleted.
+    pub hard: Option<Meta<bool>>,
 }
 
 /// Values clause for INSERT/UPDATE.
@@ -682,6 +921,18 @@ impl Select {
         self.first.is_some()
     }
 
+    /// Check if this query streams its results (`stream true`).
+    pub fn is_stream(&self) -> bool {
+        self.stream.as_ref().is_some_and(|stream| stream.get())
+    }
+
+    /// The keyset of a keyset-paginated query.
+    pub fn keyset(&self) -> Option<&Keyset> {
+        self.paginate
+            .as_ref()
+            .map(|Paginate::Keyset(keyset)| keyset)
+    }
+
     /// Check if this query has any relations in its select clause.
     pub fn has_relations(&self) -> bool {
         self.fields
@@ -750,6 +1001,11 @@ impl SelectFields {
             .any(|field_def| matches!(field_def, Some(FieldDef::Count(_))))
     }
 
+    /// Check if this select has any aggregates over the queried rows.
+    pub fn has_aggregates(&self) -> bool {
+        self.aggregates().next().is_some()
+    }
+
     /// Iterate over simple columns (fields with None FieldDef).
     pub fn columns(&self) -> impl Iterator<Item = (&Meta<ColumnName>, &Option<FieldDef>)> {
         self.fields
@@ -768,10 +1024,10 @@ impl SelectFields {
         })
     }
 
-    /// Iterate over count aggregations (fields with Some(FieldDef::Count(_))).
+    /// Iterate over related-row counts (`@count(table_name)` fields).
     pub fn counts(&self) -> impl Iterator<Item = (&Meta<ColumnName>, &Vec<Meta<TableName>>)> {
         self.fields.iter().filter_map(|(name, field_def)| {
-            if let Some(FieldDef::Count(tables)) = field_def {
+            if let Some(FieldDef::Count(Some(Count::Related(tables)))) = field_def {
                 Some((name, tables))
             } else {
                 None
@@ -779,6 +1035,17 @@ impl SelectFields {
         })
     }
 
+    /// Iterate over aggregates (see [`FieldDef::aggregate`]).
+    #[allow(clippy::type_complexity)]
+    pub fn aggregates(
+        &self,
+    ) -> impl Iterator<Item = (&Meta<ColumnName>, AggregateFn, Option<&Meta<ColumnName>>)> {
+        self.fields.iter().filter_map(|(name, field_def)| {
+            let (func, column) = field_def.as_ref()?.aggregate()?;
+            Some((name, func, column))
+        })
+    }
+
     /// Get the first column name (first simple column, not a relation).
     /// Returns None if there are no simple columns.
     pub fn first_column(&self) -> Option<&ColumnName> {
@@ -102,6 +102,110 @@ fn filter_value_eq_shorthand() {
     }
 }
 
+/// Test `@or{...}` groups and several filters on one column via `@and(...)`.
+#[test]
+fn filter_groups() {
+    let source = r#"{
+        price @and(@gte($min) @lte($max))
+        search @or{handle @ilike($q), hidden @not{status "draft"}}
+    }"#;
+    let result: Result<Where, _> = facet_styx::from_str(source);
+
+    match result {
+        Ok(where_clause) => {
+            let (key, value) = where_clause.filters.first().unwrap();
+            assert_eq!(key.as_str(), "price");
+            match value {
+                FilterValue::And(Junction::Each(filters)) => {
+                    assert!(matches!(
+                        filters[..],
+                        [FilterValue::Gte(_), FilterValue::Lte(_)]
+                    ));
+                }
+                _ => panic!("Expected @and(...) on price, got {:?}", value),
+            }
+
+            let (_, value) = where_clause.filters.get_index(1).unwrap();
+            assert!(matches!(
+                value.junction(),
+                Some((JunctionKind::Or, Junction::Group(_)))
+            ));
+
+            let predicates: Vec<_> = where_clause
+                .predicates()
+                .into_iter()
+                .map(|(column, _)| column.as_str())
+                .collect();
+            assert_eq!(predicates, ["price", "price", "handle", "status"]);
+        }
+        Err(e) => {
+            panic!("Failed to parse: {}", e.render("<test>", source));
+        }
+    }
+}
+
+#[test]
+fn aggregate_fields() {
+    let source = r#"{
+        category_id
+        variant_count @count(product_variant)
+        items @count
+        priced @count{column price}
+        total @sum{column price}
+    }"#;
+    let result: Result<SelectFields, _> = facet_styx::from_str(source);
+
+    match result {
+        Ok(fields) => {
+            assert_eq!(fields.counts().count(), 1);
+            let aggregates: Vec<_> = fields
+                .aggregates()
+                .map(|(name, func, column)| (name.as_str(), func, column.map(|c| c.value.as_str())))
+                .collect();
+            assert_eq!(
+                aggregates,
+                [
+                    ("items", AggregateFn::Count, None),
+                    ("priced", AggregateFn::Count, Some("price")),
+                    ("total", AggregateFn::Sum, Some("price")),
+                ]
+            );
+        }
+        Err(e) => {
+            panic!("Failed to parse: {}", e.render("<test>", source));
+        }
+    }
+}
+
+#[test]
+fn keyset_pagination() {
+    let source = r#"{
+        from post
+        limit $limit
+        paginate @keyset{by {created_at desc, id desc}}
+        fields {id, created_at}
+    }"#;
+    let result: Result<Select, _> = facet_styx::from_str(source);
+
+    match result {
+        Ok(select) => {
+            let Some(Paginate::Keyset(keyset)) = &select.paginate else {
+                panic!("expected keyset pagination, got {:?}", select.paginate);
+            };
+            let by: Vec<_> = keyset
+                .by
+                .columns
+                .iter()
+                .map(|(col, dir)| (col.as_str(), dir.as_ref().map(|d| d.as_str())))
+                .collect();
+            assert_eq!(by, [("created_at", Some("desc")), ("id", Some("desc"))]);
+        }
+        Err(e) => {
+            panic!("Failed to parse: {}", e.render("<test>", source));
+        }
+    }
+}
+
 #[test]
 fn test_fixtures_queries1() {
     let source = include_str!("./fixtures/queries1.styx");
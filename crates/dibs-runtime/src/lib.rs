@@ -12,6 +12,12 @@ pub use facet;
 // Re-export facet-tokio-postgres for row deserialization
 pub use facet_tokio_postgres;
 
+// Re-export futures-util for the streams of `stream true` queries
+pub use futures_util;
+
+mod stream;
+pub use stream::{group_rows, stream_rows};
+
 // Re-export common types used in generated structs
 pub mod types {
     pub use dibs_jsonb::Jsonb;
@@ -28,6 +34,8 @@ pub enum QueryError {
     Database(tokio_postgres::Error),
     /// Row deserialization failed.
     Deserialize(facet_tokio_postgres::Error),
+    /// Rows couldn't be assembled into nested results.
+    Assemble(String),
 }
 
 impl std::fmt::Display for QueryError {
@@ -35,6 +43,7 @@ impl std::fmt::Display for QueryError {
         match self {
             QueryError::Database(e) => write!(f, "database error: {}", e),
             QueryError::Deserialize(e) => write!(f, "deserialization error: {:?}", e),
+            QueryError::Assemble(reason) => write!(f, "result assembly error: {}", reason),
         }
     }
 }
@@ -43,7 +52,7 @@ impl std::error::Error for QueryError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             QueryError::Database(e) => Some(e),
-            QueryError::Deserialize(_) => None,
+            QueryError::Deserialize(_) | QueryError::Assemble(_) => None,
         }
     }
 }
@@ -121,6 +130,14 @@ fn log_query_error(query: &'static str, err: &QueryError) {
                 "dibs row deserialization failed",
             );
         }
+        QueryError::Assemble(reason) => {
+            tracing::error!(
+                query,
+                kind = "assemble",
+                error = %reason,
+                "dibs result assembly failed",
+            );
+        }
     }
 }
 
@@ -128,6 +145,7 @@ fn log_query_error(query: &'static str, err: &QueryError) {
 pub mod prelude {
     pub use facet::Facet;
     pub use facet_tokio_postgres::from_row;
+    pub use futures_util::Stream;
 
     pub use super::QueryError;
     pub use super::TraceErr;
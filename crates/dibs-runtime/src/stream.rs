@@ -0,0 +1,70 @@
+//! Helpers for the row streams returned by `stream true` queries.
+
+use crate::{QueryError, TraceErr};
+use facet::Facet;
+use futures_util::{Stream, StreamExt, TryStreamExt, stream};
+use std::future::Future;
+use std::task::{Poll, ready};
+use tokio_postgres::RowStream;
+
+/// Deserialize each row of a `query_raw` call with `from_row`, as it
+/// arrives.
+///
+/// Errors are traced under `query` (see [`TraceErr`]) and end the stream.
+pub fn stream_rows<T, F>(
+    query: &'static str,
+    rows: F,
+) -> impl Stream<Item = Result<T, QueryError>> + Unpin
+where
+    T: Facet<'static>,
+    F: Future<Output = Result<RowStream, tokio_postgres::Error>>,
+{
+    Box::pin(
+        stream::once(rows)
+            .try_flatten()
+            .map(|row| Ok(facet_tokio_postgres::from_row(&row?)?))
+            .map(move |result| result.trace_err(query)),
+    )
+}
+
+/// Group consecutive rows sharing a key, then `build` one item per group.
+///
+/// Used for queries with has-many relations: ordered by the parent's key,
+/// each parent's joined rows are adjacent, so a parent is complete as soon as
+/// a row with another key (or the end of the stream) shows up.
+pub fn group_rows<S, R, K, T>(
+    rows: S,
+    mut key: impl FnMut(&R) -> K,
+    mut build: impl FnMut(Vec<R>) -> Result<T, QueryError>,
+) -> impl Stream<Item = Result<T, QueryError>> + Unpin
+where
+    S: Stream<Item = Result<R, QueryError>>,
+    K: PartialEq,
+{
+    let mut rows = Box::pin(rows.fuse());
+    let mut current: Option<(K, Vec<R>)> = None;
+
+    stream::poll_fn(move |cx| {
+        loop {
+            match ready!(rows.as_mut().poll_next(cx)) {
+                Some(Ok(row)) => {
+                    let row_key = key(&row);
+                    match &mut current {
+                        Some((group_key, group)) if *group_key == row_key => group.push(row),
+                        _ => {
+                            if let Some((_, group)) = current.replace((row_key, vec![row])) {
+                                return Poll::Ready(Some(build(group)));
+                            }
+                        }
+                    }
+                }
+                Some(Err(e)) => {
+                    // The group in progress is missing rows; drop it.
+                    current = None;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                None => return Poll::Ready(current.take().map(|(_, group)| build(group))),
+            }
+        }
+    })
+}
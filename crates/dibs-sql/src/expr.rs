@@ -27,6 +27,8 @@ pub enum Expr {
         op: BinOp,
         right: Box<Expr>,
     },
+    /// Logical negation: `NOT (expr)`
+    Not(Box<Expr>),
     /// IS NULL / IS NOT NULL
     IsNull { expr: Box<Expr>, negated: bool },
     /// LIKE pattern match (case-sensitive)
@@ -49,6 +51,8 @@ pub enum Expr {
     Excluded(ColumnName),
     /// Function call
     FnCall { name: String, args: Vec<Expr> },
+    /// Row constructor: `(a, b, c)`, e.g. for keyset comparisons
+    Row(Vec<Expr>),
     /// COUNT(table.*) for counting related rows
     Count { table: TableName },
     /// Raw SQL (escape hatch)
@@ -130,6 +134,17 @@ impl BinOp {
             BinOp::Or => "OR",
         }
     }
+
+    /// Binding strength, higher binds tighter. Operands of a lower
+    /// precedence than their parent get parenthesized when rendered.
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinOp::Or => 1,
+            BinOp::And => 2,
+            BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => 3,
+            BinOp::Add | BinOp::Sub => 4,
+        }
+    }
 }
 
 // Convenience constructors
@@ -185,6 +200,11 @@ impl Expr {
         }
     }
 
+    /// Create a NOT expression: NOT (self)
+    pub fn negate(self) -> Self {
+        Expr::Not(Box::new(self))
+    }
+
     /// Create IS NULL expression
     pub fn is_null(self) -> Self {
         Expr::IsNull {
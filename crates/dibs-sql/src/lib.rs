@@ -158,6 +158,70 @@ pub fn check_constraint_name(table: &str, expr: &str) -> String {
     format!("ck_{}_{}", table_part, suffix)
 }
 
+/// Generate a deterministic EXCLUDE constraint name for a table and definition.
+///
+/// Like [`check_constraint_name`], the name embeds a stable hash of the
+/// definition (index method, elements and WHERE clause), so changing the
+/// definition shows up as a drop and re-add.
+pub fn exclusion_constraint_name(
+    table: &str,
+    using: &str,
+    elements: &str,
+    filter: Option<&str>,
+) -> String {
+    let hash = exclusion_def_hash(using, elements, filter);
+    let suffix = hash.as_str();
+
+    const PG_IDENT_MAX: usize = 63;
+    let prefix_overhead = "ex__".len(); // "ex_" + "_" between table and suffix
+    let max_table_len = PG_IDENT_MAX.saturating_sub(prefix_overhead + suffix.len());
+
+    let table_part = if table.len() <= max_table_len {
+        table
+    } else {
+        let mut len = max_table_len.min(table.len());
+        while len > 0 && !table.is_char_boundary(len) {
+            len -= 1;
+        }
+        &table[..len]
+    };
+
+    format!("ex_{}_{}", table_part, suffix)
+}
+
+/// Hash an EXCLUDE constraint's definition (index method, elements and WHERE
+/// clause).
+///
+/// Generated constraint names end with this hash. Explicitly named
+/// constraints keep it in the constraint's comment instead, since Postgres
+/// re-renders the definition and it can't be compared with the declared one.
+pub fn exclusion_def_hash(using: &str, elements: &str, filter: Option<&str>) -> String {
+    let definition = format!(
+        "{} ({}) {}",
+        using.to_lowercase(),
+        elements,
+        filter.unwrap_or("")
+    );
+    let normalized = normalize_sql_expr_for_hash(&definition);
+    let hex = blake3::hash(normalized.as_bytes()).to_hex().to_string();
+    hex[..16].to_string()
+}
+
+/// Hash a row-level security policy's USING / WITH CHECK expressions.
+///
+/// Postgres stores policy expressions normalized (extra parens, explicit
+/// casts), so they can't be compared with the declared ones. The hash of the
+/// declared expressions is kept in the policy's comment instead.
+pub fn policy_expr_hash(using: Option<&str>, with_check: Option<&str>) -> String {
+    let input = format!(
+        "{}\0{}",
+        normalize_sql_expr_for_hash(using.unwrap_or("")),
+        normalize_sql_expr_for_hash(with_check.unwrap_or(""))
+    );
+    let hex = blake3::hash(input.as_bytes()).to_hex().to_string();
+    hex[..16].to_string()
+}
+
 /// Generate a deterministic trigger name for a trigger-enforced check.
 ///
 /// Trigger names are scoped to a table in Postgres, but we still include the table name
@@ -5,7 +5,7 @@ use std::fmt;
 
 use indexmap::IndexMap;
 
-use crate::expr::{ColumnRef, Expr};
+use crate::expr::{BinOp, ColumnRef, Expr};
 use crate::stmt::*;
 use crate::{Ident, ParamName, RenderedSql, escape_string};
 
@@ -111,10 +111,33 @@ impl Render for Expr {
             Expr::Now => write!(f, "NOW()"),
             Expr::Default => write!(f, "DEFAULT"),
             Expr::BinOp { left, op, right } => {
+                // `a AND (b OR c)`: parenthesize operands that bind looser
+                // than `op`, and right operands of equal precedence unless
+                // `op` is associative.
+                let associative = matches!(op, BinOp::And | BinOp::Or | BinOp::Add);
+                let left_parens = matches!(
+                    left.as_ref(),
+                    Expr::BinOp { op: inner, .. } if inner.precedence() < op.precedence()
+                );
+                let right_parens = matches!(
+                    right.as_ref(),
+                    Expr::BinOp { op: inner, .. }
+                        if inner.precedence() < op.precedence()
+                            || (inner.precedence() == op.precedence() && !associative)
+                );
                 let left = Fmt(ctx, left.as_ref());
                 let right = Fmt(ctx, right.as_ref());
                 let op = op.as_str();
-                write!(f, "{left} {op} {right}")
+                match (left_parens, right_parens) {
+                    (false, false) => write!(f, "{left} {op} {right}"),
+                    (true, false) => write!(f, "({left}) {op} {right}"),
+                    (false, true) => write!(f, "{left} {op} ({right})"),
+                    (true, true) => write!(f, "({left}) {op} ({right})"),
+                }
+            }
+            Expr::Not(expr) => {
+                let expr = Fmt(ctx, expr.as_ref());
+                write!(f, "NOT ({expr})")
             }
             Expr::IsNull { expr, negated } => {
                 let expr = Fmt(ctx, expr.as_ref());
@@ -174,6 +197,16 @@ impl Render for Expr {
                 }
                 write!(f, ")")
             }
+            Expr::Row(exprs) => {
+                write!(f, "(")?;
+                for (i, expr) in exprs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", Fmt(ctx, expr))?;
+                }
+                write!(f, ")")
+            }
             Expr::Count { table } => {
                 let table = Ident(table.as_str());
                 write!(f, "COUNT({table}.*)")
@@ -253,6 +286,23 @@ impl Render for SelectStmt {
             write!(f, "\nWHERE {where_}")?;
         }
 
+        // GROUP BY
+        if !self.group_by.is_empty() {
+            write!(f, "\nGROUP BY ")?;
+            for (i, expr) in self.group_by.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", Fmt(ctx, expr))?;
+            }
+        }
+
+        // HAVING
+        if let Some(having) = &self.having {
+            let having = Fmt(ctx, having);
+            write!(f, "\nHAVING {having}")?;
+        }
+
         // ORDER BY
         if !self.order_by.is_empty() {
             write!(f, "\nORDER BY ")?;
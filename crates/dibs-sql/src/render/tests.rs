@@ -178,6 +178,35 @@ fn test_select_with_params() {
     );
 }
 
+#[test]
+fn test_select_with_group_by_and_having() {
+    let sum = Expr::FnCall {
+        name: "SUM".into(),
+        args: vec![Expr::column("amount".into())],
+    };
+    let stmt = SelectStmt::new()
+        .columns([
+            SelectColumn::expr(Expr::column("category_id".into())),
+            SelectColumn::aliased(sum.clone(), "total".into()),
+        ])
+        .from(FromClause::table("order_item".into()))
+        .where_(Expr::column("status".into()).eq(Expr::param("status".into())))
+        .group_by([Expr::column("category_id".into())])
+        .having(Expr::BinOp {
+            left: Box::new(sum),
+            op: BinOp::Gt,
+            right: Box::new(Expr::param("min_total".into())),
+        })
+        .order_by(OrderBy::asc(Expr::column("category_id".into())));
+
+    let result = render(&stmt);
+    insta::assert_snapshot!(result.sql);
+    assert_eq!(
+        result.params,
+        vec![ParamName::from("status"), ParamName::from("min_total")]
+    );
+}
+
 #[test]
 fn test_select_with_join() {
     let stmt = SelectStmt::new()
@@ -426,6 +455,43 @@ fn test_jsonb_key_exists() {
     insta::assert_snapshot!(result.sql);
 }
 
+#[test]
+fn test_or_inside_and() {
+    let stmt = SelectStmt::new()
+        .columns([SelectColumn::expr(Expr::column("id".into()))])
+        .from(FromClause::table("products".into()))
+        .where_(
+            Expr::column("deleted_at".into()).is_null().and(
+                Expr::column("status".into())
+                    .eq(Expr::param("status".into()))
+                    .or(Expr::column("featured".into()).eq(Expr::Bool(true))),
+            ),
+        );
+
+    let result = render(&stmt);
+    insta::assert_snapshot!(result.sql);
+}
+
+#[test]
+fn test_not_and_nested_or() {
+    let stmt = SelectStmt::new()
+        .columns([SelectColumn::expr(Expr::column("id".into()))])
+        .from(FromClause::table("products".into()))
+        .where_(
+            Expr::column("a".into())
+                .eq(Expr::Int(1))
+                .or(Expr::column("b".into()).eq(Expr::Int(2)))
+                .and(Expr::column("c".into()).eq(Expr::Int(3)))
+                .or(Expr::column("d".into())
+                    .eq(Expr::Int(4))
+                    .and(Expr::column("e".into()).eq(Expr::Int(5)))
+                    .negate()),
+        );
+
+    let result = render(&stmt);
+    insta::assert_snapshot!(result.sql);
+}
+
 #[test]
 fn test_select_distinct() {
     let stmt = SelectStmt::new()
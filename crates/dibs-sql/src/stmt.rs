@@ -5,6 +5,7 @@ use crate::{ColumnName, PgType, TableName};
 
 /// A SQL statement.
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum Stmt {
     /// A SELECT query.
     Select(SelectStmt),
@@ -33,6 +34,10 @@ pub struct SelectStmt {
     pub joins: Vec<Join>,
     /// The WHERE clause filter condition.
     pub where_: Option<Expr>,
+    /// GROUP BY expressions.
+    pub group_by: Vec<Expr>,
+    /// The HAVING clause filter condition on groups.
+    pub having: Option<Expr>,
     /// ORDER BY clauses for sorting results.
     pub order_by: Vec<OrderBy>,
     /// LIMIT clause to restrict number of rows.
@@ -382,6 +387,16 @@ impl SelectStmt {
         self
     }
 
+    pub fn group_by(mut self, exprs: impl IntoIterator<Item = Expr>) -> Self {
+        self.group_by.extend(exprs);
+        self
+    }
+
+    pub fn having(mut self, expr: Expr) -> Self {
+        self.having = Some(expr);
+        self
+    }
+
     pub fn order_by(mut self, order: OrderBy) -> Self {
         self.order_by.push(order);
         self
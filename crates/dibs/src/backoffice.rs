@@ -142,6 +142,7 @@ fn schema_to_info(schema: &Schema) -> SchemaInfo {
     use dibs_proto::{ColumnInfo, ForeignKeyInfo, IndexColumnInfo, IndexInfo, TableInfo};
 
     SchemaInfo {
+        extensions: schema.extensions.clone(),
         tables: schema
             .tables
             .values()
@@ -175,6 +176,7 @@ fn schema_to_info(schema: &Schema) -> SchemaInfo {
                         columns: fk.columns.clone(),
                         references_table: fk.references_table.clone(),
                         references_columns: fk.references_columns.clone(),
+                        deferrable: fk.deferrable,
                     })
                     .collect(),
                 indices: t
@@ -206,6 +208,10 @@ fn schema_to_info(schema: &Schema) -> SchemaInfo {
                 source_line: t.source.line,
                 doc: t.doc.clone(),
                 icon: t.icon.clone(),
+                soft_delete: t.soft_delete.clone(),
+                created_at: t.created_at.clone(),
+                updated_at: t.updated_at.clone(),
+                audited: t.audited,
             })
             .collect(),
     }
@@ -229,13 +235,23 @@ impl<P: ConnectionProvider> SquelService for SquelServiceImpl<P> {
             .map_err(|e| DibsError::QueryError(e.to_string()))?;
         let db = Db::new(&conn);
 
+        // Hide soft-deleted rows unless the caller filters on them explicitly
+        let mut filters: Vec<Expr> = request.filters.iter().map(filter_to_expr).collect();
+        if let Some(column) = db
+            .table(&request.table)
+            .and_then(|t| t.soft_delete.as_ref())
+            && !request.filters.iter().any(|f| &f.field == column)
+        {
+            filters.push(Expr::IsNull(column.clone()));
+        }
+
         // Build the count query (same filters, no pagination)
         let mut count_builder = db
             .select(&request.table)
             .map_err(|e| DibsError::UnknownTable(e.to_string()))?;
 
-        for filter in &request.filters {
-            count_builder = count_builder.filter(filter_to_expr(filter));
+        for filter in &filters {
+            count_builder = count_builder.filter(filter.clone());
         }
 
         let total = count_builder
@@ -249,8 +265,8 @@ impl<P: ConnectionProvider> SquelService for SquelServiceImpl<P> {
             .map_err(|e| DibsError::UnknownTable(e.to_string()))?;
 
         // Apply filters
-        for filter in &request.filters {
-            builder = builder.filter(filter_to_expr(filter));
+        for filter in filters {
+            builder = builder.filter(filter);
         }
 
         // Apply sorting
@@ -396,13 +412,18 @@ impl<P: ConnectionProvider> SquelService for SquelServiceImpl<P> {
                 DibsError::InvalidRequest(format!("Table {} has no primary key", request.table))
             })?;
 
-        let affected = db
+        let mut builder = db
             .delete(&request.table)
             .map_err(|e| DibsError::UnknownTable(e.to_string()))?
             .filter(Expr::Eq(
                 pk_col.name.clone(),
                 proto_value_to_query(&request.pk),
-            ))
+            ));
+        if let Some(column) = &table.soft_delete {
+            builder = builder.soft_delete(column);
+        }
+
+        let affected = builder
             .execute()
             .await
             .map_err(|e| DibsError::QueryError(e.to_string()))?;
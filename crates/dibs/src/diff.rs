@@ -39,11 +39,24 @@
 //! ```
 
 use crate::{
-    CheckConstraint, Column, ForeignKey, Index, PgType, Schema, Table, TriggerCheckConstraint,
-    quote_ident,
+    CheckConstraint, Column, CompositeField, CompositeType, DomainType, ExclusionConstraint,
+    ForeignKey, Index, PartitionSpec, PgType, RlsPolicy, Schema, Table, TableTrigger,
+    TriggerCheckConstraint, quote_ident,
 };
 use std::collections::HashSet;
 
+/// Pseudo table name under which missing extensions are reported.
+///
+/// Extensions aren't owned by any table; grouping them under this name keeps
+/// them in the usual per-table output, listed before any real table.
+pub const EXTENSIONS_DIFF_TABLE: &str = "(extensions)";
+
+/// Pseudo table name under which domain changes are reported.
+pub const DOMAINS_DIFF_TABLE: &str = "(domains)";
+
+/// Pseudo table name under which composite type changes are reported.
+pub const COMPOSITES_DIFF_TABLE: &str = "(composite types)";
+
 /// A diff between two schemas.
 #[derive(Debug, Clone, Default)]
 pub struct SchemaDiff {
@@ -66,7 +79,12 @@ impl SchemaDiff {
     pub fn to_sql(&self) -> String {
         let mut sql = String::new();
         for table_diff in &self.table_diffs {
-            sql.push_str(&format!("-- Table: {}\n", table_diff.table));
+            match table_diff.table.as_str() {
+                EXTENSIONS_DIFF_TABLE => sql.push_str("-- Extensions\n"),
+                DOMAINS_DIFF_TABLE => sql.push_str("-- Domains\n"),
+                COMPOSITES_DIFF_TABLE => sql.push_str("-- Composite types\n"),
+                table => sql.push_str(&format!("-- Table: {}\n", table)),
+            }
             for change in &table_diff.changes {
                 sql.push_str(&change.to_sql(&table_diff.table));
                 sql.push('\n');
@@ -89,6 +107,31 @@ pub struct TableDiff {
 /// A single schema change.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Change {
+    /// Create a Postgres extension (`CREATE EXTENSION IF NOT EXISTS`).
+    ///
+    /// Reported under [`EXTENSIONS_DIFF_TABLE`] and scheduled before every
+    /// other change by the solver.
+    CreateExtension(String),
+    /// Create a domain type (reported under [`DOMAINS_DIFF_TABLE`]).
+    CreateDomain(DomainType),
+    /// Replace a domain's CHECK constraint.
+    AlterDomainCheck {
+        name: String,
+        from: Option<CheckConstraint>,
+        to: Option<CheckConstraint>,
+    },
+    /// Drop a domain type (by name).
+    DropDomain(String),
+    /// Create a composite type (reported under [`COMPOSITES_DIFF_TABLE`]).
+    CreateComposite(CompositeType),
+    /// Add, drop or retype a composite type's attributes.
+    AlterComposite {
+        name: String,
+        from: Vec<CompositeField>,
+        to: Vec<CompositeField>,
+    },
+    /// Drop a composite type (by name).
+    DropComposite(String),
     /// Add a new table.
     AddTable(Table),
     /// Drop an existing table.
@@ -107,6 +150,15 @@ pub enum Change {
         from: PgType,
         to: PgType,
     },
+    /// Change a column's domain or composite type (`None` means the plain
+    /// base type).
+    AlterColumnDomain {
+        name: String,
+        from: Option<String>,
+        to: Option<String>,
+        /// Base type of the column after the change.
+        pg_type: PgType,
+    },
     /// Change a column's nullability.
     AlterColumnNullable { name: String, from: bool, to: bool },
     /// Change a column's auto-generated status (add/remove sequence or identity).
@@ -137,6 +189,12 @@ pub enum Change {
     AddCheck(CheckConstraint),
     /// Drop a CHECK constraint (by name).
     DropCheck(String),
+    /// Add an EXCLUDE constraint.
+    AddExclusion(ExclusionConstraint),
+    /// Drop an EXCLUDE constraint (by name).
+    DropExclusion(String),
+    /// Make a foreign key deferrable (or not).
+    AlterForeignKeyDeferrable { fk: ForeignKey, deferrable: bool },
     /// Create/replace a trigger function for a trigger-enforced check.
     AddTriggerCheckFunction(TriggerCheckConstraint),
     /// Create a trigger for a trigger-enforced check.
@@ -145,6 +203,41 @@ pub enum Change {
     DropTriggerCheck(String),
     /// Drop the trigger function for a trigger-enforced check (by trigger name).
     DropTriggerCheckFunction(String),
+    /// Change a table's partition key.
+    ///
+    /// Postgres can't (un)partition a table in place, so this change is
+    /// rejected by the solver; it only exists to surface the difference.
+    AlterPartitioning {
+        from: Option<PartitionSpec>,
+        to: Option<PartitionSpec>,
+    },
+    /// Create a user-defined trigger and its function.
+    AddTrigger(TableTrigger),
+    /// Drop a user-defined trigger and its function.
+    DropTrigger {
+        name: String,
+        /// Name of the trigger function, which depends on the old body.
+        function: String,
+    },
+    /// Enable row-level security on the table.
+    EnableRls,
+    /// Disable row-level security on the table.
+    DisableRls,
+    /// Create a row-level security policy.
+    AddPolicy {
+        policy: RlsPolicy,
+        /// Columns of the table referenced by the policy expressions.
+        columns: Vec<String>,
+    },
+    /// Drop a row-level security policy (by name).
+    DropPolicy(String),
+    /// Set (or clear) the comment on the table, or on one of its columns.
+    SetComment {
+        /// Column name, or `None` for the table itself.
+        column: Option<String>,
+        from: Option<String>,
+        to: Option<String>,
+    },
 }
 
 impl Change {
@@ -154,6 +247,17 @@ impl Change {
     pub fn to_sql(&self, table_name: &str) -> String {
         let qt = quote_ident(table_name);
         match self {
+            Change::CreateExtension(name) => crate::schema::create_extension_sql(name),
+            Change::CreateDomain(domain) => crate::schema::create_domain_sql(domain),
+            Change::AlterDomainCheck { name, from, to } => {
+                crate::schema::alter_domain_check_sql(name, from.as_ref(), to.as_ref())
+            }
+            Change::DropDomain(name) => crate::schema::drop_domain_sql(name),
+            Change::CreateComposite(composite) => crate::schema::create_composite_sql(composite),
+            Change::AlterComposite { name, from, to } => {
+                crate::schema::alter_composite_sql(name, from, to)
+            }
+            Change::DropComposite(name) => crate::schema::drop_composite_sql(name),
             Change::AddTable(t) => crate::schema::create_table_sql(t),
             Change::DropTable(name) => format!("DROP TABLE {};", quote_ident(name)),
             Change::RenameTable { from, to } => {
@@ -179,7 +283,7 @@ impl Change {
                     "ALTER TABLE {} ADD COLUMN {} {}{}{}{};",
                     qt,
                     quote_ident(&col.name),
-                    col.pg_type,
+                    crate::schema::column_type_sql(col),
                     identity,
                     not_null,
                     default
@@ -206,6 +310,22 @@ impl Change {
                     to
                 )
             }
+            Change::AlterColumnDomain {
+                name, to, pg_type, ..
+            } => {
+                let ty = match to {
+                    Some(domain) => quote_ident(domain),
+                    None => pg_type.to_string(),
+                };
+                format!(
+                    "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{};",
+                    qt,
+                    quote_ident(name),
+                    ty,
+                    quote_ident(name),
+                    ty
+                )
+            }
             Change::AlterColumnNullable { name, to, .. } => {
                 if *to {
                     format!(
@@ -279,12 +399,13 @@ impl Change {
                     .map(|c| quote_ident(c))
                     .collect();
                 format!(
-                    "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({});",
+                    "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}){};",
                     qt,
                     quote_ident(&constraint_name),
                     quoted_cols.join(", "),
                     quote_ident(&fk.references_table),
-                    quoted_ref_cols.join(", ")
+                    quoted_ref_cols.join(", "),
+                    crate::schema::deferrable_sql(fk.deferrable)
                 )
             }
             Change::DropForeignKey(fk) => {
@@ -345,6 +466,37 @@ impl Change {
             Change::DropCheck(name) => {
                 format!("ALTER TABLE {} DROP CONSTRAINT {};", qt, quote_ident(name))
             }
+            Change::AddExclusion(exclusion) => {
+                let mut sql = format!(
+                    "ALTER TABLE {} ADD CONSTRAINT {} {};",
+                    qt,
+                    quote_ident(&exclusion.name),
+                    crate::schema::exclusion_constraint_sql(exclusion)
+                );
+                if let Some(comment) =
+                    crate::schema::exclusion_hash_comment_sql(table_name, exclusion)
+                {
+                    sql.push('\n');
+                    sql.push_str(&comment);
+                }
+                sql
+            }
+            Change::DropExclusion(name) => {
+                format!("ALTER TABLE {} DROP CONSTRAINT {};", qt, quote_ident(name))
+            }
+            Change::AlterForeignKeyDeferrable { fk, deferrable } => {
+                let constraint_name = format!("{}_{}_fkey", table_name, fk.columns.join("_"));
+                format!(
+                    "ALTER TABLE {} ALTER CONSTRAINT {}{};",
+                    qt,
+                    quote_ident(&constraint_name),
+                    if *deferrable {
+                        " DEFERRABLE INITIALLY DEFERRED"
+                    } else {
+                        " NOT DEFERRABLE"
+                    }
+                )
+            }
             Change::AddTriggerCheckFunction(trig) => {
                 let fn_name = crate::trigger_check_function_name(&trig.name);
                 let message = trig
@@ -382,6 +534,28 @@ impl Change {
                 let fn_name = crate::trigger_check_function_name(trigger_name);
                 format!("DROP FUNCTION IF EXISTS {}();", quote_ident(&fn_name))
             }
+            Change::AlterPartitioning { from, to } => format!(
+                "-- cannot change partitioning of {} in place ({} -> {}): recreate the table and copy its rows",
+                qt,
+                partition_display(from),
+                partition_display(to)
+            ),
+            Change::AddTrigger(trig) => crate::schema::create_trigger_sql(table_name, trig),
+            Change::DropTrigger { name, function } => {
+                crate::schema::drop_trigger_sql(table_name, name, function)
+            }
+            Change::EnableRls => crate::schema::set_row_level_security_sql(table_name, true),
+            Change::DisableRls => crate::schema::set_row_level_security_sql(table_name, false),
+            Change::AddPolicy { policy, .. } => {
+                crate::schema::create_policy_sql(table_name, policy)
+            }
+            Change::DropPolicy(name) => crate::schema::drop_policy_sql(table_name, name),
+            Change::SetComment { column, to, .. } => match column {
+                Some(column) => {
+                    crate::schema::comment_on_column_sql(table_name, column, to.as_deref())
+                }
+                None => crate::schema::comment_on_table_sql(table_name, to.as_deref()),
+            },
         }
     }
 }
@@ -401,6 +575,16 @@ impl std::fmt::Display for Change {
             Change::AlterColumnType { name, from, to } => {
                 write!(f, "~ {}: {} -> {}", name, from, to)
             }
+            Change::AlterColumnDomain {
+                name,
+                from,
+                to,
+                pg_type,
+            } => {
+                let from_str = from.clone().unwrap_or_else(|| pg_type.to_string());
+                let to_str = to.clone().unwrap_or_else(|| pg_type.to_string());
+                write!(f, "~ {}: {} -> {}", name, from_str, to_str)
+            }
             Change::AlterColumnNullable { name, from, to } => {
                 let from_str = if *from { "nullable" } else { "not null" };
                 let to_str = if *to { "nullable" } else { "not null" };
@@ -462,6 +646,23 @@ impl std::fmt::Display for Change {
             Change::DropUnique(col) => write!(f, "- UNIQUE ({})", col),
             Change::AddCheck(check) => write!(f, "+ CHECK {}: {}", check.name, check.expr),
             Change::DropCheck(name) => write!(f, "- CHECK {}", name),
+            Change::AddExclusion(exclusion) => write!(
+                f,
+                "+ EXCLUDE {}: USING {} ({})",
+                exclusion.name, exclusion.using, exclusion.elements
+            ),
+            Change::DropExclusion(name) => write!(f, "- EXCLUDE {}", name),
+            Change::AlterForeignKeyDeferrable { fk, deferrable } => write!(
+                f,
+                "~ FOREIGN KEY ({}) -> {}: {}",
+                fk.columns.join(", "),
+                fk.references_table,
+                if *deferrable {
+                    "deferrable"
+                } else {
+                    "not deferrable"
+                }
+            ),
             Change::AddTriggerCheckFunction(trig) => {
                 write!(
                     f,
@@ -476,10 +677,76 @@ impl std::fmt::Display for Change {
                 "- TRIGGER FUNCTION {}",
                 crate::trigger_check_function_name(name)
             ),
+            Change::AlterPartitioning { from, to } => write!(
+                f,
+                "~ PARTITION BY: {} -> {}",
+                partition_display(from),
+                partition_display(to)
+            ),
+            Change::AddTrigger(trig) => write!(
+                f,
+                "+ TRIGGER {} ({} {} FOR EACH {})",
+                trig.name,
+                trig.timing,
+                trig.events.join(" OR "),
+                trig.for_each
+            ),
+            Change::DropTrigger { name, .. } => write!(f, "- TRIGGER {}", name),
+            Change::CreateExtension(name) => write!(f, "+ EXTENSION {}", name),
+            Change::CreateDomain(domain) => {
+                write!(f, "+ DOMAIN {} AS {}", domain.name, domain.base_type)
+            }
+            Change::AlterDomainCheck { name, from, to } => write!(
+                f,
+                "~ DOMAIN {} CHECK: {} -> {}",
+                name,
+                from.as_ref().map(|c| c.expr.as_str()).unwrap_or("(none)"),
+                to.as_ref().map(|c| c.expr.as_str()).unwrap_or("(none)")
+            ),
+            Change::DropDomain(name) => write!(f, "- DOMAIN {}", name),
+            Change::CreateComposite(composite) => {
+                let fields: Vec<String> = composite
+                    .fields
+                    .iter()
+                    .map(|field| format!("{} {}", field.name, field.pg_type))
+                    .collect();
+                write!(f, "+ TYPE {} AS ({})", composite.name, fields.join(", "))
+            }
+            Change::AlterComposite { name, from, to } => {
+                let render = |fields: &[CompositeField]| {
+                    fields
+                        .iter()
+                        .map(|field| format!("{} {}", field.name, field.pg_type))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                write!(f, "~ TYPE {}: ({}) -> ({})", name, render(from), render(to))
+            }
+            Change::DropComposite(name) => write!(f, "- TYPE {}", name),
+            Change::EnableRls => write!(f, "+ ROW LEVEL SECURITY"),
+            Change::DisableRls => write!(f, "- ROW LEVEL SECURITY"),
+            Change::AddPolicy { policy, .. } => {
+                write!(f, "+ POLICY {} FOR {}", policy.name, policy.command)
+            }
+            Change::DropPolicy(name) => write!(f, "- POLICY {}", name),
+            Change::SetComment { column, from, to } => {
+                let from_str = from.as_deref().unwrap_or("(none)");
+                let to_str = to.as_deref().unwrap_or("(none)");
+                match column {
+                    Some(column) => write!(f, "~ {} comment: {} -> {}", column, from_str, to_str),
+                    None => write!(f, "~ comment: {} -> {}", from_str, to_str),
+                }
+            }
         }
     }
 }
 
+fn partition_display(spec: &Option<PartitionSpec>) -> String {
+    spec.as_ref()
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "(none)".to_string())
+}
+
 /// Check if two names are likely plural/singular variants of each other.
 ///
 /// Recognizes common English plural patterns:
@@ -759,6 +1026,46 @@ impl SchemaExt for Schema {
         // Sort by table name for consistent output
         table_diffs.sort_by(|a, b| a.table.cmp(&b.table));
 
+        let composite_changes = diff_composites(&self.composites, &db_schema.composites);
+        if !composite_changes.is_empty() {
+            table_diffs.insert(
+                0,
+                TableDiff {
+                    table: COMPOSITES_DIFF_TABLE.to_string(),
+                    changes: composite_changes,
+                },
+            );
+        }
+
+        let domain_changes = diff_domains(&self.domains, &db_schema.domains);
+        if !domain_changes.is_empty() {
+            table_diffs.insert(
+                0,
+                TableDiff {
+                    table: DOMAINS_DIFF_TABLE.to_string(),
+                    changes: domain_changes,
+                },
+            );
+        }
+
+        // Missing extensions go first. Extensions that exist in the database
+        // but aren't declared are left alone: they may be managed outside dibs.
+        let missing_extensions: Vec<Change> = self
+            .extensions
+            .iter()
+            .filter(|ext| !db_schema.extensions.contains(ext))
+            .map(|ext| Change::CreateExtension(ext.clone()))
+            .collect();
+        if !missing_extensions.is_empty() {
+            table_diffs.insert(
+                0,
+                TableDiff {
+                    table: EXTENSIONS_DIFF_TABLE.to_string(),
+                    changes: missing_extensions,
+                },
+            );
+        }
+
         SchemaDiff { table_diffs }
     }
 }
@@ -775,6 +1082,23 @@ fn diff_table(
 ) -> Vec<Change> {
     let mut changes = Vec::new();
 
+    // Diff table comment
+    if desired.doc != current.doc {
+        changes.push(Change::SetComment {
+            column: None,
+            from: current.doc.clone(),
+            to: desired.doc.clone(),
+        });
+    }
+
+    // Diff partitioning
+    if desired.partition_by != current.partition_by {
+        changes.push(Change::AlterPartitioning {
+            from: current.partition_by.clone(),
+            to: desired.partition_by.clone(),
+        });
+    }
+
     // Diff columns
     changes.extend(diff_columns(&desired.columns, &current.columns));
 
@@ -784,12 +1108,31 @@ fn diff_table(
         &current.check_constraints,
     ));
 
+    // Diff EXCLUDE constraints
+    changes.extend(diff_exclusion_constraints(
+        &desired.exclusion_constraints,
+        &current.exclusion_constraints,
+    ));
+
     // Diff trigger-enforced checks
     changes.extend(diff_trigger_checks(
         &desired.trigger_checks,
         &current.trigger_checks,
     ));
 
+    // Diff user-defined triggers
+    changes.extend(diff_triggers(desired, current));
+
+    // Diff row-level security
+    if desired.rls != current.rls {
+        changes.push(if desired.rls {
+            Change::EnableRls
+        } else {
+            Change::DisableRls
+        });
+    }
+    changes.extend(diff_policies(desired, current));
+
     // Diff foreign keys
     changes.extend(diff_foreign_keys(
         &desired.foreign_keys,
@@ -828,6 +1171,44 @@ fn diff_check_constraints(desired: &[CheckConstraint], current: &[CheckConstrain
     changes
 }
 
+/// Diff EXCLUDE constraints by name.
+///
+/// Generated names hash the definition, so definition changes show up as a
+/// different name. Postgres can't alter the deferrability of an EXCLUDE
+/// constraint, so that's a drop and re-add.
+fn diff_exclusion_constraints(
+    desired: &[ExclusionConstraint],
+    current: &[ExclusionConstraint],
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    // Generated names change with the definition; explicit names are
+    // compared through the definition hash kept in the constraint's comment.
+    // Deferrability can't be altered in place either.
+    let same_definition = |d: &ExclusionConstraint, c: &ExclusionConstraint| {
+        d.deferrable == c.deferrable && (d.def_hash.is_none() || d.def_hash == c.def_hash)
+    };
+
+    for c in current {
+        match desired.iter().find(|d| d.name == c.name) {
+            None => changes.push(Change::DropExclusion(c.name.clone())),
+            Some(d) if !same_definition(d, c) => {
+                changes.push(Change::DropExclusion(c.name.clone()));
+                changes.push(Change::AddExclusion(d.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+
+    for d in desired {
+        if !current.iter().any(|c| c.name == d.name) {
+            changes.push(Change::AddExclusion(d.clone()));
+        }
+    }
+
+    changes
+}
+
 fn diff_trigger_checks(
     desired: &[TriggerCheckConstraint],
     current: &[TriggerCheckConstraint],
@@ -858,12 +1239,136 @@ fn diff_trigger_checks(
     changes
 }
 
+fn diff_triggers(desired: &Table, current: &Table) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    // The function name hashes the body, so comparing it (plus the trigger
+    // clauses) detects any edit. Triggers are never altered in place: a changed
+    // trigger is dropped and recreated.
+    let function_name = |table: &Table, trig: &TableTrigger| {
+        crate::trigger_function_name(&table.name, &trig.name, &trig.function_body)
+    };
+    let unchanged = |des: &TableTrigger, cur: &TableTrigger| {
+        des.timing == cur.timing
+            && des.events == cur.events
+            && des.for_each == cur.for_each
+            && function_name(desired, des) == function_name(current, cur)
+    };
+
+    for cur in &current.triggers {
+        match desired.triggers.iter().find(|t| t.name == cur.name) {
+            Some(des) if unchanged(des, cur) => {}
+            _ => changes.push(Change::DropTrigger {
+                name: cur.name.clone(),
+                function: function_name(current, cur),
+            }),
+        }
+    }
+
+    for des in &desired.triggers {
+        match current.triggers.iter().find(|t| t.name == des.name) {
+            Some(cur) if unchanged(des, cur) => {}
+            _ => changes.push(Change::AddTrigger(des.clone())),
+        }
+    }
+
+    changes
+}
+
+fn diff_policies(desired: &Table, current: &Table) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    // Expressions come back from Postgres normalized (extra parens, casts), so
+    // they are compared through the hash of the declared ones, which dibs keeps
+    // in the policy's comment. Any difference recreates the policy.
+    let same_definition = |a: &RlsPolicy, b: &RlsPolicy| {
+        let mut a_roles = a.roles.clone();
+        let mut b_roles = b.roles.clone();
+        a_roles.sort();
+        b_roles.sort();
+        a.command == b.command && a_roles == b_roles && a.expr_hash == b.expr_hash
+    };
+
+    // Drops (including policies that need to be recreated)
+    for cur in &current.policies {
+        match desired.policies.iter().find(|p| p.name == cur.name) {
+            Some(des) if same_definition(des, cur) => {}
+            _ => changes.push(Change::DropPolicy(cur.name.clone())),
+        }
+    }
+
+    // Adds
+    for des in &desired.policies {
+        match current.policies.iter().find(|p| p.name == des.name) {
+            Some(cur) if same_definition(des, cur) => {}
+            _ => changes.push(Change::AddPolicy {
+                policy: des.clone(),
+                columns: policy_column_refs(des, &desired.columns),
+            }),
+        }
+    }
+
+    changes
+}
+
+/// Columns from `columns` that a policy's USING / WITH CHECK expressions mention.
+///
+/// This is a lexical scan (string literals are skipped, quoted identifiers are
+/// respected), which is enough for ordering: the policy must be created after
+/// these columns exist, and dropped before any of them is.
+pub(crate) fn policy_column_refs(policy: &RlsPolicy, columns: &[Column]) -> Vec<String> {
+    let mut idents = HashSet::new();
+    for expr in [&policy.using, &policy.with_check].into_iter().flatten() {
+        let mut chars = expr.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                // Skip string literal ('' is an escaped quote, which just
+                // looks like two adjacent literals here)
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                }
+            } else if c == '"' {
+                let mut ident = String::new();
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    ident.push(c);
+                }
+                idents.insert(ident);
+            } else if c.is_alphabetic() || c == '_' {
+                let mut ident = c.to_lowercase().to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '$' {
+                        ident.extend(c.to_lowercase());
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                idents.insert(ident);
+            }
+        }
+    }
+
+    columns
+        .iter()
+        .filter(|c| idents.contains(&c.name))
+        .map(|c| c.name.clone())
+        .collect()
+}
+
 /// Generate all changes needed to create a new table.
 ///
 /// This is the single source of truth for table creation. It includes:
 /// - The CREATE TABLE statement
 /// - All foreign key constraints (as ALTER TABLE ADD CONSTRAINT)
 /// - All indices (as CREATE INDEX)
+/// - User-defined triggers
+/// - Row-level security and its policies
+/// - Table and column comments (as COMMENT ON)
 ///
 /// By centralizing this logic, we prevent bugs where new table features
 /// (like FKs or indices) are forgotten when adding tables.
@@ -891,6 +1396,40 @@ fn table_creation_changes(table: &Table) -> Vec<Change> {
         changes.push(Change::AddTriggerCheck(trig.clone()));
     }
 
+    // User-defined triggers
+    for trig in &table.triggers {
+        changes.push(Change::AddTrigger(trig.clone()));
+    }
+
+    // Row-level security and policies
+    if table.rls {
+        changes.push(Change::EnableRls);
+    }
+    for policy in &table.policies {
+        changes.push(Change::AddPolicy {
+            policy: policy.clone(),
+            columns: policy_column_refs(policy, &table.columns),
+        });
+    }
+
+    // Table and column comments
+    if table.doc.is_some() {
+        changes.push(Change::SetComment {
+            column: None,
+            from: None,
+            to: table.doc.clone(),
+        });
+    }
+    for col in &table.columns {
+        if col.doc.is_some() {
+            changes.push(Change::SetComment {
+                column: Some(col.name.clone()),
+                from: None,
+                to: col.doc.clone(),
+            });
+        }
+    }
+
     changes
 }
 
@@ -1019,10 +1558,12 @@ fn diff_column_properties(
         long: _,                           // UI hint only
         label: _,                          // UI hint only
         enum_variants: _,                  // Derived from type
-        doc: _,                            // Documentation only
+        doc: desired_doc,
         icon: _,                           // UI hint only
         lang: _,                           // UI hint only
         subtype: _,                        // UI hint only
+        domain: _,                         // Compared through named_type()
+        composite: _,                      // Compared through named_type()
     } = desired;
 
     #[rustfmt::skip]
@@ -1038,14 +1579,25 @@ fn diff_column_properties(
         long: _,
         label: _,
         enum_variants: _,
-        doc: _,
+        doc: current_doc,
         icon: _,
         lang: _,
         subtype: _,
+        domain: _,
+        composite: _,
     } = current;
 
-    // Type change
-    if desired_pg_type != current_pg_type {
+    // Domain or composite type change (covers the base type, which belongs
+    // to the domain)
+    let desired_domain = desired.named_type();
+    if desired_domain != current.named_type() {
+        changes.push(Change::AlterColumnDomain {
+            name: name.to_string(),
+            from: current.named_type().map(str::to_string),
+            to: desired_domain.map(str::to_string),
+            pg_type: *desired_pg_type,
+        });
+    } else if desired_domain.is_none() && desired_pg_type != current_pg_type {
         changes.push(Change::AlterColumnType {
             name: name.to_string(),
             from: *current_pg_type,
@@ -1088,6 +1640,78 @@ fn diff_column_properties(
             to: *desired_auto,
         });
     }
+
+    // Comment change
+    if desired_doc != current_doc {
+        changes.push(Change::SetComment {
+            column: Some(name.to_string()),
+            from: current_doc.clone(),
+            to: desired_doc.clone(),
+        });
+    }
+}
+
+/// Diff domain types by name.
+///
+/// A domain whose base type changed is dropped and recreated; the solver
+/// rejects that while columns still use it.
+fn diff_domains(desired: &[DomainType], current: &[DomainType]) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for domain in desired {
+        match current.iter().find(|d| d.name == domain.name) {
+            None => changes.push(Change::CreateDomain(domain.clone())),
+            Some(existing) if existing.base_type != domain.base_type => {
+                changes.push(Change::DropDomain(existing.name.clone()));
+                changes.push(Change::CreateDomain(domain.clone()));
+            }
+            Some(existing) => {
+                let existing_check = existing.check.as_ref().map(|c| &c.name);
+                if existing_check != domain.check.as_ref().map(|c| &c.name) {
+                    changes.push(Change::AlterDomainCheck {
+                        name: domain.name.clone(),
+                        from: existing.check.clone(),
+                        to: domain.check.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    for domain in current {
+        if !desired.iter().any(|d| d.name == domain.name) {
+            changes.push(Change::DropDomain(domain.name.clone()));
+        }
+    }
+
+    changes
+}
+
+/// Diff composite types by name. Attribute edits become a single `ALTER TYPE`.
+fn diff_composites(desired: &[CompositeType], current: &[CompositeType]) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for composite in desired {
+        match current.iter().find(|c| c.name == composite.name) {
+            None => changes.push(Change::CreateComposite(composite.clone())),
+            Some(existing) if existing.fields != composite.fields => {
+                changes.push(Change::AlterComposite {
+                    name: composite.name.clone(),
+                    from: existing.fields.clone(),
+                    to: composite.fields.clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    for composite in current {
+        if !desired.iter().any(|c| c.name == composite.name) {
+            changes.push(Change::DropComposite(composite.name.clone()));
+        }
+    }
+
+    changes
 }
 
 /// Diff columns between desired and current state.
@@ -1178,6 +1802,7 @@ fn diff_foreign_keys(
                     columns: fk.columns.clone(),
                     references_table: new_name.clone(),
                     references_columns: fk.references_columns.clone(),
+                    deferrable: fk.deferrable,
                 }
             } else {
                 fk.clone()
@@ -1206,6 +1831,19 @@ fn diff_foreign_keys(
         }
     }
 
+    // FKs in both whose deferrability changed
+    for fk in desired {
+        if let Some(current_fk) = transformed_current
+            .iter()
+            .find(|c| fk_key(c) == fk_key(fk) && c.deferrable != fk.deferrable)
+        {
+            changes.push(Change::AlterForeignKeyDeferrable {
+                fk: current_fk.clone(),
+                deferrable: fk.deferrable,
+            });
+        }
+    }
+
     // FKs to drop (in current but not in desired, after accounting for renames)
     // We compare transformed current against desired to see what's truly missing
     for fk in &transformed_current {
@@ -1364,6 +2002,8 @@ mod tests {
             icon: None,
             lang: None,
             subtype: None,
+            domain: None,
+            composite: None,
         }
     }
 
@@ -1372,7 +2012,16 @@ mod tests {
             name: name.to_string(),
             columns,
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
             foreign_keys: Vec::new(),
             indices: Vec::new(),
             source: SourceLocation::default(),
@@ -1384,6 +2033,9 @@ mod tests {
     fn make_schema(tables: Vec<Table>) -> Schema {
         Schema {
             tables: tables.into_iter().map(|t| (t.name.clone(), t)).collect(),
+            extensions: Vec::new(),
+            domains: Vec::new(),
+            composites: Vec::new(),
         }
     }
 
@@ -1546,6 +2198,465 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_diff_comments() {
+        let mut desired_table = make_table(
+            "users",
+            vec![
+                make_column("id", PgType::BigInt, false),
+                make_column("email", PgType::Text, false),
+            ],
+        );
+        desired_table.doc = Some("A registered user.".to_string());
+        desired_table.columns[1].doc = Some("Login address.".to_string());
+
+        let mut current_table = desired_table.clone();
+        current_table.doc = None;
+        current_table.columns[1].doc = Some("Old text".to_string());
+
+        let desired = make_schema(vec![desired_table]);
+        let current = make_schema(vec![current_table]);
+
+        let diff = desired.diff(&current);
+        let changes = &diff.table_diffs[0].changes;
+        assert_eq!(changes.len(), 2, "changes: {:?}", changes);
+        assert_eq!(
+            changes[0].to_sql("users"),
+            r#"COMMENT ON TABLE "users" IS 'A registered user.';"#
+        );
+        assert_eq!(
+            changes[1].to_sql("users"),
+            r#"COMMENT ON COLUMN "users"."email" IS 'Login address.';"#
+        );
+    }
+
+    #[test]
+    fn test_new_table_includes_comments() {
+        let mut table = make_table("users", vec![make_column("id", PgType::BigInt, false)]);
+        table.doc = Some("A registered user.".to_string());
+        table.columns[0].doc = Some("Surrogate key.".to_string());
+
+        let changes = table_creation_changes(&table);
+        assert!(changes.contains(&Change::SetComment {
+            column: None,
+            from: None,
+            to: Some("A registered user.".to_string()),
+        }));
+        assert!(changes.contains(&Change::SetComment {
+            column: Some("id".to_string()),
+            from: None,
+            to: Some("Surrogate key.".to_string()),
+        }));
+    }
+
+    fn tenant_policy() -> RlsPolicy {
+        let mut policy = RlsPolicy {
+            name: "tenant_isolation".to_string(),
+            command: "ALL".to_string(),
+            roles: vec![],
+            using: Some("tenant_id = current_setting('app.tenant')::bigint".to_string()),
+            with_check: None,
+            expr_hash: None,
+        };
+        policy.expr_hash = Some(policy.declared_expr_hash());
+        policy
+    }
+
+    #[test]
+    fn test_diff_domains() {
+        let check = |expr: &str| CheckConstraint {
+            name: crate::check_constraint_name("email", expr),
+            expr: expr.to_string(),
+        };
+        let email = DomainType {
+            name: "email".to_string(),
+            base_type: PgType::Text,
+            check: Some(check("VALUE ~ '@'")),
+        };
+
+        let mut address = make_column("address", PgType::Text, false);
+        let mut desired = make_schema(vec![make_table("subscriber", vec![address.clone()])]);
+        address.domain = Some("email".to_string());
+        desired.tables["subscriber"].columns = vec![address];
+        desired.domains = vec![email.clone()];
+
+        let mut current = make_schema(vec![make_table(
+            "subscriber",
+            vec![make_column("address", PgType::Text, false)],
+        )]);
+        current.domains = vec![
+            DomainType {
+                check: Some(check("VALUE LIKE '%@%'")),
+                ..email.clone()
+            },
+            DomainType {
+                name: "legacy_code".to_string(),
+                base_type: PgType::Integer,
+                check: None,
+            },
+        ];
+
+        let diff = desired.diff(&current);
+        assert_eq!(diff.table_diffs[0].table, DOMAINS_DIFF_TABLE);
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![
+                Change::AlterDomainCheck {
+                    name: "email".to_string(),
+                    from: Some(check("VALUE LIKE '%@%'")),
+                    to: Some(check("VALUE ~ '@'")),
+                },
+                Change::DropDomain("legacy_code".to_string()),
+            ]
+        );
+
+        // Switching a column to the domain is a domain change, not a type change
+        let column_change = &diff.table_diffs[1].changes[0];
+        assert_eq!(
+            *column_change,
+            Change::AlterColumnDomain {
+                name: "address".to_string(),
+                from: None,
+                to: Some("email".to_string()),
+                pg_type: PgType::Text,
+            }
+        );
+        assert_eq!(
+            column_change.to_sql("subscriber"),
+            r#"ALTER TABLE "subscriber" ALTER COLUMN "address" TYPE "email" USING "address"::"email";"#
+        );
+
+        // A domain that doesn't exist yet is created
+        current.domains.clear();
+        let diff = desired.diff(&current);
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![Change::CreateDomain(email)]
+        );
+    }
+
+    #[test]
+    fn test_diff_composites() {
+        let field = |name: &str, pg_type| CompositeField {
+            name: name.to_string(),
+            pg_type,
+        };
+        let address = CompositeType {
+            name: "postal_address".to_string(),
+            fields: vec![field("street", PgType::Text), field("zip", PgType::Text)],
+        };
+
+        let mut desired = make_schema(vec![]);
+        desired.composites = vec![address.clone()];
+        let mut current = make_schema(vec![]);
+        current.composites = vec![
+            CompositeType {
+                fields: vec![field("street", PgType::Text), field("zip", PgType::Integer)],
+                ..address.clone()
+            },
+            CompositeType {
+                name: "legacy_point".to_string(),
+                fields: vec![field("x", PgType::Integer)],
+            },
+        ];
+
+        let diff = desired.diff(&current);
+        assert_eq!(diff.table_diffs.len(), 1);
+        assert_eq!(diff.table_diffs[0].table, COMPOSITES_DIFF_TABLE);
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![
+                Change::AlterComposite {
+                    name: "postal_address".to_string(),
+                    from: current.composites[0].fields.clone(),
+                    to: address.fields.clone(),
+                },
+                Change::DropComposite("legacy_point".to_string()),
+            ]
+        );
+
+        // A composite type that doesn't exist yet is created
+        current.composites.clear();
+        let diff = desired.diff(&current);
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![Change::CreateComposite(address.clone())]
+        );
+
+        // Identical definitions produce no change
+        current.composites = vec![address];
+        assert!(desired.diff(&current).is_empty());
+    }
+
+    #[test]
+    fn test_diff_extensions() {
+        let mut desired = make_schema(vec![make_table(
+            "article",
+            vec![make_column("id", PgType::BigInt, false)],
+        )]);
+        desired.extensions = vec!["citext".to_string(), "pg_trgm".to_string()];
+        let mut current = desired.clone();
+        current.extensions = vec!["pg_trgm".to_string(), "postgis".to_string()];
+
+        // Only missing extensions are reported; undeclared ones are left alone
+        let diff = desired.diff(&current);
+        assert_eq!(diff.table_diffs.len(), 1);
+        assert_eq!(diff.table_diffs[0].table, EXTENSIONS_DIFF_TABLE);
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![Change::CreateExtension("citext".to_string())]
+        );
+        assert_eq!(
+            diff.to_sql(),
+            "-- Extensions\nCREATE EXTENSION IF NOT EXISTS \"citext\";\n\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_rls_policies() {
+        let mut desired_table = make_table(
+            "post",
+            vec![
+                make_column("id", PgType::BigInt, false),
+                make_column("tenant_id", PgType::BigInt, false),
+            ],
+        );
+        desired_table.rls = true;
+        desired_table.policies = vec![tenant_policy()];
+
+        let mut current_table = desired_table.clone();
+        current_table.rls = false;
+        current_table.policies = vec![RlsPolicy {
+            name: "legacy".to_string(),
+            ..tenant_policy()
+        }];
+
+        let diff = make_schema(vec![desired_table.clone()]).diff(&make_schema(vec![current_table]));
+        let changes = &diff.table_diffs[0].changes;
+        assert_eq!(
+            changes,
+            &vec![
+                Change::EnableRls,
+                Change::DropPolicy("legacy".to_string()),
+                Change::AddPolicy {
+                    policy: tenant_policy(),
+                    columns: vec!["tenant_id".to_string()],
+                },
+            ]
+        );
+
+        // Postgres normalizes expressions, so they are compared by hash
+        let mut introspected = desired_table.clone();
+        introspected.policies[0].using =
+            Some("(tenant_id = (current_setting('app.tenant'::text))::bigint)".to_string());
+        let diff =
+            make_schema(vec![desired_table.clone()]).diff(&make_schema(vec![introspected.clone()]));
+        assert!(diff.is_empty(), "diff: {:?}", diff);
+
+        // An edited expression recreates the policy
+        let mut edited = desired_table.clone();
+        edited.policies[0].using = Some("tenant_id = 1".to_string());
+        edited.policies[0].expr_hash = Some(edited.policies[0].declared_expr_hash());
+        let diff = make_schema(vec![edited.clone()]).diff(&make_schema(vec![introspected.clone()]));
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![
+                Change::DropPolicy("tenant_isolation".to_string()),
+                Change::AddPolicy {
+                    policy: edited.policies[0].clone(),
+                    columns: vec!["tenant_id".to_string()],
+                },
+            ]
+        );
+
+        // A changed command recreates the policy
+        introspected.policies[0].command = "SELECT".to_string();
+        let diff = make_schema(vec![desired_table]).diff(&make_schema(vec![introspected]));
+        let changes = &diff.table_diffs[0].changes;
+        assert_eq!(changes.len(), 2, "changes: {:?}", changes);
+        assert_eq!(
+            changes[0],
+            Change::DropPolicy("tenant_isolation".to_string())
+        );
+    }
+
+    #[test]
+    fn test_diff_triggers() {
+        let touch = TableTrigger {
+            name: "trg_touch".to_string(),
+            timing: "BEFORE".to_string(),
+            events: vec!["UPDATE".to_string()],
+            for_each: "ROW".to_string(),
+            function_body: "BEGIN NEW.updated_at = now(); RETURN NEW; END;".to_string(),
+        };
+        let mut desired_table = make_table("post", vec![make_column("id", PgType::BigInt, false)]);
+        desired_table.triggers = vec![touch.clone()];
+
+        // New trigger
+        let current_table = make_table("post", vec![make_column("id", PgType::BigInt, false)]);
+        let diff = make_schema(vec![desired_table.clone()]).diff(&make_schema(vec![current_table]));
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![Change::AddTrigger(touch.clone())]
+        );
+
+        // Reformatted body: no change
+        let mut current_table = desired_table.clone();
+        current_table.triggers[0].function_body =
+            "BEGIN\n  NEW.updated_at = now();\n  RETURN NEW;\nEND;".to_string();
+        let diff = make_schema(vec![desired_table.clone()]).diff(&make_schema(vec![current_table]));
+        assert!(diff.is_empty(), "diff: {:?}", diff);
+
+        // Edited body: drop the old function, recreate
+        let mut current_table = desired_table.clone();
+        current_table.triggers[0].function_body = "BEGIN RETURN NEW; END;".to_string();
+        let diff = make_schema(vec![desired_table]).diff(&make_schema(vec![current_table]));
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![
+                Change::DropTrigger {
+                    name: "trg_touch".to_string(),
+                    function: crate::trigger_function_name(
+                        "post",
+                        "trg_touch",
+                        "BEGIN RETURN NEW; END;"
+                    ),
+                },
+                Change::AddTrigger(touch),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_exclusion_constraints() {
+        let no_overlap = ExclusionConstraint {
+            name: "ex_booking_no_overlap".to_string(),
+            using: "gist".to_string(),
+            elements: "room_id WITH =, during WITH &&".to_string(),
+            filter: None,
+            deferrable: false,
+            def_hash: None,
+        };
+        let mut desired_table =
+            make_table("booking", vec![make_column("id", PgType::BigInt, false)]);
+        desired_table.exclusion_constraints = vec![no_overlap.clone()];
+
+        // New constraint
+        let current_table = make_table("booking", vec![make_column("id", PgType::BigInt, false)]);
+        let diff = make_schema(vec![desired_table.clone()]).diff(&make_schema(vec![current_table]));
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![Change::AddExclusion(no_overlap.clone())]
+        );
+        assert_eq!(
+            diff.table_diffs[0].changes[0].to_sql("booking"),
+            r#"ALTER TABLE "booking" ADD CONSTRAINT "ex_booking_no_overlap" EXCLUDE USING gist (room_id WITH =, during WITH &&);"#
+        );
+
+        // Introspected elements are formatted differently; only the name matters
+        let mut current_table = desired_table.clone();
+        current_table.exclusion_constraints[0].elements = "room_id WITH =,during WITH &&".into();
+        let diff = make_schema(vec![desired_table.clone()]).diff(&make_schema(vec![current_table]));
+        assert!(diff.is_empty(), "diff: {:?}", diff);
+
+        // Deferrability can't be altered in place
+        let mut deferred_table = desired_table.clone();
+        deferred_table.exclusion_constraints[0].deferrable = true;
+        let diff = make_schema(vec![deferred_table.clone()])
+            .diff(&make_schema(vec![desired_table.clone()]));
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![
+                Change::DropExclusion("ex_booking_no_overlap".to_string()),
+                Change::AddExclusion(deferred_table.exclusion_constraints[0].clone()),
+            ]
+        );
+        assert!(
+            diff.table_diffs[0].changes[1]
+                .to_sql("booking")
+                .ends_with(" DEFERRABLE INITIALLY DEFERRED;")
+        );
+
+        // Explicitly named constraints are compared through their definition hash
+        let mut named = no_overlap.clone();
+        named.name = "no_double_booking".to_string();
+        named.def_hash = Some(named.declared_def_hash());
+        let mut named_table = desired_table.clone();
+        named_table.exclusion_constraints = vec![named.clone()];
+        assert_eq!(
+            Change::AddExclusion(named.clone()).to_sql("booking"),
+            format!(
+                "ALTER TABLE \"booking\" ADD CONSTRAINT \"no_double_booking\" EXCLUDE USING gist (room_id WITH =, during WITH &&);\n\
+                 COMMENT ON CONSTRAINT \"no_double_booking\" ON \"booking\" IS 'dibs:def={}';",
+                named.declared_def_hash()
+            )
+        );
+        let diff =
+            make_schema(vec![named_table.clone()]).diff(&make_schema(vec![named_table.clone()]));
+        assert!(diff.is_empty(), "diff: {:?}", diff);
+
+        let mut edited_table = named_table.clone();
+        edited_table.exclusion_constraints[0].filter = Some("NOT cancelled".to_string());
+        edited_table.exclusion_constraints[0].def_hash =
+            Some(edited_table.exclusion_constraints[0].declared_def_hash());
+        let diff = make_schema(vec![edited_table.clone()]).diff(&make_schema(vec![named_table]));
+        assert_eq!(
+            diff.table_diffs[0].changes,
+            vec![
+                Change::DropExclusion("no_double_booking".to_string()),
+                Change::AddExclusion(edited_table.exclusion_constraints[0].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_foreign_key_deferrable() {
+        let fk = ForeignKey {
+            columns: vec!["parent_id".to_string()],
+            references_table: "node".to_string(),
+            references_columns: vec!["id".to_string()],
+            deferrable: false,
+        };
+        let columns = vec![
+            make_column("id", PgType::BigInt, false),
+            make_column("parent_id", PgType::BigInt, true),
+        ];
+        let mut current = make_table("node", columns);
+        current.foreign_keys = vec![fk.clone()];
+        let mut desired = current.clone();
+        desired.foreign_keys[0].deferrable = true;
+
+        let diff = make_schema(vec![desired]).diff(&make_schema(vec![current]));
+        let change = Change::AlterForeignKeyDeferrable {
+            fk,
+            deferrable: true,
+        };
+        assert_eq!(diff.table_diffs[0].changes, vec![change.clone()]);
+        assert_eq!(
+            change.to_sql("node"),
+            r#"ALTER TABLE "node" ALTER CONSTRAINT "node_parent_id_fkey" DEFERRABLE INITIALLY DEFERRED;"#
+        );
+    }
+
+    #[test]
+    fn test_policy_column_refs() {
+        let columns = vec![
+            make_column("id", PgType::BigInt, false),
+            make_column("tenant_id", PgType::BigInt, false),
+            make_column("Owner", PgType::Text, false),
+            make_column("status", PgType::Text, false),
+        ];
+        let policy = RlsPolicy {
+            using: Some("TENANT_ID = 1 AND \"Owner\" = current_user".to_string()),
+            with_check: Some("'status' <> ''".to_string()),
+            ..tenant_policy()
+        };
+        assert_eq!(
+            policy_column_refs(&policy, &columns),
+            vec!["tenant_id".to_string(), "Owner".to_string()]
+        );
+    }
+
     // ===== Snapshot tests for SQL generation =====
 
     fn make_pk_column(name: &str, pg_type: PgType) -> Column {
@@ -1565,6 +2676,8 @@ mod tests {
             icon: None,
             lang: None,
             subtype: None,
+            domain: None,
+            composite: None,
         }
     }
 
@@ -1590,6 +2703,8 @@ mod tests {
             icon: None,
             lang: None,
             subtype: None,
+            domain: None,
+            composite: None,
         }
     }
 
@@ -1610,6 +2725,8 @@ mod tests {
             icon: None,
             lang: None,
             subtype: None,
+            domain: None,
+            composite: None,
         }
     }
 
@@ -1625,7 +2742,16 @@ mod tests {
                 make_column_with_default("created_at", PgType::Timestamptz, false, "now()"),
             ],
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
             foreign_keys: Vec::new(),
             indices: Vec::new(),
             source: SourceLocation::default(),
@@ -1647,7 +2773,16 @@ mod tests {
                 make_column_with_default("created_at", PgType::Timestamptz, false, "now()"),
             ],
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
             foreign_keys: Vec::new(),
             indices: Vec::new(),
             source: SourceLocation::default(),
@@ -1670,17 +2805,28 @@ mod tests {
                 make_column("body", PgType::Text, false),
             ],
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
             foreign_keys: vec![
                 ForeignKey {
                     columns: vec!["author_id".to_string()],
                     references_table: "users".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
                 ForeignKey {
                     columns: vec!["category_id".to_string()],
                     references_table: "categories".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
             ],
             indices: Vec::new(),
@@ -1703,17 +2849,28 @@ mod tests {
                 make_pk_column("tag_id", PgType::BigInt),
             ],
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
             foreign_keys: vec![
                 ForeignKey {
                     columns: vec!["post_id".to_string()],
                     references_table: "posts".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
                 ForeignKey {
                     columns: vec!["tag_id".to_string()],
                     references_table: "tags".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
             ],
             indices: Vec::new(),
@@ -1737,7 +2894,16 @@ mod tests {
                     make_column("name", PgType::Text, false),
                 ],
                 check_constraints: Vec::new(),
+                exclusion_constraints: Vec::new(),
                 trigger_checks: Vec::new(),
+                triggers: Vec::new(),
+                partition_by: None,
+                rls: false,
+                policies: Vec::new(),
+                soft_delete: None,
+                created_at: None,
+                updated_at: None,
+                audited: false,
                 foreign_keys: Vec::new(),
                 indices: Vec::new(),
                 source: SourceLocation::default(),
@@ -1752,11 +2918,21 @@ mod tests {
                     make_column("title", PgType::Text, false),
                 ],
                 check_constraints: Vec::new(),
+                exclusion_constraints: Vec::new(),
                 trigger_checks: Vec::new(),
+                triggers: Vec::new(),
+                partition_by: None,
+                rls: false,
+                policies: Vec::new(),
+                soft_delete: None,
+                created_at: None,
+                updated_at: None,
+                audited: false,
                 foreign_keys: vec![ForeignKey {
                     columns: vec!["author_id".to_string()],
                     references_table: "users".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
                 indices: Vec::new(),
                 source: SourceLocation::default(),
@@ -1770,17 +2946,28 @@ mod tests {
                     make_pk_column("post_id", PgType::BigInt),
                 ],
                 check_constraints: Vec::new(),
+                exclusion_constraints: Vec::new(),
                 trigger_checks: Vec::new(),
+                triggers: Vec::new(),
+                partition_by: None,
+                rls: false,
+                policies: Vec::new(),
+                soft_delete: None,
+                created_at: None,
+                updated_at: None,
+                audited: false,
                 foreign_keys: vec![
                     ForeignKey {
                         columns: vec!["user_id".to_string()],
                         references_table: "users".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                     ForeignKey {
                         columns: vec!["post_id".to_string()],
                         references_table: "posts".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                 ],
                 indices: Vec::new(),
@@ -1946,7 +3133,16 @@ mod tests {
                 name: name.to_string(),
                 columns,
                 check_constraints: Vec::new(),
+                exclusion_constraints: Vec::new(),
                 trigger_checks: Vec::new(),
+                triggers: Vec::new(),
+                partition_by: None,
+                rls: false,
+                policies: Vec::new(),
+                soft_delete: None,
+                created_at: None,
+                updated_at: None,
+                audited: false,
                 foreign_keys: fks,
                 indices: Vec::new(),
                 source: SourceLocation::default(),
@@ -1966,6 +3162,7 @@ mod tests {
                 columns: vec!["parent_id".to_string()],
                 references_table: "categories".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         )]);
 
@@ -1980,6 +3177,7 @@ mod tests {
                 columns: vec!["parent_id".to_string()],
                 references_table: "category".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         )]);
 
@@ -2016,7 +3214,16 @@ mod tests {
                 name: name.to_string(),
                 columns,
                 check_constraints: Vec::new(),
+                exclusion_constraints: Vec::new(),
                 trigger_checks: Vec::new(),
+                triggers: Vec::new(),
+                partition_by: None,
+                rls: false,
+                policies: Vec::new(),
+                soft_delete: None,
+                created_at: None,
+                updated_at: None,
+                audited: false,
                 foreign_keys: fks,
                 indices: Vec::new(),
                 source: SourceLocation::default(),
@@ -2038,6 +3245,7 @@ mod tests {
                     columns: vec!["author_id".to_string()],
                     references_table: "users".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -2055,6 +3263,7 @@ mod tests {
                     columns: vec!["author_id".to_string()],
                     references_table: "user".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -2273,7 +3482,16 @@ mod tests {
                 name: name.to_string(),
                 columns,
                 check_constraints: Vec::new(),
+                exclusion_constraints: Vec::new(),
                 trigger_checks: Vec::new(),
+                triggers: Vec::new(),
+                partition_by: None,
+                rls: false,
+                policies: Vec::new(),
+                soft_delete: None,
+                created_at: None,
+                updated_at: None,
+                audited: false,
                 foreign_keys: fks,
                 indices: Vec::new(),
                 source: SourceLocation::default(),
@@ -2296,11 +3514,13 @@ mod tests {
                         columns: vec!["shop_id".to_string()],
                         references_table: "shop".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                     ForeignKey {
                         columns: vec!["parent_id".to_string()],
                         references_table: "category".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                 ],
             ),
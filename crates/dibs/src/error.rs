@@ -122,6 +122,16 @@ pub enum Error {
 
     #[error("connection pool error: {0}")]
     Pool(String),
+
+    #[error("invalid seed data:\n{0}")]
+    InvalidSeed(String),
+
+    #[error("{event} hook {name} failed: {source}")]
+    Hook {
+        event: crate::HookEvent,
+        name: &'static str,
+        source: Box<Error>,
+    },
 }
 
 impl Error {
@@ -154,6 +164,7 @@ impl Error {
     pub fn sql_context(&self) -> Option<&SqlErrorContext> {
         match self {
             Error::SqlWithContext(ctx) => Some(ctx),
+            Error::Hook { source, .. } => source.sql_context(),
             _ => None,
         }
     }
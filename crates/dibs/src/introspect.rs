@@ -4,8 +4,9 @@
 //! from the current state of a database.
 
 use crate::{
-    CheckConstraint, Column, ForeignKey, Index, IndexColumn, PgType, Result, Schema,
-    SourceLocation, Table, TriggerCheckConstraint,
+    CheckConstraint, Column, CompositeField, CompositeType, DomainType, ExclusionConstraint,
+    ForeignKey, Index, IndexColumn, PartitionKind, PartitionSpec, PgType, Result, RlsPolicy,
+    Schema, SourceLocation, Table, TableTrigger, TriggerCheckConstraint,
 };
 use indexmap::IndexMap;
 
@@ -36,13 +37,129 @@ pub trait SchemaIntrospect {
 impl SchemaIntrospect for Schema {
     async fn from_database(client: &Client) -> Result<Self> {
         let tables = introspect_tables(client).await?;
-        Ok(Self { tables })
+        let extensions = introspect_extensions(client).await?;
+        let domains = introspect_domains(client).await?;
+        let composites = introspect_composites(client).await?;
+        Ok(Self {
+            tables,
+            extensions,
+            domains,
+            composites,
+        })
     }
 }
 
+/// Introspect installed extensions (excluding the built-in `plpgsql`).
+async fn introspect_extensions(client: &Client) -> Result<Vec<String>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT extname
+            FROM pg_extension
+            WHERE extname <> 'plpgsql'
+            ORDER BY extname
+            "#,
+            &[],
+        )
+        .await?;
+
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+/// Introspect domain types in the public schema with their CHECK constraint.
+async fn introspect_domains(client: &Client) -> Result<Vec<DomainType>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                t.typname,
+                bt.typname AS base_udt_name,
+                format_type(t.typbasetype, NULL) AS base_data_type,
+                con.conname,
+                pg_get_expr(con.conbin, 0) AS expr
+            FROM pg_type t
+            JOIN pg_namespace nsp ON nsp.oid = t.typnamespace
+            JOIN pg_type bt ON bt.oid = t.typbasetype
+            LEFT JOIN pg_constraint con ON con.contypid = t.oid AND con.contype = 'c'
+            WHERE nsp.nspname = 'public'
+              AND t.typtype = 'd'
+            ORDER BY t.typname
+            "#,
+            &[],
+        )
+        .await?;
+
+    let mut domains: Vec<DomainType> = Vec::new();
+    for row in rows {
+        let name: String = row.get(0);
+        let base_udt_name: String = row.get(1);
+        let base_data_type: String = row.get(2);
+        let check_name: Option<String> = row.get(3);
+        let expr: Option<String> = row.get(4);
+
+        // Only one CHECK per domain is managed; extra ones keep the first
+        if domains.iter().any(|d| d.name == name) {
+            continue;
+        }
+        domains.push(DomainType {
+            name,
+            // format_type spells arrays as `text[]`, information_schema as `ARRAY`
+            base_type: if base_data_type.ends_with("[]") {
+                pg_type_from_info_schema("ARRAY", &base_udt_name)
+            } else {
+                pg_type_from_info_schema(&base_data_type, &base_udt_name)
+            },
+            check: check_name
+                .zip(expr)
+                .map(|(name, expr)| CheckConstraint { name, expr }),
+        });
+    }
+
+    Ok(domains)
+}
+
+/// Introspect standalone composite types (`CREATE TYPE ... AS (...)`) in the
+/// public schema, skipping the row types Postgres creates for every table.
+async fn introspect_composites(client: &Client) -> Result<Vec<CompositeType>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT a.udt_name, a.attribute_name, a.data_type, a.attribute_udt_name
+            FROM information_schema.attributes a
+            JOIN pg_type t ON t.typname = a.udt_name
+            JOIN pg_namespace nsp ON nsp.oid = t.typnamespace AND nsp.nspname = a.udt_schema
+            JOIN pg_class rel ON rel.oid = t.typrelid
+            WHERE a.udt_schema = 'public'
+              AND rel.relkind = 'c'
+            ORDER BY a.udt_name, a.ordinal_position
+            "#,
+            &[],
+        )
+        .await?;
+
+    let mut composites: Vec<CompositeType> = Vec::new();
+    for row in rows {
+        let name: String = row.get(0);
+        let field = CompositeField {
+            name: row.get(1),
+            pg_type: pg_type_from_info_schema(row.get(2), row.get(3)),
+        };
+        match composites.last_mut() {
+            Some(last) if last.name == name => last.fields.push(field),
+            _ => composites.push(CompositeType {
+                name,
+                fields: vec![field],
+            }),
+        }
+    }
+
+    Ok(composites)
+}
+
 /// Introspect all tables in the public schema.
 async fn introspect_tables(client: &Client) -> Result<IndexMap<String, Table>> {
-    // Get all base tables in public schema, excluding dibs meta tables
+    // Get all base tables in public schema, excluding dibs meta tables and
+    // partitions (which are managed by migrations, not the schema diff)
     let rows = client
         .query(
             r#"
@@ -52,6 +169,11 @@ async fn introspect_tables(client: &Client) -> Result<IndexMap<String, Table>> {
               AND table_type = 'BASE TABLE'
               AND table_name NOT LIKE '_dibs_%'
               AND table_name NOT LIKE '__dibs_%'
+              AND NOT (
+                  SELECT rel.relispartition
+                  FROM pg_class rel
+                  WHERE rel.oid = (quote_ident(table_schema) || '.' || quote_ident(table_name))::regclass
+              )
             ORDER BY table_name
             "#,
             &[],
@@ -74,9 +196,15 @@ async fn introspect_table(client: &Client, table_name: &str) -> Result<Table> {
     let primary_keys = introspect_primary_keys(client, table_name).await?;
     let unique_columns = introspect_unique_constraints(client, table_name).await?;
     let check_constraints = introspect_check_constraints(client, table_name).await?;
+    let exclusion_constraints = introspect_exclusion_constraints(client, table_name).await?;
     let trigger_checks = introspect_trigger_checks(client, table_name).await?;
+    let triggers = introspect_triggers(client, table_name).await?;
+    let partition_by = introspect_partition_key(client, table_name).await?;
+    let rls = introspect_rls_enabled(client, table_name).await?;
+    let policies = introspect_policies(client, table_name).await?;
     let foreign_keys = introspect_foreign_keys(client, table_name).await?;
     let indices = introspect_indices(client, table_name).await?;
+    let doc = introspect_table_comment(client, table_name).await?;
 
     // Mark columns with PK and unique flags
     let columns: Vec<Column> = columns
@@ -92,15 +220,226 @@ async fn introspect_table(client: &Client, table_name: &str) -> Result<Table> {
         name: table_name.to_string(),
         columns,
         check_constraints,
+        exclusion_constraints,
         trigger_checks,
+        triggers,
+        partition_by,
+        rls,
+        policies,
+        soft_delete: None, // Query-level behaviour, not stored in the database
+        created_at: None,
+        updated_at: None,
+        audited: false,
         foreign_keys,
         indices,
         source: SourceLocation::default(), // DB tables don't have Rust source
-        doc: None,
+        doc,
         icon: None, // Not available from introspection
     })
 }
 
+/// Introspect the `COMMENT ON TABLE` text for a table.
+async fn introspect_table_comment(client: &Client, table_name: &str) -> Result<Option<String>> {
+    let row = client
+        .query_opt(
+            r#"
+            SELECT obj_description(rel.oid, 'pg_class')
+            FROM pg_class rel
+            JOIN pg_namespace nsp ON nsp.oid = rel.relnamespace
+            WHERE nsp.nspname = 'public'
+              AND rel.relname = $1
+            "#,
+            &[&table_name],
+        )
+        .await?;
+
+    Ok(row.and_then(|r| r.get(0)))
+}
+
+/// Introspect dibs-managed user-defined triggers for a table.
+///
+/// Only triggers whose function follows [`crate::trigger_function_name`]'s
+/// naming are returned; the body is read back from `pg_proc` so the diff can
+/// re-derive the function name and notice edits.
+async fn introspect_triggers(client: &Client, table_name: &str) -> Result<Vec<TableTrigger>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT tg.tgname::text, tg.tgtype::int4, pr.prosrc
+            FROM pg_trigger tg
+            JOIN pg_class rel ON rel.oid = tg.tgrelid
+            JOIN pg_namespace nsp ON nsp.oid = rel.relnamespace
+            JOIN pg_proc pr ON pr.oid = tg.tgfoid
+            WHERE nsp.nspname = 'public'
+              AND rel.relname = $1
+              AND tg.tgisinternal = false
+              AND pr.proname LIKE 'trgdef\_%' ESCAPE '\'
+            ORDER BY tg.tgname
+            "#,
+            &[&table_name],
+        )
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let tgtype: i32 = row.get(1);
+            let body: String = row.get(2);
+            trigger_from_tgtype(row.get(0), tgtype, body.trim().to_string())
+        })
+        .collect())
+}
+
+/// Decode `pg_trigger.tgtype` (see `TRIGGER_TYPE_*` in Postgres' `pg_trigger.h`).
+fn trigger_from_tgtype(name: String, tgtype: i32, function_body: String) -> TableTrigger {
+    const ROW: i32 = 1 << 0;
+    const BEFORE: i32 = 1 << 1;
+    const INSTEAD: i32 = 1 << 6;
+    const EVENT_BITS: [(i32, &str); 4] = [
+        (1 << 2, "INSERT"),
+        (1 << 4, "UPDATE"),
+        (1 << 3, "DELETE"),
+        (1 << 5, "TRUNCATE"),
+    ];
+
+    let timing = if tgtype & BEFORE != 0 {
+        "BEFORE"
+    } else if tgtype & INSTEAD != 0 {
+        "INSTEAD OF"
+    } else {
+        "AFTER"
+    };
+
+    TableTrigger {
+        name,
+        timing: timing.to_string(),
+        events: EVENT_BITS
+            .iter()
+            .filter(|(bit, _)| tgtype & bit != 0)
+            .map(|(_, event)| event.to_string())
+            .collect(),
+        for_each: if tgtype & ROW != 0 {
+            "ROW"
+        } else {
+            "STATEMENT"
+        }
+        .to_string(),
+        function_body,
+    }
+}
+
+/// Introspect the partition key of a partitioned table.
+async fn introspect_partition_key(
+    client: &Client,
+    table_name: &str,
+) -> Result<Option<PartitionSpec>> {
+    let row = client
+        .query_opt(
+            r#"
+            SELECT
+                pt.partstrat::text,
+                ARRAY(
+                    SELECT att.attname::text
+                    FROM unnest(pt.partattrs::int2[]) WITH ORDINALITY AS key(attnum, ord)
+                    JOIN pg_attribute att
+                      ON att.attrelid = pt.partrelid AND att.attnum = key.attnum
+                    ORDER BY key.ord
+                ) AS columns
+            FROM pg_partitioned_table pt
+            JOIN pg_class rel ON rel.oid = pt.partrelid
+            JOIN pg_namespace nsp ON nsp.oid = rel.relnamespace
+            WHERE nsp.nspname = 'public'
+              AND rel.relname = $1
+            "#,
+            &[&table_name],
+        )
+        .await?;
+
+    Ok(row.and_then(|row| {
+        let kind = match row.get::<_, String>(0).as_str() {
+            "r" => PartitionKind::Range,
+            "l" => PartitionKind::List,
+            "h" => PartitionKind::Hash,
+            _ => return None,
+        };
+        Some(PartitionSpec {
+            kind,
+            columns: row.get(1),
+        })
+    }))
+}
+
+/// Introspect whether row-level security is enabled on a table.
+async fn introspect_rls_enabled(client: &Client, table_name: &str) -> Result<bool> {
+    let row = client
+        .query_opt(
+            r#"
+            SELECT rel.relrowsecurity
+            FROM pg_class rel
+            JOIN pg_namespace nsp ON nsp.oid = rel.relnamespace
+            WHERE nsp.nspname = 'public'
+              AND rel.relname = $1
+            "#,
+            &[&table_name],
+        )
+        .await?;
+
+    Ok(row.map(|r| r.get(0)).unwrap_or(false))
+}
+
+/// Introspect row-level security policies for a table.
+async fn introspect_policies(client: &Client, table_name: &str) -> Result<Vec<RlsPolicy>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                pol.polname::text,
+                CASE pol.polcmd
+                    WHEN 'r' THEN 'SELECT'
+                    WHEN 'a' THEN 'INSERT'
+                    WHEN 'w' THEN 'UPDATE'
+                    WHEN 'd' THEN 'DELETE'
+                    ELSE 'ALL'
+                END AS command,
+                ARRAY(
+                    SELECT rol.rolname::text
+                    FROM pg_roles rol
+                    WHERE rol.oid = ANY(pol.polroles)
+                    ORDER BY rol.rolname
+                ) AS roles,
+                pg_get_expr(pol.polqual, pol.polrelid) AS using_expr,
+                pg_get_expr(pol.polwithcheck, pol.polrelid) AS with_check_expr,
+                obj_description(pol.oid, 'pg_policy') AS comment
+            FROM pg_policy pol
+            JOIN pg_class rel ON rel.oid = pol.polrelid
+            JOIN pg_namespace nsp ON nsp.oid = rel.relnamespace
+            WHERE nsp.nspname = 'public'
+              AND rel.relname = $1
+            ORDER BY pol.polname
+            "#,
+            &[&table_name],
+        )
+        .await?;
+
+    // PUBLIC is stored as role oid 0, which has no pg_roles entry, so it
+    // naturally comes back as an empty role list.
+    Ok(rows
+        .into_iter()
+        .map(|row| RlsPolicy {
+            name: row.get(0),
+            command: row.get(1),
+            roles: row.get(2),
+            using: row.get(3),
+            with_check: row.get(4),
+            expr_hash: row.get::<_, Option<String>>(5).and_then(|comment| {
+                comment
+                    .strip_prefix(crate::schema::POLICY_HASH_COMMENT_PREFIX)
+                    .map(str::to_string)
+            }),
+        })
+        .collect())
+}
+
 /// Introspect trigger-enforced checks for a table.
 async fn introspect_trigger_checks(
     client: &Client,
@@ -167,6 +506,108 @@ async fn introspect_check_constraints(
     Ok(checks)
 }
 
+/// Introspect EXCLUDE constraints for a table.
+async fn introspect_exclusion_constraints(
+    client: &Client,
+    table_name: &str,
+) -> Result<Vec<ExclusionConstraint>> {
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                con.conname,
+                am.amname,
+                pg_get_constraintdef(con.oid) AS def,
+                con.condeferrable AND con.condeferred AS deferrable,
+                obj_description(con.oid, 'pg_constraint') AS comment
+            FROM pg_constraint con
+            JOIN pg_class rel ON rel.oid = con.conrelid
+            JOIN pg_namespace nsp ON nsp.oid = rel.relnamespace
+            JOIN pg_class idx ON idx.oid = con.conindid
+            JOIN pg_am am ON am.oid = idx.relam
+            WHERE nsp.nspname = 'public'
+              AND rel.relname = $1
+              AND con.contype = 'x'
+            ORDER BY con.conname
+            "#,
+            &[&table_name],
+        )
+        .await?;
+
+    let mut constraints = Vec::new();
+    for row in rows {
+        let name: String = row.get(0);
+        let using: String = row.get(1);
+        let def: String = row.get(2);
+        let deferrable: bool = row.get(3);
+        let comment: Option<String> = row.get(4);
+        let (elements, filter) = parse_exclusion_def(&def);
+        constraints.push(ExclusionConstraint {
+            name,
+            using,
+            elements,
+            filter,
+            deferrable,
+            def_hash: comment.and_then(|comment| {
+                comment
+                    .strip_prefix(crate::schema::EXCLUSION_HASH_COMMENT_PREFIX)
+                    .map(str::to_string)
+            }),
+        });
+    }
+
+    Ok(constraints)
+}
+
+/// Split `pg_get_constraintdef` output for an EXCLUDE constraint, e.g.
+/// `EXCLUDE USING gist (room_id WITH =, during WITH &&) WHERE ((NOT cancelled)) DEFERRABLE`,
+/// into its element list and WHERE clause.
+fn parse_exclusion_def(def: &str) -> (String, Option<String>) {
+    // Elements are the first balanced parenthesized group
+    let Some(open) = def.find('(') else {
+        return (String::new(), None);
+    };
+    let mut depth = 0;
+    let mut close = def.len();
+    for (i, ch) in def[open..].char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = open + i;
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let elements = def[open + 1..close.min(def.len())].to_string();
+
+    let rest = def.get(close + 1..).unwrap_or("");
+    let filter = rest.find("WHERE (").map(|start| {
+        let clause = &rest[start + "WHERE (".len()..];
+        let mut depth = 1;
+        let mut end = clause.len();
+        for (i, ch) in clause.char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = i;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        clause[..end].to_string()
+    });
+
+    (elements, filter)
+}
+
 /// Introspect columns for a table.
 async fn introspect_columns(client: &Client, table_name: &str) -> Result<Vec<Column>> {
     let rows = client
@@ -178,7 +619,19 @@ async fn introspect_columns(client: &Client, table_name: &str) -> Result<Vec<Col
                 udt_name,
                 is_nullable,
                 column_default,
-                is_identity
+                is_identity,
+                domain_name,
+                EXISTS (
+                    SELECT 1
+                    FROM pg_type t
+                    JOIN pg_class rel ON rel.oid = t.typrelid
+                    WHERE t.typname = udt_name
+                      AND rel.relkind = 'c'
+                ) AS is_composite,
+                col_description(
+                    (quote_ident(table_schema) || '.' || quote_ident(table_name))::regclass,
+                    ordinal_position
+                ) AS comment
             FROM information_schema.columns
             WHERE table_schema = 'public' AND table_name = $1
             ORDER BY ordinal_position
@@ -195,8 +648,15 @@ async fn introspect_columns(client: &Client, table_name: &str) -> Result<Vec<Col
         let is_nullable: String = row.get(3);
         let column_default: Option<String> = row.get(4);
         let is_identity: String = row.get(5);
+        let domain: Option<String> = row.get(6);
+        let is_composite: bool = row.get(7);
+        let doc: Option<String> = row.get(8);
 
-        let pg_type = pg_type_from_info_schema(&data_type, &udt_name);
+        let (pg_type, composite) = if is_composite {
+            (PgType::Composite, Some(udt_name))
+        } else {
+            (pg_type_from_info_schema(&data_type, &udt_name), None)
+        };
         let nullable = is_nullable == "YES";
 
         // Clean up default value (remove type casts like ::text)
@@ -218,10 +678,12 @@ async fn introspect_columns(client: &Client, table_name: &str) -> Result<Vec<Col
             long: false,           // Not available from introspection
             label: false,          // Not available from introspection
             enum_variants: vec![], // TODO: fetch from pg_enum if pg_type is USER-DEFINED
-            doc: None,             // Not available from introspection
-            lang: None,            // Not available from introspection
-            icon: None,            // Not available from introspection
-            subtype: None,         // Not available from introspection
+            doc,
+            lang: None,    // Not available from introspection
+            icon: None,    // Not available from introspection
+            subtype: None, // Not available from introspection
+            domain,
+            composite,
         });
     }
 
@@ -282,7 +744,8 @@ async fn introspect_foreign_keys(client: &Client, table_name: &str) -> Result<Ve
                 kcu.column_name,
                 ccu.table_name AS foreign_table,
                 ccu.column_name AS foreign_column,
-                kcu.ordinal_position
+                kcu.ordinal_position,
+                tc.is_deferrable = 'YES' AND tc.initially_deferred = 'YES' AS deferrable
             FROM information_schema.table_constraints tc
             JOIN information_schema.key_column_usage kcu
                 ON tc.constraint_name = kcu.constraint_name
@@ -309,6 +772,7 @@ async fn introspect_foreign_keys(client: &Client, table_name: &str) -> Result<Ve
         let foreign_table: String = row.get(2);
         let foreign_column: String = row.get(3);
         let ordinal: i32 = row.get(4);
+        let deferrable: bool = row.get(5);
 
         fk_map
             .entry(constraint_name)
@@ -318,6 +782,7 @@ async fn introspect_foreign_keys(client: &Client, table_name: &str) -> Result<Ve
                         columns: Vec::new(),
                         references_table: foreign_table,
                         references_columns: Vec::new(),
+                        deferrable,
                     },
                     Vec::new(),
                 )
@@ -660,6 +1125,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_exclusion_def() {
+        assert_eq!(
+            parse_exclusion_def("EXCLUDE USING gist (room_id WITH =, during WITH &&)"),
+            ("room_id WITH =, during WITH &&".to_string(), None)
+        );
+        assert_eq!(
+            parse_exclusion_def(
+                "EXCLUDE USING gist (tstzrange(starts_at, ends_at) WITH &&) WHERE ((NOT cancelled)) DEFERRABLE INITIALLY DEFERRED"
+            ),
+            (
+                "tstzrange(starts_at, ends_at) WITH &&".to_string(),
+                Some("(NOT cancelled)".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_trigger_from_tgtype() {
+        // ROW | BEFORE | INSERT | UPDATE
+        let trig = trigger_from_tgtype("trg".to_string(), 1 | 2 | 4 | 16, String::new());
+        assert_eq!(trig.timing, "BEFORE");
+        assert_eq!(trig.events, vec!["INSERT", "UPDATE"]);
+        assert_eq!(trig.for_each, "ROW");
+
+        // AFTER DELETE, statement-level
+        let trig = trigger_from_tgtype("trg".to_string(), 8, String::new());
+        assert_eq!(trig.timing, "AFTER");
+        assert_eq!(trig.events, vec!["DELETE"]);
+        assert_eq!(trig.for_each, "STATEMENT");
+    }
+
     #[test]
     fn test_clean_default_value() {
         assert_eq!(clean_default_value("'foo'::text"), "'foo'");
@@ -39,6 +39,15 @@
 //! Use `MigrationResult` instead of `Result` to enable `#[track_caller]` - when an
 //! error occurs, the exact source location (file:line:column) is captured.
 //!
+//! Migrations can also be plain `.sql` files. `sql_migrations!` embeds every
+//! `.sql` file in a directory (relative to the crate root) and registers it,
+//! versioned by filename like Rust migrations and ordered together with them:
+//!
+//! ```ignore
+//! // migrations/m_2026_01_18_090000_seed_roles.sql
+//! dibs::sql_migrations!("migrations/");
+//! ```
+//!
 //! Run migrations with `MigrationRunner`:
 //!
 //! ```ignore
@@ -53,6 +62,7 @@ use std::pin::Pin;
 pub mod backoffice;
 pub mod diff;
 mod error;
+pub mod fleet;
 pub mod introspect;
 pub mod meta;
 mod migrate;
@@ -60,27 +70,44 @@ mod plugin;
 pub mod pool;
 pub mod query;
 pub mod schema;
+pub mod seed;
 pub mod service;
 pub mod solver;
+mod sql_migration;
 mod traced;
+pub mod types;
 
 pub use backoffice::SquelServiceImpl;
 pub use dibs_jsonb::Jsonb;
 pub use diff::{Change, SchemaDiff, TableDiff};
 pub use error::{Error, MigrationError, SqlErrorContext};
-pub use meta::{create_meta_tables_sql, record_migration_sql, sync_tables_sql};
+pub use fleet::{
+    FailurePolicy, FleetDatabaseReport, FleetEvent, FleetEventKind, FleetOutcome, FleetReport,
+    FleetRunner, FleetTarget,
+};
+pub use meta::{
+    ObjectProvenance, create_meta_tables_sql, record_migration_sql, sync_sources_sql,
+    sync_tables_sql, track_provenance,
+};
 pub use migrate::{
-    AppliedMigration, Migration, MigrationContext, MigrationRunner, MigrationStatus, RanMigration,
+    AppliedMigration, AppliedRepeatable, HookEvent, Migration, MigrationContext, MigrationDrift,
+    MigrationHook, MigrationRunner, MigrationStatus, RanMigration, RepeatableMigration,
 };
 pub use pool::ConnectionProvider;
+pub use seed::{SeedEnv, SeedFile, SeedValue, SeededTable, TableSeed, apply_seeds};
 pub use service::{DibsServiceImpl, serve, serve_listener};
+#[doc(hidden)]
+pub use sql_migration::__run_sql_migration;
+pub use sql_migration::{SqlChunk, split_sql_into_chunks};
 pub use traced::{Connection, ConnectionExt, TracedConn, TracedObject, TracedPool};
 
 // Re-export schema types from dibs_db_schema
 pub use dibs_db_schema::{
-    __attr, __parse_attr, Attr, Check, CheckConstraint, Column, CompositeIndex, CompositeUnique,
-    ForeignKey, Index, IndexColumn, NullsOrder, PgType, Schema, SortOrder, SourceLocation, Table,
-    TableDef, TriggerCheck, TriggerCheckConstraint,
+    __attr, __parse_attr, Attr, Check, CheckConstraint, Column, Composite, CompositeField,
+    CompositeIndex, CompositeType, CompositeUnique, Domain, DomainType, Exclude,
+    ExclusionConstraint, ExtensionDef, ForeignKey, Index, IndexColumn, NullsOrder, PartitionBy,
+    PartitionKind, PartitionSpec, PgType, Policy, RlsPolicy, Schema, SortOrder, SourceLocation,
+    Table, TableDef, TableTrigger, Trigger, TriggerCheck, TriggerCheckConstraint,
 };
 
 // Re-export proto types for convenience
@@ -90,7 +117,7 @@ pub use dibs_proto::*;
 pub use inventory;
 
 // Re-export the proc macro
-pub use dibs_macros::migration;
+pub use dibs_macros::{hook, migration, sql_migrations};
 
 // Re-export query DSL codegen types
 pub use dibs_qgen::{GeneratedCode, QueryFile, generate_rust_code, parse_query_file};
@@ -182,6 +209,18 @@ pub fn trigger_check_function_name(trigger_name: &str) -> String {
     format!("trgfn_{}", &hex[..20])
 }
 
+/// Derive the function name for a user-defined trigger.
+///
+/// The name hashes the table, the trigger name and the (whitespace-normalized)
+/// function body, so editing the body yields a different function and the
+/// trigger shows up in the diff.
+pub fn trigger_function_name(table: &str, trigger_name: &str, body: &str) -> String {
+    let normalized = normalize_sql_expr_for_hash(body);
+    let input = format!("{}\0{}\0{}", table, trigger_name, normalized);
+    let hex = blake3::hash(input.as_bytes()).to_hex().to_string();
+    format!("trgdef_{}", &hex[..20])
+}
+
 fn normalize_sql_expr_for_hash(expr: &str) -> String {
     let mut out = String::with_capacity(expr.len());
     let mut pending_space = false;
@@ -303,6 +342,45 @@ pub type MigrationFn = for<'a> fn(
 
 // Register Migration with inventory
 inventory::collect!(Migration);
+inventory::collect!(RepeatableMigration);
+inventory::collect!(MigrationHook);
+
+/// Register a repeatable migration, re-run whenever its SQL changes.
+///
+/// Repeatable migrations run after all versioned ones, ordered by name. Use
+/// them for objects that are replaced wholesale, like views, functions and
+/// grants, so the SQL should be safe to re-run (`CREATE OR REPLACE ...`).
+///
+/// ```ignore
+/// dibs::repeatable_migration!("active_users", include_str!("views/active_users.sql"));
+/// ```
+#[macro_export]
+macro_rules! repeatable_migration {
+    ($name:expr, $sql:expr $(,)?) => {
+        $crate::inventory::submit!($crate::RepeatableMigration {
+            name: $name,
+            sql: $sql,
+            source_file: (env!("CARGO_MANIFEST_DIR"), file!()),
+        });
+    };
+}
+
+/// Declare a Postgres extension the schema depends on.
+///
+/// Registered extensions are picked up by [`collect_schema`](schema::collect_schema);
+/// `dibs diff` reports any that are missing from the database and the generated
+/// migration creates them before any other change.
+///
+/// ```ignore
+/// dibs::extension!("pg_trgm");
+/// dibs::extension!("citext");
+/// ```
+#[macro_export]
+macro_rules! extension {
+    ($name:literal) => {
+        $crate::inventory::submit!($crate::ExtensionDef::new($name));
+    };
+}
 
 /// Generate query code from a `.styx` file.
 ///
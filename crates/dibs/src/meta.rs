@@ -4,19 +4,57 @@
 //! - Source locations of schema elements (file, line, column)
 //! - Doc comments from Rust code
 //! - Migration history (which migration created/modified each element)
+//!
+//! [`MigrationRunner`](crate::MigrationRunner) creates them, records every
+//! migration in `__dibs_migrations`, and calls [`track_provenance`] after each
+//! one so every table, column and index knows which migration created and
+//! last modified it.
 
-use crate::Schema;
+use crate::{Result, Schema};
+use tokio_postgres::{Client, Transaction};
 
 /// SQL to create the __dibs_migrations table.
+///
+/// Repeatable migrations are recorded by name, with the checksum of the SQL
+/// they last ran; versioned migrations have no checksum.
 pub const CREATE_MIGRATIONS_TABLE: &str = r#"
 CREATE TABLE IF NOT EXISTS __dibs_migrations (
     name TEXT PRIMARY KEY,
     applied_at TIMESTAMPTZ NOT NULL DEFAULT now(),
     checksum TEXT,
-    execution_time_ms INTEGER
+    execution_time_ms INTEGER,
+    repeatable BOOLEAN NOT NULL DEFAULT false
 );
 "#;
 
+/// SQL to copy rows from the `_dibs_migrations` table used by older
+/// versions of dibs into `__dibs_migrations`.
+///
+/// The old table is left untouched, so a build still on an older version can
+/// read it, and rows already imported are skipped.
+pub const IMPORT_LEGACY_MIGRATIONS: &str = r#"
+DO $$
+BEGIN
+    IF to_regclass('_dibs_migrations') IS NULL THEN
+        RETURN;
+    END IF;
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = '_dibs_migrations' AND column_name = 'checksum'
+          AND table_schema = ANY (current_schemas(false))
+    ) THEN
+        INSERT INTO __dibs_migrations (name, applied_at, checksum, repeatable)
+        SELECT version, applied_at, checksum, checksum IS NOT NULL FROM _dibs_migrations
+        ON CONFLICT (name) DO NOTHING;
+    ELSE
+        INSERT INTO __dibs_migrations (name, applied_at)
+        SELECT version, applied_at FROM _dibs_migrations
+        ON CONFLICT (name) DO NOTHING;
+    END IF;
+END
+$$;
+"#;
+
 /// SQL to create the __dibs_tables table.
 pub const CREATE_TABLES_TABLE: &str = r#"
 CREATE TABLE IF NOT EXISTS __dibs_tables (
@@ -25,6 +63,7 @@ CREATE TABLE IF NOT EXISTS __dibs_tables (
     source_line INTEGER,
     source_column INTEGER,
     doc_comment TEXT,
+    fingerprint TEXT,
     created_by_migration TEXT,
     modified_by_migration TEXT,
     created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
@@ -50,6 +89,7 @@ CREATE TABLE IF NOT EXISTS __dibs_columns (
     is_indexed BOOLEAN NOT NULL DEFAULT false,
     fk_references_table TEXT,
     fk_references_column TEXT,
+    fingerprint TEXT,
     created_by_migration TEXT,
     modified_by_migration TEXT,
     created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
@@ -69,6 +109,7 @@ CREATE TABLE IF NOT EXISTS __dibs_indices (
     columns TEXT[] NOT NULL,
     is_unique BOOLEAN NOT NULL DEFAULT false,
     where_clause TEXT,
+    fingerprint TEXT,
     created_by_migration TEXT,
     modified_by_migration TEXT,
     created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
@@ -78,6 +119,8 @@ CREATE TABLE IF NOT EXISTS __dibs_indices (
 "#;
 
 /// Generate SQL to create all meta tables.
+///
+/// Safe to run repeatedly: every table is created only if missing.
 pub fn create_meta_tables_sql() -> String {
     format!(
         "{}\n{}\n{}\n{}",
@@ -289,6 +332,267 @@ ON CONFLICT (name) DO NOTHING;
     )
 }
 
+/// Tables tracked for provenance: plain and partitioned tables in the
+/// current schema, except partitions and dibs' own meta tables.
+const TRACKED_TABLES: &str = r#"
+    SELECT c.oid, c.relname::text AS table_name
+    FROM pg_class c
+    JOIN pg_namespace n ON n.oid = c.relnamespace
+    WHERE n.nspname = current_schema()
+      AND c.relkind IN ('r', 'p')
+      AND NOT c.relispartition
+      AND c.relname NOT LIKE '\_\_dibs\_%'
+"#;
+
+/// Upsert `__dibs_tables` from the catalog; `$1` is the migration.
+///
+/// A table's fingerprint covers its columns and constraints.
+fn track_tables_sql() -> String {
+    format!(
+        r#"
+WITH tracked AS ({TRACKED_TABLES}),
+live AS (
+    SELECT t.table_name, md5(
+        coalesce((
+            SELECT string_agg(
+                a.attname || ' ' || format_type(a.atttypid, a.atttypmod)
+                    || CASE WHEN a.attnotnull THEN ' NOT NULL' ELSE '' END,
+                ', ' ORDER BY a.attnum)
+            FROM pg_attribute a
+            WHERE a.attrelid = t.oid AND a.attnum > 0 AND NOT a.attisdropped
+        ), '') || ';' || coalesce((
+            SELECT string_agg(k.conname || ' ' || pg_get_constraintdef(k.oid), ', ' ORDER BY k.conname)
+            FROM pg_constraint k
+            WHERE k.conrelid = t.oid
+        ), '')
+    ) AS fingerprint
+    FROM tracked t
+)
+INSERT INTO __dibs_tables (table_name, fingerprint, created_by_migration, modified_by_migration)
+SELECT table_name, fingerprint, $1::text, $1::text FROM live
+ON CONFLICT (table_name) DO UPDATE SET
+    fingerprint = EXCLUDED.fingerprint,
+    modified_by_migration = EXCLUDED.modified_by_migration,
+    modified_at = now()
+WHERE __dibs_tables.fingerprint IS DISTINCT FROM EXCLUDED.fingerprint
+"#
+    )
+}
+
+/// Upsert `__dibs_columns` from the catalog; `$1` is the migration.
+///
+/// A column's fingerprint covers its type, nullability and default.
+fn track_columns_sql() -> String {
+    format!(
+        r#"
+WITH tracked AS ({TRACKED_TABLES}),
+live AS (
+    SELECT t.table_name, a.attname::text AS column_name, md5(
+        format_type(a.atttypid, a.atttypmod) || ' ' || a.attnotnull::text || ' '
+            || coalesce(pg_get_expr(d.adbin, d.adrelid), '')
+    ) AS fingerprint
+    FROM tracked t
+    JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum > 0 AND NOT a.attisdropped
+    LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+)
+INSERT INTO __dibs_columns (table_name, column_name, fingerprint, created_by_migration, modified_by_migration)
+SELECT table_name, column_name, fingerprint, $1::text, $1::text FROM live
+ON CONFLICT (table_name, column_name) DO UPDATE SET
+    fingerprint = EXCLUDED.fingerprint,
+    modified_by_migration = EXCLUDED.modified_by_migration,
+    modified_at = now()
+WHERE __dibs_columns.fingerprint IS DISTINCT FROM EXCLUDED.fingerprint
+"#
+    )
+}
+
+/// Upsert `__dibs_indices` from the catalog; `$1` is the migration.
+///
+/// An index's fingerprint is its definition.
+fn track_indices_sql() -> String {
+    format!(
+        r#"
+WITH tracked AS ({TRACKED_TABLES}),
+live AS (
+    SELECT t.table_name, i.relname::text AS index_name,
+        ARRAY(
+            SELECT a.attname::text
+            FROM unnest(x.indkey::int2[]) WITH ORDINALITY AS k(attnum, ord)
+            JOIN pg_attribute a ON a.attrelid = x.indrelid AND a.attnum = k.attnum
+            ORDER BY k.ord
+        ) AS columns,
+        x.indisunique AS is_unique,
+        md5(pg_get_indexdef(x.indexrelid)) AS fingerprint
+    FROM tracked t
+    JOIN pg_index x ON x.indrelid = t.oid
+    JOIN pg_class i ON i.oid = x.indexrelid
+)
+INSERT INTO __dibs_indices (table_name, index_name, columns, is_unique, fingerprint, created_by_migration, modified_by_migration)
+SELECT table_name, index_name, columns, is_unique, fingerprint, $1::text, $1::text FROM live
+ON CONFLICT (table_name, index_name) DO UPDATE SET
+    columns = EXCLUDED.columns,
+    is_unique = EXCLUDED.is_unique,
+    fingerprint = EXCLUDED.fingerprint,
+    modified_by_migration = EXCLUDED.modified_by_migration,
+    modified_at = now()
+WHERE __dibs_indices.fingerprint IS DISTINCT FROM EXCLUDED.fingerprint
+"#
+    )
+}
+
+/// Delete meta rows for tables, columns and indices that no longer exist.
+fn forget_dropped_sql() -> String {
+    format!(
+        r#"
+DELETE FROM __dibs_tables m
+WHERE NOT EXISTS (
+    SELECT 1 FROM ({TRACKED_TABLES}) t WHERE t.table_name = m.table_name
+);
+DELETE FROM __dibs_columns m
+WHERE NOT EXISTS (
+    SELECT 1 FROM ({TRACKED_TABLES}) t
+    JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum > 0 AND NOT a.attisdropped
+    WHERE t.table_name = m.table_name AND a.attname = m.column_name
+);
+DELETE FROM __dibs_indices m
+WHERE NOT EXISTS (
+    SELECT 1 FROM ({TRACKED_TABLES}) t
+    JOIN pg_index x ON x.indrelid = t.oid
+    JOIN pg_class i ON i.oid = x.indexrelid
+    WHERE t.table_name = m.table_name AND i.relname = m.index_name
+);
+"#
+    )
+}
+
+/// Record which migration created or last modified each table, column and
+/// index.
+///
+/// The catalog is compared against fingerprints stored in the meta tables:
+/// new objects are attributed to `migration` as their creator, changed ones
+/// as their last modifier, and rows for dropped objects are deleted. With
+/// `None`, objects that predate tracking are recorded without a migration.
+pub async fn track_provenance(tx: &Transaction<'_>, migration: Option<&str>) -> Result<()> {
+    for sql in [track_tables_sql(), track_columns_sql(), track_indices_sql()] {
+        tx.execute(&sql, &[&migration]).await?;
+    }
+    tx.batch_execute(&forget_dropped_sql()).await?;
+    Ok(())
+}
+
+/// Generate SQL to attach source locations and doc comments from the
+/// current schema to the objects tracked in the meta tables.
+///
+/// Unlike [`sync_tables_sql`], this only updates rows that already exist and
+/// leaves migration provenance alone.
+pub fn sync_sources_sql(schema: &Schema) -> String {
+    fn quote(s: &str) -> String {
+        format!("'{}'", s.replace('\'', "''"))
+    }
+    fn quote_opt(s: Option<&str>) -> String {
+        s.map(quote).unwrap_or_else(|| "NULL".to_string())
+    }
+    fn num_opt(n: Option<u32>) -> String {
+        n.map(|n| n.to_string())
+            .unwrap_or_else(|| "NULL".to_string())
+    }
+
+    let mut sql = String::new();
+    for table in schema.tables.values() {
+        let name = quote(&table.name);
+        let source = format!(
+            "source_file = {}, source_line = {}, source_column = {}",
+            quote_opt(table.source.file.as_deref()),
+            num_opt(table.source.line),
+            num_opt(table.source.column),
+        );
+        sql.push_str(&format!(
+            "UPDATE __dibs_tables SET {source}, doc_comment = {} WHERE table_name = {name};\n",
+            quote_opt(table.doc.as_deref()),
+        ));
+        for col in &table.columns {
+            sql.push_str(&format!(
+                "UPDATE __dibs_columns SET {source}, doc_comment = {}, rust_type = {}, \
+                 sql_type = {} WHERE table_name = {name} AND column_name = {};\n",
+                quote_opt(col.doc.as_deref()),
+                quote_opt(col.rust_type.as_deref()),
+                quote(&col.pg_type.to_string()),
+                quote(&col.name),
+            ));
+        }
+        for idx in &table.indices {
+            sql.push_str(&format!(
+                "UPDATE __dibs_indices SET {source} WHERE table_name = {name} AND index_name = {};\n",
+                quote(&idx.name),
+            ));
+        }
+    }
+    sql
+}
+
+/// Provenance of a table, column or index, as recorded in the meta tables.
+#[derive(Debug, Clone)]
+pub struct ObjectProvenance {
+    /// "table", "column" or "index"
+    pub kind: &'static str,
+    pub table: String,
+    /// Column or index name (`None` for tables)
+    pub name: Option<String>,
+    /// Migration that created it (`None` if it predates tracking)
+    pub created_by: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Migration that last modified it
+    pub modified_by: Option<String>,
+    pub modified_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Load the recorded provenance of every tracked table, column and index.
+///
+/// Returns nothing if migrations never ran against the database.
+pub async fn provenance(client: &Client) -> Result<Vec<ObjectProvenance>> {
+    let exists: bool = client
+        .query_one("SELECT to_regclass('__dibs_tables') IS NOT NULL", &[])
+        .await?
+        .get(0);
+    if !exists {
+        return Ok(Vec::new());
+    }
+
+    let rows = client
+        .query(
+            "SELECT 'table', table_name, NULL::text, created_by_migration, created_at,
+                    modified_by_migration, modified_at
+             FROM __dibs_tables
+             UNION ALL
+             SELECT 'column', table_name, column_name, created_by_migration, created_at,
+                    modified_by_migration, modified_at
+             FROM __dibs_columns
+             UNION ALL
+             SELECT 'index', table_name, index_name, created_by_migration, created_at,
+                    modified_by_migration, modified_at
+             FROM __dibs_indices
+             ORDER BY 2, 1, 3",
+            &[],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|r| ObjectProvenance {
+            kind: match r.get::<_, &str>(0) {
+                "table" => "table",
+                "column" => "column",
+                _ => "index",
+            },
+            table: r.get(1),
+            name: r.get(2),
+            created_by: r.get(3),
+            created_at: r.get(4),
+            modified_by: r.get(5),
+            modified_at: r.get(6),
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -301,4 +605,12 @@ mod tests {
         assert!(sql.contains("__dibs_columns"));
         assert!(sql.contains("__dibs_indices"));
     }
+
+    #[test]
+    fn test_tracking_skips_meta_tables() {
+        for sql in [track_tables_sql(), track_columns_sql(), track_indices_sql()] {
+            assert!(sql.contains(r"NOT LIKE '\_\_dibs\_%'"));
+            assert!(sql.contains("$1::text"));
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{MigrationError, MigrationFn, Result};
+use crate::{MigrationError, MigrationFn, Result, Schema};
 use tokio_postgres::{Client, Transaction};
 use tracing::Instrument;
 
@@ -12,58 +12,183 @@ pub struct Migration {
     pub run: MigrationFn,
     /// Source file path (CARGO_MANIFEST_DIR, file!())
     pub source_file: (&'static str, &'static str),
+    /// Versions this migration replaces, if it is a squashed baseline.
+    ///
+    /// A database that already applied all of them records the baseline as
+    /// applied without running it.
+    pub squashes: &'static [&'static str],
+    /// `statement_timeout` for the migration's transaction, e.g. "30s"
+    pub timeout: Option<&'static str>,
 }
 
 impl Migration {
     /// Get the resolved source file path.
     ///
-    /// This handles the complexity of `file!()` in workspace members, where
-    /// `file!()` returns a path relative to the workspace root (e.g.,
-    /// `examples/my-app-db/src/...`) while `CARGO_MANIFEST_DIR` is the
-    /// absolute path to the crate (e.g., `/path/to/workspace/examples/my-app-db`).
+    /// See [`resolve_source_path`] for how workspace members are handled.
     pub fn source_path(&self) -> std::path::PathBuf {
-        let (manifest_dir, file_path) = self.source_file;
-        let file_path = std::path::Path::new(file_path);
+        resolve_source_path(self.source_file)
+    }
+}
 
-        if file_path.is_absolute() {
-            return file_path.to_path_buf();
-        }
+/// A repeatable migration, re-run whenever its SQL changes.
+///
+/// Suited to objects that are replaced wholesale rather than altered, like
+/// views, functions and grants. Repeatable migrations run after all versioned
+/// ones, ordered by name, and are tracked by a checksum of their SQL.
+/// Register them with [`repeatable_migration!`](crate::repeatable_migration)
+/// or as `r_*.sql` files picked up by `sql_migrations!`.
+pub struct RepeatableMigration {
+    /// Unique name, e.g. "active_users_view"
+    pub name: &'static str,
+    /// The SQL to run, split into statements like a `.sql` migration
+    pub sql: &'static str,
+    /// Source file path (CARGO_MANIFEST_DIR, file!())
+    pub source_file: (&'static str, &'static str),
+}
 
-        // Try manifest_dir + file_path first (works for non-workspace crates)
-        let full = std::path::Path::new(manifest_dir).join(file_path);
-        if full.exists() {
-            return full;
-        }
+impl RepeatableMigration {
+    /// Checksum of the SQL (blake3, first 16 hex digits), stored when it runs.
+    pub fn checksum(&self) -> String {
+        let hex = blake3::hash(self.sql.as_bytes()).to_hex();
+        hex[..16].to_string()
+    }
 
-        // file!() in workspace members includes the path from workspace root
-        // e.g., file!() = "examples/my-app-db/src/..." and manifest_dir ends with "examples/my-app-db"
-        // Strip the duplicated crate path portion
-        if let Some(crate_name) = std::path::Path::new(manifest_dir).file_name() {
-            let crate_name = crate_name.to_string_lossy();
-            let file_str = file_path.to_string_lossy();
-            if let Some(pos) = file_str.find(&*crate_name) {
-                let relative = &file_str[pos + crate_name.len()..];
-                let relative = relative.trim_start_matches('/');
-                let full = std::path::Path::new(manifest_dir).join(relative);
-                if full.exists() {
-                    return full;
-                }
-            }
-        }
+    /// Get the resolved source file path.
+    pub fn source_path(&self) -> std::path::PathBuf {
+        resolve_source_path(self.source_file)
+    }
+}
+
+/// When a [`MigrationHook`] runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// Once per run, before any migration
+    BeforeAll,
+    /// Inside each migration's transaction, before it runs
+    BeforeEach,
+    /// Inside each migration's transaction, after it ran
+    AfterEach,
+    /// Once per run, after all migrations succeeded
+    AfterAll,
+}
+
+impl std::fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HookEvent::BeforeAll => "before_all",
+            HookEvent::BeforeEach => "before_each",
+            HookEvent::AfterEach => "after_each",
+            HookEvent::AfterAll => "after_all",
+        })
+    }
+}
 
-        // Try walking up to workspace root
-        let mut workspace = std::path::Path::new(manifest_dir);
-        while let Some(parent) = workspace.parent() {
-            let candidate = parent.join(file_path);
-            if candidate.exists() {
-                return candidate;
+/// A function run around migrations, registered with
+/// [`#[dibs::hook]`](crate::hook).
+///
+/// `BeforeAll` and `AfterAll` hooks run in a transaction of their own, so
+/// plain `SET` (unlike `SET LOCAL`) carries over to the migrations, and
+/// `NOTIFY` is sent when they commit. `BeforeEach` and `AfterEach` hooks run in
+/// the migration's transaction, and repeatable migrations get them too. A
+/// failing hook fails the run like a failing migration.
+pub struct MigrationHook {
+    /// Function name, used in errors and to order hooks of the same event
+    pub name: &'static str,
+    pub event: HookEvent,
+    /// The hook function
+    pub run: MigrationFn,
+    /// Source file path (CARGO_MANIFEST_DIR, file!())
+    pub source_file: (&'static str, &'static str),
+}
+
+impl MigrationHook {
+    /// Registered hooks for `event`, ordered by name.
+    pub fn for_event(event: HookEvent) -> Vec<&'static MigrationHook> {
+        let mut hooks: Vec<_> = inventory::iter::<MigrationHook>
+            .into_iter()
+            .filter(|h| h.event == event)
+            .collect();
+        hooks.sort_by_key(|h| h.name);
+        hooks
+    }
+}
+
+/// Run the hooks registered for `event` in `tx`.
+///
+/// Errors are wrapped in [`Error::Hook`](crate::Error::Hook) so they name the
+/// hook, keeping the location the hook failed at.
+async fn run_hooks(
+    event: HookEvent,
+    tx: &Transaction<'_>,
+    migration: Option<&'static str>,
+) -> std::result::Result<(), MigrationError> {
+    for hook in MigrationHook::for_event(event) {
+        let span = tracing::debug_span!("migration.hook", hook = hook.name, %event);
+        let mut ctx = MigrationContext { tx, migration };
+        (hook.run)(&mut ctx)
+            .instrument(span)
+            .await
+            .map_err(|e| MigrationError {
+                inner: crate::Error::Hook {
+                    event,
+                    name: hook.name,
+                    source: Box::new(e.inner),
+                },
+                caller: e.caller,
+            })?;
+    }
+    Ok(())
+}
+
+/// Resolve a `(CARGO_MANIFEST_DIR, file!())` pair to a path on disk.
+///
+/// This handles the complexity of `file!()` in workspace members, where
+/// `file!()` returns a path relative to the workspace root (e.g.,
+/// `examples/my-app-db/src/...`) while `CARGO_MANIFEST_DIR` is the
+/// absolute path to the crate (e.g., `/path/to/workspace/examples/my-app-db`).
+fn resolve_source_path(
+    (manifest_dir, file_path): (&'static str, &'static str),
+) -> std::path::PathBuf {
+    let file_path = std::path::Path::new(file_path);
+
+    if file_path.is_absolute() {
+        return file_path.to_path_buf();
+    }
+
+    // Try manifest_dir + file_path first (works for non-workspace crates)
+    let full = std::path::Path::new(manifest_dir).join(file_path);
+    if full.exists() {
+        return full;
+    }
+
+    // file!() in workspace members includes the path from workspace root
+    // e.g., file!() = "examples/my-app-db/src/..." and manifest_dir ends with "examples/my-app-db"
+    // Strip the duplicated crate path portion
+    if let Some(crate_name) = std::path::Path::new(manifest_dir).file_name() {
+        let crate_name = crate_name.to_string_lossy();
+        let file_str = file_path.to_string_lossy();
+        if let Some(pos) = file_str.find(&*crate_name) {
+            let relative = &file_str[pos + crate_name.len()..];
+            let relative = relative.trim_start_matches('/');
+            let full = std::path::Path::new(manifest_dir).join(relative);
+            if full.exists() {
+                return full;
             }
-            workspace = parent;
         }
+    }
 
-        // Last resort: return the combined path even if it doesn't exist
-        std::path::Path::new(manifest_dir).join(file_path)
+    // Try walking up to workspace root
+    let mut workspace = std::path::Path::new(manifest_dir);
+    while let Some(parent) = workspace.parent() {
+        let candidate = parent.join(file_path);
+        if candidate.exists() {
+            return candidate;
+        }
+        workspace = parent;
     }
+
+    // Last resort: return the combined path even if it doesn't exist
+    std::path::Path::new(manifest_dir).join(file_path)
 }
 
 /// Context passed to migration functions.
@@ -71,11 +196,22 @@ impl Migration {
 /// Wraps a database transaction, ensuring all migration operations are atomic.
 pub struct MigrationContext<'a> {
     tx: &'a Transaction<'a>,
+    migration: Option<&'static str>,
 }
 
 impl<'a> MigrationContext<'a> {
     pub fn new(tx: &'a Transaction<'a>) -> Self {
-        Self { tx }
+        Self {
+            tx,
+            migration: None,
+        }
+    }
+
+    /// Version (or repeatable name) of the migration being run.
+    ///
+    /// `None` in `before_all` and `after_all` hooks.
+    pub fn migration(&self) -> Option<&'static str> {
+        self.migration
     }
 
     /// Execute a SQL statement.
@@ -138,20 +274,141 @@ impl<'a> MigrationContext<'a> {
         Ok(total)
     }
 
+    /// Create a partition of a partitioned table, if it doesn't exist yet.
+    ///
+    /// `bounds` is a partition bound spec, e.g. `FOR VALUES IN ('eu')`,
+    /// `FOR VALUES WITH (MODULUS 4, REMAINDER 0)` or `DEFAULT`.
+    pub async fn create_partition(
+        &self,
+        parent: &str,
+        partition: &str,
+        bounds: &str,
+    ) -> Result<()> {
+        self.execute(&crate::schema::create_partition_sql(
+            parent, partition, bounds,
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Create a range partition covering `[from, to)`, if it doesn't exist yet.
+    pub async fn create_range_partition(
+        &self,
+        parent: &str,
+        partition: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<()> {
+        let bounds = crate::schema::range_partition_bounds(from, to);
+        self.create_partition(parent, partition, &bounds).await
+    }
+
+    /// Create the monthly range partition of `parent` containing `day`.
+    ///
+    /// The partition is named `{parent}_{YYYY}_{MM}` and covers the whole
+    /// calendar month. Returns the partition name.
+    pub async fn create_monthly_partition(
+        &self,
+        parent: &str,
+        day: chrono::NaiveDate,
+    ) -> Result<String> {
+        let (name, from, to) = monthly_partition(parent, day);
+        self.create_range_partition(parent, &name, &from.to_string(), &to.to_string())
+            .await?;
+        Ok(name)
+    }
+
+    /// Attach an existing table as a partition of `parent`.
+    pub async fn attach_partition(
+        &self,
+        parent: &str,
+        partition: &str,
+        bounds: &str,
+    ) -> Result<()> {
+        self.execute(&crate::schema::attach_partition_sql(
+            parent, partition, bounds,
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Detach a partition from `parent`, leaving it as a standalone table.
+    pub async fn detach_partition(&self, parent: &str, partition: &str) -> Result<()> {
+        self.execute(&crate::schema::detach_partition_sql(parent, partition))
+            .await?;
+        Ok(())
+    }
+
     /// Get the underlying transaction for complex operations.
     pub fn transaction(&self) -> &Transaction<'a> {
         self.tx
     }
 }
 
+/// Milliseconds since `start`, as stored in `__dibs_migrations`.
+fn elapsed_ms(start: std::time::Instant) -> i32 {
+    i32::try_from(start.elapsed().as_millis()).unwrap_or(i32::MAX)
+}
+
+/// Name and `[from, to)` bounds of the monthly partition containing `day`.
+fn monthly_partition(
+    parent: &str,
+    day: chrono::NaiveDate,
+) -> (String, chrono::NaiveDate, chrono::NaiveDate) {
+    use chrono::Datelike;
+
+    let from = day.with_day(1).expect("day 1 exists in every month");
+    let to = from
+        .checked_add_months(chrono::Months::new(1))
+        .expect("date out of range");
+    let name = format!("{}_{:04}_{:02}", parent, from.year(), from.month());
+    (name, from, to)
+}
+
+/// Called with each migration as it's committed.
+type OnRan<'a> = &'a mut (dyn FnMut(&RanMigration) + Send + Sync);
+
 /// Runs migrations against a database.
 pub struct MigrationRunner<'a> {
     client: &'a mut Client,
+    strict: bool,
+    schema: Option<&'a Schema>,
+    on_ran: Option<OnRan<'a>>,
 }
 
 impl<'a> MigrationRunner<'a> {
     pub fn new(client: &'a mut Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            strict: false,
+            schema: None,
+            on_ran: None,
+        }
+    }
+
+    /// Refuse to run migrations when [`drift`](Self::drift) finds
+    /// out-of-order or missing migrations, instead of warning.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// The schema the code declares, usually from
+    /// [`collect_schema`](crate::schema::collect_schema).
+    ///
+    /// After migrating, tracked objects are pointed at their definitions in
+    /// it. Without one, the source locations recorded in the meta tables are left
+    /// as they were.
+    pub fn with_schema(mut self, schema: &'a Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Call `on_ran` as each migration is committed, instead of waiting for
+    /// the list [`migrate`](Self::migrate) returns once all of them ran.
+    pub fn on_ran(mut self, on_ran: OnRan<'a>) -> Self {
+        self.on_ran = Some(on_ran);
+        self
     }
 
     /// Get the total number of registered migrations.
@@ -159,16 +416,18 @@ impl<'a> MigrationRunner<'a> {
         inventory::iter::<Migration>.into_iter().count()
     }
 
-    /// Ensure the migrations tracking table exists.
+    /// Ensure the `__dibs_*` meta tables exist.
+    ///
+    /// Migrations are recorded in `__dibs_migrations`; repeatable ones by
+    /// name, with the checksum of the SQL they last ran. History from the
+    /// `_dibs_migrations` table of older versions is copied over; that table
+    /// is left in place.
     pub async fn init(&self) -> Result<()> {
         self.client
-            .execute(
-                "CREATE TABLE IF NOT EXISTS _dibs_migrations (
-                    version TEXT PRIMARY KEY,
-                    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
-                )",
-                &[],
-            )
+            .batch_execute(&crate::meta::create_meta_tables_sql())
+            .await?;
+        self.client
+            .batch_execute(crate::meta::IMPORT_LEGACY_MIGRATIONS)
             .await?;
         Ok(())
     }
@@ -178,7 +437,8 @@ impl<'a> MigrationRunner<'a> {
         let rows = self
             .client
             .query(
-                "SELECT version, applied_at FROM _dibs_migrations ORDER BY version",
+                "SELECT name, applied_at FROM __dibs_migrations
+                 WHERE NOT repeatable ORDER BY name",
                 &[],
             )
             .await?;
@@ -191,6 +451,45 @@ impl<'a> MigrationRunner<'a> {
             .collect())
     }
 
+    /// Get the repeatable migrations that have run, with the checksum of the
+    /// SQL they last ran and when.
+    pub async fn applied_repeatable(&self) -> Result<Vec<AppliedRepeatable>> {
+        let rows = self
+            .client
+            .query(
+                "SELECT name, checksum, applied_at FROM __dibs_migrations
+                 WHERE repeatable ORDER BY name",
+                &[],
+            )
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|r| AppliedRepeatable {
+                name: r.get(0),
+                checksum: r.get(1),
+                applied_at: r.get(2),
+            })
+            .collect())
+    }
+
+    /// Get the repeatable migrations that never ran or whose SQL changed
+    /// since, ordered by name.
+    pub fn changed_repeatable(
+        &self,
+        applied: &[AppliedRepeatable],
+    ) -> Vec<&'static RepeatableMigration> {
+        let mut migrations: Vec<_> = inventory::iter::<RepeatableMigration>
+            .into_iter()
+            .filter(|m| {
+                !applied
+                    .iter()
+                    .any(|a| a.name == m.name && a.checksum == m.checksum())
+            })
+            .collect();
+        migrations.sort_by_key(|m| m.name);
+        migrations
+    }
+
     /// Get all pending migrations (registered but not applied).
     pub fn pending(&self, applied: &[AppliedMigration]) -> Vec<&'static Migration> {
         let applied_versions: std::collections::HashSet<&str> =
@@ -203,7 +502,7 @@ impl<'a> MigrationRunner<'a> {
         migrations
     }
 
-    /// Run all pending migrations.
+    /// Run all pending migrations, then any changed repeatable migrations.
     ///
     /// Each migration runs in its own transaction. If a migration fails,
     /// all its changes are rolled back and subsequent migrations are skipped.
@@ -211,66 +510,393 @@ impl<'a> MigrationRunner<'a> {
     /// Returns `MigrationError` on failure, which includes the exact source
     /// location where the error occurred (captured via `#[track_caller]`).
     pub async fn migrate(&mut self) -> std::result::Result<Vec<RanMigration>, MigrationError> {
+        self.migrate_pending(None).await
+    }
+
+    /// Run pending migrations up to and including `version`.
+    ///
+    /// Migrations after the target are left pending, so a single migration
+    /// can be applied and inspected in isolation. Repeatable migrations are
+    /// not run. Fails if no registered migration has that version.
+    pub async fn migrate_to(
+        &mut self,
+        version: &str,
+    ) -> std::result::Result<Vec<RanMigration>, MigrationError> {
+        if !inventory::iter::<Migration>
+            .into_iter()
+            .any(|m| m.version == version)
+        {
+            return Err(
+                crate::Error::Migration(format!("unknown migration version '{version}'")).into(),
+            );
+        }
+        self.migrate_pending(Some(version)).await
+    }
+
+    /// Run pending migrations, stopping after `through` if given.
+    pub(crate) async fn migrate_pending(
+        &mut self,
+        through: Option<&str>,
+    ) -> std::result::Result<Vec<RanMigration>, MigrationError> {
         self.init().await?;
         let applied = self.applied().await?;
         let pending = self.pending(&applied);
 
+        let drift = self.drift(&applied);
+        if !drift.is_empty() {
+            if self.strict {
+                return Err(crate::Error::Migration(format!(
+                    "refusing to run in strict mode: {drift}"
+                ))
+                .into());
+            }
+            tracing::warn!(%drift, "Migration history has drifted from the code");
+        }
+        let mut applied_versions: std::collections::HashSet<&str> =
+            applied.iter().map(|m| m.version.as_str()).collect();
+
+        // Record objects that predate tracking (or were changed by hand)
+        // before attributing anything to a migration
+        let tx = self.client.transaction().await?;
+        crate::meta::track_provenance(&tx, None).await?;
+        tx.commit().await?;
+
+        let tx = self.client.transaction().await?;
+        run_hooks(HookEvent::BeforeAll, &tx, None).await?;
+        tx.commit().await?;
+
         let mut ran = Vec::new();
         for migration in pending {
+            if through.is_some_and(|through| migration.version > through) {
+                break;
+            }
+
+            // A squashed baseline is only run on databases that never saw
+            // the migrations it replaces (including ones run just now)
+            let covered = migration
+                .squashes
+                .iter()
+                .filter(|v| applied_versions.contains(*v))
+                .count();
+            if covered == migration.squashes.len() && covered > 0 {
+                self.mark_applied(migration.version).await?;
+                continue;
+            }
+            if covered > 0 {
+                return Err(crate::Error::Migration(format!(
+                    "{} squashes migrations this database has only partly applied; \
+                     migrate it with a build that still has them first",
+                    migration.version
+                ))
+                .into());
+            }
+
             let start = std::time::Instant::now();
 
             // Each migration runs in its own transaction
             let tx = self.client.transaction().await?;
 
-            let mut ctx = MigrationContext::new(&tx);
+            run_hooks(HookEvent::BeforeEach, &tx, Some(migration.version)).await?;
+
+            // The timeout covers the migration itself, not the hooks around it
+            let previous_timeout: Option<String> = match migration.timeout {
+                Some(timeout) => {
+                    let previous = tx.query_one("SHOW statement_timeout", &[]).await?.get(0);
+                    tx.execute(
+                        "SELECT set_config('statement_timeout', $1, true)",
+                        &[&timeout],
+                    )
+                    .await?;
+                    Some(previous)
+                }
+                None => None,
+            };
+
+            let mut ctx = MigrationContext {
+                tx: &tx,
+                migration: Some(migration.version),
+            };
             (migration.run)(&mut ctx).await?;
 
-            // Record the migration as applied (inside the same transaction)
+            if let Some(previous) = previous_timeout {
+                tx.execute(
+                    "SELECT set_config('statement_timeout', $1, true)",
+                    &[&previous],
+                )
+                .await?;
+            }
+            run_hooks(HookEvent::AfterEach, &tx, Some(migration.version)).await?;
+
+            // Record the migration and what it changed (inside the same transaction)
+            crate::meta::track_provenance(&tx, Some(migration.version)).await?;
             tx.execute(
-                "INSERT INTO _dibs_migrations (version) VALUES ($1)",
-                &[&migration.version],
+                "INSERT INTO __dibs_migrations (name, execution_time_ms) VALUES ($1, $2)",
+                &[&migration.version, &elapsed_ms(start)],
             )
             .await?;
 
             // Commit the transaction
             tx.commit().await?;
 
-            ran.push(RanMigration {
-                version: migration.version,
-                duration: start.elapsed(),
-            });
+            applied_versions.insert(migration.version);
+            self.committed(
+                &mut ran,
+                RanMigration {
+                    version: migration.version,
+                    duration: start.elapsed(),
+                    repeatable: false,
+                },
+            );
         }
 
+        if through.is_none() {
+            ran.extend(self.run_repeatable().await?);
+        }
+
+        // Point tracked objects at their current definitions in the code
+        if let Some(schema) = self.schema {
+            let sources = crate::meta::sync_sources_sql(schema);
+            if !sources.is_empty() {
+                self.client.batch_execute(&sources).await?;
+            }
+        }
+
+        let tx = self.client.transaction().await?;
+        run_hooks(HookEvent::AfterAll, &tx, None).await?;
+        tx.commit().await?;
+
         Ok(ran)
     }
 
-    /// Get status of all migrations.
-    pub async fn status(&self) -> Result<Vec<MigrationStatus>> {
+    /// Run the repeatable migrations whose SQL changed since they last ran.
+    async fn run_repeatable(&mut self) -> std::result::Result<Vec<RanMigration>, MigrationError> {
+        let applied = self.applied_repeatable().await?;
+
+        let mut ran = Vec::new();
+        for migration in self.changed_repeatable(&applied) {
+            let start = std::time::Instant::now();
+
+            let tx = self.client.transaction().await?;
+
+            run_hooks(HookEvent::BeforeEach, &tx, Some(migration.name)).await?;
+            let mut ctx = MigrationContext {
+                tx: &tx,
+                migration: Some(migration.name),
+            };
+            crate::__run_sql_migration(&mut ctx, migration.sql).await?;
+            run_hooks(HookEvent::AfterEach, &tx, Some(migration.name)).await?;
+
+            crate::meta::track_provenance(&tx, Some(migration.name)).await?;
+            // Names are shared with versioned migrations; never overwrite one
+            let recorded = tx
+                .execute(
+                    "INSERT INTO __dibs_migrations (name, checksum, execution_time_ms, repeatable)
+                     VALUES ($1, $2, $3, true)
+                     ON CONFLICT (name) DO UPDATE SET
+                         checksum = EXCLUDED.checksum,
+                         execution_time_ms = EXCLUDED.execution_time_ms,
+                         applied_at = NOW()
+                     WHERE __dibs_migrations.repeatable",
+                    &[&migration.name, &migration.checksum(), &elapsed_ms(start)],
+                )
+                .await?;
+            if recorded == 0 {
+                return Err(crate::Error::Migration(format!(
+                    "repeatable migration '{}' has the same name as an applied versioned migration",
+                    migration.name
+                ))
+                .into());
+            }
+
+            tx.commit().await?;
+
+            self.committed(
+                &mut ran,
+                RanMigration {
+                    version: migration.name,
+                    duration: start.elapsed(),
+                    repeatable: true,
+                },
+            );
+        }
+
+        Ok(ran)
+    }
+
+    /// Report a migration whose transaction was just committed.
+    fn committed(&mut self, ran: &mut Vec<RanMigration>, migration: RanMigration) {
+        if let Some(on_ran) = self.on_ran.as_mut() {
+            on_ran(&migration);
+        }
+        ran.push(migration);
+    }
+
+    /// Record a migration as applied without running it.
+    ///
+    /// Used for baseline migrations describing a schema that already exists
+    /// in the database. Recording an already-applied version is a no-op.
+    pub async fn mark_applied(&self, version: &str) -> Result<()> {
         self.init().await?;
-        let applied = self.applied().await?;
+        self.client
+            .execute(
+                "INSERT INTO __dibs_migrations (name) VALUES ($1) ON CONFLICT (name) DO NOTHING",
+                &[&version],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Find pending migrations older than the newest applied one, and
+    /// applied migrations that are no longer defined in code.
+    ///
+    /// Versions replaced by a squashed baseline are not missing, and a
+    /// baseline whose squashed migrations were all applied is not out of
+    /// order: it's recorded, not run.
+    pub fn drift(&self, applied: &[AppliedMigration]) -> MigrationDrift {
         let applied_versions: std::collections::HashSet<&str> =
             applied.iter().map(|m| m.version.as_str()).collect();
+        let known: std::collections::HashSet<&str> = inventory::iter::<Migration>
+            .into_iter()
+            .flat_map(|m| std::iter::once(m.version).chain(m.squashes.iter().copied()))
+            .collect();
+
+        let newest_applied = applied.iter().map(|m| m.version.as_str()).max();
+        let out_of_order = self
+            .pending(applied)
+            .into_iter()
+            .filter(|m| newest_applied.is_some_and(|newest| m.version < newest))
+            .filter(|m| {
+                m.squashes.is_empty() || !m.squashes.iter().all(|v| applied_versions.contains(v))
+            })
+            .map(|m| m.version)
+            .collect();
+        let missing = applied
+            .iter()
+            .filter(|m| !known.contains(m.version.as_str()))
+            .map(|m| m.version.clone())
+            .collect();
+
+        MigrationDrift {
+            out_of_order,
+            missing,
+        }
+    }
+
+    /// Get status of all migrations, including applied ones that are no
+    /// longer defined in code.
+    ///
+    /// Repeatable migrations come last, ordered by name; they count as
+    /// applied while their current SQL is the one that last ran.
+    pub async fn status(&self) -> Result<Vec<MigrationStatus>> {
+        self.init().await?;
+        let applied = self.applied().await?;
+        let applied_at: std::collections::HashMap<&str, _> = applied
+            .iter()
+            .map(|m| (m.version.as_str(), m.applied_at))
+            .collect();
+        let drift = self.drift(&applied);
 
         let mut all: Vec<_> = inventory::iter::<Migration>
             .into_iter()
             .map(|m| MigrationStatus {
-                version: m.version,
+                version: m.version.to_string(),
+                name: m.name,
+                applied: applied_at.contains_key(m.version),
+                applied_at: applied_at.get(m.version).copied(),
+                out_of_order: drift.out_of_order.contains(&m.version),
+                missing: false,
+                repeatable: false,
+                source_path: Some(m.source_path()),
+            })
+            .chain(drift.missing.iter().map(|version| MigrationStatus {
+                version: version.clone(),
+                name: "",
+                applied: true,
+                applied_at: applied_at.get(version.as_str()).copied(),
+                out_of_order: false,
+                missing: true,
+                repeatable: false,
+                source_path: None,
+            }))
+            .collect();
+        all.sort_by(|a, b| a.version.cmp(&b.version));
+
+        let applied_repeatable = self.applied_repeatable().await?;
+        let changed = self.changed_repeatable(&applied_repeatable);
+        let mut repeatable: Vec<_> = inventory::iter::<RepeatableMigration>
+            .into_iter()
+            .map(|m| MigrationStatus {
+                version: m.name.to_string(),
                 name: m.name,
-                applied: applied_versions.contains(m.version),
-                source_path: m.source_path(),
+                applied: !changed.iter().any(|c| c.name == m.name),
+                applied_at: applied_repeatable
+                    .iter()
+                    .find(|a| a.name == m.name)
+                    .map(|a| a.applied_at),
+                out_of_order: false,
+                missing: false,
+                repeatable: true,
+                source_path: Some(m.source_path()),
             })
             .collect();
-        all.sort_by_key(|m| m.version);
+        repeatable.sort_by(|a, b| a.version.cmp(&b.version));
+        all.extend(repeatable);
         Ok(all)
     }
 }
 
+/// Migrations whose order in the database disagrees with the code.
+#[derive(Debug, Default)]
+pub struct MigrationDrift {
+    /// Pending migrations older than the newest applied one, e.g. merged
+    /// from a branch that was started before it
+    pub out_of_order: Vec<&'static str>,
+    /// Applied migrations that are no longer defined in code
+    pub missing: Vec<String>,
+}
+
+impl MigrationDrift {
+    /// True when nothing has drifted.
+    pub fn is_empty(&self) -> bool {
+        self.out_of_order.is_empty() && self.missing.is_empty()
+    }
+}
+
+impl std::fmt::Display for MigrationDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if !self.out_of_order.is_empty() {
+            parts.push(format!(
+                "pending migrations older than the newest applied one: {}",
+                self.out_of_order.join(", ")
+            ));
+        }
+        if !self.missing.is_empty() {
+            parts.push(format!(
+                "applied migrations missing from the code: {}",
+                self.missing.join(", ")
+            ));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
 /// Status of a single migration.
 pub struct MigrationStatus {
-    pub version: &'static str,
+    pub version: String,
+    /// Function name (empty for missing migrations)
     pub name: &'static str,
     pub applied: bool,
-    pub source_path: std::path::PathBuf,
+    /// When it was applied (last run, for repeatable migrations)
+    pub applied_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Pending, but older than the newest applied migration
+    pub out_of_order: bool,
+    /// Applied, but no longer defined in code
+    pub missing: bool,
+    /// A repeatable migration; `applied` is false when its SQL changed
+    pub repeatable: bool,
+    /// Source file (`None` for missing migrations)
+    pub source_path: Option<std::path::PathBuf>,
 }
 
 /// A migration that was already applied.
@@ -279,8 +905,68 @@ pub struct AppliedMigration {
     pub applied_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// A repeatable migration that has run.
+pub struct AppliedRepeatable {
+    pub name: String,
+    /// Checksum of the SQL it last ran
+    pub checksum: String,
+    /// When it last ran
+    pub applied_at: chrono::DateTime<chrono::Utc>,
+}
+
 /// A migration that was just run.
+#[derive(Debug)]
 pub struct RanMigration {
+    /// Version, or name for repeatable migrations
     pub version: &'static str,
     pub duration: std::time::Duration,
+    pub repeatable: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monthly_partition() {
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 12, 17).unwrap();
+        let (name, from, to) = monthly_partition("event", day);
+        assert_eq!(name, "event_2024_12");
+        assert_eq!(from.to_string(), "2024-12-01");
+        assert_eq!(to.to_string(), "2025-01-01");
+    }
+
+    #[test]
+    fn test_repeatable_checksum_tracks_sql() {
+        let view = |sql| RepeatableMigration {
+            name: "v",
+            sql,
+            source_file: ("", ""),
+        };
+        let checksum = view("CREATE OR REPLACE VIEW v AS SELECT 1").checksum();
+        assert_eq!(checksum.len(), 16);
+        assert_eq!(
+            checksum,
+            view("CREATE OR REPLACE VIEW v AS SELECT 1").checksum()
+        );
+        assert_ne!(
+            checksum,
+            view("CREATE OR REPLACE VIEW v AS SELECT 2").checksum()
+        );
+    }
+
+    #[test]
+    fn test_migration_drift_display() {
+        let drift = MigrationDrift {
+            out_of_order: vec!["2026_01_02-b"],
+            missing: vec!["2026_01_01-a".to_string()],
+        };
+        assert!(!drift.is_empty());
+        assert_eq!(
+            drift.to_string(),
+            "pending migrations older than the newest applied one: 2026_01_02-b; \
+             applied migrations missing from the code: 2026_01_01-a"
+        );
+        assert!(MigrationDrift::default().is_empty());
+    }
 }
@@ -183,6 +183,8 @@ pub struct DeleteQuery {
     pub filters: Vec<Expr>,
     /// Columns to return (RETURNING clause)
     pub returning: Vec<String>,
+    /// Soft-delete column: when set, rows are marked deleted instead of removed
+    pub soft_delete: Option<String>,
 }
 
 impl DeleteQuery {
@@ -192,9 +194,16 @@ impl DeleteQuery {
             table: table.into(),
             filters: Vec::new(),
             returning: Vec::new(),
+            soft_delete: None,
         }
     }
 
+    /// Mark rows deleted by setting `column` to `now()` instead of removing them.
+    pub fn soft_delete(mut self, column: impl Into<String>) -> Self {
+        self.soft_delete = Some(column.into());
+        self
+    }
+
     /// Add a filter condition.
     pub fn filter(mut self, expr: Expr) -> Self {
         self.filters.push(expr);
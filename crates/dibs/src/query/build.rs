@@ -311,10 +311,23 @@ impl DeleteQuery {
     pub fn build(&self) -> BuiltQuery {
         let mut b = SqlBuilder::new();
 
-        b.push("DELETE FROM ");
-        b.push_ident(&self.table);
+        if let Some(column) = &self.soft_delete {
+            b.push("UPDATE ");
+            b.push_ident(&self.table);
+            b.push(" SET ");
+            b.push_ident(column);
+            b.push(" = now()");
+
+            // Rows that are already deleted keep their original timestamp
+            let mut filters = self.filters.clone();
+            filters.push(Expr::IsNull(column.clone()));
+            b.build_where(&filters);
+        } else {
+            b.push("DELETE FROM ");
+            b.push_ident(&self.table);
 
-        b.build_where(&self.filters);
+            b.build_where(&self.filters);
+        }
         b.build_returning(&self.returning);
 
         b.finish()
@@ -409,6 +422,18 @@ mod tests {
         assert_eq!(q.sql, r#"DELETE FROM "users" WHERE "id" = $1"#);
     }
 
+    #[test]
+    fn test_soft_delete() {
+        let q = DeleteQuery::new("users")
+            .filter(Expr::eq("id", 42i64))
+            .soft_delete("deleted_at")
+            .build();
+        assert_eq!(
+            q.sql,
+            r#"UPDATE "users" SET "deleted_at" = now() WHERE "id" = $1 AND "deleted_at" IS NULL"#
+        );
+    }
+
     #[test]
     fn test_or_expression() {
         let q = SelectQuery::new("users")
@@ -384,6 +384,12 @@ impl<'a> DeleteBuilder<'a> {
         self
     }
 
+    /// Mark rows deleted by setting `column` to `now()` instead of removing them.
+    pub fn soft_delete(mut self, column: impl Into<String>) -> Self {
+        self.query = self.query.soft_delete(column);
+        self
+    }
+
     /// Execute the delete, returning the number of rows affected.
     pub async fn execute(self) -> Result<u64, Error> {
         let built = self.query.build();
@@ -27,10 +27,13 @@
 //! ```
 
 pub mod codegen;
+pub mod import;
 
 pub use dibs_db_schema::{
-    CheckConstraint, Column, ForeignKey, Index, IndexColumn, NullsOrder, PgType, Schema, SortOrder,
-    SourceLocation, Table, TableDef, TriggerCheckConstraint,
+    CheckConstraint, Column, CompositeField, CompositeType, DomainType, ExclusionConstraint,
+    ExtensionDef, ForeignKey, Index, IndexColumn, NullsOrder, PartitionKind, PartitionSpec, PgType,
+    RlsPolicy, Schema, SortOrder, SourceLocation, Table, TableDef, TableTrigger,
+    TriggerCheckConstraint,
 };
 
 /// Extension trait for Schema to add SQL generation.
@@ -48,7 +51,9 @@ impl SchemaCodegen for Schema {
 /// Generate CREATE TABLE SQL statement.
 ///
 /// Does not include foreign key constraints (those should be added
-/// separately to handle table creation order).
+/// separately to handle table creation order). Partitioned tables get a
+/// `PARTITION BY` clause; their partitions are created by migrations (see
+/// [`crate::MigrationContext::create_range_partition`]).
 pub fn create_table_sql(table: &Table) -> String {
     let mut sql = format!("CREATE TABLE {} (\n", crate::quote_ident(&table.name));
 
@@ -67,7 +72,11 @@ pub fn create_table_sql(table: &Table) -> String {
         .columns
         .iter()
         .map(|col| {
-            let mut def = format!("    {} {}", crate::quote_ident(&col.name), col.pg_type);
+            let mut def = format!(
+                "    {} {}",
+                crate::quote_ident(&col.name),
+                column_type_sql(col)
+            );
 
             // Auto-generated integer columns with no explicit default become
             // identity columns, so inserts that omit them still get a value.
@@ -113,12 +122,84 @@ pub fn create_table_sql(table: &Table) -> String {
         ));
     }
 
+    // Add EXCLUDE constraints
+    for exclusion in &table.exclusion_constraints {
+        parts.push(format!(
+            "    CONSTRAINT {} {}",
+            crate::quote_ident(&exclusion.name),
+            exclusion_constraint_sql(exclusion)
+        ));
+    }
+
     sql.push_str(&parts.join(",\n"));
-    sql.push_str("\n);");
+    sql.push_str("\n)");
+
+    if let Some(partition) = &table.partition_by {
+        let quoted_cols: Vec<_> = partition
+            .columns
+            .iter()
+            .map(|c| crate::quote_ident(c))
+            .collect();
+        sql.push_str(&format!(
+            " PARTITION BY {} ({})",
+            partition.kind.to_sql(),
+            quoted_cols.join(", ")
+        ));
+    }
+    sql.push(';');
+
+    for comment in table
+        .exclusion_constraints
+        .iter()
+        .filter_map(|exclusion| exclusion_hash_comment_sql(&table.name, exclusion))
+    {
+        sql.push('\n');
+        sql.push_str(&comment);
+    }
+
+    sql
+}
 
+/// The body of an EXCLUDE constraint, after `CONSTRAINT name`.
+pub fn exclusion_constraint_sql(exclusion: &ExclusionConstraint) -> String {
+    let mut sql = format!("EXCLUDE USING {} ({})", exclusion.using, exclusion.elements);
+    if let Some(filter) = &exclusion.filter {
+        sql.push_str(&format!(" WHERE ({})", filter));
+    }
+    sql.push_str(deferrable_sql(exclusion.deferrable));
     sql
 }
 
+/// Generate the `COMMENT ON CONSTRAINT` statement recording an explicitly
+/// named EXCLUDE constraint's definition hash, if it has one.
+pub fn exclusion_hash_comment_sql(
+    table_name: &str,
+    exclusion: &ExclusionConstraint,
+) -> Option<String> {
+    exclusion.def_hash.as_ref().map(|hash| {
+        format!(
+            "COMMENT ON CONSTRAINT {} ON {} IS '{}{}';",
+            crate::quote_ident(&exclusion.name),
+            crate::quote_ident(table_name),
+            EXCLUSION_HASH_COMMENT_PREFIX,
+            hash
+        )
+    })
+}
+
+/// Prefix of the comment dibs puts on explicitly named EXCLUDE constraints,
+/// followed by the hash of their declared definition.
+pub(crate) const EXCLUSION_HASH_COMMENT_PREFIX: &str = "dibs:def=";
+
+/// The clause making a constraint deferred until commit (empty if not deferrable).
+pub fn deferrable_sql(deferrable: bool) -> &'static str {
+    if deferrable {
+        " DEFERRABLE INITIALLY DEFERRED"
+    } else {
+        ""
+    }
+}
+
 /// Generate CREATE INDEX SQL statement for a given index.
 pub fn create_index_sql(table: &Table, idx: &Index) -> String {
     let unique = if idx.unique { "UNIQUE " } else { "" };
@@ -172,6 +253,275 @@ pub fn create_trigger_check_sql(table: &Table, trig: &TriggerCheckConstraint) ->
     )
 }
 
+/// Generate CREATE FUNCTION + CREATE TRIGGER SQL for a user-defined trigger.
+pub fn create_trigger_sql(table_name: &str, trig: &TableTrigger) -> String {
+    let fn_name = crate::trigger_function_name(table_name, &trig.name, &trig.function_body);
+    format!(
+        "CREATE OR REPLACE FUNCTION {fn_name}() RETURNS trigger LANGUAGE plpgsql AS $dibs$\n\
+         {body}\n\
+         $dibs$;\n\
+         CREATE TRIGGER {name} {timing} {events} ON {table} FOR EACH {for_each} EXECUTE FUNCTION {fn_name}();",
+        fn_name = crate::quote_ident(&fn_name),
+        body = trig.function_body,
+        name = crate::quote_ident(&trig.name),
+        timing = trig.timing,
+        events = trig.events.join(" OR "),
+        table = crate::quote_ident(table_name),
+        for_each = trig.for_each,
+    )
+}
+
+/// Generate DROP TRIGGER + DROP FUNCTION SQL for a user-defined trigger.
+pub fn drop_trigger_sql(table_name: &str, trigger_name: &str, function_name: &str) -> String {
+    format!(
+        "DROP TRIGGER {} ON {};\nDROP FUNCTION IF EXISTS {}();",
+        crate::quote_ident(trigger_name),
+        crate::quote_ident(table_name),
+        crate::quote_ident(function_name)
+    )
+}
+
+/// Generate the `FOR VALUES FROM (..) TO (..)` bound of a range partition.
+///
+/// Bounds are given as text and quoted as SQL literals; Postgres casts them to
+/// the partition key's type.
+pub fn range_partition_bounds(from: &str, to: &str) -> String {
+    format!(
+        "FOR VALUES FROM ('{}') TO ('{}')",
+        from.replace('\'', "''"),
+        to.replace('\'', "''")
+    )
+}
+
+/// Generate CREATE TABLE .. PARTITION OF SQL.
+///
+/// `bounds` is a partition bound spec such as `FOR VALUES IN ('eu')`,
+/// `FOR VALUES WITH (MODULUS 4, REMAINDER 0)` or `DEFAULT`.
+pub fn create_partition_sql(parent: &str, partition: &str, bounds: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} PARTITION OF {} {};",
+        crate::quote_ident(partition),
+        crate::quote_ident(parent),
+        bounds
+    )
+}
+
+/// Generate ALTER TABLE .. ATTACH PARTITION SQL.
+pub fn attach_partition_sql(parent: &str, partition: &str, bounds: &str) -> String {
+    format!(
+        "ALTER TABLE {} ATTACH PARTITION {} {};",
+        crate::quote_ident(parent),
+        crate::quote_ident(partition),
+        bounds
+    )
+}
+
+/// Generate ALTER TABLE .. DETACH PARTITION SQL.
+pub fn detach_partition_sql(parent: &str, partition: &str) -> String {
+    format!(
+        "ALTER TABLE {} DETACH PARTITION {};",
+        crate::quote_ident(parent),
+        crate::quote_ident(partition)
+    )
+}
+
+/// The SQL type of a column: its domain or composite type if it has one,
+/// else its Postgres type.
+pub fn column_type_sql(col: &Column) -> String {
+    match col.named_type() {
+        Some(domain) => crate::quote_ident(domain),
+        None => col.pg_type.to_string(),
+    }
+}
+
+/// Generate CREATE DOMAIN SQL.
+pub fn create_domain_sql(domain: &DomainType) -> String {
+    let mut sql = format!(
+        "CREATE DOMAIN {} AS {}",
+        crate::quote_ident(&domain.name),
+        domain.base_type
+    );
+    if let Some(check) = &domain.check {
+        sql.push_str(&format!(
+            " CONSTRAINT {} CHECK ({})",
+            crate::quote_ident(&check.name),
+            check.expr
+        ));
+    }
+    sql.push(';');
+    sql
+}
+
+/// Generate DROP DOMAIN SQL.
+pub fn drop_domain_sql(name: &str) -> String {
+    format!("DROP DOMAIN {};", crate::quote_ident(name))
+}
+
+/// Generate SQL replacing a domain's CHECK constraint.
+pub fn alter_domain_check_sql(
+    name: &str,
+    from: Option<&CheckConstraint>,
+    to: Option<&CheckConstraint>,
+) -> String {
+    let mut stmts = Vec::new();
+    if let Some(from) = from {
+        stmts.push(format!(
+            "ALTER DOMAIN {} DROP CONSTRAINT {};",
+            crate::quote_ident(name),
+            crate::quote_ident(&from.name)
+        ));
+    }
+    if let Some(to) = to {
+        stmts.push(format!(
+            "ALTER DOMAIN {} ADD CONSTRAINT {} CHECK ({});",
+            crate::quote_ident(name),
+            crate::quote_ident(&to.name),
+            to.expr
+        ));
+    }
+    stmts.join("\n")
+}
+
+/// Generate CREATE TYPE SQL for a composite type.
+pub fn create_composite_sql(composite: &CompositeType) -> String {
+    let fields: Vec<String> = composite
+        .fields
+        .iter()
+        .map(|field| format!("{} {}", crate::quote_ident(&field.name), field.pg_type))
+        .collect();
+    format!(
+        "CREATE TYPE {} AS ({});",
+        crate::quote_ident(&composite.name),
+        fields.join(", ")
+    )
+}
+
+/// Generate DROP TYPE SQL for a composite type.
+pub fn drop_composite_sql(name: &str) -> String {
+    format!("DROP TYPE {};", crate::quote_ident(name))
+}
+
+/// Generate ALTER TYPE SQL turning a composite type's attributes from `from`
+/// into `to`. Attributes are matched by name.
+pub fn alter_composite_sql(name: &str, from: &[CompositeField], to: &[CompositeField]) -> String {
+    let mut actions = Vec::new();
+    for field in from {
+        if !to.iter().any(|f| f.name == field.name) {
+            actions.push(format!(
+                "DROP ATTRIBUTE {}",
+                crate::quote_ident(&field.name)
+            ));
+        }
+    }
+    for field in to {
+        match from.iter().find(|f| f.name == field.name) {
+            None => actions.push(format!(
+                "ADD ATTRIBUTE {} {}",
+                crate::quote_ident(&field.name),
+                field.pg_type
+            )),
+            Some(existing) if existing.pg_type != field.pg_type => actions.push(format!(
+                "ALTER ATTRIBUTE {} TYPE {}",
+                crate::quote_ident(&field.name),
+                field.pg_type
+            )),
+            Some(_) => {}
+        }
+    }
+    format!(
+        "ALTER TYPE {} {};",
+        crate::quote_ident(name),
+        actions.join(", ")
+    )
+}
+
+/// Generate CREATE EXTENSION SQL.
+pub fn create_extension_sql(name: &str) -> String {
+    format!(
+        "CREATE EXTENSION IF NOT EXISTS {};",
+        crate::quote_ident(name)
+    )
+}
+
+/// Generate ALTER TABLE SQL to enable or disable row-level security.
+pub fn set_row_level_security_sql(table_name: &str, enabled: bool) -> String {
+    format!(
+        "ALTER TABLE {} {} ROW LEVEL SECURITY;",
+        crate::quote_ident(table_name),
+        if enabled { "ENABLE" } else { "DISABLE" }
+    )
+}
+
+/// Generate CREATE POLICY SQL for a row-level security policy.
+pub fn create_policy_sql(table_name: &str, policy: &RlsPolicy) -> String {
+    let mut sql = format!(
+        "CREATE POLICY {} ON {} FOR {}",
+        crate::quote_ident(&policy.name),
+        crate::quote_ident(table_name),
+        policy.command
+    );
+    if !policy.roles.is_empty() {
+        let roles: Vec<_> = policy.roles.iter().map(|r| crate::quote_ident(r)).collect();
+        sql.push_str(&format!(" TO {}", roles.join(", ")));
+    }
+    if let Some(using) = &policy.using {
+        sql.push_str(&format!(" USING ({})", using));
+    }
+    if let Some(with_check) = &policy.with_check {
+        sql.push_str(&format!(" WITH CHECK ({})", with_check));
+    }
+    sql.push(';');
+    if let Some(hash) = &policy.expr_hash {
+        sql.push_str(&format!(
+            "\nCOMMENT ON POLICY {} ON {} IS '{}{}';",
+            crate::quote_ident(&policy.name),
+            crate::quote_ident(table_name),
+            POLICY_HASH_COMMENT_PREFIX,
+            hash
+        ));
+    }
+    sql
+}
+
+/// Prefix of the comment dibs puts on the policies it creates, followed by
+/// the hash of their declared expressions.
+pub(crate) const POLICY_HASH_COMMENT_PREFIX: &str = "dibs:expr=";
+
+/// Generate DROP POLICY SQL.
+pub fn drop_policy_sql(table_name: &str, policy_name: &str) -> String {
+    format!(
+        "DROP POLICY {} ON {};",
+        crate::quote_ident(policy_name),
+        crate::quote_ident(table_name)
+    )
+}
+
+/// Generate COMMENT ON TABLE SQL. A `None` comment removes the existing one.
+pub fn comment_on_table_sql(table_name: &str, comment: Option<&str>) -> String {
+    format!(
+        "COMMENT ON TABLE {} IS {};",
+        crate::quote_ident(table_name),
+        comment_literal(comment)
+    )
+}
+
+/// Generate COMMENT ON COLUMN SQL. A `None` comment removes the existing one.
+pub fn comment_on_column_sql(table_name: &str, column: &str, comment: Option<&str>) -> String {
+    format!(
+        "COMMENT ON COLUMN {}.{} IS {};",
+        crate::quote_ident(table_name),
+        crate::quote_ident(column),
+        comment_literal(comment)
+    )
+}
+
+fn comment_literal(comment: Option<&str>) -> String {
+    match comment {
+        Some(text) => format!("'{}'", text.replace('\'', "''")),
+        None => "NULL".to_string(),
+    }
+}
+
 /// Returns the SQL fragment for an index column (name + order + nulls).
 pub fn index_column_to_sql(col: &IndexColumn) -> String {
     format!(
@@ -196,14 +546,87 @@ pub fn index_column_to_sql(col: &IndexColumn) -> String {
 /// `TypeId::of`/`type_name` reference is a const intrinsic and does NOT link
 /// the crate's statics). Silently returning an empty schema instead would make
 /// codegen fall back to wrong column types and corrupt data at runtime.
+///
+/// Invalid declarations are reported on stderr instead: tables that can't be
+/// converted (see [`TableDef::try_to_table`]) are skipped, an audited table
+/// whose `<table>_history` name is taken by a declared table isn't audited,
+/// and of two differing domain or composite types with the same name, the
+/// first one wins.
 pub fn collect_schema() -> Schema {
-    let tables = inventory::iter::<TableDef>
+    let mut tables: indexmap::IndexMap<String, Table> = inventory::iter::<TableDef>
         .into_iter()
         .filter_map(|def| def.to_table())
         .map(|t| (t.name.clone(), t))
         .collect();
 
-    let schema = Schema { tables };
+    // Audited tables get a generated history table next to them
+    let history_tables: Vec<(String, Table)> = tables
+        .values()
+        .filter_map(|t| Some((t.name.clone(), t.history_table()?)))
+        .collect();
+    for (audited, history) in history_tables {
+        if tables.contains_key(&history.name) {
+            eprintln!(
+                "dibs: table '{}' clashes with the history table generated for audited table '{}'; not auditing it",
+                history.name, audited
+            );
+            if let Some(table) = tables.get_mut(&audited) {
+                table.audited = false;
+                table.triggers.retain(|t| t.name != TableTrigger::AUDIT);
+            }
+            continue;
+        }
+        tables.insert(history.name.clone(), history);
+    }
+
+    let mut extensions: Vec<String> = inventory::iter::<ExtensionDef>
+        .into_iter()
+        .map(|def| def.name.to_string())
+        .collect();
+    extensions.sort();
+    extensions.dedup();
+
+    // Domains are defined by the field types that use them; the same newtype
+    // may appear in several tables
+    let mut domains: Vec<DomainType> = Vec::new();
+    for domain in inventory::iter::<TableDef>
+        .into_iter()
+        .flat_map(|def| def.domains())
+    {
+        match domains.iter().find(|d| d.name == domain.name) {
+            Some(existing) if *existing != domain => eprintln!(
+                "dibs: conflicting definitions for domain '{}'; keeping the first",
+                domain.name
+            ),
+            Some(_) => {}
+            None => domains.push(domain),
+        }
+    }
+    domains.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Same for composite types
+    let mut composites: Vec<CompositeType> = Vec::new();
+    for composite in inventory::iter::<TableDef>
+        .into_iter()
+        .flat_map(|def| def.composites())
+    {
+        match composites.iter().find(|c| c.name == composite.name) {
+            Some(existing) if *existing != composite => eprintln!(
+                "dibs: conflicting definitions for composite type '{}'; keeping the first",
+                composite.name
+            ),
+            Some(_) => {}
+            None => composites.push(composite),
+        }
+    }
+    composites.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let schema = Schema {
+        tables,
+        extensions,
+        domains,
+        composites,
+    };
 
     assert!(
         !schema.tables.is_empty(),
@@ -248,4 +671,208 @@ mod tests {
         };
         assert_eq!(index_column_to_sql(&col), "\"priority\" DESC NULLS LAST");
     }
+
+    #[test]
+    fn test_partition_sql() {
+        let table = Table {
+            name: "event".to_string(),
+            columns: vec![Column {
+                name: "created_at".to_string(),
+                pg_type: PgType::Timestamptz,
+                rust_type: None,
+                nullable: false,
+                default: None,
+                primary_key: false,
+                unique: false,
+                auto_generated: false,
+                long: false,
+                label: false,
+                enum_variants: vec![],
+                doc: None,
+                icon: None,
+                lang: None,
+                subtype: None,
+                domain: None,
+                composite: None,
+            }],
+            check_constraints: vec![],
+            exclusion_constraints: Vec::new(),
+            trigger_checks: vec![],
+            triggers: vec![],
+            partition_by: Some(PartitionSpec {
+                kind: PartitionKind::Range,
+                columns: vec!["created_at".to_string()],
+            }),
+            rls: false,
+            policies: vec![],
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
+            foreign_keys: vec![],
+            indices: vec![],
+            source: SourceLocation::default(),
+            doc: None,
+            icon: None,
+        };
+        assert_eq!(
+            create_table_sql(&table),
+            "CREATE TABLE \"event\" (\n    \"created_at\" TIMESTAMPTZ NOT NULL\n) PARTITION BY RANGE (\"created_at\");"
+        );
+
+        let bounds = range_partition_bounds("2024-01-01", "2024-02-01");
+        assert_eq!(
+            create_partition_sql("event", "event_2024_01", &bounds),
+            r#"CREATE TABLE IF NOT EXISTS "event_2024_01" PARTITION OF "event" FOR VALUES FROM ('2024-01-01') TO ('2024-02-01');"#
+        );
+        assert_eq!(
+            detach_partition_sql("event", "event_2024_01"),
+            r#"ALTER TABLE "event" DETACH PARTITION "event_2024_01";"#
+        );
+    }
+
+    #[test]
+    fn test_domain_sql() {
+        let check = CheckConstraint {
+            name: "ck_money_cents_0123".to_string(),
+            expr: "VALUE >= 0".to_string(),
+        };
+        let domain = DomainType {
+            name: "money_cents".to_string(),
+            base_type: PgType::BigInt,
+            check: Some(check.clone()),
+        };
+        assert_eq!(
+            create_domain_sql(&domain),
+            r#"CREATE DOMAIN "money_cents" AS BIGINT CONSTRAINT "ck_money_cents_0123" CHECK (VALUE >= 0);"#
+        );
+        assert_eq!(
+            alter_domain_check_sql("money_cents", Some(&check), None),
+            r#"ALTER DOMAIN "money_cents" DROP CONSTRAINT "ck_money_cents_0123";"#
+        );
+        assert_eq!(
+            drop_domain_sql("money_cents"),
+            r#"DROP DOMAIN "money_cents";"#
+        );
+    }
+
+    #[test]
+    fn test_composite_sql() {
+        let field = |name: &str, pg_type| CompositeField {
+            name: name.to_string(),
+            pg_type,
+        };
+        let address = CompositeType {
+            name: "postal_address".to_string(),
+            fields: vec![field("street", PgType::Text), field("zip", PgType::Integer)],
+        };
+        assert_eq!(
+            create_composite_sql(&address),
+            r#"CREATE TYPE "postal_address" AS ("street" TEXT, "zip" INTEGER);"#
+        );
+        assert_eq!(
+            alter_composite_sql(
+                "postal_address",
+                &address.fields,
+                &[
+                    field("street", PgType::Text),
+                    field("zip", PgType::Text),
+                    field("city", PgType::Text)
+                ]
+            ),
+            r#"ALTER TYPE "postal_address" ALTER ATTRIBUTE "zip" TYPE TEXT, ADD ATTRIBUTE "city" TEXT;"#
+        );
+        assert_eq!(
+            alter_composite_sql("postal_address", &address.fields, &address.fields[..1]),
+            r#"ALTER TYPE "postal_address" DROP ATTRIBUTE "zip";"#
+        );
+        assert_eq!(
+            drop_composite_sql("postal_address"),
+            r#"DROP TYPE "postal_address";"#
+        );
+    }
+
+    #[test]
+    fn test_trigger_sql() {
+        let trig = TableTrigger {
+            name: "trg_touch".to_string(),
+            timing: "BEFORE".to_string(),
+            events: vec!["INSERT".to_string(), "UPDATE".to_string()],
+            for_each: "ROW".to_string(),
+            function_body: "BEGIN NEW.updated_at = now(); RETURN NEW; END;".to_string(),
+        };
+        let fn_name = crate::trigger_function_name("post", "trg_touch", &trig.function_body);
+        let sql = create_trigger_sql("post", &trig);
+        assert!(sql.starts_with(&format!(
+            "CREATE OR REPLACE FUNCTION \"{fn_name}\"() RETURNS trigger LANGUAGE plpgsql AS $dibs$\nBEGIN NEW.updated_at"
+        )));
+        assert!(sql.ends_with(&format!(
+            "CREATE TRIGGER \"trg_touch\" BEFORE INSERT OR UPDATE ON \"post\" FOR EACH ROW EXECUTE FUNCTION \"{fn_name}\"();"
+        )));
+
+        // Whitespace-only edits keep the function name; real edits change it
+        assert_eq!(
+            fn_name,
+            crate::trigger_function_name(
+                "post",
+                "trg_touch",
+                "BEGIN\n    NEW.updated_at = now();\n    RETURN NEW;\nEND;"
+            )
+        );
+        assert_ne!(
+            fn_name,
+            crate::trigger_function_name("post", "trg_touch", "BEGIN RETURN NEW; END;")
+        );
+    }
+
+    #[test]
+    fn test_policy_sql() {
+        let policy = RlsPolicy {
+            name: "tenant_isolation".to_string(),
+            command: "ALL".to_string(),
+            roles: vec![],
+            using: Some("tenant_id = current_setting('app.tenant')::bigint".to_string()),
+            with_check: None,
+            expr_hash: None,
+        };
+        assert_eq!(
+            create_policy_sql("post", &policy),
+            r#"CREATE POLICY "tenant_isolation" ON "post" FOR ALL USING (tenant_id = current_setting('app.tenant')::bigint);"#
+        );
+
+        let policy = RlsPolicy {
+            name: "own_rows".to_string(),
+            command: "UPDATE".to_string(),
+            roles: vec!["app_user".to_string()],
+            using: Some("owner_id = 1".to_string()),
+            with_check: Some("owner_id = 1".to_string()),
+            expr_hash: Some("0123456789abcdef".to_string()),
+        };
+        assert_eq!(
+            create_policy_sql("post", &policy),
+            "CREATE POLICY \"own_rows\" ON \"post\" FOR UPDATE TO \"app_user\" USING (owner_id = 1) WITH CHECK (owner_id = 1);\n\
+             COMMENT ON POLICY \"own_rows\" ON \"post\" IS 'dibs:expr=0123456789abcdef';"
+        );
+
+        assert_eq!(
+            drop_policy_sql("post", "own_rows"),
+            r#"DROP POLICY "own_rows" ON "post";"#
+        );
+        assert_eq!(
+            set_row_level_security_sql("post", true),
+            r#"ALTER TABLE "post" ENABLE ROW LEVEL SECURITY;"#
+        );
+    }
+
+    #[test]
+    fn test_comment_sql() {
+        assert_eq!(
+            comment_on_table_sql("user", Some("A user's account")),
+            r#"COMMENT ON TABLE "user" IS 'A user''s account';"#
+        );
+        assert_eq!(
+            comment_on_column_sql("user", "email", None),
+            r#"COMMENT ON COLUMN "user"."email" IS NULL;"#
+        );
+    }
 }
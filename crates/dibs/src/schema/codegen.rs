@@ -1,15 +1,47 @@
 use super::Schema;
 use crate::schema::{
-    create_index_sql, create_table_sql, create_trigger_check_function_sql, create_trigger_check_sql,
+    comment_on_column_sql, comment_on_table_sql, create_composite_sql, create_domain_sql,
+    create_extension_sql, create_index_sql, create_policy_sql, create_table_sql,
+    create_trigger_check_function_sql, create_trigger_check_sql, create_trigger_sql,
+    set_row_level_security_sql,
 };
 
 /// Generate SQL to create all tables, foreign keys, and indices.
 ///
 /// Returns a complete SQL script that can be executed to create the schema.
-/// Tables are created first, then foreign keys (as ALTER TABLE), then indices.
+/// Extensions are created first, then domains and composite types, then tables, then foreign keys (as ALTER TABLE), then indices,
+/// then trigger checks and user-defined triggers, then row-level security and policies, then `COMMENT ON`
+/// statements for documented tables and columns.
 pub fn schema_to_sql(schema: &Schema) -> String {
     let mut sql = String::new();
 
+    // Create extensions first: column types and index operator classes may need them
+    for ext in &schema.extensions {
+        sql.push_str(&create_extension_sql(ext));
+        sql.push('\n');
+    }
+    if !schema.extensions.is_empty() {
+        sql.push('\n');
+    }
+
+    // Then domains, which columns refer to
+    for domain in &schema.domains {
+        sql.push_str(&create_domain_sql(domain));
+        sql.push('\n');
+    }
+    if !schema.domains.is_empty() {
+        sql.push('\n');
+    }
+
+    // Then composite types, which columns refer to as well
+    for composite in &schema.composites {
+        sql.push_str(&create_composite_sql(composite));
+        sql.push('\n');
+    }
+    if !schema.composites.is_empty() {
+        sql.push('\n');
+    }
+
     // Create tables (without foreign keys to avoid dependency issues)
     for table in schema.tables.values() {
         sql.push_str(&create_table_sql(table));
@@ -27,12 +59,13 @@ pub fn schema_to_sql(schema: &Schema) -> String {
                 .map(|c| crate::quote_ident(c))
                 .collect();
             sql.push_str(&format!(
-                "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {}({});\n",
+                "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {}({}){};\n",
                 crate::quote_ident(&table.name),
                 crate::quote_ident(&constraint_name),
                 quoted_cols.join(", "),
                 crate::quote_ident(&fk.references_table),
-                quoted_ref_cols.join(", ")
+                quoted_ref_cols.join(", "),
+                crate::schema::deferrable_sql(fk.deferrable)
             ));
         }
     }
@@ -59,5 +92,39 @@ pub fn schema_to_sql(schema: &Schema) -> String {
         }
     }
 
+    // Create user-defined triggers
+    for table in schema.tables.values() {
+        for trig in &table.triggers {
+            sql.push_str(&create_trigger_sql(&table.name, trig));
+            sql.push('\n');
+        }
+    }
+
+    // Row-level security and policies
+    for table in schema.tables.values() {
+        if table.rls {
+            sql.push_str(&set_row_level_security_sql(&table.name, true));
+            sql.push('\n');
+        }
+        for policy in &table.policies {
+            sql.push_str(&create_policy_sql(&table.name, policy));
+            sql.push('\n');
+        }
+    }
+
+    // Doc comments
+    for table in schema.tables.values() {
+        if let Some(doc) = &table.doc {
+            sql.push_str(&comment_on_table_sql(&table.name, Some(doc)));
+            sql.push('\n');
+        }
+        for col in &table.columns {
+            if let Some(doc) = &col.doc {
+                sql.push_str(&comment_on_column_sql(&table.name, &col.name, Some(doc)));
+                sql.push('\n');
+            }
+        }
+    }
+
     sql.trim_end().to_string()
 }
@@ -0,0 +1,697 @@
+//! Rust source generation for `dibs import`.
+//!
+//! Turns a [`Schema`] introspected from a live database back into
+//! `#[facet(dibs::table)]` struct definitions, so an existing database can be
+//! adopted without hand-writing every table.
+
+use std::fmt::Write as _;
+
+use super::{Column, CompositeType, DomainType, Index, IndexColumn, PgType, Schema, Table};
+
+/// Render the schema as Rust source: extension declarations, domain newtypes,
+/// composite type structs and one table struct per table.
+///
+/// Everything the attribute grammar can express is carried over: primary,
+/// unique and foreign keys, defaults, identity columns, indices, CHECK and
+/// EXCLUDE constraints, trigger checks, triggers, partitioning, row-level
+/// security, and table and column comments as doc comments. Composite
+/// foreign keys can't be expressed as a field attribute and are left as a
+/// comment on the struct.
+pub fn schema_to_rust(schema: &Schema) -> String {
+    let mut out = String::new();
+    out.push_str("//! Tables imported from an existing database by `dibs import`.\n");
+    out.push_str("//!\n");
+    out.push_str("//! Review the generated names and types, then treat these like any other\n");
+    out.push_str("//! table definitions.\n\n");
+    out.push_str("use facet::Facet;\n");
+
+    if !schema.extensions.is_empty() {
+        out.push('\n');
+        for ext in &schema.extensions {
+            writeln!(out, "dibs::extension!({ext:?});").unwrap();
+        }
+    }
+
+    for domain in &schema.domains {
+        out.push('\n');
+        render_domain(&mut out, domain);
+    }
+
+    for composite in &schema.composites {
+        out.push('\n');
+        render_composite(&mut out, composite);
+    }
+
+    for table in schema.tables.values() {
+        out.push('\n');
+        render_table(&mut out, schema, table);
+    }
+
+    out
+}
+
+fn render_domain(out: &mut String, domain: &DomainType) {
+    out.push_str("#[derive(Debug, Clone, Facet)]\n");
+    match &domain.check {
+        Some(check) => writeln!(
+            out,
+            "#[facet(dibs::domain(name = {:?}, check = {:?}))]",
+            domain.name, check.expr
+        ),
+        None => writeln!(out, "#[facet(dibs::domain(name = {:?}))]", domain.name),
+    }
+    .unwrap();
+    writeln!(
+        out,
+        "pub struct {}(pub {});",
+        to_pascal_case(&domain.name),
+        rust_type(domain.base_type)
+    )
+    .unwrap();
+}
+
+fn render_composite(out: &mut String, composite: &CompositeType) {
+    out.push_str("#[derive(Debug, Clone, Facet)]\n");
+    writeln!(
+        out,
+        "#[facet(dibs::composite(name = {:?}))]",
+        composite.name
+    )
+    .unwrap();
+    writeln!(out, "pub struct {} {{", to_pascal_case(&composite.name)).unwrap();
+    for field in &composite.fields {
+        let (ident, renamed) = field_name(&field.name);
+        if renamed {
+            writeln!(out, "    #[facet(dibs::column = {:?})]", field.name).unwrap();
+        }
+        writeln!(
+            out,
+            "    pub {ident}: Option<{}>,",
+            rust_type(field.pg_type)
+        )
+        .unwrap();
+    }
+    out.push_str("}\n");
+}
+
+fn render_table(out: &mut String, schema: &Schema, table: &Table) {
+    let name = &table.name;
+
+    render_doc(out, "", table.doc.as_deref());
+    for fk in table.foreign_keys.iter().filter(|fk| fk.columns.len() != 1) {
+        writeln!(
+            out,
+            "// Composite foreign key ({}) -> {} ({}) has no field attribute and was not imported.",
+            fk.columns.join(", "),
+            fk.references_table,
+            fk.references_columns.join(", ")
+        )
+        .unwrap();
+    }
+    out.push_str("#[derive(Debug, Clone, Facet)]\n");
+    out.push_str("#[facet(derive(dibs::Table))]\n");
+    writeln!(out, "#[facet(dibs::table = {name:?})]").unwrap();
+
+    let pk_count = table.columns.iter().filter(|c| c.primary_key).count();
+    for idx in &table.indices {
+        if field_index(table, idx).is_none() {
+            render_composite_index(out, table, idx);
+        }
+    }
+    for check in &table.check_constraints {
+        if check.name == crate::check_constraint_name(name, &check.expr) {
+            writeln!(out, "#[facet(dibs::check(expr = {:?}))]", check.expr).unwrap();
+        } else {
+            writeln!(
+                out,
+                "#[facet(dibs::check(name = {:?}, expr = {:?}))]",
+                check.name, check.expr
+            )
+            .unwrap();
+        }
+    }
+    for exclude in &table.exclusion_constraints {
+        let mut args = vec![
+            format!("name = {:?}", exclude.name),
+            format!("using = {:?}", exclude.using),
+            format!("elements = {:?}", exclude.elements),
+        ];
+        if let Some(filter) = &exclude.filter {
+            args.push(format!("filter = {filter:?}"));
+        }
+        if exclude.deferrable {
+            args.push("deferrable = true".to_string());
+        }
+        writeln!(out, "#[facet(dibs::exclude({}))]", args.join(", ")).unwrap();
+    }
+    for check in &table.trigger_checks {
+        let mut args = vec![
+            format!("name = {:?}", check.name),
+            format!("expr = {:?}", check.expr),
+        ];
+        if let Some(message) = &check.message {
+            args.push(format!("message = {message:?}"));
+        }
+        writeln!(out, "#[facet(dibs::trigger_check({}))]", args.join(", ")).unwrap();
+    }
+    for trigger in &table.triggers {
+        writeln!(
+            out,
+            "#[facet(dibs::trigger(name = {:?}, timing = {:?}, events = {:?}, for_each = {:?}, function_body = {:?}))]",
+            trigger.name,
+            trigger.timing.to_lowercase(),
+            trigger.events.join(", ").to_lowercase(),
+            trigger.for_each.to_lowercase(),
+            trigger.function_body
+        )
+        .unwrap();
+    }
+    if let Some(partition) = &table.partition_by {
+        writeln!(
+            out,
+            "#[facet(dibs::partition_by(kind = {:?}, columns = {:?}))]",
+            partition.kind.to_sql().to_lowercase(),
+            partition.columns.join(", ")
+        )
+        .unwrap();
+    }
+    if table.rls {
+        out.push_str("#[facet(dibs::rls)]\n");
+    }
+    for policy in &table.policies {
+        let mut args = vec![
+            format!("name = {:?}", policy.name),
+            format!("command = {:?}", policy.command.to_lowercase()),
+        ];
+        if !policy.roles.is_empty() {
+            args.push(format!("roles = {:?}", policy.roles.join(", ")));
+        }
+        if let Some(using) = &policy.using {
+            args.push(format!("using = {using:?}"));
+        }
+        if let Some(with_check) = &policy.with_check {
+            args.push(format!("with_check = {with_check:?}"));
+        }
+        writeln!(out, "#[facet(dibs::policy({}))]", args.join(", ")).unwrap();
+    }
+
+    writeln!(out, "pub struct {} {{", to_pascal_case(name)).unwrap();
+    for (i, col) in table.columns.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        render_column(out, schema, table, col, pk_count);
+    }
+    out.push_str("}\n");
+}
+
+fn render_column(out: &mut String, schema: &Schema, table: &Table, col: &Column, pk_count: usize) {
+    render_doc(out, "    ", col.doc.as_deref());
+
+    let (field, renamed) = field_name(&col.name);
+    let mut attrs = Vec::new();
+    if col.primary_key {
+        attrs.push("dibs::pk".to_string());
+    }
+    let sequence_default = col
+        .default
+        .as_deref()
+        .is_some_and(|d| d.to_lowercase().contains("nextval("));
+    if col.is_identity() || sequence_default {
+        attrs.push("dibs::auto".to_string());
+    }
+    // A single-column primary key is unique already
+    if col.unique && !(col.primary_key && pk_count == 1) {
+        attrs.push("dibs::unique".to_string());
+    }
+    if let Some(fk) = table
+        .foreign_keys
+        .iter()
+        .find(|fk| fk.columns.len() == 1 && fk.columns[0] == col.name)
+    {
+        attrs.push(format!(
+            "dibs::fk = \"{}.{}\"",
+            fk.references_table, fk.references_columns[0]
+        ));
+        if fk.deferrable {
+            attrs.push("dibs::deferrable".to_string());
+        }
+    }
+    if let Some(default) = col.default.as_deref().filter(|_| !sequence_default) {
+        attrs.push(format!("dibs::default = {default:?}"));
+    }
+    if let Some(idx) = table
+        .indices
+        .iter()
+        .find(|idx| field_index(table, idx) == Some(col.name.as_str()))
+    {
+        if idx.name == crate::index_name(&table.name, &[&col.name]) {
+            attrs.push("dibs::index".to_string());
+        } else {
+            attrs.push(format!("dibs::index = {:?}", idx.name));
+        }
+    }
+    if renamed {
+        attrs.push(format!("dibs::column = {:?}", col.name));
+    }
+    if !attrs.is_empty() {
+        writeln!(out, "    #[facet({})]", attrs.join(", ")).unwrap();
+    }
+
+    let ty = match col.named_type().filter(|d| {
+        schema.domains.iter().any(|known| known.name == *d)
+            || schema.composites.iter().any(|known| known.name == *d)
+    }) {
+        Some(domain) => to_pascal_case(domain),
+        None => rust_type(col.pg_type),
+    };
+    if col.nullable {
+        writeln!(out, "    pub {field}: Option<{ty}>,").unwrap();
+    } else {
+        writeln!(out, "    pub {field}: {ty},").unwrap();
+    }
+}
+
+fn render_composite_index(out: &mut String, table: &Table, idx: &Index) {
+    let col_names: Vec<&str> = idx.columns.iter().map(|c| c.name.as_str()).collect();
+    let (key, default_name) = if idx.unique {
+        (
+            "composite_unique",
+            crate::unique_index_name(&table.name, &col_names),
+        )
+    } else {
+        (
+            "composite_index",
+            crate::index_name(&table.name, &col_names),
+        )
+    };
+
+    let mut args = Vec::new();
+    if idx.name != default_name {
+        args.push(format!("name = {:?}", idx.name));
+    }
+    let columns: Vec<String> = idx.columns.iter().map(index_column_spec).collect();
+    args.push(format!("columns = {:?}", columns.join(", ")));
+    if let Some(filter) = &idx.where_clause {
+        args.push(format!("filter = {filter:?}"));
+    }
+    writeln!(out, "#[facet(dibs::{key}({}))]", args.join(", ")).unwrap();
+}
+
+/// The column an index can be declared on with a field-level `dibs::index`:
+/// a plain, non-unique, ascending index over a single column.
+fn field_index<'a>(table: &Table, idx: &'a Index) -> Option<&'a str> {
+    match idx.columns.as_slice() {
+        [col]
+            if !idx.unique
+                && idx.where_clause.is_none()
+                && *col == IndexColumn::new(col.name.clone())
+                && table.columns.iter().any(|c| c.name == col.name) =>
+        {
+            Some(col.name.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// An index column as written in a `columns = "..."` list, e.g. `created_at DESC`.
+fn index_column_spec(col: &IndexColumn) -> String {
+    format!("{}{}{}", col.name, col.order.to_sql(), col.nulls.to_sql())
+}
+
+fn render_doc(out: &mut String, indent: &str, doc: Option<&str>) {
+    let Some(doc) = doc.map(str::trim).filter(|d| !d.is_empty()) else {
+        return;
+    };
+    for line in doc.lines() {
+        if line.trim().is_empty() {
+            writeln!(out, "{indent}///").unwrap();
+        } else {
+            writeln!(out, "{indent}/// {}", line.trim_end()).unwrap();
+        }
+    }
+}
+
+/// Paths for the names [`PgType::to_rust_type`] leaves unqualified, so the
+/// generated module doesn't depend on the caller's imports.
+const TYPE_PATHS: &[(&str, &str)] = &[
+    ("Decimal", "rust_decimal::Decimal"),
+    ("Timestamp", "jiff::Timestamp"),
+    ("Date", "jiff::civil::Date"),
+    ("Time", "jiff::civil::Time"),
+    ("Uuid", "uuid::Uuid"),
+    ("Jsonb<", "dibs::Jsonb<"),
+];
+
+/// The Rust type a column of this Postgres type is declared with.
+fn rust_type(pg_type: PgType) -> String {
+    let name = pg_type.to_rust_type();
+    TYPE_PATHS
+        .iter()
+        .find_map(|(short, path)| {
+            let rest = name.strip_prefix(short)?;
+            (short.ends_with('<') || rest.is_empty()).then(|| format!("{path}{rest}"))
+        })
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Keywords that can be used as raw identifiers (`r#type`).
+const RAW_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// The field name for a column, and whether it differs from the column name
+/// (and so needs a `dibs::column` attribute).
+fn field_name(column: &str) -> (String, bool) {
+    let is_ident = column
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && column
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && column != "_";
+
+    if is_ident && RAW_KEYWORDS.contains(&column) {
+        return (format!("r#{column}"), false);
+    }
+    if is_ident && !matches!(column, "self" | "super" | "crate") {
+        return (column.to_string(), false);
+    }
+
+    let mut field: String = column
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !field.starts_with(|c: char| c.is_ascii_lowercase()) {
+        field.insert_str(0, "col_");
+    } else if matches!(field.as_str(), "self" | "super" | "crate")
+        || RAW_KEYWORDS.contains(&field.as_str())
+    {
+        field.push('_');
+    }
+    (field, true)
+}
+
+fn to_pascal_case(s: &str) -> String {
+    let mut result = String::new();
+    let mut capitalize_next = true;
+
+    for c in s.chars() {
+        if !c.is_ascii_alphanumeric() {
+            capitalize_next = true;
+        } else if capitalize_next {
+            result.push(c.to_ascii_uppercase());
+            capitalize_next = false;
+        } else {
+            result.push(c);
+        }
+    }
+
+    if result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, 'T');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CheckConstraint, CompositeField, ForeignKey, SourceLocation};
+
+    fn column(name: &str, pg_type: PgType) -> Column {
+        Column {
+            name: name.to_string(),
+            pg_type,
+            rust_type: None,
+            nullable: false,
+            default: None,
+            primary_key: false,
+            unique: false,
+            auto_generated: false,
+            long: false,
+            label: false,
+            enum_variants: Vec::new(),
+            doc: None,
+            lang: None,
+            icon: None,
+            subtype: None,
+            domain: None,
+            composite: None,
+        }
+    }
+
+    fn table(name: &str, columns: Vec<Column>) -> Table {
+        Table {
+            name: name.to_string(),
+            columns,
+            check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
+            foreign_keys: Vec::new(),
+            indices: Vec::new(),
+            source: SourceLocation::default(),
+            doc: None,
+            icon: None,
+        }
+    }
+
+    fn schema(tables: Vec<Table>) -> Schema {
+        Schema {
+            tables: tables.into_iter().map(|t| (t.name.clone(), t)).collect(),
+            ..Schema::default()
+        }
+    }
+
+    #[test]
+    fn test_schema_to_rust_columns() {
+        let mut posts = table(
+            "blog_posts",
+            vec![
+                Column {
+                    primary_key: true,
+                    auto_generated: true,
+                    ..column("id", PgType::BigInt)
+                },
+                Column {
+                    unique: true,
+                    doc: Some("URL-friendly handle".to_string()),
+                    ..column("slug", PgType::Text)
+                },
+                column("author_id", PgType::BigInt),
+                Column {
+                    nullable: true,
+                    ..column("body", PgType::Text)
+                },
+                Column {
+                    default: Some("now()".to_string()),
+                    auto_generated: true,
+                    ..column("created_at", PgType::Timestamptz)
+                },
+                column("type", PgType::Text),
+            ],
+        );
+        posts.doc = Some("A blog post.".to_string());
+        posts.foreign_keys.push(ForeignKey {
+            columns: vec!["author_id".to_string()],
+            references_table: "authors".to_string(),
+            references_columns: vec!["id".to_string()],
+            deferrable: false,
+        });
+
+        let rust = schema_to_rust(&schema(vec![posts]));
+
+        assert!(rust.contains("use facet::Facet;\n"));
+        assert!(rust.contains(
+            "/// A blog post.\n\
+             #[derive(Debug, Clone, Facet)]\n\
+             #[facet(derive(dibs::Table))]\n\
+             #[facet(dibs::table = \"blog_posts\")]\n\
+             pub struct BlogPosts {\n"
+        ));
+        assert!(rust.contains("    #[facet(dibs::pk, dibs::auto)]\n    pub id: i64,\n"));
+        assert!(rust.contains(
+            "    /// URL-friendly handle\n    #[facet(dibs::unique)]\n    pub slug: String,\n"
+        ));
+        assert!(
+            rust.contains("    #[facet(dibs::fk = \"authors.id\")]\n    pub author_id: i64,\n")
+        );
+        assert!(rust.contains("    pub body: Option<String>,\n"));
+        assert!(rust.contains(
+            "    #[facet(dibs::default = \"now()\")]\n    pub created_at: jiff::Timestamp,\n"
+        ));
+        assert!(rust.contains("    pub r#type: String,\n"));
+    }
+
+    #[test]
+    fn test_schema_to_rust_constraints_and_indices() {
+        let mut events = table(
+            "events",
+            vec![
+                Column {
+                    primary_key: true,
+                    ..column("id", PgType::Uuid)
+                },
+                column("tenant_id", PgType::BigInt),
+                column("starts_at", PgType::Timestamptz),
+                column("Kind", PgType::Text),
+            ],
+        );
+        events.indices = vec![
+            Index {
+                name: "idx_events_tenant_id".to_string(),
+                columns: vec![IndexColumn::new("tenant_id")],
+                unique: false,
+                where_clause: None,
+            },
+            Index {
+                name: "idx_events_tenant_id_starts_at".to_string(),
+                columns: vec![
+                    IndexColumn::new("tenant_id"),
+                    IndexColumn::desc("starts_at"),
+                ],
+                unique: false,
+                where_clause: None,
+            },
+            Index {
+                name: "events_one_per_slot".to_string(),
+                columns: vec![IndexColumn::new("tenant_id"), IndexColumn::new("starts_at")],
+                unique: true,
+                where_clause: Some("\"Kind\" <> 'draft'".to_string()),
+            },
+        ];
+        let expr = "starts_at > '2000-01-01'";
+        events.check_constraints = vec![
+            CheckConstraint {
+                name: crate::check_constraint_name("events", expr),
+                expr: expr.to_string(),
+            },
+            CheckConstraint {
+                name: "events_kind_check".to_string(),
+                expr: "\"Kind\" <> ''".to_string(),
+            },
+        ];
+
+        let rust = schema_to_rust(&schema(vec![events]));
+
+        assert!(rust.contains(
+            "#[facet(dibs::composite_index(columns = \"tenant_id, starts_at DESC\"))]\n"
+        ));
+        assert!(rust.contains(
+            "#[facet(dibs::composite_unique(name = \"events_one_per_slot\", columns = \"tenant_id, starts_at\", filter = \"\\\"Kind\\\" <> 'draft'\"))]\n"
+        ));
+        assert!(rust.contains("#[facet(dibs::check(expr = \"starts_at > '2000-01-01'\"))]\n"));
+        assert!(rust.contains(
+            "#[facet(dibs::check(name = \"events_kind_check\", expr = \"\\\"Kind\\\" <> ''\"))]\n"
+        ));
+        assert!(rust.contains("    #[facet(dibs::pk)]\n    pub id: uuid::Uuid,\n"));
+        assert!(rust.contains("    #[facet(dibs::index)]\n    pub tenant_id: i64,\n"));
+        assert!(rust.contains("    #[facet(dibs::column = \"Kind\")]\n    pub kind: String,\n"));
+    }
+
+    #[test]
+    fn test_schema_to_rust_extensions_and_domains() {
+        let mut users = table(
+            "users",
+            vec![Column {
+                domain: Some("email_address".to_string()),
+                composite: None,
+                nullable: true,
+                ..column("email", PgType::Text)
+            }],
+        );
+        users.foreign_keys.push(ForeignKey {
+            columns: vec!["org_id".to_string(), "team_id".to_string()],
+            references_table: "teams".to_string(),
+            references_columns: vec!["org_id".to_string(), "id".to_string()],
+            deferrable: false,
+        });
+        let mut schema = schema(vec![users]);
+        schema.extensions = vec!["citext".to_string()];
+        schema.domains = vec![DomainType {
+            name: "email_address".to_string(),
+            base_type: PgType::Text,
+            check: Some(CheckConstraint {
+                name: "ck_email_address".to_string(),
+                expr: "VALUE ~ '@'".to_string(),
+            }),
+        }];
+
+        let rust = schema_to_rust(&schema);
+
+        assert!(rust.contains("dibs::extension!(\"citext\");\n"));
+        assert!(rust.contains(
+            "#[facet(dibs::domain(name = \"email_address\", check = \"VALUE ~ '@'\"))]\n\
+             pub struct EmailAddress(pub String);\n"
+        ));
+        assert!(rust.contains("    pub email: Option<EmailAddress>,\n"));
+        assert!(rust.contains(
+            "// Composite foreign key (org_id, team_id) -> teams (org_id, id) has no field attribute and was not imported.\n"
+        ));
+    }
+
+    #[test]
+    fn test_schema_to_rust_composites() {
+        let users = table(
+            "users",
+            vec![Column {
+                composite: Some("postal_address".to_string()),
+                ..column("address", PgType::Composite)
+            }],
+        );
+        let mut schema = schema(vec![users]);
+        schema.composites = vec![CompositeType {
+            name: "postal_address".to_string(),
+            fields: vec![
+                CompositeField {
+                    name: "street".to_string(),
+                    pg_type: PgType::Text,
+                },
+                CompositeField {
+                    name: "Zip".to_string(),
+                    pg_type: PgType::Integer,
+                },
+            ],
+        }];
+
+        let rust = schema_to_rust(&schema);
+
+        assert!(rust.contains(
+            "#[facet(dibs::composite(name = \"postal_address\"))]\n\
+             pub struct PostalAddress {\n\
+             \x20   pub street: Option<String>,\n\
+             \x20   #[facet(dibs::column = \"Zip\")]\n\
+             \x20   pub zip: Option<i32>,\n\
+             }\n"
+        ));
+        assert!(rust.contains("    pub address: PostalAddress,\n"));
+    }
+
+    #[test]
+    fn test_rust_type_qualifies_paths() {
+        assert_eq!(rust_type(PgType::BigInt), "i64");
+        assert_eq!(rust_type(PgType::Numeric), "rust_decimal::Decimal");
+        assert_eq!(rust_type(PgType::Timestamptz), "jiff::Timestamp");
+        assert_eq!(rust_type(PgType::Time), "jiff::civil::Time");
+        assert_eq!(rust_type(PgType::Jsonb), "dibs::Jsonb<facet_value::Value>");
+        assert_eq!(rust_type(PgType::TextArray), "Vec<String>");
+    }
+}
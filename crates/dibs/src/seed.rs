@@ -0,0 +1,583 @@
+//! Seed data: reference rows and dev fixtures declared in styx.
+//!
+//! A seed file maps table names to the rows they should contain:
+//!
+//! ```styx
+//! // Reference data, loaded in every environment.
+//! currency {
+//!     key (code)
+//!     rows (
+//!         {code EUR, name Euro, decimals 2}
+//!         {code USD, name "US Dollar", decimals 2}
+//!     )
+//! }
+//!
+//! // Demo accounts, only for local development and tests.
+//! user {
+//!     env (dev test)
+//!     rows (
+//!         {id 1, email "alice@example.com", bio @null}
+//!     )
+//! }
+//! ```
+//!
+//! Rows are upserted on the table's primary key, or on `key` when given (it
+//! must match a unique constraint), so seeding twice changes nothing.
+
+use crate::types::{infer_literal_type, types_compatible};
+use crate::{Error, Result, Schema, Table};
+use facet::Facet;
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use tokio_postgres::Client;
+
+/// An environment seed data can be restricted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[facet(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum SeedEnv {
+    /// Local development
+    Dev,
+    /// Test databases
+    Test,
+    /// Production
+    Prod,
+}
+
+impl SeedEnv {
+    /// The name used in seed files and on the command line.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeedEnv::Dev => "dev",
+            SeedEnv::Test => "test",
+            SeedEnv::Prod => "prod",
+        }
+    }
+}
+
+impl fmt::Display for SeedEnv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SeedEnv {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "dev" => Ok(SeedEnv::Dev),
+            "test" => Ok(SeedEnv::Test),
+            "prod" => Ok(SeedEnv::Prod),
+            _ => Err(format!(
+                "unknown seed environment '{s}' (expected dev, test or prod)"
+            )),
+        }
+    }
+}
+
+/// A value in a seed row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Facet)]
+#[facet(rename_all = "kebab-case")]
+#[repr(u8)]
+pub enum SeedValue {
+    /// SQL NULL (`@null`)
+    Null,
+    /// A literal, cast by Postgres to the column's type
+    #[facet(other)]
+    Literal(String),
+}
+
+/// The rows seeded into one table.
+#[derive(Debug, Clone, Facet)]
+pub struct TableSeed {
+    /// Environments these rows are loaded in (all of them when absent).
+    pub env: Option<Vec<SeedEnv>>,
+
+    /// Columns identifying a row (the primary key when absent).
+    pub key: Option<Vec<String>>,
+
+    /// The rows, as column name to value.
+    pub rows: Vec<IndexMap<String, SeedValue>>,
+}
+
+impl TableSeed {
+    /// Whether these rows are loaded in `env`.
+    pub fn applies_to(&self, env: SeedEnv) -> bool {
+        self.env.as_ref().is_none_or(|envs| envs.contains(&env))
+    }
+
+    /// The columns rows are upserted on.
+    pub fn key_columns(&self, table: &Table) -> Vec<String> {
+        match &self.key {
+            Some(key) => key.clone(),
+            None => table
+                .columns
+                .iter()
+                .filter(|c| c.primary_key)
+                .map(|c| c.name.clone())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Facet)]
+#[facet(transparent)]
+struct SeedTables(IndexMap<String, TableSeed>);
+
+/// A parsed seed file.
+#[derive(Debug, Clone)]
+pub struct SeedFile {
+    /// Path of the file, used in messages
+    pub path: String,
+    /// Table name to the rows seeded into it, in file order
+    pub tables: IndexMap<String, TableSeed>,
+}
+
+impl SeedFile {
+    /// Parse a seed file's styx source.
+    pub fn parse(path: impl Into<String>, source: &str) -> Result<Self> {
+        let path = path.into();
+        let SeedTables(tables) =
+            facet_styx::from_str(source).map_err(|e| Error::InvalidSeed(format!("{path}: {e}")))?;
+        Ok(SeedFile { path, tables })
+    }
+
+    /// Check every table, key, column and value against the schema.
+    ///
+    /// Returns one message per problem; an empty list means the file is valid.
+    pub fn validate(&self, schema: &Schema) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (table_name, seed) in &self.tables {
+            let mut problem = |msg: String| {
+                problems.push(format!("{}: {}: {}", self.path, table_name, msg));
+            };
+
+            let Some(table) = schema.get_table(table_name) else {
+                problem("unknown table".to_string());
+                continue;
+            };
+
+            let key = seed.key_columns(table);
+            if key.is_empty() {
+                problem("table has no primary key; declare a `key`".to_string());
+                continue;
+            }
+            if let Some(col) = key
+                .iter()
+                .find(|k| !table.columns.iter().any(|c| &c.name == *k))
+            {
+                problem(format!("unknown key column '{col}'"));
+                continue;
+            }
+            if !is_unique_key(table, &key) {
+                problem(format!(
+                    "key ({}) is not the primary key or a unique constraint",
+                    key.join(", ")
+                ));
+            }
+
+            let mut seen: HashMap<Vec<&SeedValue>, usize> = HashMap::new();
+            for (i, row) in seed.rows.iter().enumerate() {
+                let row_no = i + 1;
+                for (col_name, value) in row {
+                    let Some(column) = table.columns.iter().find(|c| &c.name == col_name) else {
+                        problem(format!("row {row_no}: unknown column '{col_name}'"));
+                        continue;
+                    };
+                    match value {
+                        SeedValue::Null if !column.nullable => {
+                            problem(format!("row {row_no}: column '{col_name}' is NOT NULL"));
+                        }
+                        SeedValue::Literal(literal) => {
+                            let sql_type = column.pg_type.to_string();
+                            if !literal_fits(literal, &sql_type) {
+                                problem(format!(
+                                    "row {row_no}: type mismatch: literal '{}' is {} but column '{}' is {}",
+                                    literal,
+                                    infer_literal_type(literal).unwrap_or("string"),
+                                    col_name,
+                                    sql_type
+                                ));
+                            }
+                        }
+                        SeedValue::Null => {}
+                    }
+                }
+
+                let key_values: Option<Vec<&SeedValue>> = key
+                    .iter()
+                    .map(|k| row.get(k).filter(|v| **v != SeedValue::Null))
+                    .collect();
+                match key_values {
+                    None => problem(format!(
+                        "row {row_no}: missing a value for key ({})",
+                        key.join(", ")
+                    )),
+                    Some(values) => {
+                        if let Some(first) = seen.insert(values, row_no) {
+                            problem(format!("row {row_no}: same key as row {first}"));
+                        }
+                    }
+                }
+            }
+        }
+        problems
+    }
+}
+
+/// Rows a seed run loaded into one table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeededTable {
+    /// Seed file the rows came from
+    pub file: String,
+    /// Table name
+    pub table: String,
+    /// Rows that didn't exist yet
+    pub inserted: u32,
+    /// Existing rows whose values changed
+    pub updated: u32,
+    /// Existing rows that already matched
+    pub unchanged: u32,
+}
+
+/// Validate seed files against the schema and upsert the rows for `env`.
+///
+/// Nothing is written unless every file is valid, and all tables are seeded
+/// in one transaction, in file order.
+pub async fn apply_seeds(
+    client: &mut Client,
+    schema: &Schema,
+    files: &[SeedFile],
+    env: SeedEnv,
+) -> Result<Vec<SeededTable>> {
+    let problems: Vec<String> = files.iter().flat_map(|f| f.validate(schema)).collect();
+    if !problems.is_empty() {
+        return Err(Error::InvalidSeed(problems.join("\n")));
+    }
+
+    let tx = client.transaction().await?;
+    let mut seeded = Vec::new();
+    for file in files {
+        for (table_name, seed) in &file.tables {
+            if !seed.applies_to(env) {
+                continue;
+            }
+            let table = schema
+                .get_table(table_name)
+                .ok_or_else(|| Error::UnknownTable(table_name.clone()))?;
+            let key = seed.key_columns(table);
+
+            let mut result = SeededTable {
+                file: file.path.clone(),
+                table: table_name.clone(),
+                inserted: 0,
+                updated: 0,
+                unchanged: 0,
+            };
+            for row in &seed.rows {
+                let sql = upsert_sql(table_name, &key, row);
+                let inserted = tx
+                    .query_opt(&sql, &[])
+                    .await
+                    .map_err(|e| Error::from_postgres_with_sql(e, &sql))?
+                    .map(|r| r.get::<_, bool>(0));
+                match inserted {
+                    Some(true) => result.inserted += 1,
+                    Some(false) => result.updated += 1,
+                    None => result.unchanged += 1,
+                }
+            }
+
+            // Explicit values bypass the sequence: move it past them so the
+            // application's own inserts don't collide with seeded rows
+            for column in &table.columns {
+                let has_sequence = column.is_identity()
+                    || column
+                        .default
+                        .as_deref()
+                        .is_some_and(|d| d.starts_with("nextval("));
+                if has_sequence && seed.rows.iter().any(|r| r.contains_key(&column.name)) {
+                    let sql = format!(
+                        "SELECT setval(pg_get_serial_sequence({}, {}), (SELECT max({}) FROM {}))",
+                        quote_literal(&crate::quote_ident(table_name)),
+                        quote_literal(&column.name),
+                        crate::quote_ident(&column.name),
+                        crate::quote_ident(table_name)
+                    );
+                    tx.execute(&sql, &[])
+                        .await
+                        .map_err(|e| Error::from_postgres_with_sql(e, &sql))?;
+                }
+            }
+
+            seeded.push(result);
+        }
+    }
+    tx.commit().await?;
+    Ok(seeded)
+}
+
+/// Build the statement upserting one seed row.
+///
+/// It returns a single `inserted` boolean for new and changed rows, and no
+/// row at all when the existing row already matched.
+fn upsert_sql(table: &str, key: &[String], row: &IndexMap<String, SeedValue>) -> String {
+    let table = crate::quote_ident(table);
+    let columns: Vec<String> = row.keys().map(|c| crate::quote_ident(c)).collect();
+    let values: Vec<String> = row
+        .values()
+        .map(|v| match v {
+            SeedValue::Null => "NULL".to_string(),
+            SeedValue::Literal(literal) => quote_literal(literal),
+        })
+        .collect();
+    let key_sql: Vec<String> = key.iter().map(|k| crate::quote_ident(k)).collect();
+
+    let mut sql = format!(
+        "INSERT INTO {table} ({}) VALUES ({}) ON CONFLICT ({}) ",
+        columns.join(", "),
+        values.join(", "),
+        key_sql.join(", ")
+    );
+
+    let updated: Vec<&String> = row.keys().filter(|c| !key.contains(c)).collect();
+    if updated.is_empty() {
+        sql.push_str("DO NOTHING");
+    } else {
+        let set: Vec<String> = updated
+            .iter()
+            .map(|c| {
+                let c = crate::quote_ident(c);
+                format!("{c} = EXCLUDED.{c}")
+            })
+            .collect();
+        let current: Vec<String> = updated
+            .iter()
+            .map(|c| format!("{table}.{}", crate::quote_ident(c)))
+            .collect();
+        let excluded: Vec<String> = updated
+            .iter()
+            .map(|c| format!("EXCLUDED.{}", crate::quote_ident(c)))
+            .collect();
+        sql.push_str(&format!(
+            "DO UPDATE SET {} WHERE ({}) IS DISTINCT FROM ({})",
+            set.join(", "),
+            current.join(", "),
+            excluded.join(", ")
+        ));
+    }
+    sql.push_str(" RETURNING (xmax = 0) AS inserted");
+    sql
+}
+
+/// Whether `key` is the primary key, a unique column, or a full unique index.
+fn is_unique_key(table: &Table, key: &[String]) -> bool {
+    let same_columns = |cols: Vec<&str>| {
+        cols.len() == key.len() && cols.iter().all(|c| key.iter().any(|k| k == c))
+    };
+    let primary_key: Vec<&str> = table
+        .columns
+        .iter()
+        .filter(|c| c.primary_key)
+        .map(|c| c.name.as_str())
+        .collect();
+
+    same_columns(primary_key)
+        || (key.len() == 1 && table.columns.iter().any(|c| c.unique && c.name == key[0]))
+        || table.indices.iter().any(|idx| {
+            idx.unique
+                && idx.where_clause.is_none()
+                && same_columns(idx.columns.iter().map(|c| c.name.as_str()).collect())
+        })
+}
+
+fn quote_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// Whether a seed literal can be stored in a column of `sql_type`.
+///
+/// Uses the same type table as the query linter, but only for integer, boolean and
+/// floating-point columns: bare literals are text too (`01234` is a fine zip
+/// code), and Postgres casts strings into everything else (UUIDs, timestamps,
+/// JSON, ...) when the row is written.
+fn literal_fits(literal: &str, sql_type: &str) -> bool {
+    let checked = ["int", "boolean", "float"]
+        .iter()
+        .any(|family| types_compatible(family, sql_type));
+    if !checked {
+        return true;
+    }
+    match infer_literal_type(literal).unwrap_or("string") {
+        "int" => types_compatible("int", sql_type) || types_compatible("float", sql_type),
+        literal_type => types_compatible(literal_type, sql_type),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Column, PgType, SourceLocation};
+
+    fn make_column(name: &str, pg_type: PgType, nullable: bool) -> Column {
+        Column {
+            name: name.to_string(),
+            pg_type,
+            rust_type: None,
+            nullable,
+            default: None,
+            primary_key: false,
+            unique: false,
+            auto_generated: false,
+            long: false,
+            label: false,
+            enum_variants: vec![],
+            doc: None,
+            icon: None,
+            lang: None,
+            subtype: None,
+            domain: None,
+            composite: None,
+        }
+    }
+
+    fn currency_schema() -> Schema {
+        let mut id = make_column("id", PgType::BigInt, false);
+        id.primary_key = true;
+        let mut code = make_column("code", PgType::Text, false);
+        code.unique = true;
+        let table = Table {
+            name: "currency".to_string(),
+            columns: vec![
+                id,
+                code,
+                make_column("name", PgType::Text, true),
+                make_column("decimals", PgType::Integer, false),
+            ],
+            check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
+            trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
+            foreign_keys: Vec::new(),
+            indices: Vec::new(),
+            source: SourceLocation::default(),
+            doc: None,
+            icon: None,
+        };
+        Schema {
+            tables: [(table.name.clone(), table)].into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_seed_file() {
+        let file = SeedFile::parse(
+            "reference.styx",
+            r#"
+            currency {
+                env (dev test)
+                key (code)
+                rows (
+                    {code EUR, name Euro, decimals 2}
+                    {code XXX, name @null, decimals 0}
+                )
+            }
+            "#,
+        )
+        .unwrap();
+
+        let seed = &file.tables["currency"];
+        assert!(seed.applies_to(SeedEnv::Dev));
+        assert!(!seed.applies_to(SeedEnv::Prod));
+        assert_eq!(seed.key, Some(vec!["code".to_string()]));
+        assert_eq!(seed.rows[0]["name"], SeedValue::Literal("Euro".to_string()));
+        assert_eq!(seed.rows[1]["name"], SeedValue::Null);
+        assert!(file.validate(&currency_schema()).is_empty());
+
+        let err = SeedFile::parse("bad.styx", "currency { env (staging), rows () }").unwrap_err();
+        assert!(err.to_string().contains("bad.styx"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let file = SeedFile::parse(
+            "bad.styx",
+            r#"
+            currency {
+                key (name)
+                rows (
+                    {id 1, code EUR, decimals two, color red}
+                    {id 1, code USD, decimals @null}
+                )
+            }
+            country {
+                rows ({code FR})
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.validate(&currency_schema()),
+            vec![
+                "bad.styx: currency: key (name) is not the primary key or a unique constraint",
+                "bad.styx: currency: row 1: type mismatch: literal 'two' is string but column 'decimals' is INTEGER",
+                "bad.styx: currency: row 1: unknown column 'color'",
+                "bad.styx: currency: row 1: missing a value for key (name)",
+                "bad.styx: currency: row 2: column 'decimals' is NOT NULL",
+                "bad.styx: currency: row 2: missing a value for key (name)",
+                "bad.styx: country: unknown table",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_literal_fits() {
+        assert!(literal_fits("42", "BIGINT"));
+        assert!(literal_fits("42", "NUMERIC"));
+        assert!(literal_fits("01234", "TEXT"));
+        assert!(literal_fits("true", "BOOLEAN"));
+        assert!(literal_fits("2026-01-01T00:00:00Z", "TIMESTAMPTZ"));
+        assert!(!literal_fits("yes", "BOOLEAN"));
+        assert!(!literal_fits("1.5", "INTEGER"));
+        assert!(!literal_fits("$5", "BIGINT"));
+    }
+
+    #[test]
+    fn test_upsert_sql() {
+        let row: IndexMap<String, SeedValue> = [
+            ("code".to_string(), SeedValue::Literal("EUR".to_string())),
+            ("name".to_string(), SeedValue::Literal("L'euro".to_string())),
+            ("note".to_string(), SeedValue::Null),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            upsert_sql("currency", &["code".to_string()], &row),
+            "INSERT INTO \"currency\" (\"code\", \"name\", \"note\") VALUES ('EUR', 'L''euro', NULL) \
+             ON CONFLICT (\"code\") DO UPDATE SET \"name\" = EXCLUDED.\"name\", \"note\" = EXCLUDED.\"note\" \
+             WHERE (\"currency\".\"name\", \"currency\".\"note\") IS DISTINCT FROM (EXCLUDED.\"name\", EXCLUDED.\"note\") \
+             RETURNING (xmax = 0) AS inserted"
+        );
+
+        let key_only: IndexMap<String, SeedValue> =
+            [("code".to_string(), SeedValue::Literal("EUR".to_string()))]
+                .into_iter()
+                .collect();
+        assert!(
+            upsert_sql("currency", &["code".to_string()], &key_only)
+                .contains("ON CONFLICT (\"code\") DO NOTHING RETURNING")
+        );
+    }
+}
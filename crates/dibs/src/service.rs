@@ -18,10 +18,15 @@ use std::{io, net::SocketAddr};
 use tokio::net::TcpListener;
 
 /// Convert a MigrationError to a DibsError for the protocol.
+fn to_migration_error(err: MigrationError) -> DibsError {
+    DibsError::MigrationFailed(migration_sql_error(err))
+}
+
+/// Describe a MigrationError for the protocol.
 ///
 /// Extracts the caller location from MigrationError (captured via `#[track_caller]`)
 /// and includes it in the SqlError for display in the TUI.
-fn to_migration_error(err: MigrationError) -> DibsError {
+fn migration_sql_error(err: MigrationError) -> SqlError {
     let caller_str = format!(
         "{}:{}:{}",
         err.caller.file(),
@@ -30,23 +35,29 @@ fn to_migration_error(err: MigrationError) -> DibsError {
     );
 
     if let Some(ctx) = err.inner.sql_context() {
-        DibsError::MigrationFailed(SqlError {
-            message: ctx.message.clone(),
+        let message = match &err.inner {
+            crate::Error::Hook { event, name, .. } => {
+                format!("{event} hook {name} failed: {}", ctx.message)
+            }
+            _ => ctx.message.clone(),
+        };
+        SqlError {
+            message,
             sql: Some(ctx.sql.clone()),
             position: ctx.position.map(|p| p as u32),
             hint: ctx.hint.clone(),
             detail: ctx.detail.clone(),
             caller: Some(caller_str),
-        })
+        }
     } else {
-        DibsError::MigrationFailed(SqlError {
+        SqlError {
             message: err.inner.to_string(),
             sql: None,
             position: None,
             hint: None,
             detail: None,
             caller: Some(caller_str),
-        })
+        }
     }
 }
 
@@ -238,13 +249,19 @@ impl DibsService for DibsServiceImpl {
         Ok(status
             .into_iter()
             .map(|s| {
-                let source = std::fs::read_to_string(&s.source_path).ok();
+                let source = s
+                    .source_path
+                    .as_ref()
+                    .and_then(|path| std::fs::read_to_string(path).ok());
                 MigrationInfo {
-                    version: s.version.to_string(),
+                    version: s.version,
                     name: s.name.to_string(),
                     applied: s.applied,
-                    applied_at: None, // TODO: track this
-                    source_file: Some(s.source_path.display().to_string()),
+                    out_of_order: s.out_of_order,
+                    missing: s.missing,
+                    repeatable: s.repeatable,
+                    applied_at: s.applied_at.map(|at| at.to_string()),
+                    source_file: s.source_path.map(|path| path.display().to_string()),
                     source,
                 }
             })
@@ -277,7 +294,10 @@ impl DibsService for DibsServiceImpl {
         let total_defined = crate::MigrationRunner::total_defined() as u32;
 
         // Run migrations
-        let mut runner = crate::MigrationRunner::new(&mut client);
+        let schema = crate::schema::collect_schema();
+        let mut runner = crate::MigrationRunner::new(&mut client)
+            .strict(request.strict)
+            .with_schema(&schema);
 
         // Initialize and get already-applied migrations
         let setup_start = std::time::Instant::now();
@@ -285,24 +305,46 @@ impl DibsService for DibsServiceImpl {
         let already_applied = runner.applied().await.map_err(error_to_dibs_error)?;
         let setup_ms = setup_start.elapsed().as_millis() as u64;
 
-        // Check for specific migration request
-        if let Some(migration) = request.migration {
-            return Err(DibsError::InvalidRequest(format!(
-                "Running specific migration '{}' not yet implemented",
-                migration
-            )));
+        let drift = runner.drift(&already_applied);
+        if !drift.is_empty() {
+            let _ = logs
+                .send(MigrationLog {
+                    level: if request.strict {
+                        LogLevel::Error
+                    } else {
+                        LogLevel::Warn
+                    },
+                    message: format!("Migration history has drifted from the code: {drift}"),
+                    migration: None,
+                    database: None,
+                })
+                .await;
         }
 
-        // Run all pending
-        let ran = runner.migrate().await.map_err(to_migration_error)?;
+        // Run all pending, or only up to the requested target
+        let ran = match &request.migration {
+            Some(target) => runner.migrate_to(target).await,
+            None => runner.migrate().await,
+        }
+        .map_err(to_migration_error)?;
 
         // Log each applied migration
         for m in &ran {
             let _ = logs
                 .send(MigrationLog {
                     level: LogLevel::Info,
-                    message: format!("Applied {} ({}ms)", m.version, m.duration.as_millis()),
+                    message: format!(
+                        "{} {} ({}ms)",
+                        if m.repeatable {
+                            "Re-ran repeatable"
+                        } else {
+                            "Applied"
+                        },
+                        m.version,
+                        m.duration.as_millis()
+                    ),
                     migration: Some(m.version.to_string()),
+                    database: None,
                 })
                 .await;
         }
@@ -323,17 +365,383 @@ impl DibsService for DibsServiceImpl {
                 .map(|m| ProtoRan {
                     version: m.version.to_string(),
                     duration_ms: m.duration.as_millis() as u64,
+                    repeatable: m.repeatable,
                 })
                 .collect(),
             setup_ms,
             total_time_ms,
         })
     }
+
+    async fn migrate_fleet(
+        &self,
+        request: FleetMigrateRequest,
+        logs: vox::Tx<MigrationLog>,
+    ) -> Result<FleetMigrateResult, DibsError> {
+        use crate::{FleetEventKind, FleetOutcome};
+        use dibs_proto::RanMigration as ProtoRan;
+
+        let mut targets: Vec<crate::FleetTarget> = request
+            .targets
+            .into_iter()
+            .map(|t| crate::FleetTarget {
+                name: t.name,
+                database_url: t.database_url,
+                schema: t.schema,
+            })
+            .collect();
+        if let Some(discovery) = &request.discovery {
+            let discovered =
+                crate::FleetRunner::discover(&discovery.database_url, &discovery.query)
+                    .await
+                    .map_err(error_to_dibs_error)?;
+            targets.extend(discovered);
+        }
+        if targets.is_empty() {
+            return Err(DibsError::InvalidRequest(
+                "no databases to migrate".to_string(),
+            ));
+        }
+
+        let mut runner = crate::FleetRunner::new(targets)
+            .concurrency(request.concurrency as usize)
+            .on_failure(if request.halt_on_failure {
+                crate::FailurePolicy::Halt
+            } else {
+                crate::FailurePolicy::Continue
+            })
+            .strict(request.strict)
+            .with_schema(crate::schema::collect_schema());
+        if let Some(target) = request.migration {
+            runner = runner.migrate_to(target);
+        }
+
+        // Events arrive synchronously from every database at once; forward
+        // them to the client in order from a single task
+        let (log_tx, mut log_rx) = tokio::sync::mpsc::unbounded_channel::<MigrationLog>();
+        let forwarder = tokio::spawn(async move {
+            while let Some(log) = log_rx.recv().await {
+                let _ = logs.send(log).await;
+            }
+        });
+
+        let strict = request.strict;
+        let report = runner
+            .run(move |event| {
+                let (level, message, migration) = match event.kind {
+                    FleetEventKind::Started => (LogLevel::Debug, "Migrating".to_string(), None),
+                    FleetEventKind::Drift(drift) => (
+                        if strict {
+                            LogLevel::Error
+                        } else {
+                            LogLevel::Warn
+                        },
+                        format!("Migration history has drifted from the code: {drift}"),
+                        None,
+                    ),
+                    FleetEventKind::Ran(m) => (
+                        LogLevel::Info,
+                        format!(
+                            "{} {} ({}ms)",
+                            if m.repeatable {
+                                "Re-ran repeatable"
+                            } else {
+                                "Applied"
+                            },
+                            m.version,
+                            m.duration.as_millis()
+                        ),
+                        Some(m.version.to_string()),
+                    ),
+                    FleetEventKind::Finished => (LogLevel::Info, "Up to date".to_string(), None),
+                    FleetEventKind::Failed(e) => (LogLevel::Error, e.to_string(), None),
+                    FleetEventKind::Skipped => (
+                        LogLevel::Warn,
+                        "Skipped after an earlier failure".to_string(),
+                        None,
+                    ),
+                };
+                let _ = log_tx.send(MigrationLog {
+                    level,
+                    message,
+                    migration,
+                    database: Some(event.database.to_string()),
+                });
+            })
+            .await;
+        let _ = forwarder.await;
+
+        Ok(FleetMigrateResult {
+            databases: report
+                .databases
+                .into_iter()
+                .map(|db| {
+                    let (status, applied, error) = match db.outcome {
+                        FleetOutcome::Migrated(ran) => (FleetStatus::Migrated, ran, None),
+                        FleetOutcome::Failed(e) => (
+                            FleetStatus::Failed,
+                            Vec::new(),
+                            Some(migration_sql_error(e)),
+                        ),
+                        FleetOutcome::Skipped => (FleetStatus::Skipped, Vec::new(), None),
+                    };
+                    FleetDatabaseResult {
+                        name: db.name,
+                        status,
+                        applied: applied
+                            .into_iter()
+                            .map(|m| ProtoRan {
+                                version: m.version.to_string(),
+                                duration_ms: m.duration.as_millis() as u64,
+                                repeatable: m.repeatable,
+                            })
+                            .collect(),
+                        error,
+                        duration_ms: db.duration.as_millis() as u64,
+                    }
+                })
+                .collect(),
+            total_time_ms: report.duration.as_millis() as u64,
+        })
+    }
+
+    async fn import_schema(&self, request: DiffRequest) -> Result<ImportResult, DibsError> {
+        use crate::schema::SchemaCodegen;
+
+        // Connect to database
+        let (client, connection) =
+            tokio_postgres::connect(&request.database_url, tokio_postgres::NoTls)
+                .await
+                .map_err(|e| DibsError::ConnectionFailed(e.to_string()))?;
+
+        // Spawn connection handler
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::warn!(error = %e, "Database connection ended");
+            }
+        });
+
+        let db_schema = Schema::from_database(&client)
+            .await
+            .map_err(|e| DibsError::ConnectionFailed(e.to_string()))?;
+
+        Ok(ImportResult {
+            rust: crate::schema::import::schema_to_rust(&db_schema),
+            sql: db_schema.to_sql(),
+            table_count: db_schema.tables.len() as u32,
+        })
+    }
+
+    async fn baseline(&self, request: BaselineRequest) -> Result<(), DibsError> {
+        // Connect to database
+        let (mut client, connection) =
+            tokio_postgres::connect(&request.database_url, tokio_postgres::NoTls)
+                .await
+                .map_err(|e| DibsError::ConnectionFailed(e.to_string()))?;
+
+        // Spawn connection handler
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::warn!(error = %e, "Database connection ended");
+            }
+        });
+
+        let runner = crate::MigrationRunner::new(&mut client);
+        runner
+            .mark_applied(&request.version)
+            .await
+            .map_err(error_to_dibs_error)
+    }
+
+    async fn squash(&self, request: SquashRequest) -> Result<SquashResult, DibsError> {
+        // Connect to database
+        let (mut client, connection) =
+            tokio_postgres::connect(&request.database_url, tokio_postgres::NoTls)
+                .await
+                .map_err(|e| DibsError::ConnectionFailed(e.to_string()))?;
+
+        // Spawn connection handler
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::warn!(error = %e, "Database connection ended");
+            }
+        });
+
+        let runner = crate::MigrationRunner::new(&mut client);
+        let status = runner.status().await.map_err(error_to_dibs_error)?;
+        if !status
+            .iter()
+            .any(|s| !s.missing && !s.repeatable && s.version == request.up_to)
+        {
+            return Err(DibsError::InvalidRequest(format!(
+                "no migration with version '{}'",
+                request.up_to
+            )));
+        }
+
+        let sql = scratch_schema_sql(&client, &request.database_url, &request.up_to).await?;
+
+        Ok(SquashResult {
+            baseline_version: format!("{}_squashed", request.up_to),
+            sql,
+            squashed: status
+                .into_iter()
+                .filter(|s| !s.missing && !s.repeatable && s.version <= request.up_to)
+                .map(|s| MigrationInfo {
+                    version: s.version,
+                    name: s.name.to_string(),
+                    applied: s.applied,
+                    out_of_order: s.out_of_order,
+                    missing: false,
+                    repeatable: false,
+                    applied_at: s.applied_at.map(|at| at.to_string()),
+                    source_file: s.source_path.map(|path| path.display().to_string()),
+                    source: None,
+                })
+                .collect(),
+        })
+    }
+
+    async fn provenance(
+        &self,
+        request: ProvenanceRequest,
+    ) -> Result<Vec<ProvenanceInfo>, DibsError> {
+        // Connect to database
+        let (client, connection) =
+            tokio_postgres::connect(&request.database_url, tokio_postgres::NoTls)
+                .await
+                .map_err(|e| DibsError::ConnectionFailed(e.to_string()))?;
+
+        // Spawn connection handler
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::warn!(error = %e, "Database connection ended");
+            }
+        });
+
+        let provenance = crate::meta::provenance(&client)
+            .await
+            .map_err(error_to_dibs_error)?;
+        let timestamp =
+            |at: chrono::DateTime<chrono::Utc>| at.format("%Y-%m-%d %H:%M:%S UTC").to_string();
+        Ok(provenance
+            .into_iter()
+            .map(|p| ProvenanceInfo {
+                kind: p.kind.to_string(),
+                table: p.table,
+                name: p.name,
+                created_by: p.created_by,
+                created_at: timestamp(p.created_at),
+                modified_by: p.modified_by,
+                modified_at: timestamp(p.modified_at),
+            })
+            .collect())
+    }
+
+    async fn seed(&self, request: SeedRequest) -> Result<Vec<SeededTableInfo>, DibsError> {
+        let env: crate::SeedEnv = request.env.parse().map_err(DibsError::InvalidRequest)?;
+        let files = request
+            .files
+            .iter()
+            .map(|f| crate::SeedFile::parse(&f.path, &f.content))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| DibsError::InvalidRequest(e.to_string()))?;
+
+        // Connect to database
+        let (mut client, connection) =
+            tokio_postgres::connect(&request.database_url, tokio_postgres::NoTls)
+                .await
+                .map_err(|e| DibsError::ConnectionFailed(e.to_string()))?;
+
+        // Spawn connection handler
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::warn!(error = %e, "Database connection ended");
+            }
+        });
+
+        let schema = crate::schema::collect_schema();
+        let seeded = crate::apply_seeds(&mut client, &schema, &files, env)
+            .await
+            .map_err(|e| match e {
+                crate::Error::InvalidSeed(problems) => DibsError::InvalidRequest(problems),
+                e => error_to_dibs_error(e),
+            })?;
+        Ok(seeded
+            .into_iter()
+            .map(|t| SeededTableInfo {
+                file: t.file,
+                table: t.table,
+                inserted: t.inserted,
+                updated: t.updated,
+                unchanged: t.unchanged,
+            })
+            .collect())
+    }
+}
+
+/// Run the migrations up to `up_to` in a throwaway database on the same
+/// server, and return the SQL recreating the resulting schema.
+async fn scratch_schema_sql(
+    admin: &tokio_postgres::Client,
+    database_url: &str,
+    up_to: &str,
+) -> Result<String, DibsError> {
+    use crate::schema::SchemaCodegen;
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let scratch = format!("_dibs_squash_{}_{}", std::process::id(), nanos);
+    admin
+        .batch_execute(&format!("CREATE DATABASE {}", crate::quote_ident(&scratch)))
+        .await
+        .map_err(|e| DibsError::QueryError(format!("creating scratch database: {e}")))?;
+
+    let result = async {
+        let mut config: tokio_postgres::Config = database_url
+            .parse()
+            .map_err(|e: tokio_postgres::Error| DibsError::ConnectionFailed(e.to_string()))?;
+        config.dbname(&scratch);
+        let (mut client, connection) = config
+            .connect(tokio_postgres::NoTls)
+            .await
+            .map_err(|e| DibsError::ConnectionFailed(e.to_string()))?;
+        let connection = tokio::spawn(connection);
+
+        crate::MigrationRunner::new(&mut client)
+            .migrate_pending(Some(up_to))
+            .await
+            .map_err(to_migration_error)?;
+        let schema = Schema::from_database(&client)
+            .await
+            .map_err(|e| DibsError::ConnectionFailed(e.to_string()))?;
+
+        // Close the connection so the scratch database can be dropped
+        drop(client);
+        let _ = connection.await;
+        Ok(schema.to_sql())
+    }
+    .await;
+
+    if let Err(e) = admin
+        .batch_execute(&format!(
+            "DROP DATABASE IF EXISTS {} WITH (FORCE)",
+            crate::quote_ident(&scratch)
+        ))
+        .await
+    {
+        tracing::warn!(error = %e, database = %scratch, "Failed to drop scratch database");
+    }
+
+    result
 }
 
 /// Convert a Schema to SchemaInfo for the wire protocol.
 fn schema_to_info(schema: &Schema) -> SchemaInfo {
     SchemaInfo {
+        extensions: schema.extensions.clone(),
         tables: schema
             .tables
             .values()
@@ -367,6 +775,7 @@ fn schema_to_info(schema: &Schema) -> SchemaInfo {
                         columns: fk.columns.clone(),
                         references_table: fk.references_table.clone(),
                         references_columns: fk.references_columns.clone(),
+                        deferrable: fk.deferrable,
                     })
                     .collect(),
                 indices: t
@@ -398,6 +807,10 @@ fn schema_to_info(schema: &Schema) -> SchemaInfo {
                 source_line: t.source.line,
                 doc: t.doc.clone(),
                 icon: t.icon.clone(),
+                soft_delete: t.soft_delete.clone(),
+                created_at: t.created_at.clone(),
+                updated_at: t.updated_at.clone(),
+                audited: t.audited,
             })
             .collect(),
     }
@@ -416,15 +829,22 @@ fn diff_to_result(diff: &crate::SchemaDiff) -> DiffResult {
                     .iter()
                     .map(|c| {
                         let kind = match c {
-                            Change::AddTable(_)
+                            Change::CreateExtension(_)
+                            | Change::CreateDomain(_)
+                            | Change::CreateComposite(_)
+                            | Change::AddTable(_)
                             | Change::AddColumn(_)
                             | Change::AddPrimaryKey(_)
                             | Change::AddForeignKey(_)
                             | Change::AddIndex(_)
                             | Change::AddUnique(_)
                             | Change::AddCheck(_)
+                            | Change::AddExclusion(_)
                             | Change::AddTriggerCheckFunction(_)
-                            | Change::AddTriggerCheck(_) => ChangeKind::Add,
+                            | Change::AddTriggerCheck(_)
+                            | Change::AddTrigger(_)
+                            | Change::EnableRls
+                            | Change::AddPolicy { .. } => ChangeKind::Add,
                             Change::DropTable(_)
                             | Change::DropColumn(_)
                             | Change::DropPrimaryKey
@@ -432,14 +852,26 @@ fn diff_to_result(diff: &crate::SchemaDiff) -> DiffResult {
                             | Change::DropIndex(_)
                             | Change::DropUnique(_)
                             | Change::DropCheck(_)
+                            | Change::DropExclusion(_)
                             | Change::DropTriggerCheck(_)
-                            | Change::DropTriggerCheckFunction(_) => ChangeKind::Drop,
+                            | Change::DropTriggerCheckFunction(_)
+                            | Change::DropTrigger { .. }
+                            | Change::DisableRls
+                            | Change::DropPolicy(_)
+                            | Change::DropDomain(_)
+                            | Change::DropComposite(_) => ChangeKind::Drop,
                             Change::RenameTable { .. }
                             | Change::RenameColumn { .. }
                             | Change::AlterColumnType { .. }
+                            | Change::AlterColumnDomain { .. }
+                            | Change::AlterForeignKeyDeferrable { .. }
+                            | Change::AlterDomainCheck { .. }
+                            | Change::AlterComposite { .. }
                             | Change::AlterColumnNullable { .. }
                             | Change::AlterColumnDefault { .. }
-                            | Change::AlterColumnAutoGenerated { .. } => ChangeKind::Alter,
+                            | Change::AlterColumnAutoGenerated { .. }
+                            | Change::AlterPartitioning { .. }
+                            | Change::SetComment { .. } => ChangeKind::Alter,
                         };
                         ChangeInfo {
                             description: format!("{}", c),
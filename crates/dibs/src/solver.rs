@@ -65,6 +65,15 @@ pub enum SolverError {
         second: String,
         reason: String,
     },
+    /// A unique constraint on a partitioned table doesn't include the partition key.
+    PartitionKeyNotInUnique {
+        change: String,
+        table: String,
+        columns: Vec<String>,
+        partition_columns: Vec<String>,
+    },
+    /// The change can't be expressed as a migration and must be done by hand.
+    Unsupported { change: String, reason: String },
     /// Migration simulation didn't produce the expected result.
     SimulationMismatch {
         /// Human-readable diff between expected and actual state.
@@ -156,6 +165,24 @@ impl std::fmt::Display for SolverError {
                     first, second, reason
                 )
             }
+            SolverError::PartitionKeyNotInUnique {
+                change,
+                table,
+                columns,
+                partition_columns,
+            } => {
+                write!(
+                    f,
+                    "{}: unique constraint ({}) on partitioned table '{}' must include the partition key ({})",
+                    change,
+                    columns.join(", "),
+                    table,
+                    partition_columns.join(", ")
+                )
+            }
+            SolverError::Unsupported { change, reason } => {
+                write!(f, "{}: {}", change, reason)
+            }
             SolverError::SimulationMismatch { diff } => {
                 write!(
                     f,
@@ -177,7 +204,16 @@ struct VirtualTable {
     indices: HashSet<String>,
     unique_constraints: HashSet<String>,
     check_constraints: HashSet<String>,
+    exclusion_constraints: HashSet<String>,
     trigger_checks: HashSet<String>,
+    triggers: HashSet<String>,
+    /// Partition key columns (empty if the table isn't partitioned).
+    partition_columns: Vec<String>,
+    rls: bool,
+    /// Policy name -> columns its expressions reference.
+    policies: HashMap<String, Vec<String>>,
+    /// Column name -> domain or composite type, for columns typed with one.
+    column_domains: HashMap<String, String>,
 }
 
 /// Virtual schema state for simulating migrations.
@@ -205,7 +241,13 @@ impl VirtualSchema {
                     indices: HashSet::new(),
                     unique_constraints: HashSet::new(),
                     check_constraints: HashSet::new(),
+                    exclusion_constraints: HashSet::new(),
                     trigger_checks: HashSet::new(),
+                    triggers: HashSet::new(),
+                    partition_columns: Vec::new(),
+                    rls: false,
+                    policies: HashMap::new(),
+                    column_domains: HashMap::new(),
                 },
             );
         }
@@ -238,11 +280,30 @@ impl VirtualSchema {
                         .iter()
                         .map(|c| c.name.clone())
                         .collect(),
+                    exclusion_constraints: table
+                        .exclusion_constraints
+                        .iter()
+                        .map(|c| c.name.clone())
+                        .collect(),
                     trigger_checks: table
                         .trigger_checks
                         .iter()
                         .map(|t| t.name.clone())
                         .collect(),
+                    triggers: table.triggers.iter().map(|t| t.name.clone()).collect(),
+                    partition_columns: partition_columns(table),
+                    rls: table.rls,
+                    policies: table
+                        .policies
+                        .iter()
+                        .map(|p| {
+                            (
+                                p.name.clone(),
+                                crate::diff::policy_column_refs(p, &table.columns),
+                            )
+                        })
+                        .collect(),
+                    column_domains: column_domains(&table.columns),
                 },
             );
         }
@@ -378,6 +439,18 @@ impl VirtualSchema {
                     }
                 }
 
+                // EXCLUDE constraints
+                for ex_name in &self_table.exclusion_constraints {
+                    if !other_table.exclusion_constraints.contains(ex_name) {
+                        diffs.push(format!("+ {}.exclude({})", name, ex_name));
+                    }
+                }
+                for ex_name in &other_table.exclusion_constraints {
+                    if !self_table.exclusion_constraints.contains(ex_name) {
+                        diffs.push(format!("- {}.exclude({})", name, ex_name));
+                    }
+                }
+
                 // Trigger checks
                 for trig in &self_table.trigger_checks {
                     if !other_table.trigger_checks.contains(trig) {
@@ -389,6 +462,46 @@ impl VirtualSchema {
                         diffs.push(format!("- {}.trigger_check({})", name, trig));
                     }
                 }
+
+                // User-defined triggers
+                for trig in &self_table.triggers {
+                    if !other_table.triggers.contains(trig) {
+                        diffs.push(format!("+ {}.trigger({})", name, trig));
+                    }
+                }
+                for trig in &other_table.triggers {
+                    if !self_table.triggers.contains(trig) {
+                        diffs.push(format!("- {}.trigger({})", name, trig));
+                    }
+                }
+
+                // Row-level security and policies
+                if self_table.rls != other_table.rls {
+                    let sign = if self_table.rls { "+" } else { "-" };
+                    diffs.push(format!("{} {}.rls", sign, name));
+                }
+                for policy in self_table.policies.keys() {
+                    if !other_table.policies.contains_key(policy) {
+                        diffs.push(format!("+ {}.policy({})", name, policy));
+                    }
+                }
+                for policy in other_table.policies.keys() {
+                    if !self_table.policies.contains_key(policy) {
+                        diffs.push(format!("- {}.policy({})", name, policy));
+                    }
+                }
+
+                // Column domains
+                for (col, domain) in &self_table.column_domains {
+                    if other_table.column_domains.get(col) != Some(domain) {
+                        diffs.push(format!("+ {}.{}::{}", name, col, domain));
+                    }
+                }
+                for (col, domain) in &other_table.column_domains {
+                    if self_table.column_domains.get(col) != Some(domain) {
+                        diffs.push(format!("- {}.{}::{}", name, col, domain));
+                    }
+                }
             }
         }
 
@@ -416,6 +529,31 @@ impl VirtualSchema {
         let change_desc = format!("{}", change);
 
         match change {
+            // Extensions, domain and composite type definitions live outside
+            // the table model; only their use by columns is tracked
+            Change::CreateExtension(_)
+            | Change::CreateDomain(_)
+            | Change::AlterDomainCheck { .. }
+            | Change::CreateComposite(_)
+            | Change::AlterComposite { .. } => {}
+
+            Change::DropDomain(domain) | Change::DropComposite(domain) => {
+                // Postgres refuses to drop a type a column still uses
+                for (table_name, table) in &self.tables {
+                    if let Some((column, _)) =
+                        table.column_domains.iter().find(|(_, d)| *d == domain)
+                    {
+                        return Err(SolverError::ConflictingOperations {
+                            first: change_desc,
+                            second: format!(
+                                "column {}.{} uses type {}",
+                                table_name, column, domain
+                            ),
+                            reason: "type is still in use".to_string(),
+                        });
+                    }
+                }
+            }
             Change::AddTable(t) => {
                 if self.table_exists(&t.name) {
                     return Err(SolverError::TableAlreadyExists {
@@ -423,6 +561,29 @@ impl VirtualSchema {
                         table: t.name.clone(),
                     });
                 }
+                // Postgres requires unique constraints on partitioned tables to
+                // include every partition key column
+                let partition_cols = partition_columns(t);
+                let mut unique_sets: Vec<Vec<String>> = Vec::new();
+                let pk: Vec<String> = t
+                    .columns
+                    .iter()
+                    .filter(|c| c.primary_key)
+                    .map(|c| c.name.clone())
+                    .collect();
+                if !pk.is_empty() {
+                    unique_sets.push(pk);
+                }
+                for col in t.columns.iter().filter(|c| c.unique && !c.primary_key) {
+                    unique_sets.push(vec![col.name.clone()]);
+                }
+                for idx in t.indices.iter().filter(|i| i.unique) {
+                    unique_sets.push(idx.columns.iter().map(|c| c.name.clone()).collect());
+                }
+                for columns in unique_sets {
+                    check_partition_key(&change_desc, &t.name, &partition_cols, &columns)?;
+                }
+
                 // Note: We don't validate FK targets here. The FKs in the Table struct
                 // are metadata for tracking. The actual FK constraints are created by
                 // AddForeignKey changes, which validate targets when applied.
@@ -445,11 +606,22 @@ impl VirtualSchema {
                             .iter()
                             .map(|c| c.name.clone())
                             .collect(),
+                        exclusion_constraints: t
+                            .exclusion_constraints
+                            .iter()
+                            .map(|c| c.name.clone())
+                            .collect(),
                         trigger_checks: t
                             .trigger_checks
                             .iter()
                             .map(|trig| trig.name.clone())
                             .collect(),
+                        // Triggers, RLS and policies are created by their own changes
+                        triggers: HashSet::new(),
+                        partition_columns: partition_cols,
+                        rls: false,
+                        policies: HashMap::new(),
+                        column_domains: column_domains(&t.columns),
                     },
                 );
             }
@@ -502,6 +674,7 @@ impl VirtualSchema {
                                     columns: fk.columns.clone(),
                                     references_table: to.clone(),
                                     references_columns: fk.references_columns.clone(),
+                                    deferrable: fk.deferrable,
                                 }
                             } else {
                                 fk.clone()
@@ -526,12 +699,27 @@ impl VirtualSchema {
                         column: col.name.clone(),
                     });
                 }
+                if col.unique
+                    && let Some(table) = self.tables.get(table_context)
+                {
+                    check_partition_key(
+                        &change_desc,
+                        table_context,
+                        &table.partition_columns,
+                        std::slice::from_ref(&col.name),
+                    )?;
+                }
                 if let Some(table) = self.tables.get_mut(table_context) {
                     table.columns.insert(col.name.clone());
                     // Track unique constraint if column is unique
                     if col.unique {
                         table.unique_constraints.insert(col.name.clone());
                     }
+                    if let Some(domain) = col.named_type() {
+                        table
+                            .column_domains
+                            .insert(col.name.clone(), domain.to_string());
+                    }
                 }
             }
 
@@ -544,9 +732,20 @@ impl VirtualSchema {
                 }
                 // Note: We don't require column to exist since we may not have full column info
                 if let Some(table) = self.tables.get_mut(table_context) {
+                    // Postgres refuses to drop a column a policy depends on
+                    if let Some((policy, _)) =
+                        table.policies.iter().find(|(_, cols)| cols.contains(name))
+                    {
+                        return Err(SolverError::ConflictingOperations {
+                            first: change_desc,
+                            second: format!("policy {} references column {}", policy, name),
+                            reason: "column is used by a row-level security policy".to_string(),
+                        });
+                    }
                     table.columns.remove(name);
                     // Also remove unique constraint if it existed
                     table.unique_constraints.remove(name);
+                    table.column_domains.remove(name);
                 }
             }
 
@@ -573,6 +772,15 @@ impl VirtualSchema {
                     if table.unique_constraints.remove(from) {
                         table.unique_constraints.insert(to.clone());
                     }
+                    if let Some(domain) = table.column_domains.remove(from) {
+                        table.column_domains.insert(to.clone(), domain);
+                    }
+                    // Postgres rewrites policy expressions to follow the rename
+                    for cols in table.policies.values_mut() {
+                        for col in cols.iter_mut().filter(|c| *c == from) {
+                            *col = to.clone();
+                        }
+                    }
                     // Note: We don't update FKs here because they reference
                     // other tables' columns, not our own column names
                 }
@@ -609,6 +817,25 @@ impl VirtualSchema {
                 }
             }
 
+            Change::AlterColumnDomain { name, to, .. } => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                if let Some(table) = self.tables.get_mut(table_context) {
+                    match to {
+                        Some(domain) => {
+                            table.column_domains.insert(name.clone(), domain.clone());
+                        }
+                        None => {
+                            table.column_domains.remove(name);
+                        }
+                    }
+                }
+            }
+
             // Column alterations just need the table to exist
             Change::AlterColumnType { .. }
             | Change::AlterColumnNullable { .. }
@@ -623,7 +850,22 @@ impl VirtualSchema {
             }
 
             // Primary key constraints
-            Change::AddPrimaryKey(_) | Change::DropPrimaryKey => {
+            Change::AddPrimaryKey(columns) => {
+                let Some(table) = self.tables.get(table_context) else {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                };
+                check_partition_key(
+                    &change_desc,
+                    table_context,
+                    &table.partition_columns,
+                    columns,
+                )?;
+            }
+
+            Change::DropPrimaryKey => {
                 if !self.table_exists(table_context) {
                     return Err(SolverError::TableNotFound {
                         change: change_desc,
@@ -632,13 +874,34 @@ impl VirtualSchema {
                 }
             }
 
+            // Partitioning can only be chosen when a table is created
+            Change::AlterPartitioning { .. } => {
+                return Err(SolverError::Unsupported {
+                    change: change_desc,
+                    reason: format!(
+                        "Postgres can't change the partitioning of '{}' in place; \
+                         write a migration that recreates the table and copies its rows",
+                        table_context
+                    ),
+                });
+            }
+
             // Index operations
             Change::AddIndex(idx) => {
-                if !self.table_exists(table_context) {
+                let Some(table) = self.tables.get(table_context) else {
                     return Err(SolverError::TableNotFound {
                         change: change_desc,
                         table: table_context.to_string(),
                     });
+                };
+                if idx.unique {
+                    let columns: Vec<String> = idx.columns.iter().map(|c| c.name.clone()).collect();
+                    check_partition_key(
+                        &change_desc,
+                        table_context,
+                        &table.partition_columns,
+                        &columns,
+                    )?;
                 }
                 if let Some(table) = self.tables.get_mut(table_context) {
                     table.indices.insert(idx.name.clone());
@@ -659,12 +922,18 @@ impl VirtualSchema {
 
             // Unique constraint operations
             Change::AddUnique(col) => {
-                if !self.table_exists(table_context) {
+                let Some(table) = self.tables.get(table_context) else {
                     return Err(SolverError::TableNotFound {
                         change: change_desc,
                         table: table_context.to_string(),
                     });
-                }
+                };
+                check_partition_key(
+                    &change_desc,
+                    table_context,
+                    &table.partition_columns,
+                    std::slice::from_ref(col),
+                )?;
                 if let Some(table) = self.tables.get_mut(table_context) {
                     table.unique_constraints.insert(col.clone());
                 }
@@ -702,6 +971,59 @@ impl VirtualSchema {
                 }
             }
 
+            Change::AddExclusion(exclusion) => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                if let Some(table) = self.tables.get_mut(table_context)
+                    && !table.exclusion_constraints.insert(exclusion.name.clone())
+                {
+                    return Err(SolverError::ConflictingOperations {
+                        first: change_desc,
+                        second: format!("constraint {} already exists", exclusion.name),
+                        reason: "exclusion constraint already exists".to_string(),
+                    });
+                }
+            }
+
+            Change::DropExclusion(name) => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                if let Some(table) = self.tables.get_mut(table_context)
+                    && !table.exclusion_constraints.remove(name)
+                {
+                    return Err(SolverError::ConflictingOperations {
+                        first: change_desc,
+                        second: format!("constraint {} not found", name),
+                        reason: "exclusion constraint doesn't exist".to_string(),
+                    });
+                }
+            }
+
+            Change::AlterForeignKeyDeferrable { fk, deferrable } => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                if let Some(table) = self.tables.get_mut(table_context)
+                    && table.foreign_keys.remove(fk)
+                {
+                    table.foreign_keys.insert(ForeignKey {
+                        deferrable: *deferrable,
+                        ..fk.clone()
+                    });
+                }
+            }
+
             Change::DropCheck(name) => {
                 if !self.table_exists(table_context) {
                     return Err(SolverError::TableNotFound {
@@ -775,6 +1097,123 @@ impl VirtualSchema {
                 self.trigger_check_functions
                     .remove(&crate::trigger_check_function_name(trigger_name));
             }
+
+            // User-defined trigger operations
+            Change::AddTrigger(trig) => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                if let Some(table) = self.tables.get_mut(table_context)
+                    && !table.triggers.insert(trig.name.clone())
+                {
+                    return Err(SolverError::ConflictingOperations {
+                        first: change_desc,
+                        second: format!("trigger {} already exists", trig.name),
+                        reason: "trigger already exists".to_string(),
+                    });
+                }
+            }
+
+            Change::DropTrigger { name, .. } => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                if let Some(table) = self.tables.get_mut(table_context)
+                    && !table.triggers.remove(name)
+                {
+                    return Err(SolverError::ConflictingOperations {
+                        first: change_desc,
+                        second: format!("trigger {} not found", name),
+                        reason: "trigger not found".to_string(),
+                    });
+                }
+            }
+
+            // Row-level security operations
+            Change::EnableRls | Change::DisableRls => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                if let Some(table) = self.tables.get_mut(table_context) {
+                    table.rls = matches!(change, Change::EnableRls);
+                }
+            }
+
+            Change::AddPolicy { policy, columns } => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                // The policy expressions can only reference columns that exist
+                if let Some(column) = columns
+                    .iter()
+                    .find(|c| !self.column_exists(table_context, c))
+                {
+                    return Err(SolverError::ColumnNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                        column: column.clone(),
+                    });
+                }
+                if let Some(table) = self.tables.get_mut(table_context) {
+                    if table.policies.contains_key(&policy.name) {
+                        return Err(SolverError::ConflictingOperations {
+                            first: change_desc,
+                            second: format!("policy {} already exists", policy.name),
+                            reason: "policy already exists".to_string(),
+                        });
+                    }
+                    table.policies.insert(policy.name.clone(), columns.clone());
+                }
+            }
+
+            Change::DropPolicy(name) => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                if let Some(table) = self.tables.get_mut(table_context)
+                    && table.policies.remove(name).is_none()
+                {
+                    return Err(SolverError::ConflictingOperations {
+                        first: change_desc,
+                        second: format!("policy {} not found", name),
+                        reason: "policy not found".to_string(),
+                    });
+                }
+            }
+
+            // Comments need their target to exist (e.g. after an add or rename)
+            Change::SetComment { column, .. } => {
+                if !self.table_exists(table_context) {
+                    return Err(SolverError::TableNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                    });
+                }
+                if let Some(column) = column
+                    && !self.column_exists(table_context, column)
+                {
+                    return Err(SolverError::ColumnNotFound {
+                        change: change_desc,
+                        table: table_context.to_string(),
+                        column: column.clone(),
+                    });
+                }
+            }
         }
 
         Ok(())
@@ -787,6 +1226,42 @@ impl VirtualSchema {
     }
 }
 
+/// Partition key columns of a table (empty if it isn't partitioned).
+fn partition_columns(table: &crate::Table) -> Vec<String> {
+    table
+        .partition_by
+        .as_ref()
+        .map(|p| p.columns.clone())
+        .unwrap_or_default()
+}
+
+/// Column name -> domain or composite type for the columns of a table that
+/// have one.
+fn column_domains(columns: &[crate::Column]) -> HashMap<String, String> {
+    columns
+        .iter()
+        .filter_map(|c| Some((c.name.clone(), c.named_type()?.to_string())))
+        .collect()
+}
+
+/// Check that a unique column set includes every partition key column.
+fn check_partition_key(
+    change: &str,
+    table: &str,
+    partition_columns: &[String],
+    columns: &[String],
+) -> Result<(), SolverError> {
+    if partition_columns.iter().all(|p| columns.contains(p)) {
+        return Ok(());
+    }
+    Err(SolverError::PartitionKeyNotInUnique {
+        change: change.to_string(),
+        table: table.to_string(),
+        columns: columns.to_vec(),
+        partition_columns: partition_columns.to_vec(),
+    })
+}
+
 /// A change with its context (which table it belongs to).
 #[derive(Debug, Clone)]
 pub struct ContextualChange {
@@ -827,16 +1302,31 @@ pub fn order_changes(
     current: &VirtualSchema,
     desired: &VirtualSchema,
 ) -> Result<OrderedChanges, SolverError> {
-    // Flatten all changes with their table context
+    // Flatten all changes with their table context. Extensions go first so
+    // they're scheduled before anything that might use their types, functions
+    // or operator classes, followed by domain and composite type definitions
+    // that columns use.
+    let rank = |change: &Change| match change {
+        Change::CreateExtension(_) => 0,
+        Change::CreateDomain(_)
+        | Change::AlterDomainCheck { .. }
+        | Change::CreateComposite(_)
+        | Change::AlterComposite { .. } => 1,
+        _ => 2,
+    };
+    let mut flattened: Vec<(&String, &Change)> = diff
+        .table_diffs
+        .iter()
+        .flat_map(|td| td.changes.iter().map(move |c| (&td.table, c)))
+        .collect();
+    flattened.sort_by_key(|(_, c)| rank(c));
     let mut all_changes: Vec<ContextualChange> = Vec::new();
-    for table_diff in &diff.table_diffs {
-        for change in &table_diff.changes {
-            all_changes.push(ContextualChange {
-                table: table_diff.table.clone(),
-                change: change.clone(),
-                original_index: all_changes.len(),
-            });
-        }
+    for (table, change) in flattened {
+        all_changes.push(ContextualChange {
+            table: table.clone(),
+            change: change.clone(),
+            original_index: all_changes.len(),
+        });
     }
 
     // Start with the current schema state
@@ -989,6 +1479,8 @@ mod tests {
             icon: None,
             lang: None,
             subtype: None,
+            domain: None,
+            composite: None,
         }
     }
 
@@ -997,7 +1489,16 @@ mod tests {
             name: name.to_string(),
             columns,
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
             foreign_keys: Vec::new(),
             indices: Vec::new(),
             source: SourceLocation::default(),
@@ -1011,7 +1512,16 @@ mod tests {
             name: name.to_string(),
             columns,
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
             foreign_keys: fks,
             indices: Vec::new(),
             source: SourceLocation::default(),
@@ -1023,6 +1533,9 @@ mod tests {
     fn make_schema(tables: Vec<Table>) -> Schema {
         Schema {
             tables: tables.into_iter().map(|t| (t.name.clone(), t)).collect(),
+            extensions: Vec::new(),
+            domains: Vec::new(),
+            composites: Vec::new(),
         }
     }
 
@@ -1088,6 +1601,7 @@ mod tests {
                     columns: vec!["category_id".to_string()],
                     references_table: "categories".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -1120,6 +1634,7 @@ mod tests {
                 columns: vec!["parent_id".to_string()],
                 references_table: "category".to_string(), // SELF-REFERENCE
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         )]);
 
@@ -1149,6 +1664,7 @@ mod tests {
                 columns: vec!["parent_id".to_string()],
                 references_table: "category".to_string(), // SELF-REFERENCE
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         );
 
@@ -1180,6 +1696,7 @@ mod tests {
                 columns: vec!["current_version_id".to_string()],
                 references_table: "product_version".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         );
 
@@ -1193,6 +1710,7 @@ mod tests {
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         );
 
@@ -1222,6 +1740,7 @@ mod tests {
             columns: vec!["current_version_id".to_string()],
             references_table: "product_version".to_string(),
             references_columns: vec!["id".to_string()],
+            deferrable: false,
         };
         let result3 = schema.apply("product", &Change::AddForeignKey(fk1));
         assert!(
@@ -1234,6 +1753,7 @@ mod tests {
             columns: vec!["product_id".to_string()],
             references_table: "product".to_string(),
             references_columns: vec!["id".to_string()],
+            deferrable: false,
         };
         let result4 = schema.apply("product_version", &Change::AddForeignKey(fk2));
         assert!(
@@ -1255,11 +1775,21 @@ mod tests {
                 make_column("current_version_id", PgType::BigInt, true),
             ],
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
             foreign_keys: vec![ForeignKey {
                 columns: vec!["current_version_id".to_string()],
                 references_table: "product_version".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
             indices: Vec::new(),
             source: SourceLocation::default(),
@@ -1274,11 +1804,21 @@ mod tests {
                 make_column("product_id", PgType::BigInt, false),
             ],
             check_constraints: Vec::new(),
+            exclusion_constraints: Vec::new(),
             trigger_checks: Vec::new(),
+            triggers: Vec::new(),
+            partition_by: None,
+            rls: false,
+            policies: Vec::new(),
+            soft_delete: None,
+            created_at: None,
+            updated_at: None,
+            audited: false,
             foreign_keys: vec![ForeignKey {
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
             indices: Vec::new(),
             source: SourceLocation::default(),
@@ -1351,6 +1891,7 @@ mod tests {
                 columns: vec!["b_id".to_string()],
                 references_table: "table_b".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         );
 
@@ -1364,6 +1905,7 @@ mod tests {
                 columns: vec!["c_id".to_string()],
                 references_table: "table_c".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         );
 
@@ -1377,6 +1919,7 @@ mod tests {
                 columns: vec!["a_id".to_string()],
                 references_table: "table_a".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         );
 
@@ -1396,6 +1939,7 @@ mod tests {
                         columns: vec!["b_id".to_string()],
                         references_table: "table_b".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     })
                 )
                 .is_ok()
@@ -1408,6 +1952,7 @@ mod tests {
                         columns: vec!["c_id".to_string()],
                         references_table: "table_c".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     })
                 )
                 .is_ok()
@@ -1420,6 +1965,7 @@ mod tests {
                         columns: vec!["a_id".to_string()],
                         references_table: "table_a".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     })
                 )
                 .is_ok()
@@ -1447,6 +1993,7 @@ mod tests {
             columns: vec!["category_id".to_string()],
             references_table: "category".to_string(), // doesn't exist!
             references_columns: vec!["id".to_string()],
+            deferrable: false,
         };
 
         let result = schema.apply("product", &Change::AddForeignKey(fk));
@@ -1477,11 +2024,13 @@ mod tests {
                     columns: vec!["shop_id".to_string()],
                     references_table: "shop".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
                 ForeignKey {
                     columns: vec!["parent_id".to_string()],
                     references_table: "category".to_string(), // self-ref
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
             ],
         );
@@ -1498,11 +2047,13 @@ mod tests {
                     columns: vec!["shop_id".to_string()],
                     references_table: "shop".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
                 ForeignKey {
                     columns: vec!["current_version_id".to_string()],
                     references_table: "product_version".to_string(), // mutual with product_version
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
             ],
         );
@@ -1517,6 +2068,7 @@ mod tests {
                 columns: vec!["product_id".to_string()],
                 references_table: "product".to_string(), // mutual with product
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         );
 
@@ -1532,11 +2084,13 @@ mod tests {
                     columns: vec!["product_id".to_string()],
                     references_table: "product".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
                 ForeignKey {
                     columns: vec!["source_version_id".to_string()],
                     references_table: "product_version".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 },
             ],
         );
@@ -1582,6 +2136,7 @@ mod tests {
             columns: vec!["category_id".to_string()],
             references_table: "categories".to_string(),
             references_columns: vec!["id".to_string()],
+            deferrable: false,
         };
 
         let current = make_schema(vec![
@@ -1655,6 +2210,7 @@ mod tests {
                     columns: vec!["parent_id".to_string()],
                     references_table: "categories".to_string(), // self-ref
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
             make_table_with_fks(
@@ -1667,6 +2223,7 @@ mod tests {
                     columns: vec!["category_id".to_string()],
                     references_table: "categories".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -1735,6 +2292,7 @@ mod tests {
             columns: vec!["author_id".to_string()],
             references_table: "users".to_string(),
             references_columns: vec!["id".to_string()],
+            deferrable: false,
         };
 
         let result = schema.apply("posts", &Change::AddForeignKey(fk));
@@ -1749,6 +2307,7 @@ mod tests {
             columns: vec!["author_id".to_string()],
             references_table: "users".to_string(), // doesn't exist!
             references_columns: vec!["id".to_string()],
+            deferrable: false,
         };
 
         let result = schema.apply("posts", &Change::AddForeignKey(fk));
@@ -1777,6 +2336,7 @@ mod tests {
                     columns: vec!["post_id".to_string()],
                     references_table: "post".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -1848,11 +2408,13 @@ mod tests {
                         columns: vec!["post_id".to_string()],
                         references_table: "post".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                     ForeignKey {
                         columns: vec!["author_id".to_string()],
                         references_table: "user".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                 ],
             ),
@@ -1938,6 +2500,7 @@ mod tests {
                     columns: vec!["post_id".to_string()],
                     references_table: "post".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -1997,6 +2560,7 @@ mod tests {
                     columns: vec!["category_id".to_string()],
                     references_table: "category".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -2079,11 +2643,13 @@ mod tests {
                         columns: vec!["follower_id".to_string()],
                         references_table: "user".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                     ForeignKey {
                         columns: vec!["following_id".to_string()],
                         references_table: "user".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                 ],
             ),
@@ -2098,6 +2664,7 @@ mod tests {
                     columns: vec!["parent_id".to_string()],
                     references_table: "category".to_string(), // SELF-REFERENCE!
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
             make_table_with_fks(
@@ -2112,11 +2679,13 @@ mod tests {
                         columns: vec!["author_id".to_string()],
                         references_table: "user".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                     ForeignKey {
                         columns: vec!["category_id".to_string()],
                         references_table: "category".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                 ],
             ),
@@ -2132,11 +2701,13 @@ mod tests {
                         columns: vec!["post_id".to_string()],
                         references_table: "post".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                     ForeignKey {
                         columns: vec!["tag_id".to_string()],
                         references_table: "tag".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                 ],
             ),
@@ -2246,6 +2817,7 @@ mod tests {
                     columns: vec!["category_id".to_string()],
                     references_table: "category".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
             // This table exists in DB but isn't in Rust schema - it won't be dropped
@@ -2259,6 +2831,7 @@ mod tests {
                     columns: vec!["category_id".to_string()],
                     references_table: "category".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -2339,6 +2912,7 @@ mod tests {
                     columns: vec!["b_id".to_string()],
                     references_table: "table_b".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
             make_table_with_fks(
@@ -2351,6 +2925,7 @@ mod tests {
                     columns: vec!["a_id".to_string()],
                     references_table: "table_a".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -2392,6 +2967,7 @@ mod tests {
                     columns: vec!["user_id".to_string()],
                     references_table: "nonexistent".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 })],
             }],
         };
@@ -2472,6 +3048,7 @@ mod tests {
                     columns: vec!["post_id".to_string()],
                     references_table: "post".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -2520,6 +3097,7 @@ mod tests {
                     columns: vec!["user_id".to_string()],
                     references_table: "nonexistent".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 })],
             }],
         };
@@ -2593,11 +3171,13 @@ mod tests {
                         columns: vec!["author_id".to_string()],
                         references_table: "user".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                     ForeignKey {
                         columns: vec!["category_id".to_string()],
                         references_table: "category".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                 ],
             ),
@@ -2613,11 +3193,13 @@ mod tests {
                         columns: vec!["post_id".to_string()],
                         references_table: "post".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                     ForeignKey {
                         columns: vec!["author_id".to_string()],
                         references_table: "user".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     },
                 ],
             ),
@@ -2743,6 +3325,258 @@ mod tests {
 
     // ==================== Simulation Mismatch Tests ====================
 
+    #[test]
+    fn test_column_comment_after_rename() {
+        let mut renamed = make_column("user_email", PgType::Text, false);
+        renamed.doc = Some("Login address.".to_string());
+
+        let desired = make_schema(vec![make_table(
+            "users",
+            vec![make_column("id", PgType::BigInt, false), renamed],
+        )]);
+        let current = make_schema(vec![make_table(
+            "users",
+            vec![
+                make_column("id", PgType::BigInt, false),
+                make_column("email", PgType::Text, false),
+            ],
+        )]);
+
+        let diff = desired.diff(&current);
+        let current_schema = VirtualSchema::from_tables(current.tables.values());
+        let desired_schema = VirtualSchema::from_tables(desired.tables.values());
+
+        let ordered = order_changes(&diff, &current_schema, &desired_schema).unwrap();
+        let rename_pos = ordered
+            .changes
+            .iter()
+            .position(|c| matches!(&c.change, Change::RenameColumn { .. }))
+            .expect("Should have rename");
+        let comment_pos = ordered
+            .changes
+            .iter()
+            .position(|c| matches!(&c.change, Change::SetComment { .. }))
+            .expect("Should have comment");
+        assert!(rename_pos < comment_pos);
+    }
+
+    #[test]
+    fn test_partition_key_must_be_in_unique_constraints() {
+        let mut id = make_column("id", PgType::BigInt, false);
+        id.primary_key = true;
+        let mut event = make_table(
+            "event",
+            vec![id, make_column("created_at", PgType::Timestamptz, false)],
+        );
+        event.partition_by = Some(crate::PartitionSpec {
+            kind: crate::PartitionKind::Range,
+            columns: vec!["created_at".to_string()],
+        });
+
+        let mut schema = VirtualSchema::new();
+        let result = schema.apply("event", &Change::AddTable(event.clone()));
+        assert!(
+            matches!(result, Err(SolverError::PartitionKeyNotInUnique { .. })),
+            "got {:?}",
+            result
+        );
+
+        // Composite PK including the partition key is fine
+        event.columns[1].primary_key = true;
+        let mut schema = VirtualSchema::new();
+        schema
+            .apply("event", &Change::AddTable(event.clone()))
+            .unwrap();
+
+        // ...but a unique index on another column isn't
+        let idx = crate::Index {
+            name: crate::unique_index_name("event", &["id"]),
+            columns: vec![crate::IndexColumn::new("id")],
+            unique: true,
+            where_clause: None,
+        };
+        let result = schema.apply("event", &Change::AddIndex(idx));
+        assert!(matches!(
+            result,
+            Err(SolverError::PartitionKeyNotInUnique { .. })
+        ));
+    }
+
+    #[test]
+    fn test_partitioning_change_is_rejected() {
+        let current = make_schema(vec![make_table(
+            "event",
+            vec![make_column("created_at", PgType::Timestamptz, false)],
+        )]);
+        let mut event = make_table(
+            "event",
+            vec![make_column("created_at", PgType::Timestamptz, false)],
+        );
+        event.partition_by = Some(crate::PartitionSpec {
+            kind: crate::PartitionKind::Range,
+            columns: vec!["created_at".to_string()],
+        });
+        let desired = make_schema(vec![event]);
+
+        let diff = desired.diff(&current);
+        let result = order_changes(
+            &diff,
+            &VirtualSchema::from_tables(current.tables.values()),
+            &VirtualSchema::from_tables(desired.tables.values()),
+        );
+        assert!(matches!(result, Err(SolverError::Unsupported { .. })));
+    }
+
+    #[test]
+    fn test_domain_ordering_around_columns() {
+        let domain = |name: &str| crate::DomainType {
+            name: name.to_string(),
+            base_type: PgType::Text,
+            check: None,
+        };
+        let with_domain = |name: &str, domain: &str| {
+            let mut col = make_column(name, PgType::Text, false);
+            col.domain = Some(domain.to_string());
+            col
+        };
+
+        // Move a column from one domain to another and drop the old domain
+        let mut current = make_schema(vec![make_table(
+            "subscriber",
+            vec![
+                make_column("id", PgType::BigInt, false),
+                with_domain("address", "old_email"),
+            ],
+        )]);
+        current.domains = vec![domain("old_email")];
+        let mut desired = make_schema(vec![make_table(
+            "subscriber",
+            vec![
+                make_column("id", PgType::BigInt, false),
+                with_domain("address", "email"),
+            ],
+        )]);
+        desired.domains = vec![domain("email")];
+
+        let diff = desired.diff(&current);
+        let ordered = order_changes(
+            &diff,
+            &VirtualSchema::from_tables(current.tables.values()),
+            &VirtualSchema::from_tables(desired.tables.values()),
+        )
+        .unwrap();
+        let position = |pred: &dyn Fn(&Change) -> bool| {
+            ordered
+                .changes
+                .iter()
+                .position(|c| pred(&c.change))
+                .unwrap()
+        };
+        let create = position(&|c| matches!(c, Change::CreateDomain(_)));
+        let alter = position(&|c| matches!(c, Change::AlterColumnDomain { .. }));
+        let drop = position(&|c| matches!(c, Change::DropDomain(_)));
+        assert!(create < alter, "domain must exist before columns use it");
+        assert!(
+            alter < drop,
+            "domain can't be dropped while a column uses it"
+        );
+
+        // Dropping a domain that's still in use is rejected
+        let mut schema = VirtualSchema::from_tables(current.tables.values());
+        assert!(matches!(
+            schema.apply(
+                crate::diff::DOMAINS_DIFF_TABLE,
+                &Change::DropDomain("old_email".to_string())
+            ),
+            Err(SolverError::ConflictingOperations { .. })
+        ));
+    }
+
+    #[test]
+    fn test_extensions_are_created_first() {
+        let current = Schema::new();
+        let mut desired = make_schema(vec![make_table(
+            "article",
+            vec![make_column("id", PgType::BigInt, false)],
+        )]);
+        desired.extensions = vec!["pg_trgm".to_string()];
+
+        let diff = desired.diff(&current);
+        let ordered = order_changes(
+            &diff,
+            &VirtualSchema::from_tables(current.tables.values()),
+            &VirtualSchema::from_tables(desired.tables.values()),
+        )
+        .unwrap();
+        assert!(matches!(
+            &ordered.changes[0].change,
+            Change::CreateExtension(name) if name == "pg_trgm"
+        ));
+        assert!(matches!(&ordered.changes[1].change, Change::AddTable(_)));
+    }
+
+    #[test]
+    fn test_policy_ordering_around_columns() {
+        let policy = |name: &str, using: &str| crate::RlsPolicy {
+            name: name.to_string(),
+            command: "ALL".to_string(),
+            roles: vec![],
+            using: Some(using.to_string()),
+            with_check: None,
+            expr_hash: None,
+        };
+
+        let mut current_table = make_table(
+            "post",
+            vec![
+                make_column("id", PgType::BigInt, false),
+                make_column("org_id", PgType::BigInt, false),
+            ],
+        );
+        current_table.rls = true;
+        current_table.policies = vec![policy("org_isolation", "org_id = 1")];
+
+        let mut desired_table = make_table(
+            "post",
+            vec![
+                make_column("id", PgType::BigInt, false),
+                // Different type, so this is not detected as a rename
+                make_column("tenant_id", PgType::Text, false),
+            ],
+        );
+        desired_table.rls = true;
+        desired_table.policies = vec![policy("tenant_isolation", "tenant_id = 'a'")];
+
+        let current = make_schema(vec![current_table]);
+        let desired = make_schema(vec![desired_table]);
+
+        let diff = desired.diff(&current);
+        let current_schema = VirtualSchema::from_tables(current.tables.values());
+        let desired_schema = VirtualSchema::from_tables(desired.tables.values());
+
+        let ordered = order_changes(&diff, &current_schema, &desired_schema).unwrap();
+        let position = |pred: &dyn Fn(&Change) -> bool| {
+            ordered
+                .changes
+                .iter()
+                .position(|c| pred(&c.change))
+                .expect("change should be scheduled")
+        };
+        let drop_policy = position(&|c| matches!(c, Change::DropPolicy(_)));
+        let drop_column = position(&|c| matches!(c, Change::DropColumn(_)));
+        let add_column = position(&|c| matches!(c, Change::AddColumn(_)));
+        let add_policy = position(&|c| matches!(c, Change::AddPolicy { .. }));
+
+        assert!(
+            drop_policy < drop_column,
+            "policy must go before its column"
+        );
+        assert!(
+            add_column < add_policy,
+            "column must exist before the policy"
+        );
+    }
+
     #[test]
     fn test_simulation_detects_add_then_drop_same_fk() {
         // This tests the key scenario: the diff algorithm might generate
@@ -2765,6 +3599,7 @@ mod tests {
                 columns: vec!["parent_id".to_string()],
                 references_table: "categories".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         )]);
 
@@ -2779,6 +3614,7 @@ mod tests {
                 columns: vec!["parent_id".to_string()],
                 references_table: "category".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
         )]);
 
@@ -2796,11 +3632,13 @@ mod tests {
                         columns: vec!["parent_id".to_string()],
                         references_table: "category".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     }),
                     Change::DropForeignKey(ForeignKey {
                         columns: vec!["parent_id".to_string()],
                         references_table: "categories".to_string(),
                         references_columns: vec!["id".to_string()],
+                        deferrable: false,
                     }),
                 ],
             }],
@@ -2924,6 +3762,7 @@ mod tests {
                     columns: vec!["author_id".to_string()],
                     references_table: "user".to_string(),
                     references_columns: vec!["id".to_string()],
+                    deferrable: false,
                 }],
             ),
         ]);
@@ -2933,6 +3772,7 @@ mod tests {
             columns: vec!["author_id".to_string()],
             references_table: "user".to_string(),
             references_columns: vec!["id".to_string()],
+            deferrable: false,
         };
 
         let buggy_diff = SchemaDiff {
@@ -3077,6 +3917,8 @@ mod proptests {
                 icon: None,
                 lang: None,
                 subtype: None,
+                domain: None,
+                composite: None,
             },
         )
     }
@@ -3141,6 +3983,8 @@ mod proptests {
                             icon: None,
                             lang: None,
                             subtype: None,
+                            domain: None,
+                            composite: None,
                         },
                     );
                 }
@@ -3164,7 +4008,16 @@ mod proptests {
                     name,
                     columns,
                     check_constraints: vec![],
+                    exclusion_constraints: Vec::new(),
                     trigger_checks: vec![],
+                    triggers: Vec::new(),
+                    partition_by: None,
+                    rls: false,
+                    policies: vec![],
+                    soft_delete: None,
+                    created_at: None,
+                    updated_at: None,
+                    audited: false,
                     foreign_keys: vec![],
                     indices,
                     source: SourceLocation::default(),
@@ -3203,6 +4056,7 @@ mod proptests {
                             columns: vec![col_name],
                             references_table: table_name.clone(),
                             references_columns: vec!["id".to_string()],
+                            deferrable: false,
                         });
                         continue;
                     }
@@ -3214,6 +4068,7 @@ mod proptests {
                             columns: vec![col_name],
                             references_table: ref_table,
                             references_columns: vec!["id".to_string()],
+                            deferrable: false,
                         });
                     }
                 }
@@ -3221,6 +4076,9 @@ mod proptests {
 
             Schema {
                 tables: tables.into_iter().map(|t| (t.name.clone(), t)).collect(),
+                extensions: Vec::new(),
+                domains: Vec::new(),
+                composites: Vec::new(),
             }
         })
     }
@@ -0,0 +1,201 @@
+//! Plain `.sql` file migrations.
+//!
+//! `dibs::sql_migrations!("migrations/")` embeds every `.sql` file in a
+//! directory at compile time and registers each one as a [`Migration`](crate::Migration),
+//! ordered together with `#[dibs::migration]` functions by version.
+
+use crate::{MigrationContext, MigrationResult};
+
+/// Run an embedded `.sql` migration, one statement at a time.
+///
+/// Used by the code `dibs::sql_migrations!` generates.
+#[doc(hidden)]
+pub async fn __run_sql_migration(
+    ctx: &mut MigrationContext<'_>,
+    sql: &'static str,
+) -> MigrationResult<()> {
+    for chunk in split_sql_into_chunks(sql) {
+        if let SqlChunk::Statement(stmt) = chunk {
+            let stmt = stmt.trim().trim_end_matches(';');
+            if !stmt.is_empty() {
+                ctx.execute(stmt).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A piece of a SQL script, as split by [`split_sql_into_chunks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqlChunk {
+    /// A `--` line comment, without the leading dashes.
+    Comment(String),
+    /// A statement, including its terminating semicolon if it had one.
+    Statement(String),
+}
+
+/// Split a SQL script into statements and comment lines.
+///
+/// This is *not* a full SQL parser, but it does correctly avoid splitting on
+/// semicolons inside:
+/// - single-quoted strings: `'...'`
+/// - double-quoted identifiers: `"identifier"`
+/// - dollar-quoted strings: `$$ ... $$` or `$tag$ ... $tag$`
+pub fn split_sql_into_chunks(sql: &str) -> Vec<SqlChunk> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+
+    let mut in_single_quote = false;
+    let mut in_double_quote = false;
+    let mut dollar_quote_tag: Option<String> = None;
+
+    let mut i = 0usize;
+    while i < sql.len() {
+        // Handle exiting a dollar-quoted section.
+        if let Some(tag) = dollar_quote_tag.as_deref()
+            && sql[i..].starts_with(tag)
+        {
+            current.push_str(tag);
+            i += tag.len();
+            dollar_quote_tag = None;
+            continue;
+        }
+
+        let ch = sql[i..]
+            .chars()
+            .next()
+            .expect("i is always a char boundary");
+        let ch_len = ch.len_utf8();
+
+        // SQL line comments: -- ...\n
+        if !in_single_quote
+            && !in_double_quote
+            && dollar_quote_tag.is_none()
+            && ch == '-'
+            && sql.as_bytes().get(i + 1) == Some(&b'-')
+        {
+            // Flush any pending statement first.
+            if !current.trim().is_empty() {
+                out.push(SqlChunk::Statement(std::mem::take(&mut current)));
+            } else {
+                current.clear();
+            }
+
+            let rest = &sql[i..];
+            let end = rest.find('\n').map(|pos| i + pos).unwrap_or(sql.len());
+            let line = sql[i..end].trim_start_matches("--").trim().to_string();
+            out.push(SqlChunk::Comment(line));
+
+            i = if end < sql.len() { end + 1 } else { end };
+            continue;
+        }
+
+        // Entering a dollar-quoted section: $$ ... $$ or $tag$ ... $tag$
+        if !in_single_quote && !in_double_quote && dollar_quote_tag.is_none() && ch == '$' {
+            let rest = &sql[i + 1..];
+            if let Some(end_rel) = rest.find('$') {
+                let tag_body = &rest[..end_rel];
+                if tag_body
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+                {
+                    let tag_end = i + 1 + end_rel + 1;
+                    let tag = sql[i..tag_end].to_string();
+                    current.push_str(&tag);
+                    i = tag_end;
+                    dollar_quote_tag = Some(tag);
+                    continue;
+                }
+            }
+        }
+
+        // Single-quoted string handling (including '' escaping)
+        if dollar_quote_tag.is_none() && !in_double_quote && ch == '\'' {
+            if in_single_quote && sql.as_bytes().get(i + 1) == Some(&b'\'') {
+                // Escaped single quote: ''
+                current.push_str("''");
+                i += 2;
+                continue;
+            }
+            in_single_quote = !in_single_quote;
+            current.push('\'');
+            i += ch_len;
+            continue;
+        }
+
+        // Double-quoted identifier handling (including "" escaping)
+        if dollar_quote_tag.is_none() && !in_single_quote && ch == '"' {
+            if in_double_quote && sql.as_bytes().get(i + 1) == Some(&b'"') {
+                // Escaped double quote: ""
+                current.push_str("\"\"");
+                i += 2;
+                continue;
+            }
+            in_double_quote = !in_double_quote;
+            current.push('"');
+            i += ch_len;
+            continue;
+        }
+
+        // Statement terminator (only when not inside any quoted context)
+        if !in_single_quote && !in_double_quote && dollar_quote_tag.is_none() && ch == ';' {
+            current.push(';');
+            out.push(SqlChunk::Statement(std::mem::take(&mut current)));
+            i += ch_len;
+            continue;
+        }
+
+        current.push_str(&sql[i..i + ch_len]);
+        i += ch_len;
+    }
+
+    if !current.trim().is_empty() {
+        out.push(SqlChunk::Statement(current));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statements(sql: &str) -> Vec<String> {
+        split_sql_into_chunks(sql)
+            .into_iter()
+            .filter_map(|chunk| match chunk {
+                SqlChunk::Statement(stmt) => Some(stmt.trim().to_string()),
+                SqlChunk::Comment(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_keeps_dollar_quoted_bodies_whole() {
+        let sql = r#"
+-- a function
+CREATE FUNCTION f() RETURNS trigger LANGUAGE plpgsql AS $body$
+BEGIN
+    RAISE EXCEPTION 'no; really';
+END;
+$body$;
+INSERT INTO t (s) VALUES ('a;b'), ('it''s');
+"#;
+        let stmts = statements(sql);
+        assert_eq!(stmts.len(), 2, "{stmts:?}");
+        assert!(stmts[0].ends_with("$body$;"));
+        assert_eq!(stmts[1], "INSERT INTO t (s) VALUES ('a;b'), ('it''s');");
+    }
+
+    #[test]
+    fn test_split_reports_comments() {
+        let chunks = split_sql_into_chunks("-- hello\nSELECT 1");
+        assert_eq!(
+            chunks,
+            vec![
+                SqlChunk::Comment("hello".to_string()),
+                SqlChunk::Statement("SELECT 1".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Loose type checks between literals, query params and SQL column types.
+//!
+//! Types are named the way query files spell them (`string`, `int`, `bool`,
+//! ...), and column types are matched by their SQL names.
+
+/// Check if a param or literal type is compatible with a SQL column type.
+///
+/// Used by seed validation and the query linter's type mismatch checks.
+pub fn types_compatible(param_type: &str, sql_type: &str) -> bool {
+    match param_type {
+        "string" => matches!(
+            sql_type.to_uppercase().as_str(),
+            "TEXT" | "VARCHAR" | "CHAR" | "CHARACTER VARYING"
+        ),
+        "int" => matches!(
+            sql_type.to_uppercase().as_str(),
+            "INT" | "INTEGER" | "BIGINT" | "SMALLINT" | "INT4" | "INT8" | "INT2"
+        ),
+        "bool" | "boolean" => matches!(sql_type.to_uppercase().as_str(), "BOOLEAN" | "BOOL"),
+        "float" => matches!(
+            sql_type.to_uppercase().as_str(),
+            "FLOAT" | "DOUBLE" | "REAL" | "NUMERIC" | "DECIMAL" | "FLOAT4" | "FLOAT8"
+        ),
+        "jsonb" => matches!(sql_type.to_uppercase().as_str(), "JSON" | "JSONB"),
+        _ => true, // Unknown types are assumed compatible
+    }
+}
+
+/// Infer the type of a literal value.
+/// Returns None if it's a param reference (starts with $) or unknown.
+pub fn infer_literal_type(value: &str) -> Option<&'static str> {
+    if value.starts_with('$') {
+        return None; // Param reference, not a literal
+    }
+    if value == "true" || value == "false" {
+        return Some("boolean");
+    }
+    if value.parse::<i64>().is_ok() {
+        return Some("int");
+    }
+    if value.parse::<f64>().is_ok() {
+        return Some("float");
+    }
+    // Everything else is a string (quoted strings arrive without quotes in EqBare)
+    Some("string")
+}
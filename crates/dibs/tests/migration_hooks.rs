@@ -0,0 +1,204 @@
+//! Migration hooks and timeouts, in a binary of their own so the hooks don't
+//! run in every other integration test.
+
+use dibs::{MigrationContext, MigrationResult};
+use dockside::{Container, containers};
+use std::time::Duration;
+use tokio_postgres::NoTls;
+
+#[dibs::hook(before_all)]
+async fn create_hook_log(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {
+    ctx.execute(
+        "CREATE TABLE IF NOT EXISTS hook_log (
+             id BIGSERIAL PRIMARY KEY, event TEXT NOT NULL, migration TEXT, setting TEXT
+         )",
+    )
+    .await?;
+    ctx.execute("SET lock_timeout = '3s'").await?;
+    Ok(())
+}
+
+#[dibs::hook(before_each)]
+async fn log_before(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {
+    ctx.execute_params(
+        "INSERT INTO hook_log (event, migration) VALUES ('before_each', $1)",
+        &[&ctx.migration()],
+    )
+    .await?;
+    Ok(())
+}
+
+#[dibs::hook(after_each)]
+async fn log_after(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {
+    ctx.execute_params(
+        "INSERT INTO hook_log (event, migration, setting)
+         VALUES ('after_each', $1, current_setting('statement_timeout'))",
+        &[&ctx.migration()],
+    )
+    .await?;
+    Ok(())
+}
+
+#[dibs::hook(after_each)]
+async fn reject_when_asked(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {
+    ctx.execute(
+        "DO $$ BEGIN
+             IF current_setting('dibs_test.reject', true) = 'yes' THEN
+                 RAISE EXCEPTION 'rejected by test';
+             END IF;
+         END $$",
+    )
+    .await?;
+    Ok(())
+}
+
+#[dibs::hook(after_all)]
+async fn log_after_all(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {
+    ctx.execute(
+        "INSERT INTO hook_log (event, setting) VALUES ('after_all', current_setting('lock_timeout'))",
+    )
+    .await?;
+    Ok(())
+}
+
+#[dibs::migration("hooks_0001-timed", timeout = "5s")]
+async fn timed(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {
+    ctx.execute(
+        "INSERT INTO hook_log (event, migration, setting)
+         VALUES ('migration', 'hooks_0001-timed', current_setting('statement_timeout'))",
+    )
+    .await?;
+    Ok(())
+}
+
+#[dibs::migration("hooks_0002-noop")]
+async fn noop(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {
+    ctx.execute("SELECT 1").await?;
+    Ok(())
+}
+
+#[dibs::migration("hooks_0003-slow", timeout = "50ms")]
+async fn slow(ctx: &mut MigrationContext<'_>) -> MigrationResult<()> {
+    ctx.execute(
+        "SELECT pg_sleep(CASE WHEN current_setting('dibs_test.slow', true) = 'yes' THEN 1 ELSE 0 END)",
+    )
+    .await?;
+    Ok(())
+}
+
+async fn create_postgres_container() -> (Container, tokio_postgres::Client) {
+    let (container, port) = tokio::task::spawn_blocking(|| {
+        let container = Container::run(containers::postgres("18", "postgres"))
+            .expect("Failed to start Postgres container");
+        container
+            .wait_for_log(
+                "database system is ready to accept connections",
+                Duration::from_secs(30),
+            )
+            .expect("Postgres failed to become ready");
+        let port = container
+            .wait_for_port(5432, Duration::from_secs(5))
+            .expect("Failed to connect to postgres port");
+        (container, port)
+    })
+    .await
+    .expect("spawn_blocking failed");
+
+    let connection_string = format!(
+        "host=127.0.0.1 port={} user=postgres password=postgres dbname=postgres",
+        port
+    );
+    let mut last_err = None;
+    for _ in 0..30 {
+        match tokio_postgres::connect(&connection_string, NoTls).await {
+            Ok((client, connection)) => {
+                tokio::spawn(async move {
+                    if let Err(e) = connection.await {
+                        eprintln!("Connection error: {}", e);
+                    }
+                });
+                return (container, client);
+            }
+            Err(e) => {
+                last_err = Some(e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+    panic!(
+        "Failed to connect to Postgres after retries: {:?}",
+        last_err
+    );
+}
+
+async fn hook_log(
+    client: &tokio_postgres::Client,
+) -> Vec<(String, Option<String>, Option<String>)> {
+    client
+        .query(
+            "SELECT event, migration, setting FROM hook_log ORDER BY id",
+            &[],
+        )
+        .await
+        .expect("read hook log")
+        .iter()
+        .map(|r| (r.get(0), r.get(1), r.get(2)))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_hooks_and_timeouts() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    // A failing hook fails its migration, and the error names it
+    client
+        .batch_execute("SET dibs_test.reject = 'yes'")
+        .await
+        .unwrap();
+    let err = dibs::MigrationRunner::new(&mut client)
+        .migrate_to("hooks_0001-timed")
+        .await
+        .expect_err("the hook rejects the migration");
+    assert!(
+        err.to_string()
+            .contains("after_each hook reject_when_asked failed"),
+        "{err}"
+    );
+    assert!(err.to_string().contains("rejected by test"), "{err}");
+
+    // The migration's timeout applies to it alone
+    client
+        .batch_execute("RESET dibs_test.reject; SET dibs_test.slow = 'yes'")
+        .await
+        .unwrap();
+    let err = dibs::MigrationRunner::new(&mut client)
+        .migrate()
+        .await
+        .expect_err("the slow migration times out");
+    assert!(err.to_string().contains("statement timeout"), "{err}");
+
+    let s = |v: &str| Some(v.to_string());
+    let each = |event: &str, migration: &str, setting: Option<String>| {
+        (event.to_string(), s(migration), setting)
+    };
+    assert_eq!(
+        hook_log(&client).await,
+        vec![
+            each("before_each", "hooks_0001-timed", None),
+            each("migration", "hooks_0001-timed", s("5s")),
+            each("after_each", "hooks_0001-timed", s("0")),
+            each("before_each", "hooks_0002-noop", None),
+            each("after_each", "hooks_0002-noop", s("0")),
+        ]
+    );
+
+    // After-all hooks run once everything succeeded, with the session
+    // settings from before-all hooks still in place
+    client.batch_execute("RESET dibs_test.slow").await.unwrap();
+    dibs::MigrationRunner::new(&mut client)
+        .migrate()
+        .await
+        .expect("migrate");
+    let log = hook_log(&client).await;
+    assert_eq!(log.last(), Some(&("after_all".to_string(), None, s("3s"))));
+}
@@ -66,6 +66,8 @@ fn test_column(
         lang: None,
         icon: None,
         subtype: None,
+        domain: None,
+        composite: None,
     }
 }
 
@@ -86,6 +88,8 @@ fn test_column_with_default(name: &str, pg_type: dibs::PgType, default: &str) ->
         lang: None,
         icon: None,
         subtype: None,
+        domain: None,
+        composite: None,
     }
 }
 
@@ -99,7 +103,16 @@ fn test_table(
         name: name.to_string(),
         columns,
         check_constraints: Vec::new(),
+        exclusion_constraints: Vec::new(),
         trigger_checks: Vec::new(),
+        triggers: Vec::new(),
+        partition_by: None,
+        rls: false,
+        policies: Vec::new(),
+        soft_delete: None,
+        created_at: None,
+        updated_at: None,
+        audited: false,
         foreign_keys,
         indices,
         source: dibs::SourceLocation {
@@ -115,6 +128,9 @@ fn test_table(
 fn make_schema(tables: Vec<dibs::Table>) -> Schema {
     Schema {
         tables: tables.into_iter().map(|t| (t.name.clone(), t)).collect(),
+        extensions: Vec::new(),
+        domains: Vec::new(),
+        composites: Vec::new(),
     }
 }
 
@@ -535,6 +551,173 @@ async fn test_diff_rust_vs_database() {
     );
 }
 
+/// Doc comments are written as `COMMENT ON` and read back by introspection,
+/// so a documented schema round-trips with no diff.
+#[tokio::test]
+async fn test_comments_round_trip() {
+    let (_container, client) = create_postgres_container().await;
+
+    let mut id = test_column("id", PgType::BigInt, false, true, false);
+    id.doc = Some("Surrogate key.".to_string());
+    let mut notes = test_table("test_notes", vec![id], vec![], vec![]);
+    notes.doc = Some("A note's body\nspans lines.".to_string());
+
+    let desired = make_schema(vec![notes]);
+    let sql = desired.diff(&Schema::new()).to_sql();
+    assert!(sql.contains(r#"COMMENT ON TABLE "test_notes" IS 'A note''s body"#));
+    client.batch_execute(&sql).await.expect("create table");
+
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    let table = &db_schema.tables["test_notes"];
+    assert_eq!(table.doc.as_deref(), Some("A note's body\nspans lines."));
+    assert_eq!(table.columns[0].doc.as_deref(), Some("Surrogate key."));
+
+    let diff = desired.diff(&db_schema);
+    assert!(diff.is_empty(), "comments should round-trip. Got: {diff}");
+}
+
+#[tokio::test]
+async fn test_rls_policies_round_trip() {
+    let (_container, client) = create_postgres_container().await;
+
+    let mut docs = test_table(
+        "test_docs",
+        vec![
+            test_column("id", PgType::BigInt, false, true, false),
+            test_column("tenant_id", PgType::BigInt, false, false, false),
+        ],
+        vec![],
+        vec![],
+    );
+    docs.rls = true;
+    let mut policy = dibs::RlsPolicy {
+        name: "tenant_isolation".to_string(),
+        command: "SELECT".to_string(),
+        roles: vec![],
+        using: Some("tenant_id = current_setting('app.tenant')::bigint".to_string()),
+        with_check: None,
+        expr_hash: None,
+    };
+    policy.expr_hash = Some(policy.declared_expr_hash());
+    docs.policies = vec![policy];
+
+    let desired = make_schema(vec![docs]);
+    let sql = desired.diff(&Schema::new()).to_sql();
+    client.batch_execute(&sql).await.expect("create table");
+
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    let table = &db_schema.tables["test_docs"];
+    assert!(table.rls);
+    assert_eq!(table.policies.len(), 1);
+    assert_eq!(table.policies[0].command, "SELECT");
+    assert!(table.policies[0].roles.is_empty());
+
+    let diff = desired.diff(&db_schema);
+    assert!(diff.is_empty(), "policies should round-trip. Got: {diff}");
+
+    // Editing the expression recreates the policy
+    let mut edited = desired.clone();
+    let table = edited.tables.get_mut("test_docs").unwrap();
+    table.policies[0].using = Some("tenant_id > 0".to_string());
+    table.policies[0].expr_hash = Some(table.policies[0].declared_expr_hash());
+    let diff = edited.diff(&db_schema);
+    assert!(
+        diff.to_string().contains("POLICY tenant_isolation"),
+        "an edited policy expression should be diffed. Got: {diff}"
+    );
+}
+
+#[tokio::test]
+async fn test_triggers_round_trip() {
+    let (_container, client) = create_postgres_container().await;
+
+    let mut events = test_table(
+        "test_events",
+        vec![
+            test_column("id", PgType::BigInt, false, true, false),
+            test_column("touched", PgType::Boolean, true, false, false),
+        ],
+        vec![],
+        vec![],
+    );
+    events.triggers = vec![dibs::TableTrigger {
+        name: "trg_touch".to_string(),
+        timing: "BEFORE".to_string(),
+        events: vec!["INSERT".to_string(), "UPDATE".to_string()],
+        for_each: "ROW".to_string(),
+        function_body: "BEGIN\n    NEW.touched = true;\n    RETURN NEW;\nEND;".to_string(),
+    }];
+
+    let desired = make_schema(vec![events.clone()]);
+    let sql = desired.diff(&Schema::new()).to_sql();
+    client.batch_execute(&sql).await.expect("create table");
+
+    client
+        .batch_execute("INSERT INTO test_events (id) VALUES (1)")
+        .await
+        .expect("insert");
+    let row = client
+        .query_one("SELECT touched FROM test_events", &[])
+        .await
+        .expect("select");
+    assert_eq!(row.get::<_, Option<bool>>(0), Some(true));
+
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    assert_eq!(db_schema.tables["test_events"].triggers, events.triggers);
+    let diff = desired.diff(&db_schema);
+    assert!(diff.is_empty(), "triggers should round-trip. Got: {diff}");
+
+    // Editing the body recreates the trigger
+    events.triggers[0].function_body = "BEGIN RETURN NEW; END;".to_string();
+    let diff = make_schema(vec![events]).diff(&db_schema);
+    client
+        .batch_execute(&diff.to_sql())
+        .await
+        .expect("recreate trigger");
+}
+
+#[tokio::test]
+async fn test_partitioned_table_hides_partitions() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    let mut events = test_table(
+        "test_events_log",
+        vec![
+            test_column("id", PgType::BigInt, false, true, false),
+            test_column("created_at", PgType::Timestamptz, false, true, false),
+        ],
+        vec![],
+        vec![],
+    );
+    events.partition_by = Some(dibs::PartitionSpec {
+        kind: dibs::PartitionKind::Range,
+        columns: vec!["created_at".to_string()],
+    });
+
+    let desired = make_schema(vec![events]);
+    let sql = desired.diff(&Schema::new()).to_sql();
+    assert!(sql.contains(r#"PARTITION BY RANGE ("created_at")"#));
+    client.batch_execute(&sql).await.expect("create table");
+
+    let tx = client.transaction().await.expect("begin");
+    let ctx = dibs::MigrationContext::new(&tx);
+    let day = chrono::NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+    let name = ctx
+        .create_monthly_partition("test_events_log", day)
+        .await
+        .expect("create partition");
+    assert_eq!(name, "test_events_log_2024_03");
+    tx.commit().await.expect("commit");
+
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    assert!(!db_schema.tables.contains_key("test_events_log_2024_03"));
+    let diff = desired.diff(&db_schema);
+    assert!(
+        diff.is_empty(),
+        "partitioning should round-trip. Got: {diff}"
+    );
+}
+
 #[tokio::test]
 async fn test_diff_no_changes() {
     let (_container, client) = create_postgres_container().await;
@@ -713,6 +896,7 @@ async fn test_table_rename_execution() {
                 columns: vec!["author_id".to_string()],
                 references_table: "user".to_string(),
                 references_columns: vec!["id".to_string()],
+                deferrable: false,
             }],
             vec![dibs::Index {
                 name: "idx_post_author_id".to_string(),
@@ -1273,3 +1457,654 @@ async fn test_error_message_boolean_vs_integer() {
     let error = result.unwrap_err();
     insta::assert_snapshot!("boolean_vs_integer", error.to_string());
 }
+
+#[tokio::test]
+async fn test_extensions_round_trip() {
+    let (_container, client) = create_postgres_container().await;
+
+    let mut desired = make_schema(vec![]);
+    desired.extensions = vec!["pg_trgm".to_string()];
+
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    let diff = desired.diff(&db_schema);
+    assert_eq!(diff.change_count(), 1, "missing extension. Got: {diff}");
+    client
+        .batch_execute(&diff.to_sql())
+        .await
+        .expect("create extension");
+
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    assert_eq!(db_schema.extensions, vec!["pg_trgm"]);
+    assert!(desired.diff(&db_schema).is_empty());
+}
+
+#[tokio::test]
+async fn test_domains_round_trip() {
+    let (_container, client) = create_postgres_container().await;
+
+    let mut email = test_column("email", PgType::Text, false, false, false);
+    email.domain = Some("test_email".to_string());
+    let mut desired = make_schema(vec![test_table(
+        "test_subscribers",
+        vec![test_column("id", PgType::BigInt, false, true, false), email],
+        vec![],
+        vec![],
+    )]);
+    let expr = "VALUE ~ '@'";
+    desired.domains = vec![dibs::DomainType {
+        name: "test_email".to_string(),
+        base_type: PgType::Text,
+        check: Some(dibs::CheckConstraint {
+            name: dibs::check_constraint_name("test_email", expr),
+            expr: expr.to_string(),
+        }),
+    }];
+
+    let sql = desired.diff(&Schema::new()).to_sql();
+    client.batch_execute(&sql).await.expect("create schema");
+
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    assert_eq!(db_schema.domains.len(), 1);
+    assert_eq!(db_schema.domains[0].base_type, PgType::Text);
+    let diff = desired.diff(&db_schema);
+    assert!(diff.is_empty(), "domains should round-trip. Got: {diff}");
+
+    // The domain's CHECK is enforced
+    let err = client
+        .execute(
+            "INSERT INTO test_subscribers (id, email) VALUES (1, 'nope')",
+            &[],
+        )
+        .await;
+    assert!(err.is_err());
+}
+
+#[tokio::test]
+async fn test_composites_round_trip() {
+    let (_container, client) = create_postgres_container().await;
+
+    let mut destination = test_column("destination", PgType::Composite, true, false, false);
+    destination.composite = Some("test_address".to_string());
+    let mut desired = make_schema(vec![test_table(
+        "test_shipments",
+        vec![
+            test_column("id", PgType::BigInt, false, true, false),
+            destination,
+        ],
+        vec![],
+        vec![],
+    )]);
+    let field = |name: &str, pg_type| dibs::CompositeField {
+        name: name.to_string(),
+        pg_type,
+    };
+    desired.composites = vec![dibs::CompositeType {
+        name: "test_address".to_string(),
+        fields: vec![
+            field("street", PgType::Text),
+            field("floor", PgType::Integer),
+        ],
+    }];
+
+    let sql = desired.diff(&Schema::new()).to_sql();
+    client.batch_execute(&sql).await.expect("create schema");
+
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    assert_eq!(db_schema.composites, desired.composites);
+    let diff = desired.diff(&db_schema);
+    assert!(diff.is_empty(), "composites should round-trip. Got: {diff}");
+
+    client
+        .execute(
+            "INSERT INTO test_shipments (id, destination) VALUES (1, ROW('Main St', 3))",
+            &[],
+        )
+        .await
+        .expect("insert a composite value");
+
+    // Adding an attribute is an in-place ALTER TYPE
+    desired.composites[0]
+        .fields
+        .push(field("city", PgType::Text));
+    let sql = desired.diff(&db_schema).to_sql();
+    assert!(sql.contains(r#"ALTER TYPE "test_address" ADD ATTRIBUTE "city" TEXT;"#));
+    client.batch_execute(&sql).await.expect("alter composite");
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    assert!(desired.diff(&db_schema).is_empty());
+}
+
+#[tokio::test]
+async fn test_audited_table_records_history() {
+    let (_container, client) = create_postgres_container().await;
+
+    let mut accounts = test_table(
+        "test_accounts",
+        vec![
+            test_column("id", PgType::BigInt, false, true, false),
+            test_column("balance", PgType::BigInt, false, false, false),
+        ],
+        vec![],
+        vec![],
+    );
+    accounts.audited = true;
+    accounts
+        .triggers
+        .push(dibs::TableTrigger::audit("test_accounts", "id"));
+    let history = accounts.history_table().expect("history table");
+    let desired = make_schema(vec![accounts, history]);
+
+    let sql = desired.diff(&Schema::new()).to_sql();
+    client.batch_execute(&sql).await.expect("create schema");
+
+    let db_schema = Schema::from_database(&client).await.expect("introspect");
+    let diff = desired.diff(&db_schema);
+    assert!(
+        diff.is_empty(),
+        "audited table should round-trip. Got: {diff}"
+    );
+
+    client
+        .batch_execute(
+            "BEGIN;
+             SET LOCAL dibs.actor = 'alice';
+             INSERT INTO test_accounts (id, balance) VALUES (1, 10);
+             UPDATE test_accounts SET balance = 20 WHERE id = 1;
+             DELETE FROM test_accounts WHERE id = 1;
+             COMMIT;",
+        )
+        .await
+        .expect("change rows");
+
+    let rows = client
+        .query(
+            "SELECT operation, old_row->>'balance', actor FROM test_accounts_history WHERE row_id = 1 ORDER BY id",
+            &[],
+        )
+        .await
+        .expect("read history");
+    let history: Vec<(String, Option<String>, Option<String>)> = rows
+        .iter()
+        .map(|r| (r.get(0), r.get(1), r.get(2)))
+        .collect();
+    let alice = Some("alice".to_string());
+    assert_eq!(
+        history,
+        vec![
+            ("INSERT".to_string(), None, alice.clone()),
+            ("UPDATE".to_string(), Some("10".to_string()), alice.clone()),
+            ("DELETE".to_string(), Some("20".to_string()), alice),
+        ]
+    );
+}
+
+#[dibs::migration("squash_0001-create_a")]
+async fn squash_create_a(ctx: &mut dibs::MigrationContext<'_>) -> dibs::MigrationResult<()> {
+    ctx.execute("CREATE TABLE squash_a (id BIGINT PRIMARY KEY)")
+        .await?;
+    Ok(())
+}
+
+#[dibs::migration("squash_0002-create_b")]
+async fn squash_create_b(ctx: &mut dibs::MigrationContext<'_>) -> dibs::MigrationResult<()> {
+    ctx.execute("CREATE TABLE squash_b (id BIGINT PRIMARY KEY)")
+        .await?;
+    Ok(())
+}
+
+#[dibs::migration(
+    "squash_0002-create_b_squashed",
+    squashes = ["squash_0001-create_a", "squash_0002-create_b"]
+)]
+async fn squash_baseline(ctx: &mut dibs::MigrationContext<'_>) -> dibs::MigrationResult<()> {
+    ctx.execute("CREATE TABLE squash_a (id BIGINT PRIMARY KEY)")
+        .await?;
+    ctx.execute("CREATE TABLE squash_b (id BIGINT PRIMARY KEY)")
+        .await?;
+    Ok(())
+}
+
+dibs::sql_migrations!("tests/sql_migrations/");
+
+#[test]
+fn test_sql_migrations_registered() {
+    let sql = dibs::inventory::iter::<dibs::Migration>
+        .into_iter()
+        .find(|m| m.version == "zz_sql_0001-create_c")
+        .expect("sql migration");
+    assert_eq!(sql.name, "m_zz_sql_0001-create_c.sql");
+    assert!(sql.source_path().exists());
+
+    let repeatable = dibs::inventory::iter::<dibs::RepeatableMigration>
+        .into_iter()
+        .find(|m| m.name == "sql_c_shouted")
+        .expect("repeatable sql migration");
+    assert!(repeatable.sql.contains("CREATE OR REPLACE VIEW"));
+    assert!(repeatable.source_path().exists());
+}
+
+#[tokio::test]
+async fn test_repeatable_migration_reruns_when_changed() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    let mut runner = dibs::MigrationRunner::new(&mut client);
+    runner.migrate().await.unwrap();
+
+    // Nothing changed: nothing runs
+    assert!(runner.migrate().await.unwrap().is_empty());
+    let status = runner.status().await.unwrap();
+    let view = status.last().expect("repeatable migrations come last");
+    assert!(view.repeatable && view.applied && view.applied_at.is_some());
+    assert_eq!(view.version, "sql_c_shouted");
+
+    // Pretend it last ran with different SQL
+    client
+        .execute(
+            "UPDATE __dibs_migrations SET checksum = 'stale' WHERE name = 'sql_c_shouted'",
+            &[],
+        )
+        .await
+        .unwrap();
+
+    let mut runner = dibs::MigrationRunner::new(&mut client);
+    assert!(
+        runner
+            .status()
+            .await
+            .unwrap()
+            .iter()
+            .any(|s| s.repeatable && !s.applied)
+    );
+    let ran = runner.migrate().await.unwrap();
+    assert_eq!(ran.len(), 1);
+    assert!(ran[0].repeatable && ran[0].version == "sql_c_shouted");
+
+    // Repeatable migrations never count as applied versioned migrations
+    assert!(
+        runner
+            .applied()
+            .await
+            .unwrap()
+            .iter()
+            .all(|m| m.version != "sql_c_shouted")
+    );
+
+    // A versioned migration of the same name is never overwritten
+    client
+        .batch_execute(
+            "DELETE FROM __dibs_migrations WHERE name = 'sql_c_shouted';
+             INSERT INTO __dibs_migrations (name) VALUES ('sql_c_shouted');",
+        )
+        .await
+        .unwrap();
+    let mut runner = dibs::MigrationRunner::new(&mut client);
+    let err = runner.migrate().await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("has the same name as an applied versioned migration"),
+        "unexpected error: {err}"
+    );
+    let row = client
+        .query_one(
+            "SELECT repeatable, checksum FROM __dibs_migrations WHERE name = 'sql_c_shouted'",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert!(!row.get::<_, bool>(0));
+    assert_eq!(row.get::<_, Option<String>>(1), None);
+}
+
+#[tokio::test]
+async fn test_sql_migration_runs_with_dollar_quoting() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    dibs::MigrationRunner::new(&mut client)
+        .migrate_to("zz_sql_0001-create_c")
+        .await
+        .unwrap();
+
+    client
+        .execute("INSERT INTO sql_c (id, note) VALUES (1, 'quiet')", &[])
+        .await
+        .unwrap();
+    let row = client
+        .query_one("SELECT note FROM sql_c WHERE id = 1", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, String>(0), "QUIET");
+}
+
+#[test]
+fn test_squashed_migration_registered() {
+    let baseline = dibs::inventory::iter::<dibs::Migration>
+        .into_iter()
+        .find(|m| m.version == "squash_0002-create_b_squashed")
+        .expect("baseline migration");
+    assert_eq!(
+        baseline.squashes,
+        ["squash_0001-create_a", "squash_0002-create_b"]
+    );
+}
+
+#[tokio::test]
+async fn test_squashed_baseline_recorded_when_covered() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    // The squashed migrations run, and the baseline is recorded without
+    // running (it would fail: its tables already exist)
+    let mut runner = dibs::MigrationRunner::new(&mut client);
+    let ran = runner.migrate().await.expect("migrate");
+    let ran: Vec<&str> = ran.iter().map(|m| m.version).collect();
+    assert_eq!(
+        ran,
+        vec![
+            "squash_0001-create_a",
+            "squash_0002-create_b",
+            "zz_sql_0001-create_c",
+            "sql_c_shouted",
+        ]
+    );
+
+    let applied: Vec<String> = runner
+        .applied()
+        .await
+        .expect("applied")
+        .into_iter()
+        .map(|m| m.version)
+        .collect();
+    assert_eq!(
+        applied,
+        vec![
+            "squash_0001-create_a",
+            "squash_0002-create_b",
+            "squash_0002-create_b_squashed",
+            "zz_sql_0001-create_c",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_to_target() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    let mut runner = dibs::MigrationRunner::new(&mut client);
+
+    // Unknown targets are rejected before anything runs
+    assert!(runner.migrate_to("squash_9999-nope").await.is_err());
+
+    // Only migrations up to and including the target are applied
+    let ran = runner.migrate_to("squash_0001-create_a").await.unwrap();
+    let ran: Vec<&str> = ran.iter().map(|m| m.version).collect();
+    assert_eq!(ran, vec!["squash_0001-create_a"]);
+
+    let pending: Vec<String> = runner
+        .status()
+        .await
+        .expect("status")
+        .into_iter()
+        .filter(|m| !m.applied)
+        .map(|m| m.version)
+        .collect();
+    assert_eq!(
+        pending,
+        vec![
+            "squash_0002-create_b",
+            "squash_0002-create_b_squashed",
+            "zz_sql_0001-create_c",
+        ]
+    );
+
+    // The rest can be applied later, followed by the repeatable migrations
+    let ran = runner.migrate().await.unwrap();
+    let ran: Vec<&str> = ran.iter().map(|m| m.version).collect();
+    assert_eq!(
+        ran,
+        vec![
+            "squash_0002-create_b",
+            "zz_sql_0001-create_c",
+            "sql_c_shouted"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_migrations_record_provenance() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    // A table created by hand before migrations were tracked
+    client
+        .execute("CREATE TABLE legacy_notes (id BIGINT PRIMARY KEY)", &[])
+        .await
+        .unwrap();
+
+    let schema = collect_schema();
+    dibs::MigrationRunner::new(&mut client)
+        .with_schema(&schema)
+        .migrate()
+        .await
+        .unwrap();
+
+    let provenance = dibs::meta::provenance(&client).await.unwrap();
+    let find = |kind: &str, table: &str, name: Option<&str>| {
+        provenance
+            .iter()
+            .find(|p| p.kind == kind && p.table == table && p.name.as_deref() == name)
+            .unwrap_or_else(|| panic!("no provenance for {kind} {table} {name:?}"))
+    };
+
+    assert_eq!(
+        find("table", "squash_a", None).created_by.as_deref(),
+        Some("squash_0001-create_a")
+    );
+    assert_eq!(
+        find("column", "sql_c", Some("note")).created_by.as_deref(),
+        Some("zz_sql_0001-create_c")
+    );
+    assert_eq!(
+        find("index", "sql_c", Some("sql_c_pkey"))
+            .created_by
+            .as_deref(),
+        Some("zz_sql_0001-create_c")
+    );
+    assert_eq!(find("table", "legacy_notes", None).created_by, None);
+
+    // Meta tables never track themselves
+    assert!(provenance.iter().all(|p| !p.table.starts_with("__dibs_")));
+}
+
+#[tokio::test]
+async fn test_legacy_migrations_table_is_imported() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    client
+        .batch_execute(
+            "CREATE TABLE _dibs_migrations (
+                version TEXT PRIMARY KEY,
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+            INSERT INTO _dibs_migrations (version) VALUES ('squash_0001-create_a');",
+        )
+        .await
+        .unwrap();
+
+    let runner = dibs::MigrationRunner::new(&mut client);
+    runner.init().await.unwrap();
+    let applied: Vec<String> = runner
+        .applied()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| m.version)
+        .collect();
+    assert_eq!(applied, vec!["squash_0001-create_a"]);
+
+    // Importing again doesn't duplicate or fail
+    runner.init().await.unwrap();
+    assert_eq!(runner.applied().await.unwrap().len(), 1);
+
+    let legacy: bool = client
+        .query_one("SELECT to_regclass('_dibs_migrations') IS NOT NULL", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert!(legacy, "the old table is left in place");
+}
+
+#[tokio::test]
+async fn test_migration_drift_detection() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    // The database applied the second migration and one that has since been
+    // removed from the code, but never the first
+    let runner = dibs::MigrationRunner::new(&mut client);
+    runner.mark_applied("squash_0002-create_b").await.unwrap();
+    runner.mark_applied("zz_removed").await.unwrap();
+
+    let applied = runner.applied().await.expect("applied");
+    let drift = runner.drift(&applied);
+    assert_eq!(
+        drift.out_of_order,
+        vec!["squash_0001-create_a", "squash_0002-create_b_squashed"]
+    );
+    assert_eq!(drift.missing, vec!["zz_removed"]);
+
+    let status = runner.status().await.expect("status");
+    let removed = status
+        .iter()
+        .find(|s| s.version == "zz_removed")
+        .expect("missing migration is reported");
+    assert!(removed.missing && removed.applied && removed.source_path.is_none());
+    assert!(
+        status
+            .iter()
+            .find(|s| s.version == "squash_0001-create_a")
+            .is_some_and(|s| s.out_of_order && !s.applied)
+    );
+
+    // Strict mode refuses to run anything
+    let mut runner = runner.strict(true);
+    let Err(err) = runner.migrate().await else {
+        panic!("strict mode should refuse to migrate");
+    };
+    assert!(err.to_string().contains("strict mode"), "{err}");
+    let applied = runner.applied().await.expect("applied");
+    assert_eq!(applied.len(), 2);
+}
+
+#[tokio::test]
+async fn test_seed_upserts_rows() {
+    let (_container, mut client) = create_postgres_container().await;
+
+    let mut id = test_column("id", PgType::BigInt, false, false, false);
+    id.primary_key = true;
+    id.default = Some("nextval('test_currencies_id_seq'::regclass)".to_string());
+    let schema = make_schema(vec![test_table(
+        "test_currencies",
+        vec![
+            id,
+            test_column("code", PgType::Text, false, false, true),
+            test_column("name", PgType::Text, true, false, false),
+        ],
+        vec![],
+        vec![],
+    )]);
+    client
+        .batch_execute(
+            "CREATE TABLE test_currencies (id BIGSERIAL PRIMARY KEY, code TEXT NOT NULL UNIQUE, name TEXT)",
+        )
+        .await
+        .expect("create table");
+
+    let files = vec![
+        dibs::SeedFile::parse(
+            "reference.styx",
+            "test_currencies { key (code), rows ({id 1, code EUR, name Euro} {id 2, code USD, name Dollar}) }",
+        )
+        .unwrap(),
+    ];
+    let seeded = dibs::apply_seeds(&mut client, &schema, &files, dibs::SeedEnv::Prod)
+        .await
+        .expect("first seed");
+    assert_eq!((seeded[0].inserted, seeded[0].unchanged), (2, 0));
+
+    // Seeding again is a no-op, and a changed value is updated in place
+    let seeded = dibs::apply_seeds(&mut client, &schema, &files, dibs::SeedEnv::Prod)
+        .await
+        .expect("second seed");
+    assert_eq!((seeded[0].inserted, seeded[0].unchanged), (0, 2));
+
+    let files = vec![
+        dibs::SeedFile::parse(
+            "reference.styx",
+            "test_currencies { key (code), rows ({id 2, code USD, name \"US Dollar\"}) }",
+        )
+        .unwrap(),
+    ];
+    let seeded = dibs::apply_seeds(&mut client, &schema, &files, dibs::SeedEnv::Prod)
+        .await
+        .expect("third seed");
+    assert_eq!(seeded[0].updated, 1);
+
+    // The sequence was moved past the seeded ids
+    let row = client
+        .query_one(
+            "INSERT INTO test_currencies (code) VALUES ('GBP') RETURNING id",
+            &[],
+        )
+        .await
+        .expect("insert after seeding");
+    assert_eq!(row.get::<_, i64>(0), 3);
+}
+
+#[tokio::test]
+async fn test_fleet_migrates_each_tenant_schema() {
+    let (container, client) = create_postgres_container().await;
+    let url = format!(
+        "host=127.0.0.1 port={} user=postgres password=postgres dbname=postgres",
+        container.host_port(5432).expect("postgres port")
+    );
+    client
+        .batch_execute(
+            "CREATE TABLE tenants (slug TEXT PRIMARY KEY, active BOOLEAN NOT NULL);
+             INSERT INTO tenants VALUES ('acme', true), ('globex', true), ('gone', false);",
+        )
+        .await
+        .expect("create tenants");
+
+    let targets = dibs::FleetRunner::discover(
+        &url,
+        "SELECT slug AS schema FROM tenants WHERE active ORDER BY slug",
+    )
+    .await
+    .expect("discover");
+    let names: Vec<&str> = targets.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["acme", "globex"]);
+
+    let report = dibs::FleetRunner::new(targets)
+        .concurrency(2)
+        .migrate_to("squash_0002-create_b")
+        .run(|_| {})
+        .await;
+    assert!(report.is_success(), "{report:?}");
+
+    // Each tenant got its own tables and its own migration history
+    for schema in ["acme", "globex"] {
+        let row = client
+            .query_one(
+                "SELECT count(*) FROM pg_tables WHERE schemaname = $1 AND tablename IN \
+                 ('squash_a', 'squash_b', '__dibs_migrations')",
+                &[&schema],
+            )
+            .await
+            .expect("tenant tables");
+        assert_eq!(row.get::<_, i64>(0), 3, "tables in {schema}");
+    }
+    let row = client
+        .query_one(
+            "SELECT count(*) FROM pg_tables WHERE schemaname = 'public' AND tablename = 'squash_a'",
+            &[],
+        )
+        .await
+        .expect("public tables");
+    assert_eq!(row.get::<_, i64>(0), 0);
+}
@@ -54,3 +54,460 @@ fn test_user_table() {
     assert_eq!(fk.references_table, "tenants");
     assert_eq!(fk.references_columns, vec!["id"]);
 }
+
+#[derive(Facet)]
+#[facet(derive(dibs::Table))]
+#[facet(dibs::table = "documents")]
+#[facet(dibs::rls)]
+#[facet(dibs::policy(
+    name = "tenant_isolation",
+    using = "tenant_id = current_setting('app.tenant')::bigint"
+))]
+#[facet(dibs::policy(
+    name = "writers",
+    command = "insert",
+    roles = "app_writer, public",
+    with_check = "tenant_id > 0"
+))]
+struct Document {
+    #[facet(dibs::pk)]
+    id: i64,
+    tenant_id: i64,
+}
+
+#[test]
+fn test_rls_policies() {
+    let schema = collect_schema();
+    let docs = schema.tables.get("documents").expect("documents table");
+
+    assert!(docs.rls);
+    assert_eq!(docs.policies.len(), 2);
+
+    let tenant = &docs.policies[0];
+    assert_eq!(tenant.name, "tenant_isolation");
+    assert_eq!(tenant.command, "ALL");
+    assert!(tenant.roles.is_empty());
+    assert_eq!(
+        tenant.using.as_deref(),
+        Some("tenant_id = current_setting('app.tenant')::bigint")
+    );
+
+    let writers = &docs.policies[1];
+    assert_eq!(writers.command, "INSERT");
+    assert_eq!(writers.roles, vec!["app_writer".to_string()]);
+    assert_eq!(writers.using, None);
+    assert_eq!(writers.with_check.as_deref(), Some("tenant_id > 0"));
+
+    let users = schema.tables.get("users").expect("users table");
+    assert!(!users.rls);
+    assert!(users.policies.is_empty());
+}
+
+#[derive(Facet)]
+#[facet(dibs::table = "notes")]
+#[facet(dibs::rls = true)]
+struct RlsNote {
+    #[facet(dibs::pk)]
+    id: i64,
+}
+
+#[derive(Facet)]
+#[facet(dibs::table = "drafts")]
+#[facet(dibs::rls = false)]
+struct RlsDraft {
+    #[facet(dibs::pk)]
+    id: i64,
+}
+
+#[test]
+fn test_rls_with_value() {
+    let notes = dibs::TableDef::new::<RlsNote>().to_table().unwrap();
+    assert!(notes.rls);
+
+    let drafts = dibs::TableDef::new::<RlsDraft>().to_table().unwrap();
+    assert!(!drafts.rls);
+}
+
+#[derive(Facet)]
+#[facet(derive(dibs::Table))]
+#[facet(dibs::table = "articles")]
+#[facet(dibs::trigger(
+    name = "trg_touch",
+    events = "update, insert",
+    function_body = "BEGIN NEW.updated_at = now(); RETURN NEW; END;"
+))]
+#[facet(dibs::trigger(
+    name = "trg_audit",
+    timing = "after",
+    events = "delete",
+    for_each = "statement",
+    function_body = "BEGIN INSERT INTO audit_log DEFAULT VALUES; RETURN NULL; END;"
+))]
+struct Article {
+    #[facet(dibs::pk)]
+    id: i64,
+    updated_at: i64,
+}
+
+#[test]
+fn test_triggers() {
+    let schema = collect_schema();
+    let articles = schema.tables.get("articles").expect("articles table");
+    assert_eq!(articles.triggers.len(), 2);
+
+    let touch = &articles.triggers[0];
+    assert_eq!(touch.name, "trg_touch");
+    assert_eq!(touch.timing, "BEFORE");
+    assert_eq!(touch.events, vec!["INSERT", "UPDATE"]);
+    assert_eq!(touch.for_each, "ROW");
+
+    let audit = &articles.triggers[1];
+    assert_eq!(audit.timing, "AFTER");
+    assert_eq!(audit.events, vec!["DELETE"]);
+    assert_eq!(audit.for_each, "STATEMENT");
+}
+
+#[derive(Facet)]
+#[facet(derive(dibs::Table))]
+#[facet(dibs::table = "audit_events")]
+#[facet(dibs::partition_by(kind = "range", columns = "created_at"))]
+struct AuditEvent {
+    #[facet(dibs::pk)]
+    id: i64,
+    #[facet(dibs::pk)]
+    created_at: i64,
+}
+
+#[test]
+fn test_partition_by() {
+    let schema = collect_schema();
+    let events = schema
+        .tables
+        .get("audit_events")
+        .expect("audit_events table");
+    let partition = events.partition_by.as_ref().expect("partitioned");
+    assert_eq!(partition.kind, dibs::PartitionKind::Range);
+    assert_eq!(partition.columns, vec!["created_at"]);
+}
+
+dibs::extension!("pg_trgm");
+dibs::extension!("citext");
+dibs::extension!("pg_trgm");
+
+#[test]
+fn test_extensions() {
+    let schema = collect_schema();
+    assert_eq!(schema.extensions, vec!["citext", "pg_trgm"]);
+}
+
+#[derive(Facet)]
+#[facet(dibs::domain(name = "email_address", check = "VALUE ~ '@'"))]
+struct EmailAddress(String);
+
+#[derive(Facet)]
+#[facet(derive(dibs::Table))]
+#[facet(dibs::table = "subscribers")]
+struct Subscriber {
+    #[facet(dibs::pk)]
+    id: i64,
+    email: EmailAddress,
+    backup_email: Option<EmailAddress>,
+}
+
+#[test]
+fn test_domains() {
+    let schema = collect_schema();
+    let subscribers = schema.tables.get("subscribers").expect("subscribers table");
+
+    let email = subscribers
+        .columns
+        .iter()
+        .find(|c| c.name == "email")
+        .unwrap();
+    assert_eq!(email.pg_type, dibs::PgType::Text);
+    assert_eq!(email.domain.as_deref(), Some("email_address"));
+    assert!(!email.nullable);
+
+    let backup = subscribers
+        .columns
+        .iter()
+        .find(|c| c.name == "backup_email")
+        .unwrap();
+    assert_eq!(backup.domain.as_deref(), Some("email_address"));
+    assert!(backup.nullable);
+
+    // Used twice, defined once
+    let domains: Vec<_> = schema
+        .domains
+        .iter()
+        .filter(|d| d.name == "email_address")
+        .collect();
+    assert_eq!(domains.len(), 1);
+    assert_eq!(domains[0].base_type, dibs::PgType::Text);
+    let check = domains[0].check.as_ref().expect("domain check");
+    assert_eq!(check.expr, "VALUE ~ '@'");
+    assert!(check.name.starts_with("ck_email_address_"));
+}
+
+#[derive(Facet)]
+#[facet(dibs::composite(name = "postal_address"))]
+struct PostalAddress {
+    street: String,
+    #[facet(dibs::column = "postcode")]
+    zip: Option<String>,
+    floor: i32,
+}
+
+#[derive(Facet)]
+#[facet(derive(dibs::Table))]
+#[facet(dibs::table = "shipments")]
+struct Shipment {
+    #[facet(dibs::pk)]
+    id: i64,
+    destination: PostalAddress,
+    return_to: Option<PostalAddress>,
+}
+
+#[test]
+fn test_composites() {
+    let schema = collect_schema();
+    let shipments = schema.tables.get("shipments").expect("shipments table");
+
+    let destination = shipments
+        .columns
+        .iter()
+        .find(|c| c.name == "destination")
+        .unwrap();
+    assert_eq!(destination.pg_type, dibs::PgType::Composite);
+    assert_eq!(destination.composite.as_deref(), Some("postal_address"));
+    assert!(!destination.nullable);
+
+    let return_to = shipments
+        .columns
+        .iter()
+        .find(|c| c.name == "return_to")
+        .unwrap();
+    assert_eq!(return_to.composite.as_deref(), Some("postal_address"));
+    assert!(return_to.nullable);
+
+    // Used twice, defined once, with attributes in declaration order
+    let composites: Vec<_> = schema
+        .composites
+        .iter()
+        .filter(|c| c.name == "postal_address")
+        .collect();
+    assert_eq!(composites.len(), 1);
+    let fields: Vec<_> = composites[0]
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.pg_type))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("street", dibs::PgType::Text),
+            ("postcode", dibs::PgType::Text),
+            ("floor", dibs::PgType::Integer),
+        ]
+    );
+}
+
+#[derive(Facet)]
+#[facet(derive(dibs::Table))]
+#[facet(dibs::table = "room_bookings")]
+#[facet(dibs::exclude(elements = "room_id WITH =, during WITH &&"))]
+#[facet(dibs::exclude(
+    name = "one_active_hold",
+    elements = "subscriber_id WITH =",
+    filter = "NOT released",
+    deferrable = true
+))]
+struct RoomBooking {
+    #[facet(dibs::pk)]
+    id: i64,
+    room_id: i64,
+    during: String,
+    #[facet(dibs::fk = "subscribers.id", dibs::deferrable)]
+    subscriber_id: i64,
+    released: bool,
+}
+
+#[test]
+fn test_exclusion_and_deferrable_constraints() {
+    let schema = collect_schema();
+    let bookings = schema
+        .tables
+        .get("room_bookings")
+        .expect("room_bookings table");
+
+    assert_eq!(bookings.exclusion_constraints.len(), 2);
+    let overlap = &bookings.exclusion_constraints[0];
+    assert!(overlap.name.starts_with("ex_room_bookings_"));
+    assert_eq!(overlap.using, "gist");
+    assert_eq!(overlap.elements, "room_id WITH =, during WITH &&");
+    assert_eq!(overlap.filter, None);
+    assert!(!overlap.deferrable);
+    // The generated name carries the definition hash already
+    assert_eq!(overlap.def_hash, None);
+
+    let hold = &bookings.exclusion_constraints[1];
+    assert_eq!(hold.name, "one_active_hold");
+    assert_eq!(hold.filter.as_deref(), Some("NOT released"));
+    assert!(hold.deferrable);
+    assert_eq!(hold.def_hash, Some(hold.declared_def_hash()));
+
+    let fk = &bookings.foreign_keys[0];
+    assert_eq!(fk.references_table, "subscribers");
+    assert!(fk.deferrable);
+}
+
+#[derive(Facet)]
+#[facet(derive(dibs::Table))]
+#[facet(dibs::table = "archived_notes")]
+#[facet(dibs::soft_delete = "deleted_at")]
+struct ArchivedNote {
+    #[facet(dibs::pk)]
+    id: i64,
+    body: String,
+    deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[test]
+fn test_soft_delete() {
+    let schema = collect_schema();
+    let notes = schema
+        .tables
+        .get("archived_notes")
+        .expect("archived_notes table");
+    assert_eq!(notes.soft_delete.as_deref(), Some("deleted_at"));
+
+    let subscribers = schema.tables.get("subscribers").expect("subscribers table");
+    assert_eq!(subscribers.soft_delete, None);
+}
+
+#[derive(Facet)]
+#[facet(derive(dibs::Table))]
+#[facet(dibs::table = "journal_entries")]
+struct JournalEntry {
+    #[facet(dibs::pk)]
+    id: i64,
+    #[facet(dibs::created_at)]
+    created_at: chrono::DateTime<chrono::Utc>,
+    #[facet(dibs::updated_at, dibs::default = "'epoch'")]
+    touched_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[test]
+fn test_timestamp_columns() {
+    let schema = collect_schema();
+    let entries = schema
+        .tables
+        .get("journal_entries")
+        .expect("journal_entries table");
+    assert_eq!(entries.created_at.as_deref(), Some("created_at"));
+    assert_eq!(entries.updated_at.as_deref(), Some("touched_at"));
+
+    let created = entries
+        .columns
+        .iter()
+        .find(|c| c.name == "created_at")
+        .unwrap();
+    assert_eq!(created.default.as_deref(), Some("now()"));
+    // An explicit default is kept
+    let touched = entries
+        .columns
+        .iter()
+        .find(|c| c.name == "touched_at")
+        .unwrap();
+    assert_eq!(touched.default.as_deref(), Some("'epoch'"));
+
+    assert_eq!(entries.triggers.len(), 1);
+    let trigger = &entries.triggers[0];
+    assert_eq!(trigger.name, dibs::TableTrigger::UPDATED_AT);
+    assert_eq!(trigger.timing, "BEFORE");
+    assert_eq!(trigger.events, vec!["UPDATE"]);
+    assert!(
+        trigger
+            .function_body
+            .contains(r#"NEW."touched_at" = now()"#)
+    );
+}
+
+#[derive(Facet)]
+#[facet(derive(dibs::Table))]
+#[facet(dibs::table = "ledger_accounts")]
+#[facet(dibs::audited)]
+struct LedgerAccount {
+    #[facet(dibs::pk)]
+    id: i64,
+    balance: i64,
+}
+
+#[test]
+fn test_audited() {
+    let schema = collect_schema();
+    let accounts = schema
+        .tables
+        .get("ledger_accounts")
+        .expect("ledger_accounts table");
+    assert!(accounts.audited);
+    let audit = accounts
+        .triggers
+        .iter()
+        .find(|t| t.name == dibs::TableTrigger::AUDIT)
+        .expect("audit trigger");
+    assert_eq!(audit.timing, "AFTER");
+    assert_eq!(audit.events, vec!["INSERT", "UPDATE", "DELETE"]);
+    assert!(
+        audit
+            .function_body
+            .contains(r#"INSERT INTO "ledger_accounts_history""#)
+    );
+
+    let history = schema
+        .tables
+        .get("ledger_accounts_history")
+        .expect("generated history table");
+    assert!(!history.audited);
+    let columns: Vec<_> = history.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        columns,
+        vec![
+            "id",
+            "row_id",
+            "operation",
+            "old_row",
+            "actor",
+            "changed_at"
+        ]
+    );
+    let row_id = history.columns.iter().find(|c| c.name == "row_id").unwrap();
+    assert_eq!(row_id.pg_type, dibs::PgType::BigInt);
+    assert_eq!(
+        history.indices[0].name,
+        "idx_ledger_accounts_history_row_id"
+    );
+}
+
+// Not registered with `derive(dibs::Table)`: it can't be converted, and every
+// `collect_schema` test would report it.
+#[derive(Facet)]
+#[facet(dibs::table = "ledger_entries")]
+#[facet(dibs::audited)]
+struct LedgerEntry {
+    #[facet(dibs::pk)]
+    account_id: i64,
+    #[facet(dibs::pk)]
+    line: i32,
+}
+
+#[test]
+fn test_audited_needs_single_column_pk() {
+    let def = dibs::TableDef::new::<LedgerEntry>();
+    let problem = def.try_to_table().unwrap_err();
+    assert!(
+        problem.contains("audited table 'ledger_entries' needs a single-column primary key"),
+        "unexpected diagnostic: {problem}"
+    );
+    assert!(def.to_table().is_none());
+}
@@ -0,0 +1,13 @@
+-- A plain SQL migration, ordered after the Rust ones above by version
+CREATE TABLE sql_c (id BIGINT PRIMARY KEY, note TEXT NOT NULL);
+
+CREATE FUNCTION sql_c_shout() RETURNS trigger LANGUAGE plpgsql AS $$
+BEGIN
+    -- Semicolons inside the body must not split the statement;
+    NEW.note := upper(NEW.note);
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER sql_c_shout BEFORE INSERT ON sql_c
+    FOR EACH ROW EXECUTE FUNCTION sql_c_shout();
@@ -0,0 +1,3 @@
+-- Repeatable: re-run whenever this file changes
+CREATE OR REPLACE VIEW sql_c_shouted AS
+    SELECT id, note FROM sql_c WHERE note = upper(note);